@@ -0,0 +1,100 @@
+//! Compares decoding a large telemetry message into owned types against borrowed views.
+//!
+//! Run with `cargo bench -p bifrost --bench decode_borrowed`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use bifrost::serialization::{Decode, DecodeBorrowed, Encode};
+
+const ITERATIONS: usize = 10_000;
+
+/// Allocator that counts the number of allocations, to show what the borrowed decode saves.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Encode, Decode)]
+struct Telemetry {
+    cycle: u64,
+    source: String,
+    joint_positions: [f32; 25],
+    image: Vec<u8>,
+}
+
+#[derive(DecodeBorrowed)]
+struct TelemetryView<'a> {
+    cycle: u64,
+    source: &'a str,
+    joint_positions: [f32; 25],
+    image: &'a [u8],
+}
+
+fn bench(name: &str, mut f: impl FnMut()) -> (Duration, usize) {
+    // warm up the caches
+    for _ in 0..10 {
+        f();
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_message = start.elapsed() / ITERATIONS as u32;
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - allocations) / ITERATIONS;
+
+    println!("{name:>8}: {per_message:?} and {allocations} allocations per message");
+    (per_message, allocations)
+}
+
+fn main() {
+    let telemetry = Telemetry {
+        cycle: 1234,
+        source: "top_camera".to_string(),
+        joint_positions: [0.5; 25],
+        image: (0..80 * 60 * 2).map(|i| (i % 251) as u8).collect(),
+    };
+
+    let mut encoded = Vec::new();
+    telemetry.encode(&mut encoded).unwrap();
+
+    let (owned_time, owned_allocations) = bench("owned", || {
+        let telemetry = Telemetry::decode(black_box(encoded.as_slice())).unwrap();
+        black_box((
+            telemetry.cycle,
+            telemetry.source,
+            telemetry.joint_positions,
+            telemetry.image,
+        ));
+    });
+
+    let (borrowed_time, borrowed_allocations) = bench("borrowed", || {
+        let view = TelemetryView::decode_borrowed(&mut black_box(encoded.as_slice())).unwrap();
+        black_box((view.cycle, view.source, view.joint_positions, view.image));
+    });
+
+    println!(
+        " speedup: {:.1}x, {} fewer allocations per message",
+        owned_time.as_secs_f64() / borrowed_time.as_secs_f64(),
+        owned_allocations - borrowed_allocations,
+    );
+}
@@ -4,13 +4,19 @@ mod serialization;
 use serialization::{decode, encode};
 
 /// Implements a derive macro for the [Encode] trait.
-#[proc_macro_derive(Encode)]
+#[proc_macro_derive(Encode, attributes(bifrost))]
 pub fn encode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     encode::encode(input)
 }
 
 /// Implements a derive macro for the [Decode] trait.
-#[proc_macro_derive(Decode)]
+#[proc_macro_derive(Decode, attributes(bifrost))]
 pub fn decode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     decode::decode(input)
 }
+
+/// Implements a derive macro for the [`DecodeBorrowed`] trait.
+#[proc_macro_derive(DecodeBorrowed, attributes(bifrost))]
+pub fn decode_borrowed(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    decode::decode_borrowed(input)
+}
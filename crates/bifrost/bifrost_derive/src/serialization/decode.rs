@@ -1,12 +1,12 @@
 use crate::serialization::tools::{
-    calculate_discriminants, calculate_variant_discriminant_byte_size,
+    MessageAttributes, calculate_discriminants, calculate_variant_discriminant_byte_size,
 };
 
 use proc_macro2::TokenStream;
 
 use syn::{
     Attribute, Data, DataEnum, DataStruct, DataUnion, DeriveInput, Error, Fields, FieldsNamed,
-    FieldsUnnamed, Ident, Variant, parse,
+    FieldsUnnamed, Ident, Lifetime, Type, Variant, parse,
 };
 
 use quote::quote;
@@ -19,26 +19,83 @@ pub fn decode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     .into()
 }
 
-fn construct_named_struct(fields: &FieldsNamed) -> TokenStream {
-    let (field_types, field_idents): (Vec<_>, Vec<_>) = fields
+pub fn decode_borrowed(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match parse(input) {
+        Ok(ast) => impl_borrowed_derive(&ast),
+        Err(error) => error.to_compile_error(),
+    }
+    .into()
+}
+
+/// Whether the derived implementation decodes owned values from a reader, or borrowed values
+/// from a byte slice with the given lifetime.
+enum Mode<'a> {
+    Owned,
+    Borrowed(&'a Lifetime),
+}
+
+impl Mode<'_> {
+    /// The reader that is passed to the functions that read from it.
+    fn reader(&self) -> TokenStream {
+        match self {
+            Mode::Owned => quote! { &mut read },
+            Mode::Borrowed(_) => quote! { &mut *read },
+        }
+    }
+
+    /// Decodes a single field of the given type.
+    fn decode_field(&self, ty: &Type) -> TokenStream {
+        match self {
+            Mode::Owned => quote! { <#ty>::decode(&mut read)? },
+            Mode::Borrowed(lifetime) => quote! {
+                <#ty as bifrost::serialization::DecodeBorrowed<#lifetime>>::decode_borrowed(&mut *read)?
+            },
+        }
+    }
+
+    /// Wraps the body in the signature of the decode function.
+    fn decode_fn(&self, body: &TokenStream) -> TokenStream {
+        match self {
+            Mode::Owned => quote! {
+                fn decode(mut read: impl std::io::Read) -> bifrost::Result<Self>
+                where
+                    Self: Sized,
+                {
+                    #body
+                }
+            },
+            Mode::Borrowed(lifetime) => quote! {
+                fn decode_borrowed(read: &mut &#lifetime [u8]) -> bifrost::Result<Self> {
+                    #body
+                }
+            },
+        }
+    }
+}
+
+fn construct_named_struct(fields: &FieldsNamed, mode: &Mode) -> TokenStream {
+    let (field_decodes, field_idents): (Vec<_>, Vec<_>) = fields
         .named
         .iter()
-        .map(|field| (&field.ty, &field.ident))
+        .map(|field| (mode.decode_field(&field.ty), &field.ident))
         .unzip();
 
     quote! {
         Self {
-            #(#field_idents: <#field_types>::decode(&mut read)?,)*
+            #(#field_idents: #field_decodes,)*
         }
     }
 }
 
-fn construct_unnamed_struct(fields: &FieldsUnnamed) -> TokenStream {
-    let field_types = fields.unnamed.iter().map(|field| &field.ty);
+fn construct_unnamed_struct(fields: &FieldsUnnamed, mode: &Mode) -> TokenStream {
+    let field_decodes = fields
+        .unnamed
+        .iter()
+        .map(|field| mode.decode_field(&field.ty));
 
     quote! {
         Self (
-            #(<#field_types>::decode(&mut read)?,)*
+            #(#field_decodes,)*
         )
     }
 }
@@ -50,34 +107,39 @@ fn construct_unit_struct() -> TokenStream {
     }
 }
 
-fn decode_struct(data: &DataStruct) -> TokenStream {
+fn decode_struct(data: &DataStruct, decode_header: &TokenStream, mode: &Mode) -> TokenStream {
     let constructor_arguments = &match &data.fields {
-        Fields::Named(fields) => construct_named_struct(fields),
-        Fields::Unnamed(fields) => construct_unnamed_struct(fields),
+        Fields::Named(fields) => construct_named_struct(fields, mode),
+        Fields::Unnamed(fields) => construct_unnamed_struct(fields, mode),
         Fields::Unit => construct_unit_struct(),
     };
 
-    quote! {
-        fn decode(mut read: impl std::io::Read) -> bifrost::Result<Self>
-        where
-            Self: Sized,
-        {
-            Ok(
-                #constructor_arguments
-            )
-        }
-    }
+    mode.decode_fn(&quote! {
+        #decode_header
+
+        Ok(
+            #constructor_arguments
+        )
+    })
 }
 
-fn decode_variant_discriminant(data: &DataEnum, attributes: &[Attribute]) -> TokenStream {
+fn decode_variant_discriminant(
+    data: &DataEnum,
+    attributes: &[Attribute],
+    mode: &Mode,
+) -> TokenStream {
     let num_variants = data.variants.iter().len();
     let variant_discriminant_byte_size =
         calculate_variant_discriminant_byte_size(num_variants, &mut attributes.iter());
+    let reader = mode.reader();
 
     quote! {
-        let mut variant_discriminant_buf = [0_u8; std::mem::size_of::<usize>()];
-        read.read_exact(&mut variant_discriminant_buf[0..#variant_discriminant_byte_size])?;
-        let variant_discriminant: usize = usize::from_le_bytes(variant_discriminant_buf);
+        let mut variant_discriminant_buf = [0_u8; std::mem::size_of::<u64>()];
+        std::io::Read::read_exact(
+            #reader,
+            &mut variant_discriminant_buf[0..#variant_discriminant_byte_size],
+        )?;
+        let variant_discriminant: u64 = u64::from_le_bytes(variant_discriminant_buf);
     }
 }
 
@@ -85,16 +147,17 @@ fn decode_variant_named_fields(
     discriminant: &TokenStream,
     fields: &FieldsNamed,
     ident: &Ident,
+    mode: &Mode,
 ) -> TokenStream {
-    let (field_types, field_idents): (Vec<_>, Vec<_>) = fields
+    let (field_decodes, field_idents): (Vec<_>, Vec<_>) = fields
         .named
         .iter()
-        .map(|field| (&field.ty, &field.ident))
+        .map(|field| (mode.decode_field(&field.ty), &field.ident))
         .unzip();
 
     quote! {
-        discriminant if discriminant == (#discriminant) as usize =>
-            { Ok(Self::#ident{#(#field_idents: <#field_types>::decode(&mut read)?),*}) },
+        discriminant if discriminant == (#discriminant) as u64 =>
+            { Ok(Self::#ident{#(#field_idents: #field_decodes),*}) },
     }
 }
 
@@ -102,39 +165,48 @@ fn decode_variant_unnamed_fields(
     discriminant: &TokenStream,
     fields: &FieldsUnnamed,
     ident: &Ident,
+    mode: &Mode,
 ) -> TokenStream {
-    let field_types = fields.unnamed.iter().map(|field| &field.ty);
+    let field_decodes = fields
+        .unnamed
+        .iter()
+        .map(|field| mode.decode_field(&field.ty));
 
     quote! {
-        discriminant if discriminant == (#discriminant) as usize => { Ok(Self::#ident(#(<#field_types>::decode(&mut read)?),*)) },
+        discriminant if discriminant == (#discriminant) as u64 => { Ok(Self::#ident(#(#field_decodes),*)) },
     }
 }
 
 fn decode_variant_unit_fields(discriminant: &TokenStream, ident: &Ident) -> TokenStream {
     quote! {
-        discriminant if discriminant == (#discriminant) as usize => { Ok(Self::#ident) },
+        discriminant if discriminant == (#discriminant) as u64 => { Ok(Self::#ident) },
     }
 }
 
-fn decode_variant_fields((discriminant, variant): (TokenStream, Variant)) -> TokenStream {
+fn decode_variant_fields(
+    (discriminant, variant): (TokenStream, Variant),
+    mode: &Mode,
+) -> TokenStream {
     let ident = &variant.ident;
 
     let discriminant = if let Some((_, lit)) = &variant.discriminant {
-        quote! { ( #lit as usize ) }
+        quote! { ( #lit as u64 ) }
     } else {
         quote! { #discriminant }
     };
 
     match &variant.fields {
-        Fields::Named(fields) => decode_variant_named_fields(&discriminant, fields, ident),
-        Fields::Unnamed(fields) => decode_variant_unnamed_fields(&discriminant, fields, ident),
+        Fields::Named(fields) => decode_variant_named_fields(&discriminant, fields, ident, mode),
+        Fields::Unnamed(fields) => {
+            decode_variant_unnamed_fields(&discriminant, fields, ident, mode)
+        }
         Fields::Unit => decode_variant_unit_fields(&discriminant, ident),
     }
 }
 
-fn decode_variant(enum_ident: &Ident, data: &DataEnum) -> TokenStream {
-    let variant_match_arms =
-        calculate_discriminants(data.variants.iter()).map(decode_variant_fields);
+fn decode_variant(enum_ident: &Ident, data: &DataEnum, mode: &Mode) -> TokenStream {
+    let variant_match_arms = calculate_discriminants(data.variants.iter())
+        .map(|variant| decode_variant_fields(variant, mode));
 
     quote! {
         match variant_discriminant {
@@ -144,20 +216,23 @@ fn decode_variant(enum_ident: &Ident, data: &DataEnum) -> TokenStream {
     }
 }
 
-fn decode_enum(enum_ident: &Ident, data: &DataEnum, attributes: &[Attribute]) -> TokenStream {
-    let gen_decode_variant_discriminant = decode_variant_discriminant(data, attributes);
-    let gen_decode_read = decode_variant(enum_ident, data);
+fn decode_enum(
+    enum_ident: &Ident,
+    data: &DataEnum,
+    attributes: &[Attribute],
+    decode_header: &TokenStream,
+    mode: &Mode,
+) -> TokenStream {
+    let gen_decode_variant_discriminant = decode_variant_discriminant(data, attributes, mode);
+    let gen_decode_read = decode_variant(enum_ident, data, mode);
+
+    mode.decode_fn(&quote! {
+        #decode_header
 
-    quote! {
-        fn decode(mut read: impl std::io::Read) -> bifrost::Result<Self>
-        where
-            Self: Sized,
-        {
-            #gen_decode_variant_discriminant
+        #gen_decode_variant_discriminant
 
-            #gen_decode_read
-        }
-    }
+        #gen_decode_read
+    })
 }
 
 fn decode_union(data: &DataUnion) -> TokenStream {
@@ -168,10 +243,62 @@ fn decode_union(data: &DataUnion) -> TokenStream {
     .to_compile_error()
 }
 
-fn decode_fn(ast: &DeriveInput, attributes: &[Attribute]) -> TokenStream {
+fn decode_header(type_name: &Ident, message: &MessageAttributes, mode: &Mode) -> TokenStream {
+    let reader = mode.reader();
+
+    let header = match &message.header {
+        Some(header) => quote! {
+            const HEADER: &[u8] = (#header).as_slice();
+
+            let mut header = [0_u8; HEADER.len()];
+            std::io::Read::read_exact(#reader, &mut header)?;
+
+            if header != HEADER {
+                return Err(bifrost::Error::WrongMessageType {
+                    expected: stringify!(#type_name),
+                    header: header.to_vec(),
+                });
+            }
+        },
+        None => quote! {},
+    };
+
+    let version = match &message.version {
+        Some(version) => quote! {
+            const VERSION: u32 = #version;
+
+            let mut version = [0_u8; std::mem::size_of::<u32>()];
+            std::io::Read::read_exact(#reader, &mut version)?;
+            let version = u32::from_le_bytes(version);
+
+            if version != VERSION {
+                return Err(bifrost::Error::SchemaMismatch {
+                    message: stringify!(#type_name),
+                    expected: VERSION,
+                    found: version,
+                });
+            }
+        },
+        None => quote! {},
+    };
+
+    quote! {
+        #header
+        #version
+    }
+}
+
+fn decode_fn(
+    ast: &DeriveInput,
+    attributes: &[Attribute],
+    message: &MessageAttributes,
+    mode: &Mode,
+) -> TokenStream {
+    let decode_header = decode_header(&ast.ident, message, mode);
+
     match &ast.data {
-        Data::Struct(data) => decode_struct(data),
-        Data::Enum(data) => decode_enum(&ast.ident, data, attributes),
+        Data::Struct(data) => decode_struct(data, &decode_header, mode),
+        Data::Enum(data) => decode_enum(&ast.ident, data, attributes, &decode_header, mode),
         Data::Union(data) => decode_union(data),
     }
 }
@@ -182,7 +309,12 @@ fn impl_codec_derive(ast: &DeriveInput) -> TokenStream {
     let (template_arguments_with_bounds, template_arguments_without_bounds, template_where_clause) =
         &ast.generics.split_for_impl();
 
-    let decode_fn = decode_fn(ast, &ast.attrs);
+    let message = match MessageAttributes::parse(&ast.attrs) {
+        Ok(message) => message,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    let decode_fn = decode_fn(ast, &ast.attrs, &message, &Mode::Owned);
 
     quote! {
         impl #template_arguments_with_bounds bifrost::serialization::Decode
@@ -191,3 +323,32 @@ fn impl_codec_derive(ast: &DeriveInput) -> TokenStream {
         }
     }
 }
+
+fn impl_borrowed_derive(ast: &DeriveInput) -> TokenStream {
+    let type_name = &ast.ident;
+
+    let Some(lifetime) = ast.generics.lifetimes().next().map(|param| &param.lifetime) else {
+        return Error::new(
+            type_name.span(),
+            "`DecodeBorrowed` requires a lifetime parameter, derive `Decode` for owned types",
+        )
+        .to_compile_error();
+    };
+
+    let (template_arguments_with_bounds, template_arguments_without_bounds, template_where_clause) =
+        &ast.generics.split_for_impl();
+
+    let message = match MessageAttributes::parse(&ast.attrs) {
+        Ok(message) => message,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    let decode_fn = decode_fn(ast, &ast.attrs, &message, &Mode::Borrowed(lifetime));
+
+    quote! {
+        impl #template_arguments_with_bounds bifrost::serialization::DecodeBorrowed<#lifetime>
+        for #type_name #template_arguments_without_bounds #template_where_clause {
+            #decode_fn
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::serialization::tools::{
-    calculate_discriminants, calculate_variant_discriminant_byte_size,
+    MessageAttributes, calculate_discriminants, calculate_variant_discriminant_byte_size,
 };
 
 use syn::{
@@ -48,7 +48,7 @@ fn encode_unit_struct() -> TokenStream {
     quote! {}
 }
 
-fn encode_struct(data: &DataStruct) -> TokenStream {
+fn encode_struct(data: &DataStruct, encode_header: &TokenStream) -> TokenStream {
     let encode_fn_body = match &data.fields {
         Fields::Named(fields) => encode_named_struct(fields),
         Fields::Unnamed(fields) => encode_unnamed_struct(fields),
@@ -57,6 +57,7 @@ fn encode_struct(data: &DataStruct) -> TokenStream {
 
     quote! {
         fn encode(&self, mut write: impl std::io::Write) -> bifrost::Result<()> {
+            #encode_header
             #encode_fn_body
             Ok(())
         }
@@ -70,13 +71,13 @@ fn encode_variant_discriminant(data: &DataEnum, attributes: &[Attribute]) -> Tok
 
             match &variant.fields {
                 Fields::Named(..) => quote! {
-                    Self::#ident{..} => (#discriminant) as usize
+                    Self::#ident{..} => (#discriminant) as u64
                 },
                 Fields::Unnamed(..) => quote! {
-                    Self::#ident(..) => (#discriminant) as usize
+                    Self::#ident(..) => (#discriminant) as u64
                 },
                 Fields::Unit => quote! {
-                    Self::#ident => (#discriminant) as usize
+                    Self::#ident => (#discriminant) as u64
                 },
             }
         });
@@ -86,7 +87,7 @@ fn encode_variant_discriminant(data: &DataEnum, attributes: &[Attribute]) -> Tok
         calculate_variant_discriminant_byte_size(num_variants, &mut attributes.iter());
 
     quote! {
-        let variant_discriminant: usize = match self {
+        let variant_discriminant: u64 = match self {
             #(#encode_variant_discriminant_match_arms),*
         };
         write.write_all(&variant_discriminant.to_le_bytes()[0..#variant_discriminant_byte_size])?;
@@ -140,12 +141,17 @@ fn encode_variant(data: &DataEnum) -> TokenStream {
     }
 }
 
-fn encode_enum(data: &DataEnum, attributes: &[Attribute]) -> TokenStream {
+fn encode_enum(
+    data: &DataEnum,
+    attributes: &[Attribute],
+    encode_header: &TokenStream,
+) -> TokenStream {
     let encode_variant_discriminant = encode_variant_discriminant(data, attributes);
     let encode_enum_write = encode_variant(data);
 
     quote! {
         fn encode(&self, mut write: impl std::io::Write) -> bifrost::Result<()> {
+            #encode_header
             #encode_variant_discriminant
             #encode_enum_write
             Ok(())
@@ -157,10 +163,39 @@ fn encode_union(data: &DataUnion) -> TokenStream {
     unions_unsupported_error(data.union_token)
 }
 
-fn encode_fn(ast: &DeriveInput, attributes: &[Attribute]) -> TokenStream {
+fn encode_header(message: &MessageAttributes) -> TokenStream {
+    let header = match &message.header {
+        Some(header) => quote! {
+            const HEADER: &[u8] = (#header).as_slice();
+            write.write_all(HEADER)?;
+        },
+        None => quote! {},
+    };
+
+    let version = match &message.version {
+        Some(version) => quote! {
+            const VERSION: u32 = #version;
+            write.write_all(&VERSION.to_le_bytes())?;
+        },
+        None => quote! {},
+    };
+
+    quote! {
+        #header
+        #version
+    }
+}
+
+fn encode_fn(
+    ast: &DeriveInput,
+    attributes: &[Attribute],
+    message: &MessageAttributes,
+) -> TokenStream {
+    let encode_header = encode_header(message);
+
     match &ast.data {
-        Data::Struct(data) => encode_struct(data),
-        Data::Enum(data) => encode_enum(data, attributes),
+        Data::Struct(data) => encode_struct(data, &encode_header),
+        Data::Enum(data) => encode_enum(data, attributes, &encode_header),
         Data::Union(data) => encode_union(data),
     }
 }
@@ -193,7 +228,7 @@ fn encode_len_unit_struct() -> TokenStream {
     }
 }
 
-fn encode_len_struct(data: &DataStruct) -> TokenStream {
+fn encode_len_struct(data: &DataStruct, header_len: &TokenStream) -> TokenStream {
     let encode_len_fn_body = match &data.fields {
         Fields::Named(fields) => encode_len_named_struct(fields),
         Fields::Unnamed(fields) => encode_len_unnamed_struct(fields),
@@ -202,7 +237,7 @@ fn encode_len_struct(data: &DataStruct) -> TokenStream {
 
     quote! {
         fn encode_len(&self) -> usize {
-            #encode_len_fn_body
+            #header_len #encode_len_fn_body
         }
     }
 }
@@ -244,7 +279,11 @@ fn encode_len_variant(variant: &Variant) -> TokenStream {
     }
 }
 
-fn encode_len_enum(data: &DataEnum, attributes: &[Attribute]) -> TokenStream {
+fn encode_len_enum(
+    data: &DataEnum,
+    attributes: &[Attribute],
+    header_len: &TokenStream,
+) -> TokenStream {
     let variant_match_arms = data.variants.iter().map(encode_len_variant);
     let num_variants = data.variants.iter().len();
     let variant_discriminant_byte_size =
@@ -252,7 +291,7 @@ fn encode_len_enum(data: &DataEnum, attributes: &[Attribute]) -> TokenStream {
 
     quote! {
         fn encode_len(&self) -> usize {
-            #variant_discriminant_byte_size +
+            #header_len #variant_discriminant_byte_size +
             match self {
                 #(#variant_match_arms),*
             }
@@ -264,10 +303,23 @@ fn encode_len_union(data: &DataUnion) -> TokenStream {
     unions_unsupported_error(data.union_token)
 }
 
-fn encode_len_fn(ast: &DeriveInput, attributes: &[Attribute]) -> TokenStream {
+fn encode_len_fn(
+    ast: &DeriveInput,
+    attributes: &[Attribute],
+    message: &MessageAttributes,
+) -> TokenStream {
+    let header_len = match &message.header {
+        Some(header) => quote! { (#header).len() + },
+        None => quote! {},
+    };
+    let header_len = match &message.version {
+        Some(_) => quote! { #header_len std::mem::size_of::<u32>() + },
+        None => header_len,
+    };
+
     match &ast.data {
-        Data::Struct(data) => encode_len_struct(data),
-        Data::Enum(data) => encode_len_enum(data, attributes),
+        Data::Struct(data) => encode_len_struct(data, &header_len),
+        Data::Enum(data) => encode_len_enum(data, attributes, &header_len),
         Data::Union(data) => encode_len_union(data),
     }
 }
@@ -278,8 +330,13 @@ fn impl_codec_derive(ast: &DeriveInput) -> TokenStream {
     let (template_arguments_with_bounds, template_arguments_without_bounds, template_where_clause) =
         &ast.generics.split_for_impl();
 
-    let encode_fn = encode_fn(ast, &ast.attrs);
-    let encode_len_fn = encode_len_fn(ast, &ast.attrs);
+    let message = match MessageAttributes::parse(&ast.attrs) {
+        Ok(message) => message,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    let encode_fn = encode_fn(ast, &ast.attrs, &message);
+    let encode_len_fn = encode_len_fn(ast, &ast.attrs, &message);
 
     quote! {
         impl #template_arguments_with_bounds bifrost::serialization::Encode
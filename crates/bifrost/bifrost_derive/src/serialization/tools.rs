@@ -2,6 +2,9 @@ use quote::quote;
 
 use syn::{Attribute, Expr, Meta, Variant};
 
+/// Name of the attribute that configures the derived encoding.
+const ATTRIBUTE: &str = "bifrost";
+
 use proc_macro2::TokenStream;
 
 /// Calculate the number of bytes required to hold the integer value of `value`.
@@ -81,3 +84,42 @@ pub fn calculate_discriminants<'a>(
         (discriminant, variant)
     })
 }
+
+/// The attributes of a message, parsed from `#[bifrost(header = ..., version = ...)]`.
+#[derive(Default)]
+pub struct MessageAttributes {
+    /// The header, which can be any constant expression of a byte array or byte string, such as
+    /// `b"RGme"` or a constant. When present, the header is written before the encoded data and
+    /// checked when decoding, so decoding a message of the wrong type fails immediately.
+    pub header: Option<Expr>,
+    /// The schema version, which can be any constant expression of a `u32`. When present, the
+    /// version is written after the header and checked when decoding, so decoding a message that
+    /// was encoded with another layout of the message fails immediately.
+    pub version: Option<Expr>,
+}
+
+impl MessageAttributes {
+    /// Parses the message attributes from the attributes of a type.
+    pub fn parse(attributes: &[Attribute]) -> syn::Result<Self> {
+        let mut message = Self::default();
+
+        for attribute in attributes
+            .iter()
+            .filter(|attribute| attribute.path().is_ident(ATTRIBUTE))
+        {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("header") {
+                    message.header = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("version") {
+                    message.version = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported bifrost attribute, expected `header` or `version`"))
+                }
+            })?;
+        }
+
+        Ok(message)
+    }
+}
@@ -2,9 +2,13 @@
 
 pub mod inbound;
 pub mod outbound;
+pub mod staged;
+pub mod timestamp;
 
 pub use inbound::Inbound;
 pub use outbound::{Outbound, Rate};
+pub use staged::{Coalesce, Staged};
+pub use timestamp::{Stamped, Timestamp};
 
 use std::time::{Duration, Instant};
 
@@ -66,6 +70,7 @@ impl Deadline {
 mod tests {
     use std::io::{Read, Write};
     use std::ops::Add;
+    use std::time::UNIX_EPOCH;
 
     use super::*;
     use crate::{
@@ -103,6 +108,12 @@ mod tests {
         const DEAD_SPACE: usize = 2;
     }
 
+    impl Coalesce for Dummy {
+        fn coalesce(&mut self, newer: Self) {
+            *self = newer;
+        }
+    }
+
     /// Helper type for quickly constructing timestamps.
     #[derive(Clone, Copy)]
     struct Epoch(Instant);
@@ -169,6 +180,64 @@ mod tests {
         assert_eq!(buffer.try_pack_at(t + 7), Some(vec![6, 6, 6, 6, 6, 6]));
     }
 
+    #[test]
+    fn test_staged() {
+        let rate = Rate {
+            late_threshold: Duration::ZERO,
+            automatic_deadline: Duration::ZERO,
+            early_threshold: Duration::from_secs(10),
+        };
+
+        let mut staged = Staged::new(Duration::from_secs(2));
+        let mut buffer = Outbound::new(rate);
+        let t = Epoch(Instant::now());
+
+        for n in 1..=4 {
+            staged.stage(Dummy(n));
+        }
+
+        let message = staged.try_take_at(t + 0).unwrap();
+        assert_eq!(message, Dummy(4));
+        buffer.push_at(message, Deadline::ASAP, t + 0).unwrap();
+        assert_eq!(buffer.try_pack_at(t + 0), Some(vec![4, 4, 4, 4]));
+        assert_eq!(buffer.try_pack_at(t + 0), None);
+
+        // updates are held back until the interval has passed
+        staged.stage(Dummy(2));
+        staged.stage(Dummy(3));
+        assert_eq!(staged.try_take_at(t + 1), None);
+        assert_eq!(staged.try_take_at(t + 2), Some(Dummy(3)));
+        assert_eq!(staged.try_take_at(t + 5), None);
+    }
+
+    #[test]
+    fn test_stamped() {
+        let stamped = Stamped {
+            sent: Timestamp::from(UNIX_EPOCH + Duration::from_millis(1234)),
+            message: Dummy(3),
+        };
+
+        let mut packet = Vec::new();
+        stamped.encode(&mut packet).unwrap();
+        assert_eq!(packet.len(), stamped.encode_len());
+
+        let mut buffer = Inbound::<(), Stamped<Dummy>>::new();
+        buffer.unpack(&packet, ()).unwrap();
+
+        let (_, (), received) = buffer.pop().unwrap();
+        assert_eq!(received, stamped);
+    }
+
+    #[test]
+    fn test_timestamp_elapsed() {
+        let t = Timestamp::from(UNIX_EPOCH + Duration::from_secs(10));
+        let later = Timestamp::from(UNIX_EPOCH + Duration::from_millis(10_250));
+
+        assert_eq!(t.elapsed_at(later), Duration::from_millis(250));
+        // skewed clocks result in a clamped age
+        assert_eq!(later.elapsed_at(t), Duration::ZERO);
+    }
+
     #[test]
     fn test_outbound_early() {
         let rate = Rate {
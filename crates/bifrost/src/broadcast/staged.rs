@@ -0,0 +1,85 @@
+//! Staging area for coalescing message updates before they are broadcasted
+
+use std::time::{Duration, Instant};
+
+/// Trait for messages that can combine multiple updates into a single message.
+///
+/// The combined message does not have to be a [`Message`](super::Message) itself, it may also be
+/// a collection of messages that are pushed into the buffer together once it is released.
+pub trait Coalesce {
+    /// Merges a newer update into `self`, where the fields of `newer` take precedence.
+    fn coalesce(&mut self, newer: Self);
+}
+
+/// A staged message that collects updates from multiple systems and releases them as a single
+/// combined message at most once per interval.
+///
+/// Instead of pushing into an [`Outbound`](super::Outbound) buffer directly, systems stage their
+/// updates here, and the combined message is moved into the buffer once it is released. This
+/// prevents exceeding the message budget when multiple systems want to broadcast in the same
+/// cycle.
+pub struct Staged<M: Coalesce> {
+    /// The combined update waiting to be released.
+    pending: Option<M>,
+    /// Last time a message has been released.
+    last: Option<Instant>,
+    /// The minimum interval between two released messages.
+    interval: Duration,
+}
+
+impl<M: Coalesce> Staged<M> {
+    /// Creates an empty staging area that releases at most one message per `interval`.
+    #[must_use]
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            pending: None,
+            last: None,
+            interval,
+        }
+    }
+
+    /// The minimum interval between two released messages.
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Sets the minimum interval between two released messages.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Returns the combined update that has not been released yet, if any.
+    #[must_use]
+    pub fn pending(&self) -> Option<&M> {
+        self.pending.as_ref()
+    }
+
+    /// Stages an update, merging it into the pending message if there is one.
+    pub fn stage(&mut self, message: M) {
+        match &mut self.pending {
+            Some(pending) => pending.coalesce(message),
+            None => self.pending = Some(message),
+        }
+    }
+
+    /// Releases the combined message if the interval has passed, at the current time.
+    pub fn try_take(&mut self) -> Option<M> {
+        self.try_take_at(Instant::now())
+    }
+
+    /// Releases the combined message if the interval has passed, at the given time.
+    pub fn try_take_at(&mut self, when: Instant) -> Option<M> {
+        if self
+            .last
+            .is_some_and(|last| when.duration_since(last) < self.interval)
+        {
+            return None;
+        }
+
+        let message = self.pending.take()?;
+        self.last = Some(when);
+
+        Some(message)
+    }
+}
@@ -0,0 +1,119 @@
+//! Send timestamps for broadcast messages
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::{Coalesce, Message};
+use crate::serialization::{Decode, Encode};
+
+/// Wall clock time in milliseconds since the UNIX epoch.
+///
+/// Unlike [`Instant`], this can be compared between robots, provided their clocks are
+/// synchronized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// The current wall clock time.
+    #[must_use]
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+
+    /// Milliseconds since the UNIX epoch.
+    #[must_use]
+    pub fn as_millis(self) -> u64 {
+        self.0
+    }
+
+    /// Time elapsed between `self` and `now`.
+    ///
+    /// If `now` is earlier than `self`, which can happen when the clocks of the sender and
+    /// receiver are skewed, the elapsed time is clamped to zero.
+    #[must_use]
+    pub fn elapsed_at(self, now: Self) -> Duration {
+        if now < self {
+            tracing::warn!(
+                skew_ms = self.0 - now.0,
+                "timestamp is in the future, clocks are likely skewed"
+            );
+        }
+
+        Duration::from_millis(now.0.saturating_sub(self.0))
+    }
+
+    /// Time elapsed since `self`, see [`Timestamp::elapsed_at`].
+    #[must_use]
+    pub fn elapsed(self) -> Duration {
+        self.elapsed_at(Self::now())
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        // times before the epoch are clamped to the epoch
+        let millis = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        Self(u64::try_from(millis).unwrap_or(u64::MAX))
+    }
+}
+
+/// A message together with the time it was created by the sender.
+///
+/// The timestamp is taken when the message is created rather than when the packet containing it
+/// is sent out, so the age of a received message includes the time it spent waiting in the
+/// sender's [`Outbound`](super::Outbound) buffer.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct Stamped<M: Message> {
+    /// When the message was created by the sender.
+    pub sent: Timestamp,
+    /// The message itself.
+    pub message: M,
+}
+
+impl<M: Message> Stamped<M> {
+    /// Stamps a message with the current time.
+    #[must_use]
+    pub fn now(message: M) -> Self {
+        Self {
+            sent: Timestamp::now(),
+            message,
+        }
+    }
+
+    /// How old the message is, assuming synchronized clocks.
+    ///
+    /// This can be used to down-weight stale information received from teammates.
+    #[must_use]
+    pub fn age(&self) -> Duration {
+        self.sent.elapsed()
+    }
+
+    /// One-way latency between creating the message and receiving it at `received`,
+    /// assuming synchronized clocks.
+    ///
+    /// `received` is typically the [`Instant`] reported by the [`Inbound`](super::Inbound) buffer.
+    #[must_use]
+    pub fn latency(&self, received: Instant) -> Duration {
+        self.age().saturating_sub(received.elapsed())
+    }
+}
+
+impl<M: Message> Message for Stamped<M> {
+    const MAX_PACKET_SIZE: usize = M::MAX_PACKET_SIZE;
+    const EXPECTED_SIZE: usize = M::EXPECTED_SIZE + size_of::<Timestamp>();
+    const DEAD_SPACE: usize = M::DEAD_SPACE;
+
+    fn try_merge(&mut self, old: &Self) -> bool {
+        self.message.try_merge(&old.message)
+    }
+}
+
+impl<M: Message + Coalesce> Coalesce for Stamped<M> {
+    fn coalesce(&mut self, newer: Self) {
+        self.sent = newer.sent;
+        self.message.coalesce(newer.message);
+    }
+}
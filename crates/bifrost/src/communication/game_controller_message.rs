@@ -261,11 +261,11 @@ impl TeamInfo {
 }
 
 /// A struct representing the `RoboCupGameControlData` received by the Robots.
+///
+/// The message is encoded with the [`GAME_CONTROLLER_STRUCT_HEADER`] to identify the structure.
 #[derive(Resource, Encode, Decode, Debug, Clone, Copy, PartialEq)]
+#[bifrost(header = GAME_CONTROLLER_STRUCT_HEADER)]
 pub struct GameControllerMessage {
-    /// Header to identify the structure
-    pub header: [u8; 4],
-
     /// Version of the game-controller protocol
     pub version: u8,
 
@@ -325,7 +325,6 @@ impl GameControllerMessage {
 impl Default for GameControllerMessage {
     fn default() -> Self {
         Self {
-            header: GAME_CONTROLLER_STRUCT_HEADER,
             version: GAME_CONTROLLER_STRUCT_VERSION,
             packet_number: 0,
             players_per_team: 0,
@@ -344,11 +343,11 @@ impl Default for GameControllerMessage {
 }
 
 /// A struct representing the `RoboCupGameControlReturnMessage` send by the Robots.
+///
+/// The message is encoded with the "`RGrt`" header to identify the structure.
 #[derive(Encode, Decode, Debug, PartialEq)]
+#[bifrost(header = GAME_CONTROLLER_RETURN_STRUCT_HEADER)]
 pub struct GameControllerReturnMessage {
-    /// "`RGrt`"
-    pub header: [u8; 4],
-
     /// Has to be set to `GAME_CONTROLLER_RETURN_STRUCT_VERSION`
     pub version: u8,
 
@@ -383,13 +382,13 @@ pub struct GameControllerReturnMessage {
 }
 
 impl GameControllerMessage {
-    /// Check if the [`GameControllerMessage`] has a valid header and version and
-    /// the number of players does not exceed the maximum number of players per team.
+    /// Check if the [`GameControllerMessage`] has a valid version and the number of players
+    /// does not exceed the maximum number of players per team.
+    ///
+    /// The header is already checked when decoding the message.
     #[must_use]
     pub fn is_valid(&self) -> bool {
-        self.header == GAME_CONTROLLER_STRUCT_HEADER
-            && self.version == GAME_CONTROLLER_STRUCT_VERSION
-            && self.players_per_team <= MAX_NUM_PLAYERS
+        self.version == GAME_CONTROLLER_STRUCT_VERSION && self.players_per_team <= MAX_NUM_PLAYERS
     }
 }
 
@@ -406,7 +405,6 @@ impl GameControllerReturnMessage {
         ball: [f32; 2],
     ) -> Self {
         Self {
-            header: GAME_CONTROLLER_RETURN_STRUCT_HEADER,
             version: GAME_CONTROLLER_RETURN_STRUCT_VERSION,
             player_num,
             team_num,
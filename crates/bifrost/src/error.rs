@@ -20,8 +20,38 @@ pub enum Error {
     #[error(transparent)]
     InvalidStringError(#[from] std::string::FromUtf8Error),
 
+    /// Invalid borrowed string, this can occur while decoding a `&str`
+    #[error(transparent)]
+    InvalidStrError(#[from] std::str::Utf8Error),
+
     /// Invalid Variant Id, this occurs while decoding an Enum
     /// that is encoded with a variant discriminant that's not known.
     #[error("Got an invalid variant discriminant ({0}) in enum: {1}")]
     InvalidVariantDiscriminant(usize, &'static str),
+
+    /// Wrong message type, this occurs while decoding a message with a header
+    /// (see [`Encode`](crate::serialization::Encode)) that does not match the expected header.
+    #[error("Expected a `{expected}` message, but got a message with header {header:?}")]
+    WrongMessageType {
+        /// Name of the message type that was being decoded.
+        expected: &'static str,
+        /// The header that was read instead.
+        header: Vec<u8>,
+    },
+
+    /// Schema mismatch, this occurs while decoding a message with a version
+    /// (see [`Encode`](crate::serialization::Encode)) that was encoded with another version of
+    /// the message layout, e.g. by a tool that was built from an older version of the messages.
+    #[error(
+        "Expected version {expected} of the `{message}` message, but got version {found}, \
+        make sure both sides are built from the same version of the messages"
+    )]
+    SchemaMismatch {
+        /// Name of the message type that was being decoded.
+        message: &'static str,
+        /// The version of the message layout of this build.
+        expected: u32,
+        /// The version of the message layout that the message was encoded with.
+        found: u32,
+    },
 }
@@ -0,0 +1,85 @@
+//! Decoding into types that borrow from the encoded bytes, see [`DecodeBorrowed`].
+
+use std::io;
+
+use super::{Decode, VarInt};
+use crate::Result;
+
+/// The `DecodeBorrowed` trait allows objects to be decoded from a byte slice without copying
+/// the data they can borrow from it.
+///
+/// Decoding a `Vec<u8>` or [`String`] with [`Decode`] allocates a new buffer for every field,
+/// which adds up for large messages that are decoded at a high rate. Types that only need to
+/// look at the data for as long as the buffer lives can instead use `&'a [u8]` and `&'a str`
+/// fields, which point into the buffer they were decoded from.
+///
+/// Every type that implements [`Decode`] also implements `DecodeBorrowed`, by decoding an owned
+/// value from the slice. The encoding of borrowed fields is the same as that of their owned
+/// counterparts, so a message can be encoded from owned types and decoded into borrowed ones.
+///
+/// # Deriving
+///
+/// This trait can be implemented automatically for structs and enums with a lifetime parameter
+/// by using the [`DecodeBorrowed`][macro] derive macro. The first lifetime parameter is used as
+/// the lifetime of the borrowed data, and all fields must implement `DecodeBorrowed` for it.
+///
+/// ```
+/// use bifrost::serialization::{DecodeBorrowed, Encode};
+///
+/// #[derive(Encode)]
+/// struct Image {
+///     width: u32,
+///     pixels: Vec<u8>,
+/// }
+///
+/// #[derive(DecodeBorrowed)]
+/// struct ImageView<'a> {
+///     width: u32,
+///     pixels: &'a [u8],
+/// }
+///
+/// let mut buf = vec![];
+/// Image { width: 2, pixels: vec![1, 2, 3, 4] }.encode(&mut buf).unwrap();
+///
+/// let view = ImageView::decode_borrowed(&mut buf.as_slice()).unwrap();
+/// assert_eq!(view.pixels, [1, 2, 3, 4]);
+/// ```
+///
+/// [macro]: bifrost_derive::DecodeBorrowed
+pub trait DecodeBorrowed<'a>: Sized {
+    /// Decodes a value from the start of `read`, and advances `read` past the decoded bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the decoding fails.
+    fn decode_borrowed(read: &mut &'a [u8]) -> Result<Self>;
+}
+
+impl<'a, T: Decode> DecodeBorrowed<'a> for T {
+    fn decode_borrowed(read: &mut &'a [u8]) -> Result<Self> {
+        T::decode(read)
+    }
+}
+
+impl<'a> DecodeBorrowed<'a> for &'a [u8] {
+    fn decode_borrowed(read: &mut &'a [u8]) -> Result<Self> {
+        let length = VarInt::decode(&mut *read)?.into();
+
+        if read.len() < length {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+
+        let (bytes, rest) = read.split_at(length);
+        *read = rest;
+
+        Ok(bytes)
+    }
+}
+
+impl<'a> DecodeBorrowed<'a> for &'a str {
+    fn decode_borrowed(read: &mut &'a [u8]) -> Result<Self> {
+        let bytes = <&[u8]>::decode_borrowed(read)?;
+
+        Ok(std::str::from_utf8(bytes)?)
+    }
+}
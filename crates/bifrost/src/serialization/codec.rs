@@ -1,6 +1,9 @@
 //! Implementing Encoding and Decoding for primitive types
 //! (u8, u16, u32, u64, i8, i16, i32, i64, f32, f64),
 //! arrays, strings, vectors, hashmaps and the `SPLStandardMessage` struct.
+//!
+//! All multi-byte primitives are encoded in little-endian byte order, see the
+//! [module documentation](super) for the full wire format.
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::{
@@ -21,7 +24,7 @@ use crate::{Error, Result};
 /// implement `Encode`. Components are encoded in the order they appear in the
 /// type definition.
 ///
-/// The derive macro encodes the variant with a leading [`usize`] to specify which
+/// The derive macro encodes the variant with a leading little-endian discriminant to specify which
 /// variant for encodig/decoding.
 ///
 /// ```no_run
@@ -81,7 +84,7 @@ pub trait Encode {
 /// implement `Decode`. Components are encoded in the order they appear in the
 /// type definition.
 ///
-/// The derive macro decodes enums by first reading a [`usize`] to determine the variant.
+/// The derive macro decodes enums by first reading a little-endian discriminant to determine the variant.
 ///
 ///
 /// ```
@@ -734,6 +737,46 @@ mod tests {
         Ok(())
     }
 
+    /// Decodes a value of the same type as `_value`.
+    fn decode_like<T: Decode>(_value: &T, read: &[u8]) -> Result<T> {
+        T::decode(read)
+    }
+
+    /// Checks that the value is encoded in little-endian byte order, and that a buffer that was
+    /// byte-swapped from big-endian decodes to the same value, independent of the host.
+    macro_rules! assert_little_endian {
+        ($($value:expr),* $(,)?) => {
+            $(
+                let value = $value;
+
+                let mut encoded = Vec::new();
+                value.encode(&mut encoded)?;
+                assert_eq!(encoded, value.to_le_bytes(), "{value} is not little-endian");
+
+                let mut swapped = value.to_be_bytes();
+                swapped.reverse();
+                let decoded = decode_like(&value, swapped.as_slice())?;
+                assert_eq!(decoded.to_ne_bytes(), value.to_ne_bytes());
+            )*
+        };
+    }
+
+    #[test]
+    fn test_little_endian() -> Result<()> {
+        assert_little_endian!(
+            0x1234_u16,
+            0x1234_5678_u32,
+            0x1234_5678_9abc_def0_u64,
+            -0x1234_i16,
+            -0x1234_5678_i32,
+            -0x1234_5678_9abc_def0_i64,
+            std::f32::consts::PI,
+            std::f64::consts::E,
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_complex() -> Result<()> {
         // Arrays
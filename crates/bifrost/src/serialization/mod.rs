@@ -1,8 +1,20 @@
 //! Message protocol implementation based on serialization and deserialization.
+//!
+//! # Wire format
+//!
+//! The wire format is the same on every architecture, so robots and development machines can
+//! exchange messages regardless of their native byte order or pointer width:
+//!
+//! - Multi-byte integers and floats are encoded in little-endian byte order.
+//! - [`usize`] and [`isize`], as well as lengths of collections, are encoded as a [`VarInt`].
+//! - Enum variant discriminants are encoded in little-endian byte order, using the smallest
+//!   number of bytes that fits all variants, or the size of the `repr` type if specified.
+mod borrowed;
 mod codec;
 #[cfg(feature = "nalgebra")]
 mod nalgebra;
 
+pub use borrowed::DecodeBorrowed;
 pub use codec::{Decode, Encode, VarInt};
 
 /// Derive macro to implement the [Decode] trait for structs and enums.
@@ -39,8 +51,85 @@ pub use codec::{Decode, Encode, VarInt};
 ///     Bar3{ x: f32, y: f32 },
 /// }
 /// ```
+/// ## Message headers
+///
+/// Types that are sent as messages can be given a header with `#[bifrost(header = ...)]`, which
+/// takes a byte string or a constant byte array. The header is checked before decoding any
+/// fields, and [`Error::WrongMessageType`](crate::Error::WrongMessageType) is returned when it
+/// does not match, so a message of another type fails to decode immediately.
+///
+/// ```
+/// use bifrost::{Error, serialization::{Decode, Encode}};
+///
+/// #[derive(Encode, Decode)]
+/// #[bifrost(header = b"PING")]
+/// struct Ping(u8);
+///
+/// #[derive(Encode, Decode)]
+/// #[bifrost(header = b"PONG")]
+/// struct Pong(u8);
+///
+/// let mut buf = vec![];
+/// Ping(1).encode(&mut buf).unwrap();
+///
+/// assert!(matches!(
+///     Pong::decode(buf.as_slice()),
+///     Err(Error::WrongMessageType { .. })
+/// ));
+/// ```
+///
+/// ## Schema versions
+///
+/// Messages that are exchanged between separately built programs, such as the robot and the
+/// tooling, can be given a schema version with `#[bifrost(version = ...)]`, which takes a constant
+/// `u32`. The version is encoded after the header, and
+/// [`Error::SchemaMismatch`](crate::Error::SchemaMismatch) is returned when it does not match, so
+/// a message that was encoded with another layout fails to decode instead of decoding garbage.
+/// The version has to be bumped whenever the layout of the message changes.
+///
+/// ```
+/// use bifrost::{Error, serialization::{Decode, Encode}};
+///
+/// #[derive(Encode, Decode)]
+/// #[bifrost(header = b"PING", version = 1)]
+/// struct OldPing(u8);
+///
+/// #[derive(Encode, Decode)]
+/// #[bifrost(header = b"PING", version = 2)]
+/// struct Ping(u16);
+///
+/// let mut buf = vec![];
+/// OldPing(1).encode(&mut buf).unwrap();
+///
+/// assert!(matches!(
+///     Ping::decode(buf.as_slice()),
+///     Err(Error::SchemaMismatch { expected: 2, found: 1, .. })
+/// ));
+/// ```
 pub use bifrost_derive::Decode;
 
+/// Derive macro to implement the [`DecodeBorrowed`] trait for structs and enums with a lifetime
+/// parameter.
+///
+/// The fields are decoded in the same way as with the [Decode] derive macro, including enum
+/// discriminants and `#[bifrost(header = ...)]` headers, but using [`DecodeBorrowed`] for each
+/// field. The first lifetime parameter of the type is used as the lifetime of the borrowed data.
+///
+/// Types without borrowed fields should derive [Decode] instead, which implements
+/// [`DecodeBorrowed`] as well.
+///
+/// ## Examples
+/// ```
+/// use bifrost::serialization::DecodeBorrowed;
+///
+/// #[derive(DecodeBorrowed)]
+/// enum Log<'a> {
+///     Text(&'a str),
+///     Binary { id: u32, data: &'a [u8] },
+/// }
+/// ```
+pub use bifrost_derive::DecodeBorrowed;
+
 /// Derive macro to implement the [Encode] trait for structs and enums.
 ///
 /// All the fields will be encoded individually in the same order they are defined in the struct or enum.
@@ -75,4 +164,9 @@ pub use bifrost_derive::Decode;
 ///     Bar3{ x: f32, y: f32 },
 /// }
 /// ```
+///
+/// ## Message headers
+///
+/// With `#[bifrost(header = ...)]` the header is encoded before the fields, and with
+/// `#[bifrost(version = ...)]` the schema version is encoded after the header, see [Decode].
 pub use bifrost_derive::Encode;
@@ -1,6 +1,6 @@
 use bifrost::{
-    Result,
-    serialization::{Decode, Encode},
+    Error, Result,
+    serialization::{Decode, DecodeBorrowed, Encode},
 };
 use std::fmt::Debug;
 
@@ -176,3 +176,192 @@ fn test_enum_custom_discriminant_encode_decode() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_encode_little_endian() -> Result<()> {
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    pub struct TestStruct {
+        pub short: u16,
+        pub int: i32,
+        pub float: f32,
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    #[repr(u16)]
+    pub enum TestEnum {
+        First = 0x0102,
+        Second(u32) = 0x0304,
+    }
+
+    let test_struct = TestStruct {
+        short: 0x0102,
+        int: -2,
+        float: 1.0,
+    };
+
+    let mut encoded = Vec::new();
+    test_struct.encode(&mut encoded)?;
+    assert_eq!(
+        encoded,
+        [0x02, 0x01, 0xfe, 0xff, 0xff, 0xff, 0x00, 0x00, 0x80, 0x3f]
+    );
+
+    let mut encoded = Vec::new();
+    TestEnum::Second(0x0506_0708).encode(&mut encoded)?;
+    assert_eq!(encoded, [0x04, 0x03, 0x08, 0x07, 0x06, 0x05]);
+
+    // a buffer written by a big-endian host that swapped its bytes must decode to the same value
+    let big_endian = [0x01_u16.to_be_bytes(), 0x02_u16.to_be_bytes()].concat();
+    let swapped: Vec<u8> = big_endian
+        .chunks_exact(2)
+        .flat_map(|chunk| [chunk[1], chunk[0]])
+        .collect();
+    assert_eq!(swapped, [0x01, 0x00, 0x02, 0x00]);
+    assert_eq!(<[u16; 2]>::decode(swapped.as_slice())?, [0x01, 0x02]);
+
+    test_encode_decode(&test_struct)?;
+    test_encode_decode(&TestEnum::First)?;
+    test_encode_decode(&TestEnum::Second(0x0506_0708))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_message_header() -> Result<()> {
+    const TEAM_HEADER: [u8; 2] = *b"TM";
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    #[bifrost(header = TEAM_HEADER)]
+    pub struct TeamMessage {
+        pub player: u8,
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    #[bifrost(header = b"GC")]
+    pub enum ControllerMessage {
+        Start,
+        Stop(u8),
+    }
+
+    let team_message = TeamMessage { player: 3 };
+    test_encode_decode(&team_message)?;
+    test_encode_decode(&ControllerMessage::Start)?;
+    test_encode_decode(&ControllerMessage::Stop(1))?;
+
+    let mut encoded = Vec::new();
+    team_message.encode(&mut encoded)?;
+    assert_eq!(encoded, [b'T', b'M', 3]);
+
+    // decoding a message of the wrong type fails on the header
+    let result = ControllerMessage::decode(encoded.as_slice());
+    assert!(matches!(
+        result,
+        Err(Error::WrongMessageType {
+            expected: "ControllerMessage",
+            ref header,
+        }) if header == b"TM"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_schema_version() -> Result<()> {
+    const PROTOCOL_VERSION: u32 = 3;
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    #[bifrost(header = b"TM", version = PROTOCOL_VERSION)]
+    pub struct TeamMessage {
+        pub player: u8,
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    #[bifrost(header = b"TM", version = PROTOCOL_VERSION + 1)]
+    pub struct NewTeamMessage {
+        pub player: u8,
+        pub penalized: bool,
+    }
+
+    let team_message = TeamMessage { player: 3 };
+    test_encode_decode(&team_message)?;
+    test_encode_decode(&NewTeamMessage {
+        player: 3,
+        penalized: false,
+    })?;
+
+    let mut encoded = Vec::new();
+    team_message.encode(&mut encoded)?;
+    assert_eq!(encoded, [b'T', b'M', 3, 0, 0, 0, 3]);
+
+    // decoding a message that was encoded with another layout fails on the version
+    let result = NewTeamMessage::decode(encoded.as_slice());
+    assert!(matches!(
+        result,
+        Err(Error::SchemaMismatch {
+            message: "NewTeamMessage",
+            expected: 4,
+            found: 3,
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_borrowed() -> Result<()> {
+    #[derive(Encode, Debug, PartialEq)]
+    #[bifrost(header = b"LOG")]
+    pub enum Log {
+        Text(String),
+        Frame { id: u32, data: Vec<u8> },
+    }
+
+    #[derive(DecodeBorrowed, Debug, PartialEq)]
+    #[bifrost(header = b"LOG")]
+    pub enum LogView<'a> {
+        Text(&'a str),
+        Frame { id: u32, data: &'a [u8] },
+    }
+
+    #[derive(DecodeBorrowed, Debug, PartialEq)]
+    pub struct Batch<'a> {
+        pub first: LogView<'a>,
+        pub second: LogView<'a>,
+        pub checksum: u16,
+    }
+
+    let mut encoded = Vec::new();
+    Log::Text("hello".to_string()).encode(&mut encoded)?;
+    Log::Frame {
+        id: 7,
+        data: vec![1, 2, 3],
+    }
+    .encode(&mut encoded)?;
+    42_u16.encode(&mut encoded)?;
+
+    let mut read = encoded.as_slice();
+    let batch = Batch::decode_borrowed(&mut read)?;
+    assert!(read.is_empty());
+    assert_eq!(
+        batch,
+        Batch {
+            first: LogView::Text("hello"),
+            second: LogView::Frame {
+                id: 7,
+                data: &[1, 2, 3],
+            },
+            checksum: 42,
+        }
+    );
+
+    // the borrowed data points into the encoded buffer
+    let LogView::Text(text) = batch.first else {
+        panic!("expected a text log");
+    };
+    assert!(encoded.as_ptr_range().contains(&text.as_ptr()));
+
+    // truncated data fails instead of borrowing past the end of the buffer
+    assert!(Batch::decode_borrowed(&mut &encoded[..encoded.len() - 4]).is_err());
+
+    Ok(())
+}
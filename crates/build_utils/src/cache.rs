@@ -0,0 +1,157 @@
+//! Caching of build artifacts, keyed on a hash of the sources they were built from.
+//!
+//! Even when nothing changed, `cargo build` has to check the whole dependency graph before it
+//! can decide that the binary is up to date. The [`BuildCache`] allows skipping cargo entirely
+//! when the sources, toolchain and build options are the same as those of a cached artifact.
+
+use std::{
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Directories that never contain sources of the build.
+const IGNORED_DIRECTORIES: &[&str] = &["target", "deploy"];
+
+/// Key that identifies the inputs of a build.
+///
+/// The key is only stable for a single build of the tool that computed it, as it uses the
+/// [`DefaultHasher`]. A different key results in a cache miss, so this only costs a rebuild.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildKey(u64);
+
+impl BuildKey {
+    /// Computes the key of a build from the files in `sources`, and any other `options` that
+    /// influence the artifact, such as the toolchain version, target and enabled features.
+    ///
+    /// Directories in `sources` are walked recursively, skipping hidden directories and
+    /// directories such as `target` that contain build output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the sources cannot be read.
+    pub fn new(sources: &[impl AsRef<Path>], options: &[&str]) -> io::Result<Self> {
+        let mut files = Vec::new();
+        for source in sources {
+            collect_files(source.as_ref(), &mut files)?;
+        }
+        files.sort();
+
+        let mut hasher = DefaultHasher::new();
+        options.hash(&mut hasher);
+        for file in files {
+            file.hash(&mut hasher);
+            fs::read(&file)?.hash(&mut hasher);
+        }
+
+        Ok(Self(hasher.finish()))
+    }
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        if path.exists() {
+            files.push(path.to_path_buf());
+        }
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        let ignored = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                path.is_dir() && (name.starts_with('.') || IGNORED_DIRECTORIES.contains(&name))
+            });
+
+        if !ignored {
+            collect_files(&path, files)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Cache of build artifacts, stored in the target directory.
+///
+/// Only the most recent artifact of each binary is kept.
+#[derive(Debug, Clone)]
+pub struct BuildCache {
+    dir: PathBuf,
+}
+
+impl BuildCache {
+    /// Creates a cache that stores its artifacts in `target_dir`.
+    #[must_use]
+    pub fn new(target_dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: target_dir.as_ref().join("build-cache"),
+        }
+    }
+
+    fn path(&self, binary: &str, key: BuildKey) -> PathBuf {
+        self.dir.join(binary).join(format!("{:016x}", key.0))
+    }
+
+    /// Returns the path of the cached artifact of `binary` that was built with `key`, if any.
+    #[must_use]
+    pub fn get(&self, binary: &str, key: BuildKey) -> Option<PathBuf> {
+        Some(self.path(binary, key)).filter(|path| path.is_file())
+    }
+
+    /// Stores `artifact` as the artifact of `binary` that was built with `key`, replacing any
+    /// previously cached artifact of `binary`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the artifact cannot be copied to the cache.
+    pub fn store(&self, binary: &str, key: BuildKey, artifact: impl AsRef<Path>) -> io::Result<()> {
+        let dir = self.dir.join(binary);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        fs::copy(artifact, self.path(binary, key))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_sources_are_a_cache_hit() {
+        let root = std::env::temp_dir().join(format!("build-cache-{}", std::process::id()));
+        let sources = root.join("src");
+        fs::create_dir_all(&sources).unwrap();
+        fs::write(sources.join("main.rs"), "fn main() {}").unwrap();
+        fs::write(root.join("yggdrasil"), "binary").unwrap();
+
+        let cache = BuildCache::new(root.join("target"));
+        let key = BuildKey::new(&[&sources], &["release"]).unwrap();
+        assert_eq!(cache.get("yggdrasil", key), None);
+
+        cache
+            .store("yggdrasil", key, root.join("yggdrasil"))
+            .unwrap();
+
+        // building again without changes
+        let key = BuildKey::new(&[&sources], &["release"]).unwrap();
+        let cached = cache.get("yggdrasil", key).expect("expected a cache hit");
+        assert_eq!(fs::read_to_string(cached).unwrap(), "binary");
+
+        // different build options, or a change in the sources
+        let key = BuildKey::new(&[&sources], &["release", "timings"]).unwrap();
+        assert_eq!(cache.get("yggdrasil", key), None);
+
+        fs::write(sources.join("main.rs"), "fn main() { todo!() }").unwrap();
+        let key = BuildKey::new(&[&sources], &["release"]).unwrap();
+        assert_eq!(cache.get("yggdrasil", key), None);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -128,6 +128,23 @@ pub async fn build(
     cargo(cargo_args, envs.unwrap_or_default()).await
 }
 
+/// Returns the verbose version of the `rustc` that cargo builds with.
+///
+/// This includes the commit hash and LLVM version, so it changes with every toolchain update.
+pub async fn rustc_version() -> Result<String, CargoError> {
+    let output = Command::new("rustc")
+        .arg("-vV")
+        .stdin(Stdio::null())
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(CargoError::Execution(String::from_utf8(output.stderr)?));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
 pub fn find_bin_manifest(bin: &str) -> Result<cargo_toml::Manifest, CargoError> {
     cargo_toml::Manifest::from_path("./Cargo.toml")
         .map_err(CargoError::Manifest)?
@@ -5,8 +5,12 @@
 use std::{fmt::Debug, marker::PhantomData};
 
 use nalgebra::{Cholesky, SMatrix, SVector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub mod motion_models;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Covariance matrix is not positive-definite")]
@@ -36,7 +40,14 @@ pub type SigmaPoints4 = SigmaPoints<4, 9>;
 /// Merwe scaled sigma points
 ///
 /// N should be `2 * D + 1` where D is the dimension of your state vector
+///
+/// Only the parameters are serialized, the weights are recalculated when deserializing.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "SigmaPointsParameters", into = "SigmaPointsParameters")
+)]
 pub struct SigmaPoints<const D_STATE: usize, const N_SIGMAS: usize> {
     pub alpha: f32,
     pub beta: f32,
@@ -113,10 +124,49 @@ impl<const D_STATE: usize, const N_SIGMAS: usize> SigmaPoints<D_STATE, N_SIGMAS>
     }
 }
 
+/// The parameters of [`SigmaPoints`], from which the weights can be recalculated
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SigmaPointsParameters {
+    alpha: f32,
+    beta: f32,
+    kappa: f32,
+}
+
+#[cfg(feature = "serde")]
+impl<const D_STATE: usize, const N_SIGMAS: usize> From<SigmaPointsParameters>
+    for SigmaPoints<D_STATE, N_SIGMAS>
+{
+    fn from(parameters: SigmaPointsParameters) -> Self {
+        Self::new(parameters.alpha, parameters.beta, parameters.kappa)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const D_STATE: usize, const N_SIGMAS: usize> From<SigmaPoints<D_STATE, N_SIGMAS>>
+    for SigmaPointsParameters
+{
+    fn from(sigmas: SigmaPoints<D_STATE, N_SIGMAS>) -> Self {
+        Self {
+            alpha: sigmas.alpha,
+            beta: sigmas.beta,
+            kappa: sigmas.kappa,
+        }
+    }
+}
+
 /// An Unscented Kalman Filter
 ///
 /// Uses the formulation found [here](https://nbviewer.org/github/sbitzer/UKF-exposed/blob/master/UKF.ipynb)
+///
+/// A filter that has become overconfident adapts slowly to surprising measurements. This can be
+/// countered by inflating the covariance with [`UnscentedKalmanFilter::inflate_covariance`], or
+/// continuously with a fading memory factor, see [`UnscentedKalmanFilter::with_fading_memory`].
+///
+/// With the `serde` feature enabled, the filter state, covariance, and sigma point parameters
+/// can be serialized, e.g. to checkpoint a filter to disk and restore it later.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct UnscentedKalmanFilter<const D_STATE: usize, const N_SIGMAS: usize, S>
 where
     S: StateTransform<D_STATE>,
@@ -124,10 +174,18 @@ where
     sigmas: SigmaPoints<D_STATE, N_SIGMAS>,
     pub state: StateVector<D_STATE>,
     pub covariance: CovarianceMatrix<D_STATE>,
+    #[cfg_attr(feature = "serde", serde(default = "default_fading_memory"))]
+    fading_memory: f32,
 
+    #[cfg_attr(feature = "serde", serde(skip))]
     _marker: PhantomData<S>,
 }
 
+#[cfg(feature = "serde")]
+fn default_fading_memory() -> f32 {
+    1.0
+}
+
 impl<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>
     UnscentedKalmanFilter<D_STATE, N_SIGMAS, S>
 {
@@ -154,10 +212,52 @@ impl<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>
             sigmas,
             state: state.into(),
             covariance,
+            fading_memory: 1.0,
             _marker: PhantomData,
         }
     }
 
+    /// Sets the fading memory factor, which scales the prior covariance in every
+    /// [`predict`](Self::predict) before the process noise is added.
+    ///
+    /// A factor of `1.0` disables fading memory. Factors slightly larger than one, typically
+    /// between `1.01` and `1.05`, make the filter weigh recent measurements more heavily, so it
+    /// stays responsive to changes. Larger factors make the filter increasingly noisy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the factor is smaller than one.
+    #[must_use]
+    pub fn with_fading_memory(mut self, factor: f32) -> Self {
+        self.set_fading_memory(factor);
+        self
+    }
+
+    /// Sets the fading memory factor, see [`Self::with_fading_memory`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the factor is smaller than one.
+    pub fn set_fading_memory(&mut self, factor: f32) {
+        assert!(factor >= 1.0, "fading memory factor must be at least 1");
+        self.fading_memory = factor;
+    }
+
+    /// The fading memory factor, see [`Self::with_fading_memory`].
+    #[must_use]
+    pub fn fading_memory(&self) -> f32 {
+        self.fading_memory
+    }
+
+    /// Scales the covariance by `factor`, to recover from an overconfident state.
+    ///
+    /// This is a one-off alternative to fading memory, e.g. for when a tracked object has not been
+    /// observed for a while. Factors between `2.0` and `10.0` are typical, depending on how much
+    /// of the current estimate should be distrusted.
+    pub fn inflate_covariance(&mut self, factor: f32) {
+        self.covariance *= factor;
+    }
+
     /// The predicted filter state
     #[must_use]
     pub fn state(&self) -> S {
@@ -170,6 +270,12 @@ impl<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>
         self.covariance
     }
 
+    /// The sigma points used by the filter
+    #[must_use]
+    pub fn sigma_points(&self) -> SigmaPoints<D_STATE, N_SIGMAS> {
+        self.sigmas
+    }
+
     /// Predict the next filter state based on the motion transition model and process noise.
     pub fn predict<F>(
         &mut self,
@@ -179,7 +285,8 @@ impl<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>
     where
         F: Fn(S) -> S,
     {
-        let sigma_points = self.sigmas.calculate(self.state, self.covariance)?;
+        let prior_covariance = self.covariance * self.fading_memory;
+        let sigma_points = self.sigmas.calculate(self.state, prior_covariance)?;
 
         // apply the motion model to each sigma point
         let transformed_sigma_points =
@@ -198,6 +305,28 @@ impl<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>
         Ok(())
     }
 
+    /// Predict the filter state `steps` timesteps ahead by repeatedly applying [`Self::predict`].
+    ///
+    /// The process noise is added once per step, so the uncertainty accumulates over all steps.
+    /// This is useful for propagating a state over a gap of missed measurements.
+    ///
+    /// Returns the first error encountered, leaving the filter in the state of the last successful step.
+    pub fn predict_n<F>(
+        &mut self,
+        transition_function: F,
+        process_noise: CovarianceMatrix<D_STATE>,
+        steps: usize,
+    ) -> Result<()>
+    where
+        F: Fn(S) -> S,
+    {
+        for _ in 0..steps {
+            self.predict(&transition_function, process_noise)?;
+        }
+
+        Ok(())
+    }
+
     fn transform_sigma_points<const D_FROM: usize, const D_TO: usize>(
         sigma_points: StateMatrix<D_FROM, N_SIGMAS>,
         transform: impl Fn(StateVector<D_FROM>) -> StateVector<D_TO>,
@@ -216,6 +345,50 @@ impl<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>
         measurement: M,
         measurement_noise: CovarianceMatrix<D_MEASUREMENT>,
     ) -> Result<()>
+    where
+        M: StateTransform<D_MEASUREMENT>,
+        F: Fn(S) -> M,
+    {
+        self.update_with_stats(measurement_function, measurement, measurement_noise)
+            .map(|_| ())
+    }
+
+    /// Updates the filter state with a batch of measurements, each with its own noise.
+    ///
+    /// This allows weighting measurements by their reliability, e.g. a close field mark has less
+    /// noise than a distant one, so it moves the state more. The measurements are applied one
+    /// after the other, which is equivalent to a single update if their noise is independent.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first measurement that fails to update the state, the measurements before it
+    /// have already been applied.
+    pub fn update_batch<const D_MEASUREMENT: usize, M, F>(
+        &mut self,
+        measurement_function: F,
+        measurements: impl IntoIterator<Item = (M, CovarianceMatrix<D_MEASUREMENT>)>,
+    ) -> Result<()>
+    where
+        M: StateTransform<D_MEASUREMENT>,
+        F: Fn(S) -> M,
+    {
+        for (measurement, measurement_noise) in measurements {
+            self.update(&measurement_function, measurement, measurement_noise)?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the filter state with a measurement, see [`Self::update`].
+    ///
+    /// Returns the innovation and its covariance, e.g. to gate measurements or to judge how well
+    /// they match the filter state.
+    pub fn update_with_stats<const D_MEASUREMENT: usize, M, F>(
+        &mut self,
+        measurement_function: F,
+        measurement: M,
+        measurement_noise: CovarianceMatrix<D_MEASUREMENT>,
+    ) -> Result<UpdateStats<D_MEASUREMENT>>
     where
         M: StateTransform<D_MEASUREMENT>,
         F: Fn(S) -> M,
@@ -228,11 +401,129 @@ impl<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>
         let transformed_sigma_points =
             Self::transform_sigma_points(sigma_points, |s| measurement_function(s.into()).into());
 
-        let (mean, covariance) = unscented_transform::<D_MEASUREMENT, N_SIGMAS, M>(
+        self.correct::<D_MEASUREMENT, N_SIGMAS, M>(
+            sigma_points,
             transformed_sigma_points,
-            measurement_noise,
             self.sigmas.w_m,
             self.sigmas.w_c,
+            measurement_noise,
+            measurement,
+        )
+    }
+
+    /// Predict the next filter state based on a motion transition model with non-additive
+    /// process noise.
+    ///
+    /// Unlike [`Self::predict`], which adds the process noise after the unscented transform, the
+    /// state is augmented with the noise dimensions, so the noise is propagated through the
+    /// transition model. This is more accurate for models with state dependent noise, such as
+    /// odometry where the turn noise scales with the walking speed.
+    ///
+    /// The transition function receives a sample of the noise, which is zero-mean with covariance
+    /// `process_noise`. The augmented sigma points have dimension `D_AUG = D_STATE + D_NOISE`,
+    /// so `N_AUG = 2 * D_AUG + 1` sigma points are transformed instead of `N_SIGMAS`, which makes
+    /// this more expensive than the additive version.
+    pub fn predict_augmented<const D_NOISE: usize, const D_AUG: usize, const N_AUG: usize, F>(
+        &mut self,
+        sigmas: &SigmaPoints<D_AUG, N_AUG>,
+        transition_function: F,
+        process_noise: CovarianceMatrix<D_NOISE>,
+    ) -> Result<()>
+    where
+        F: Fn(S, StateVector<D_NOISE>) -> S,
+    {
+        let prior_covariance = self.covariance * self.fading_memory;
+        let sigma_points =
+            augmented_sigma_points(sigmas, self.state, prior_covariance, process_noise)?;
+
+        // apply the motion model to the state part of each sigma point, using its noise part
+        let mut transformed_sigma_points = Matrix::<D_STATE, N_AUG>::zeros();
+        for (i, sigma_point) in sigma_points.column_iter().enumerate() {
+            let state = sigma_point.fixed_rows::<D_STATE>(0).into_owned();
+            let noise = sigma_point.fixed_rows::<D_NOISE>(D_STATE).into_owned();
+
+            transformed_sigma_points
+                .set_column(i, &transition_function(state.into(), noise).into());
+        }
+
+        let (mean, covariance) = unscented_transform::<D_STATE, N_AUG, S>(
+            transformed_sigma_points,
+            CovarianceMatrix::zeros(),
+            sigmas.w_m,
+            sigmas.w_c,
+        );
+
+        self.state = mean;
+        self.covariance = covariance;
+
+        Ok(())
+    }
+
+    /// Updates the filter state with a measurement with non-additive noise.
+    ///
+    /// The measurement function receives a sample of the noise, which is zero-mean with covariance
+    /// `measurement_noise`, see [`Self::predict_augmented`] for the trade-offs of augmentation.
+    pub fn update_augmented<
+        const D_MEASUREMENT: usize,
+        const D_NOISE: usize,
+        const D_AUG: usize,
+        const N_AUG: usize,
+        M,
+        F,
+    >(
+        &mut self,
+        sigmas: &SigmaPoints<D_AUG, N_AUG>,
+        measurement_function: F,
+        measurement: M,
+        measurement_noise: CovarianceMatrix<D_NOISE>,
+    ) -> Result<()>
+    where
+        M: StateTransform<D_MEASUREMENT>,
+        F: Fn(S, StateVector<D_NOISE>) -> M,
+    {
+        let sigma_points =
+            augmented_sigma_points(sigmas, self.state, self.covariance, measurement_noise)?;
+
+        let mut state_sigma_points = Matrix::<D_STATE, N_AUG>::zeros();
+        let mut transformed_sigma_points = Matrix::<D_MEASUREMENT, N_AUG>::zeros();
+        for (i, sigma_point) in sigma_points.column_iter().enumerate() {
+            let state = sigma_point.fixed_rows::<D_STATE>(0).into_owned();
+            let noise = sigma_point.fixed_rows::<D_NOISE>(D_STATE).into_owned();
+
+            state_sigma_points.set_column(i, &state);
+            transformed_sigma_points
+                .set_column(i, &measurement_function(state.into(), noise).into());
+        }
+
+        self.correct::<D_MEASUREMENT, N_AUG, M>(
+            state_sigma_points,
+            transformed_sigma_points,
+            sigmas.w_m,
+            sigmas.w_c,
+            CovarianceMatrix::zeros(),
+            measurement.into(),
+        )
+        .map(|_| ())
+    }
+
+    /// Corrects the filter state using sigma points and their transformation into measurement space.
+    fn correct<const D_MEASUREMENT: usize, const N: usize, M>(
+        &mut self,
+        sigma_points: StateMatrix<D_STATE, N>,
+        transformed_sigma_points: StateMatrix<D_MEASUREMENT, N>,
+        w_m: WeightVector<N>,
+        w_c: WeightVector<N>,
+        measurement_noise: CovarianceMatrix<D_MEASUREMENT>,
+        measurement: StateVector<D_MEASUREMENT>,
+    ) -> Result<UpdateStats<D_MEASUREMENT>>
+    where
+        M: StateTransform<D_MEASUREMENT>,
+    {
+        let (mean, covariance) = unscented_transform::<D_MEASUREMENT, N, M>(
+            transformed_sigma_points,
+            measurement_noise,
+            w_m,
+            w_c,
         );
 
         let cross_covariance: CrossCovarianceMatrix<D_STATE, D_MEASUREMENT> = {
@@ -249,23 +540,94 @@ impl<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>
                 // and also our predicted current motion state
                 let motion_centered = S::residual(sigma_point.into_owned(), self.state);
 
-                cross_covariance +=
-                    self.sigmas.w_c[i] * motion_centered * measurement_centered.transpose();
+                cross_covariance += w_c[i] * motion_centered * measurement_centered.transpose();
             }
 
             cross_covariance
         };
 
-        let kalman_gain = cross_covariance * covariance.try_inverse().ok_or(Error::Inversion)?;
+        let covariance_inverse = covariance.try_inverse().ok_or(Error::Inversion)?;
+        let kalman_gain = cross_covariance * covariance_inverse;
         let innovation = M::residual(measurement, mean);
 
         self.state += kalman_gain * innovation;
         self.covariance -= kalman_gain * covariance * kalman_gain.transpose();
 
-        Ok(())
+        Ok(UpdateStats::new(innovation, covariance, covariance_inverse))
+    }
+}
+
+/// Statistics of a single measurement update, see [`UnscentedKalmanFilter::update_with_stats`]
+/// and [`KalmanFilter::update_with_stats`].
+///
+/// All quantities live in measurement space, so `D_MEASUREMENT` is the dimension of the
+/// measurement vector rather than of the filter state.
+#[derive(Debug, Clone, Copy)]
+pub struct UpdateStats<const D_MEASUREMENT: usize> {
+    /// The difference between the measurement and the predicted measurement, `y = z - h(x)`.
+    pub innovation: StateVector<D_MEASUREMENT>,
+    /// The covariance of the innovation, `S`, which includes the measurement noise.
+    pub innovation_covariance: CovarianceMatrix<D_MEASUREMENT>,
+    /// The normalized innovation squared, `y^T S^-1 y`.
+    ///
+    /// This is the squared Mahalanobis distance of the measurement, which is chi-squared
+    /// distributed with `D_MEASUREMENT` degrees of freedom for a consistent filter.
+    pub nis: f32,
+}
+
+impl<const D_MEASUREMENT: usize> UpdateStats<D_MEASUREMENT> {
+    fn new(
+        innovation: StateVector<D_MEASUREMENT>,
+        innovation_covariance: CovarianceMatrix<D_MEASUREMENT>,
+        innovation_covariance_inverse: CovarianceMatrix<D_MEASUREMENT>,
+    ) -> Self {
+        Self {
+            innovation,
+            innovation_covariance,
+            nis: (innovation.transpose() * innovation_covariance_inverse * innovation).x,
+        }
     }
 }
 
+/// Compile time check that the augmented dimension is the sum of the state and noise dimensions.
+struct AugmentedDimensions<const D_STATE: usize, const D_NOISE: usize, const D_AUG: usize>;
+
+impl<const D_STATE: usize, const D_NOISE: usize, const D_AUG: usize>
+    AugmentedDimensions<D_STATE, D_NOISE, D_AUG>
+{
+    const ASSERT_CONST_PARAMS: () = assert!(D_STATE + D_NOISE == D_AUG);
+}
+
+/// Calculates the sigma points of the state augmented with zero-mean noise.
+fn augmented_sigma_points<
+    const D_STATE: usize,
+    const D_NOISE: usize,
+    const D_AUG: usize,
+    const N_AUG: usize,
+>(
+    sigmas: &SigmaPoints<D_AUG, N_AUG>,
+    state: StateVector<D_STATE>,
+    covariance: CovarianceMatrix<D_STATE>,
+    noise: CovarianceMatrix<D_NOISE>,
+) -> Result<StateMatrix<D_AUG, N_AUG>> {
+    let () = AugmentedDimensions::<D_STATE, D_NOISE, D_AUG>::ASSERT_CONST_PARAMS;
+
+    let mut augmented_state = StateVector::<D_AUG>::zeros();
+    augmented_state
+        .fixed_rows_mut::<D_STATE>(0)
+        .copy_from(&state);
+
+    let mut augmented_covariance = CovarianceMatrix::<D_AUG>::zeros();
+    augmented_covariance
+        .fixed_view_mut::<D_STATE, D_STATE>(0, 0)
+        .copy_from(&covariance);
+    augmented_covariance
+        .fixed_view_mut::<D_NOISE, D_NOISE>(D_STATE, D_STATE)
+        .copy_from(&noise);
+
+    sigmas.calculate(augmented_state, augmented_covariance)
+}
+
 /// Performs the Unscented Transform on a set of sigma points
 fn unscented_transform<const D_STATE: usize, const N_SIGMAS: usize, S: StateTransform<D_STATE>>(
     transformed_sigma_points: StateMatrix<D_STATE, N_SIGMAS>,
@@ -353,6 +715,11 @@ where
         self.covariance
     }
 
+    /// Scales the covariance by `factor`, see [`UnscentedKalmanFilter::inflate_covariance`].
+    pub fn inflate_covariance(&mut self, factor: f32) {
+        self.covariance *= factor;
+    }
+
     /// Predict the next filter state based on the state transition model and process noise.
     pub fn predict<const D_CONTROL: usize, C: Vectorize<D_CONTROL>>(
         &mut self,
@@ -374,20 +741,55 @@ where
         measurement_model: Matrix<D_MEASUREMENT, D_STATE>,
         measurement_noise: CovarianceMatrix<D_MEASUREMENT>,
     ) -> Result<()> {
+        self.update_with_stats(measurement, measurement_model, measurement_noise)
+            .map(|_| ())
+    }
+
+    /// Updates the filter state with a batch of measurements, each with its own noise, see
+    /// [`UnscentedKalmanFilter::update_batch`].
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first measurement that fails to update the state, the measurements before it
+    /// have already been applied.
+    pub fn update_batch<const D_MEASUREMENT: usize, M: Vectorize<D_MEASUREMENT>>(
+        &mut self,
+        measurement_model: Matrix<D_MEASUREMENT, D_STATE>,
+        measurements: impl IntoIterator<Item = (M, CovarianceMatrix<D_MEASUREMENT>)>,
+    ) -> Result<()> {
+        for (measurement, measurement_noise) in measurements {
+            self.update(measurement, measurement_model, measurement_noise)?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the filter state with a measurement, and returns the innovation and its covariance.
+    pub fn update_with_stats<const D_MEASUREMENT: usize, M: Vectorize<D_MEASUREMENT>>(
+        &mut self,
+        measurement: M,
+        measurement_model: Matrix<D_MEASUREMENT, D_STATE>,
+        measurement_noise: CovarianceMatrix<D_MEASUREMENT>,
+    ) -> Result<UpdateStats<D_MEASUREMENT>> {
         let measurement = measurement.into();
 
         let residual = measurement - measurement_model * self.state;
         let residual_covariance =
             measurement_model * self.covariance * measurement_model.transpose() + measurement_noise;
+        let residual_covariance_inverse =
+            residual_covariance.try_inverse().ok_or(Error::Inversion)?;
 
-        let kalman_gain = self.covariance
-            * measurement_model.transpose()
-            * residual_covariance.try_inverse().ok_or(Error::Inversion)?;
+        let kalman_gain =
+            self.covariance * measurement_model.transpose() * residual_covariance_inverse;
 
         self.state += kalman_gain * residual;
         self.covariance -= kalman_gain * measurement_model * self.covariance;
 
-        Ok(())
+        Ok(UpdateStats::new(
+            residual,
+            residual_covariance,
+            residual_covariance_inverse,
+        ))
     }
 }
 
@@ -421,3 +823,249 @@ impl<const D: usize> MahalanobisDistance<D> for CovarianceMatrix<D> {
         mahalanobis_distance(point, mean, *self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy)]
+    struct Position(StateVector<1>);
+
+    impl From<StateVector<1>> for Position {
+        fn from(state: StateVector<1>) -> Self {
+            Self(state)
+        }
+    }
+
+    impl From<Position> for StateVector<1> {
+        fn from(position: Position) -> Self {
+            position.0
+        }
+    }
+
+    impl StateTransform<1> for Position {}
+
+    type PositionUkf = UnscentedKalmanFilter<1, 3, Position>;
+
+    const PROCESS_NOISE: f32 = 1e-4;
+    const MEASUREMENT_NOISE: f32 = 0.1;
+
+    /// Lets the filter converge on zero, then jumps the target and counts the updates it takes
+    /// to get close to the new target.
+    fn steps_to_converge(mut filter: PositionUkf, inflation: Option<f32>) -> usize {
+        let measure = |filter: &mut PositionUkf, target: f32| {
+            filter.predict(|s| s, CovarianceMatrix::<1>::repeat(PROCESS_NOISE))?;
+            filter.update(
+                |s: Position| s,
+                Position(StateVector::<1>::new(target)),
+                CovarianceMatrix::<1>::repeat(MEASUREMENT_NOISE),
+            )
+        };
+
+        for _ in 0..200 {
+            measure(&mut filter, 0.0).unwrap();
+        }
+
+        if let Some(factor) = inflation {
+            filter.inflate_covariance(factor);
+        }
+
+        (1..1000)
+            .find(|_| {
+                measure(&mut filter, 1.0).unwrap();
+                filter.state.x > 0.9
+            })
+            .expect("filter should converge")
+    }
+
+    #[test]
+    fn augmented_matches_additive_for_additive_noise() {
+        let mut additive =
+            PositionUkf::new(Position(StateVector::<1>::new(1.0)), Matrix::repeat(0.5));
+        let mut augmented = additive;
+        let sigmas = SigmaPoints2::new(1.0, 0.0, 3.0);
+
+        let noise = CovarianceMatrix::<1>::repeat(0.2);
+        let measurement = Position(StateVector::<1>::new(2.5));
+
+        additive.predict(|s| Position(s.0 * 2.0), noise).unwrap();
+        augmented
+            .predict_augmented(&sigmas, |s, n| Position(s.0 * 2.0 + n), noise)
+            .unwrap();
+
+        assert!((additive.state - augmented.state).norm() < 1e-5);
+        assert!((additive.covariance - augmented.covariance).norm() < 1e-5);
+
+        additive.update(|s| s, measurement, noise).unwrap();
+        augmented
+            .update_augmented(
+                &sigmas,
+                |s: Position, n| Position(s.0 + n),
+                measurement,
+                noise,
+            )
+            .unwrap();
+
+        assert!((additive.state - augmented.state).norm() < 1e-5);
+        assert!((additive.covariance - augmented.covariance).norm() < 1e-5);
+    }
+
+    #[test]
+    fn augmented_noise_is_propagated_through_model() {
+        // the noise is scaled by the state, so it should be amplified for a larger state
+        let mut filter =
+            PositionUkf::new(Position(StateVector::<1>::new(3.0)), Matrix::repeat(0.01));
+        let sigmas = SigmaPoints2::new(1.0, 0.0, 3.0);
+
+        filter
+            .predict_augmented(
+                &sigmas,
+                |s, n| Position(s.0 + s.0 * n),
+                CovarianceMatrix::<1>::repeat(0.1),
+            )
+            .unwrap();
+
+        // var(x + x * n) ≈ var(x) + x^2 * var(n) for small var(x)
+        assert!((filter.covariance.x - (0.01 + 9.0 * 0.1)).abs() < 0.01);
+    }
+
+    #[test]
+    fn inflation_speeds_up_convergence() {
+        let filter = PositionUkf::new(Position(StateVector::<1>::zeros()), Matrix::identity());
+
+        let baseline = steps_to_converge(filter, None);
+        let inflated = steps_to_converge(filter, Some(100.0));
+        let fading = steps_to_converge(filter.with_fading_memory(1.05), None);
+
+        assert!(inflated < baseline, "{inflated} >= {baseline}");
+        assert!(fading < baseline, "{fading} >= {baseline}");
+    }
+
+    #[test]
+    fn update_stats_match_linear_filter() {
+        let state = Position(StateVector::<1>::new(1.0));
+        let covariance = CovarianceMatrix::<1>::repeat(0.5);
+        let measurement_noise = CovarianceMatrix::<1>::repeat(MEASUREMENT_NOISE);
+        let measurement = Position(StateVector::<1>::new(2.0));
+
+        let mut ukf = PositionUkf::new(state, covariance);
+        let unscented_stats = ukf
+            .update_with_stats(|s: Position| s, measurement, measurement_noise)
+            .unwrap();
+
+        let mut kf = KalmanFilter::<1, StateVector<1>>::new(state.0, covariance);
+        let linear_stats = kf
+            .update_with_stats(measurement.0, Matrix::identity(), measurement_noise)
+            .unwrap();
+
+        // for a linear measurement model, S = P + R and the innovation is z - x
+        for stats in [unscented_stats, linear_stats] {
+            assert!((stats.innovation.x - 1.0).abs() < 1e-5);
+            assert!((stats.innovation_covariance.x - 0.6).abs() < 1e-5);
+            assert!((stats.nis - 1.0 / 0.6).abs() < 1e-4);
+        }
+        assert!((ukf.state.x - kf.state.x).abs() < 1e-5);
+    }
+
+    #[test]
+    fn noisy_measurements_move_the_state_less() {
+        let filter = PositionUkf::new(Position(StateVector::<1>::zeros()), Matrix::identity());
+        let measurement = Position(StateVector::<1>::new(1.0));
+
+        let mut precise = filter;
+        precise
+            .update_batch(
+                |s: Position| s,
+                [(measurement, CovarianceMatrix::<1>::repeat(0.1))],
+            )
+            .unwrap();
+
+        let mut noisy = filter;
+        noisy
+            .update_batch(
+                |s: Position| s,
+                [(measurement, CovarianceMatrix::<1>::repeat(10.0))],
+            )
+            .unwrap();
+
+        assert!(noisy.state.x < precise.state.x);
+
+        // a precise and a noisy measurement pull the state towards the precise one
+        let mut mixed = filter;
+        mixed
+            .update_batch(
+                |s: Position| s,
+                [
+                    (measurement, CovarianceMatrix::<1>::repeat(0.1)),
+                    (
+                        Position(StateVector::<1>::new(-1.0)),
+                        CovarianceMatrix::<1>::repeat(10.0),
+                    ),
+                ],
+            )
+            .unwrap();
+        assert!(mixed.state.x > 0.5);
+
+        let mut kf =
+            KalmanFilter::<1, StateVector<1>>::new(StateVector::zeros(), Matrix::identity());
+        kf.update_batch(
+            Matrix::identity(),
+            [
+                (measurement.0, CovarianceMatrix::<1>::repeat(0.1)),
+                (
+                    StateVector::<1>::new(-1.0),
+                    CovarianceMatrix::<1>::repeat(10.0),
+                ),
+            ],
+        )
+        .unwrap();
+        assert!((mixed.state.x - kf.state.x).abs() < 1e-5);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut filter = PositionUkf::with_sigma_points(
+            SigmaPoints::new(0.5, 2.0, 1.0),
+            Position(StateVector::<1>::new(0.0)),
+            CovarianceMatrix::<1>::repeat(1.0),
+        )
+        .with_fading_memory(1.02);
+        filter
+            .update(
+                |s: Position| s,
+                Position(StateVector::<1>::new(1.0)),
+                CovarianceMatrix::<1>::repeat(MEASUREMENT_NOISE),
+            )
+            .unwrap();
+
+        let json = serde_json::to_string(&filter).unwrap();
+        let mut restored: PositionUkf = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.state, filter.state);
+        assert_eq!(restored.covariance, filter.covariance);
+        assert!((restored.fading_memory() - filter.fading_memory()).abs() < f32::EPSILON);
+
+        // the weights are recalculated from the serialized parameters
+        let (sigmas, restored_sigmas) = (filter.sigma_points(), restored.sigma_points());
+        assert_eq!(
+            (
+                restored_sigmas.alpha,
+                restored_sigmas.beta,
+                restored_sigmas.kappa
+            ),
+            (sigmas.alpha, sigmas.beta, sigmas.kappa)
+        );
+        assert_eq!(restored_sigmas.w_m, sigmas.w_m);
+        assert_eq!(restored_sigmas.w_c, sigmas.w_c);
+
+        // the restored filter continues exactly where the original left off
+        for filter in [&mut filter, &mut restored] {
+            filter
+                .predict(|s| s, CovarianceMatrix::<1>::repeat(PROCESS_NOISE))
+                .unwrap();
+        }
+        assert_eq!(restored.state, filter.state);
+        assert_eq!(restored.covariance, filter.covariance);
+    }
+}
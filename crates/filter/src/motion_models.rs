@@ -0,0 +1,231 @@
+//! Common linear motion models, for use as the transition function of a filter.
+//!
+//! The models are generic over the dimension `D` of the state vector, which stacks one block per
+//! derivative of the position, each block containing one value per spatial axis:
+//!
+//! - [`constant_velocity`] assumes `[position, velocity]`, so a 2D ball tracker has the state
+//!   `[x, y, vx, vy]` with `D = 4`.
+//! - [`constant_acceleration`] assumes `[position, velocity, acceleration]`, so the same tracker
+//!   has the state `[x, y, vx, vy, ax, ay]` with `D = 6`.
+//!
+//! The transition functions can be passed to [`UnscentedKalmanFilter::predict`], and the
+//! transition matrices to [`KalmanFilter::predict`], together with the matching process noise.
+//!
+//! ```
+//! use filter::{CovarianceMatrix, StateVector, StateTransform, UnscentedKalmanFilter};
+//! use filter::motion_models::{constant_velocity, constant_velocity_noise};
+//!
+//! # #[derive(Clone, Copy)]
+//! # struct Ball(StateVector<4>);
+//! # impl From<StateVector<4>> for Ball { fn from(s: StateVector<4>) -> Self { Self(s) } }
+//! # impl From<Ball> for StateVector<4> { fn from(b: Ball) -> Self { b.0 } }
+//! # impl StateTransform<4> for Ball {}
+//! let state = Ball(StateVector::<4>::new(1.0, 0.0, 0.5, -0.5));
+//! let mut ukf = UnscentedKalmanFilter::<4, 9, Ball>::new(state, CovarianceMatrix::identity());
+//!
+//! let dt = 0.012;
+//! ukf.predict(constant_velocity(dt), constant_velocity_noise(dt, 0.1))
+//!     .unwrap();
+//! ```
+//!
+//! [`UnscentedKalmanFilter::predict`]: crate::UnscentedKalmanFilter::predict
+//! [`KalmanFilter::predict`]: crate::KalmanFilter::predict
+
+use nalgebra::SMatrix;
+
+use crate::{CovarianceMatrix, Vectorize};
+
+/// Returns the transition matrix of the constant velocity model, see [`constant_velocity`].
+///
+/// Using a `D` that is not a multiple of two is a compile error.
+#[must_use]
+pub fn constant_velocity_transition<const D: usize>(dt: f32) -> SMatrix<f32, D, D> {
+    const {
+        assert!(
+            D.is_multiple_of(2),
+            "state must consist of a position and velocity block"
+        );
+    };
+
+    derivative_transition::<D>(D / 2, dt)
+}
+
+/// Returns the transition matrix of the constant acceleration model, see
+/// [`constant_acceleration`].
+///
+/// Using a `D` that is not a multiple of three is a compile error.
+#[must_use]
+pub fn constant_acceleration_transition<const D: usize>(dt: f32) -> SMatrix<f32, D, D> {
+    const {
+        assert!(
+            D.is_multiple_of(3),
+            "state must consist of a position, velocity and acceleration block"
+        );
+    };
+
+    derivative_transition::<D>(D / 3, dt)
+}
+
+/// Returns a transition function that moves the position by the velocity over `dt` seconds.
+///
+/// The state is `[position, velocity]`, where both blocks have `D / 2` axes. The velocity stays
+/// unchanged.
+pub fn constant_velocity<const D: usize, S: Vectorize<D>>(dt: f32) -> impl Fn(S) -> S {
+    let transition = constant_velocity_transition::<D>(dt);
+
+    move |state: S| S::from(transition * state.into())
+}
+
+/// Returns a transition function that moves the position and velocity by the acceleration over
+/// `dt` seconds.
+///
+/// The state is `[position, velocity, acceleration]`, where all blocks have `D / 3` axes. The
+/// acceleration stays unchanged.
+pub fn constant_acceleration<const D: usize, S: Vectorize<D>>(dt: f32) -> impl Fn(S) -> S {
+    let transition = constant_acceleration_transition::<D>(dt);
+
+    move |state: S| S::from(transition * state.into())
+}
+
+/// Returns the process noise of the constant velocity model, for a random acceleration that is
+/// constant over each timestep of `dt` seconds.
+///
+/// The `acceleration_variance` is the variance of that acceleration along every axis, in
+/// (m/s²)². The axes are independent.
+#[must_use]
+pub fn constant_velocity_noise<const D: usize>(
+    dt: f32,
+    acceleration_variance: f32,
+) -> CovarianceMatrix<D> {
+    const {
+        assert!(
+            D.is_multiple_of(2),
+            "state must consist of a position and velocity block"
+        );
+    };
+
+    white_noise::<D>(&[dt.powi(2) / 2.0, dt], acceleration_variance)
+}
+
+/// Returns the process noise of the constant acceleration model, for a random jerk that is
+/// constant over each timestep of `dt` seconds.
+///
+/// The `jerk_variance` is the variance of that jerk along every axis, in (m/s³)². The axes are
+/// independent.
+#[must_use]
+pub fn constant_acceleration_noise<const D: usize>(
+    dt: f32,
+    jerk_variance: f32,
+) -> CovarianceMatrix<D> {
+    const {
+        assert!(
+            D.is_multiple_of(3),
+            "state must consist of a position, velocity and acceleration block"
+        );
+    };
+
+    white_noise::<D>(&[dt.powi(3) / 6.0, dt.powi(2) / 2.0, dt], jerk_variance)
+}
+
+/// Transition matrix for a state of stacked derivatives with `axes` values each, where every
+/// derivative is integrated into the ones below it with the Taylor series over `dt`.
+fn derivative_transition<const D: usize>(axes: usize, dt: f32) -> SMatrix<f32, D, D> {
+    let orders = D / axes;
+    let mut transition = SMatrix::<f32, D, D>::identity();
+
+    for order in 0..orders {
+        for higher in order + 1..orders {
+            let power = higher - order;
+            let coefficient = dt.powi(power as i32) / factorial(power);
+
+            for axis in 0..axes {
+                transition[(order * axes + axis, higher * axes + axis)] = coefficient;
+            }
+        }
+    }
+
+    transition
+}
+
+/// Process noise `G σ² Gᵀ` for a noise that affects every derivative of an axis through `gain`.
+fn white_noise<const D: usize>(gain: &[f32], variance: f32) -> CovarianceMatrix<D> {
+    let axes = D / gain.len();
+    let mut noise = CovarianceMatrix::<D>::zeros();
+
+    for (row, row_gain) in gain.iter().enumerate() {
+        for (column, column_gain) in gain.iter().enumerate() {
+            for axis in 0..axes {
+                noise[(row * axes + axis, column * axes + axis)] =
+                    row_gain * column_gain * variance;
+            }
+        }
+    }
+
+    noise
+}
+
+fn factorial(n: usize) -> f32 {
+    (1..=n).map(|i| i as f32).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StateTransform, StateVector, UnscentedKalmanFilter};
+
+    #[derive(Debug, Clone, Copy)]
+    struct Ball<const D: usize>(StateVector<D>);
+
+    impl<const D: usize> From<StateVector<D>> for Ball<D> {
+        fn from(state: StateVector<D>) -> Self {
+            Self(state)
+        }
+    }
+
+    impl<const D: usize> From<Ball<D>> for StateVector<D> {
+        fn from(ball: Ball<D>) -> Self {
+            ball.0
+        }
+    }
+
+    impl<const D: usize> StateTransform<D> for Ball<D> {}
+
+    #[test]
+    fn constant_velocity_advances_position_by_velocity() {
+        let dt = 0.5;
+        let ball = Ball(StateVector::<4>::new(1.0, -2.0, 0.5, 1.5));
+
+        let predicted = constant_velocity(dt)(ball).0;
+        assert_eq!(predicted, StateVector::<4>::new(1.25, -1.25, 0.5, 1.5));
+
+        // without process noise, the filter follows the model
+        let mut ukf = UnscentedKalmanFilter::<4, 9, Ball<4>>::new(
+            ball,
+            CovarianceMatrix::<4>::identity() * 0.1,
+        );
+        ukf.predict(constant_velocity(dt), CovarianceMatrix::zeros())
+            .unwrap();
+        assert!((ukf.state - predicted).norm() < 1e-5);
+
+        let noise = constant_velocity_noise::<4>(dt, 2.0);
+        assert_eq!(noise, noise.transpose());
+        assert!((noise[(0, 0)] - 2.0 * dt.powi(4) / 4.0).abs() < f32::EPSILON);
+        assert!((noise[(0, 2)] - 2.0 * dt.powi(3) / 2.0).abs() < f32::EPSILON);
+        assert!((noise[(2, 2)] - 2.0 * dt.powi(2)).abs() < f32::EPSILON);
+        assert!(noise[(0, 1)].abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn constant_acceleration_integrates_acceleration() {
+        let dt = 0.5;
+        let ball = Ball(StateVector::<6>::from_column_slice(&[
+            0.0, 1.0, 1.0, 0.0, 2.0, -4.0,
+        ]));
+
+        let predicted = constant_acceleration(dt)(ball).0;
+        assert_eq!(
+            predicted,
+            StateVector::<6>::from_column_slice(&[0.75, 0.5, 2.0, -2.0, 2.0, -4.0])
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Compares the luma extraction of a 640x480 YUYV frame against a naive indexing loop.
+//!
+//! Run with `cargo bench -p heimdall --bench grayscale`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use heimdall::{extract_luma, extract_luma_scalar};
+
+const WIDTH: usize = 640;
+const HEIGHT: usize = 480;
+const ITERATIONS: u32 = 1000;
+
+fn bench(name: &str, mut f: impl FnMut()) -> Duration {
+    // warm up the caches
+    for _ in 0..10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_frame = start.elapsed() / ITERATIONS;
+
+    println!("{name:>8}: {per_frame:?} per frame");
+    per_frame
+}
+
+fn main() {
+    let yuyv: Vec<u8> = (0..WIDTH * HEIGHT * 2).map(|i| (i % 251) as u8).collect();
+    let mut luma = vec![0; WIDTH * HEIGHT];
+
+    let naive = bench("naive", || {
+        let yuyv = black_box(&yuyv);
+        let mut result = Vec::with_capacity(WIDTH * HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                result.push(yuyv[(y * WIDTH + x) * 2]);
+            }
+        }
+        black_box(result);
+    });
+
+    bench("scalar", || {
+        extract_luma_scalar(black_box(&yuyv), black_box(&mut luma));
+    });
+
+    let simd = bench("simd", || {
+        extract_luma(black_box(&yuyv), black_box(&mut luma));
+    });
+
+    println!(
+        "speedup over naive: {:.1}x",
+        naive.as_secs_f64() / simd.as_secs_f64()
+    );
+}
@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+
+/// The exposure and gain a camera is currently using.
+///
+/// With auto exposure enabled, these are the values the camera settled on for the current
+/// lighting conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExposureReading {
+    /// The absolute exposure, in the same units as [`CameraDevice::set_exposure_absolute`].
+    ///
+    /// [`CameraDevice::set_exposure_absolute`]: crate::CameraDevice::set_exposure_absolute
+    pub exposure: i32,
+    /// The gain, in the same units as [`CameraDevice::set_gain`].
+    ///
+    /// [`CameraDevice::set_gain`]: crate::CameraDevice::set_gain
+    pub gain: i32,
+}
+
+/// Heuristic that detects when the auto exposure of a camera has converged.
+///
+/// Auto exposure is considered converged once the exposure and gain of the last `window_size`
+/// readings all lie within `tolerance` of each other, relative to their largest value. Frames
+/// captured before that, e.g. right after the lighting changed, can be too dark or too bright.
+#[derive(Debug, Clone)]
+pub struct AutoExposureConvergence {
+    readings: VecDeque<ExposureReading>,
+    window_size: usize,
+    tolerance: f32,
+}
+
+impl AutoExposureConvergence {
+    /// Creates a detector that requires `window_size` stable readings, which may deviate by a
+    /// fraction of `tolerance` from each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_size` is zero.
+    #[must_use]
+    pub fn new(window_size: usize, tolerance: f32) -> Self {
+        assert!(window_size > 0, "window size must be at least one reading");
+
+        Self {
+            readings: VecDeque::with_capacity(window_size),
+            window_size,
+            tolerance,
+        }
+    }
+
+    /// Adds a new reading, returns whether the auto exposure has converged.
+    pub fn push(&mut self, reading: ExposureReading) -> bool {
+        if self.readings.len() == self.window_size {
+            self.readings.pop_front();
+        }
+        self.readings.push_back(reading);
+
+        self.auto_exposure_converged()
+    }
+
+    /// The most recent reading.
+    #[must_use]
+    pub fn latest(&self) -> Option<ExposureReading> {
+        self.readings.back().copied()
+    }
+
+    /// Forgets all readings, e.g. after the exposure settings have been changed.
+    pub fn reset(&mut self) {
+        self.readings.clear();
+    }
+
+    /// Whether the last readings have been stable, see [`AutoExposureConvergence`].
+    #[must_use]
+    pub fn auto_exposure_converged(&self) -> bool {
+        self.readings.len() == self.window_size
+            && self.is_stable(|reading| reading.exposure)
+            && self.is_stable(|reading| reading.gain)
+    }
+
+    fn is_stable(&self, value: impl Fn(&ExposureReading) -> i32) -> bool {
+        let (min, max) = self
+            .readings
+            .iter()
+            .map(value)
+            .fold((i32::MAX, i32::MIN), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+
+        (max - min) as f32 <= self.tolerance * max.abs() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(exposure: i32, gain: i32) -> ExposureReading {
+        ExposureReading { exposure, gain }
+    }
+
+    #[test]
+    fn converges_once_readings_are_stable() {
+        let mut convergence = AutoExposureConvergence::new(3, 0.05);
+
+        // the camera adjusts to a darker room
+        assert!(!convergence.push(reading(200, 16)));
+        assert!(!convergence.push(reading(400, 32)));
+        assert!(!convergence.push(reading(600, 48)));
+        assert!(!convergence.push(reading(640, 48)));
+        assert!(!convergence.push(reading(650, 50)));
+
+        // small fluctuations are accepted
+        assert!(convergence.push(reading(645, 49)));
+        assert_eq!(convergence.latest(), Some(reading(645, 49)));
+
+        // a change in gain alone also means the camera is still adjusting
+        assert!(!convergence.push(reading(645, 80)));
+
+        convergence.reset();
+        assert!(!convergence.auto_exposure_converged());
+    }
+}
@@ -10,7 +10,7 @@ use linuxvideo::{
     uvc::UvcExt,
 };
 
-use crate::exposure_weights::ExposureWeightTable;
+use crate::{auto_exposure::ExposureReading, exposure_weights::ExposureWeightTable};
 
 use super::{Error, Result, YuyvImage};
 
@@ -379,6 +379,59 @@ impl CameraDevice {
 
         Ok(())
     }
+
+    fn read_control(&self, cid: Cid, property: &str) -> Result<i32> {
+        self.device.read_control_raw(cid).map_err(|source| {
+            // the driver reports controls it does not know as an invalid argument
+            if source.kind() == io::ErrorKind::InvalidInput {
+                Error::Unsupported {
+                    property: property.to_string(),
+                }
+            } else {
+                Error::ReadDeviceProperty {
+                    property: property.to_string(),
+                    source,
+                }
+            }
+        })
+    }
+
+    /// Get the exposure the camera device currently uses.
+    ///
+    /// With auto exposure enabled, this is the exposure chosen by the camera.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::Unsupported`] if the device has no `exposure_absolute`
+    /// property, or if the property cannot be read.
+    pub fn get_exposure(&self) -> Result<i32> {
+        self.read_control(Cid::EXPOSURE_ABSOLUTE, "exposure_absolute")
+    }
+
+    /// Get the gain the camera device currently uses.
+    ///
+    /// With auto exposure enabled, this is the gain chosen by the camera.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::Unsupported`] if the device has no `gain` property, or if
+    /// the property cannot be read.
+    pub fn get_gain(&self) -> Result<i32> {
+        self.read_control(Cid::GAIN, "gain")
+    }
+
+    /// Get both the exposure and gain the camera device currently uses.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if either [`CameraDevice::get_exposure`] or
+    /// [`CameraDevice::get_gain`] fails.
+    pub fn exposure_reading(&self) -> Result<ExposureReading> {
+        Ok(ExposureReading {
+            exposure: self.get_exposure()?,
+            gain: self.get_gain()?,
+        })
+    }
 }
 
 /// Struct for retrieving images from the NAO camera.
@@ -2,10 +2,70 @@ use std::marker::PhantomData;
 
 use bevy::prelude::Resource;
 use miette::{Result, bail};
-use nalgebra::{Isometry3, Point2, Point3, Vector2, Vector3, point, vector};
+use nalgebra::{
+    Isometry3, Matrix2, Matrix3, Matrix3x2, Point2, Point3, UnitQuaternion, Vector2, Vector3,
+    point, vector,
+};
+use serde::{Deserialize, Serialize};
 
 use crate::camera::CameraLocation;
 
+/// Maximum number of fixed-point iterations used to invert the distortion model.
+const UNDISTORTION_ITERATIONS: usize = 20;
+
+/// Maximum reprojection error in pixels for an undistorted pixel to be considered valid.
+const UNDISTORTION_TOLERANCE: f32 = 0.05;
+
+/// Distance in meters of the near plane in front of the camera that segments are clipped against.
+const NEAR_PLANE_DISTANCE: f32 = 0.01;
+
+/// Maximum distance in meters from the camera of the visible ground polygon, roughly the diagonal
+/// of the field including its border.
+const FAR_PLANE_DISTANCE: f32 = 12.0;
+
+/// Step in pixels used to differentiate the ground projection with respect to the pixel.
+const PIXEL_JACOBIAN_STEP: f32 = 0.5;
+
+/// Step in radians used to differentiate the ground projection with respect to the orientation of
+/// the camera.
+const ROTATION_JACOBIAN_STEP: f32 = 1e-3;
+
+/// Lens distortion coefficients following the Brown-Conrady model, as used by `OpenCV`.
+///
+/// `k1`, `k2` and `k3` are the radial coefficients, `p1` and `p2` the tangential coefficients.
+/// The default coefficients describe a lens without any distortion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DistortionCoefficients {
+    pub k1: f32,
+    pub k2: f32,
+    pub k3: f32,
+    pub p1: f32,
+    pub p2: f32,
+}
+
+impl DistortionCoefficients {
+    /// Whether these coefficients describe a lens without any distortion.
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Applies the distortion model to a point in normalized image coordinates.
+    #[must_use]
+    pub fn distort(&self, point: Point2<f32>) -> Point2<f32> {
+        let Self { k1, k2, k3, p1, p2 } = *self;
+        let (x, y) = (point.x, point.y);
+
+        let r2 = x * x + y * y;
+        let radial = 1.0 + r2 * (k1 + r2 * (k2 + r2 * k3));
+
+        point![
+            x * radial + 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+            y * radial + p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y,
+        ]
+    }
+}
+
 /// A camera matrix that is able to project points.
 #[derive(Resource, Default, Debug)]
 pub struct CameraMatrix<T: CameraLocation> {
@@ -15,6 +75,8 @@ pub struct CameraMatrix<T: CameraLocation> {
     pub focal_lengths: Vector2<f32>,
     /// The field of view of the camera in radians.
     pub field_of_view: Vector2<f32>,
+    /// The size of the image in pixels.
+    pub image_size: Vector2<f32>,
     /// The transformation from the camera frame to the head frame.
     pub camera_to_head: Isometry3<f32>,
     /// The transformation from the robot to the camera frame.
@@ -23,6 +85,8 @@ pub struct CameraMatrix<T: CameraLocation> {
     pub camera_to_ground: Isometry3<f32>,
     /// The transformation from the robot to the ground frame.
     pub robot_to_ground: Isometry3<f32>,
+    /// The lens distortion coefficients of the camera.
+    pub distortion: DistortionCoefficients,
     _marker: PhantomData<T>,
 }
 
@@ -34,10 +98,12 @@ impl<T: CameraLocation> Clone for CameraMatrix<T> {
             cc_optical_center: self.cc_optical_center,
             focal_lengths: self.focal_lengths,
             field_of_view: self.field_of_view,
+            image_size: self.image_size,
             camera_to_head: self.camera_to_head,
             robot_to_camera: self.robot_to_camera,
             camera_to_ground: self.camera_to_ground,
             robot_to_ground: self.robot_to_ground,
+            distortion: self.distortion,
             _marker: PhantomData,
         }
     }
@@ -62,12 +128,77 @@ impl<T: CameraLocation> CameraMatrix<T> {
             cc_optical_center,
             focal_lengths,
             field_of_view,
+            image_size,
             camera_to_head,
             robot_to_camera: camera_to_robot.inverse(),
             camera_to_ground,
             _marker: PhantomData,
             robot_to_ground,
+            distortion: DistortionCoefficients::default(),
+        }
+    }
+
+    /// Sets the lens distortion coefficients, used to undistort pixels before projecting them.
+    #[must_use]
+    pub fn with_distortion(mut self, distortion: DistortionCoefficients) -> Self {
+        self.distortion = distortion;
+        self
+    }
+
+    /// Map a raw pixel to the pixel it would be in an image without lens distortion.
+    ///
+    /// The distortion model has no closed-form inverse, so it is inverted iteratively.
+    ///
+    /// # Errors
+    /// This fails if the pixel lies outside the region where the distortion model can be inverted,
+    /// which can happen near the image corners for strongly distorted lenses.
+    pub fn undistort_pixel(&self, pixel: Point2<f32>) -> Result<Point2<f32>> {
+        if self.distortion.is_identity() {
+            return Ok(pixel);
+        }
+
+        let distorted = point![
+            (pixel.x - self.cc_optical_center.x) / self.focal_lengths.x,
+            (pixel.y - self.cc_optical_center.y) / self.focal_lengths.y,
+        ];
+
+        let mut undistorted = distorted;
+        for _ in 0..UNDISTORTION_ITERATIONS {
+            let error = self.distortion.distort(undistorted) - distorted;
+            undistorted -= error;
+        }
+
+        let reprojection_error = (self.distortion.distort(undistorted) - distorted)
+            .component_mul(&self.focal_lengths)
+            .norm();
+        if !reprojection_error.is_finite() || reprojection_error > UNDISTORTION_TOLERANCE {
+            bail!("Pixel {pixel} lies outside the valid undistortion region");
+        }
+
+        Ok(point![
+            self.cc_optical_center.x + undistorted.x * self.focal_lengths.x,
+            self.cc_optical_center.y + undistorted.y * self.focal_lengths.y,
+        ])
+    }
+
+    /// Map a pixel in an image without lens distortion to the raw pixel in the distorted image.
+    ///
+    /// This is the inverse of [`CameraMatrix::undistort_pixel`].
+    #[must_use]
+    pub fn distort_pixel(&self, pixel: Point2<f32>) -> Point2<f32> {
+        if self.distortion.is_identity() {
+            return pixel;
         }
+
+        let distorted = self.distortion.distort(point![
+            (pixel.x - self.cc_optical_center.x) / self.focal_lengths.x,
+            (pixel.y - self.cc_optical_center.y) / self.focal_lengths.y,
+        ]);
+
+        point![
+            self.cc_optical_center.x + distorted.x * self.focal_lengths.x,
+            self.cc_optical_center.y + distorted.y * self.focal_lengths.y,
+        ]
     }
 
     /// Get a vector pointing from the camera through the given pixel in the image plane.
@@ -82,27 +213,107 @@ impl<T: CameraLocation> CameraMatrix<T> {
         ]
     }
 
-    /// Get the position of a point in the camera frame given a vector pointing to the camera.
+    /// Get the raw pixel of a point in the camera frame given a vector pointing to the camera.
+    ///
+    /// The pixel is distorted, so it matches the pixel in the raw image, see
+    /// [`CameraMatrix::distort_pixel`].
     fn camera_to_pixel(&self, camera_ray: Vector3<f32>) -> Result<Point2<f32>> {
         if camera_ray.x <= 0.0 {
             bail!("Point is behind the camera");
         }
 
-        Ok(point![
+        Ok(self.distort_pixel(point![
             self.cc_optical_center.x - self.focal_lengths.x * camera_ray.y / camera_ray.x,
             self.cc_optical_center.y - self.focal_lengths.y * camera_ray.z / camera_ray.x,
-        ])
+        ]))
     }
 
     /// Project a pixel to the ground coordinate frame at a given height.
     ///
     /// We assume the ground is at z = 0.0
     ///
+    /// The pixel is undistorted before it is projected, see [`CameraMatrix::undistort_pixel`].
+    ///
     /// # Errors
-    /// This fails if the point is above the horizon and cannot be projected to the ground.
+    /// This fails if the pixel cannot be undistorted, or if the point is above the horizon
+    /// and cannot be projected to the ground.
     pub fn pixel_to_ground(&self, pixel: Point2<f32>, z: f32) -> Result<Point3<f32>> {
-        let camera_ray = self.pixel_to_camera(pixel);
-        let camera_ray_over_ground = self.camera_to_ground.rotation * camera_ray;
+        let camera_ray = self.pixel_to_camera(self.undistort_pixel(pixel)?);
+
+        self.ray_to_ground(self.camera_to_ground.rotation, camera_ray, z)
+    }
+
+    /// Project a pixel to the ground coordinate frame at a given height, together with the
+    /// covariance of the projected point.
+    ///
+    /// The covariance is propagated from the `pixel_cov` of the pixel in pixels², and optionally
+    /// from the `rotation_cov` of the orientation of the camera relative to the ground in
+    /// radians², which captures the errors in the joint angles and the IMU. The roll, pitch and
+    /// yaw of the `rotation_cov` are around the axes of the ground frame.
+    ///
+    /// The propagation uses the Jacobian of the projection, which is found numerically. As the
+    /// projection stretches with the distance to the camera, the covariance grows with the
+    /// distance of the projected point. The projected point lies on the plane at height `z`, so
+    /// the covariance only has components along the x and y axes.
+    ///
+    /// # Errors
+    /// This fails if the pixel cannot be projected to the ground, see
+    /// [`CameraMatrix::pixel_to_ground`], or if the pixel is so close to the horizon that the
+    /// projection cannot be differentiated.
+    pub fn pixel_to_ground_with_cov(
+        &self,
+        pixel: Point2<f32>,
+        z: f32,
+        pixel_cov: Matrix2<f32>,
+        rotation_cov: Option<Matrix3<f32>>,
+    ) -> Result<(Point3<f32>, Matrix3<f32>)> {
+        let point = self.pixel_to_ground(pixel, z)?;
+
+        let mut pixel_jacobian = Matrix3x2::zeros();
+        for axis in 0..2 {
+            let step = Vector2::ith(axis, PIXEL_JACOBIAN_STEP);
+            let forward = self.pixel_to_ground(pixel + step, z)?;
+            let backward = self.pixel_to_ground(pixel - step, z)?;
+
+            pixel_jacobian.set_column(axis, &((forward - backward) / (2.0 * PIXEL_JACOBIAN_STEP)));
+        }
+
+        let mut covariance = pixel_jacobian * pixel_cov * pixel_jacobian.transpose();
+
+        if let Some(rotation_cov) = rotation_cov {
+            let camera_ray = self.pixel_to_camera(self.undistort_pixel(pixel)?);
+            let project = |rotation: Vector3<f32>| {
+                let rotation = UnitQuaternion::new(rotation) * self.camera_to_ground.rotation;
+                self.ray_to_ground(rotation, camera_ray, z)
+            };
+
+            let mut rotation_jacobian = Matrix3::zeros();
+            for axis in 0..3 {
+                let step = Vector3::ith(axis, ROTATION_JACOBIAN_STEP);
+                let forward = project(step)?;
+                let backward = project(-step)?;
+
+                rotation_jacobian.set_column(
+                    axis,
+                    &((forward - backward) / (2.0 * ROTATION_JACOBIAN_STEP)),
+                );
+            }
+
+            covariance += rotation_jacobian * rotation_cov * rotation_jacobian.transpose();
+        }
+
+        Ok((point, covariance))
+    }
+
+    /// Intersect a ray from the camera with the ground plane at a given height, where the camera
+    /// is rotated by `rotation` relative to the ground.
+    fn ray_to_ground(
+        &self,
+        rotation: UnitQuaternion<f32>,
+        camera_ray: Vector3<f32>,
+        z: f32,
+    ) -> Result<Point3<f32>> {
+        let camera_ray_over_ground = rotation * camera_ray;
 
         if camera_ray_over_ground.z >= 0.0
             || camera_ray_over_ground.x.is_nan()
@@ -123,6 +334,8 @@ impl<T: CameraLocation> CameraMatrix<T> {
     /// Project a point in the ground frame to a pixel in the image plane.
     ///
     /// This is done by first transforming the point to the camera frame and then projecting it to the image plane.
+    /// The lens distortion is applied to the projected pixel, so this is the inverse of
+    /// [`CameraMatrix::pixel_to_ground`].
     ///
     /// # Errors
     /// This fails if the point is behind the camera.
@@ -130,10 +343,286 @@ impl<T: CameraLocation> CameraMatrix<T> {
         self.camera_to_pixel((self.camera_to_ground.inverse() * ground_coordinates).coords)
     }
 
+    /// Project a line segment in the ground frame to a line segment in the image plane.
+    ///
+    /// The segment is clipped against the near plane of the camera before it is projected, so a
+    /// segment that is only partially in front of the camera is projected up to the point where
+    /// it leaves the view, instead of being dropped entirely.
+    ///
+    /// Returns the start and end pixel of the projected segment, or [`None`] if the whole segment
+    /// is behind the camera.
+    #[must_use]
+    pub fn project_segment(
+        &self,
+        start: Point3<f32>,
+        end: Point3<f32>,
+    ) -> Option<(Point2<f32>, Point2<f32>)> {
+        let ground_to_camera = self.camera_to_ground.inverse();
+        let mut start = ground_to_camera * start;
+        let mut end = ground_to_camera * end;
+
+        if start.x < NEAR_PLANE_DISTANCE && end.x < NEAR_PLANE_DISTANCE {
+            return None;
+        }
+
+        let clip = |behind: Point3<f32>, in_front: Point3<f32>| {
+            let t = (NEAR_PLANE_DISTANCE - behind.x) / (in_front.x - behind.x);
+            behind + (in_front - behind) * t
+        };
+
+        if start.x < NEAR_PLANE_DISTANCE {
+            start = clip(start, end);
+        } else if end.x < NEAR_PLANE_DISTANCE {
+            end = clip(end, start);
+        }
+
+        Some((
+            self.camera_to_pixel(start.coords).ok()?,
+            self.camera_to_pixel(end.coords).ok()?,
+        ))
+    }
+
+    /// The horizontal field of view of the camera in radians.
+    #[must_use]
+    pub fn horizontal_fov(&self) -> f32 {
+        self.field_of_view.x
+    }
+
+    /// The vertical field of view of the camera in radians.
+    #[must_use]
+    pub fn vertical_fov(&self) -> f32 {
+        self.field_of_view.y
+    }
+
+    /// The area of the ground that is visible in the image, as a polygon in the ground frame.
+    ///
+    /// The vertices are the ground projections of the image corners, in the order top left, top
+    /// right, bottom right, bottom left. Corners above the horizon, or that project further than
+    /// [`FAR_PLANE_DISTANCE`] from the camera, are clamped to that distance in the direction they
+    /// are looking at. So when the horizon cuts through the image, the polygon is bounded by the
+    /// far distance instead of extending to infinity.
+    #[must_use]
+    pub fn ground_fov_polygon(&self) -> Vec<Point2<f32>> {
+        let (width, height) = (self.image_size.x, self.image_size.y);
+        let camera_position = self.camera_to_ground.translation.vector;
+
+        [
+            point![0.0, 0.0],
+            point![width, 0.0],
+            point![width, height],
+            point![0.0, height],
+        ]
+        .into_iter()
+        .map(|corner| {
+            let pixel = self.undistort_pixel(corner).unwrap_or(corner);
+            let ray = self.camera_to_ground.rotation * self.pixel_to_camera(pixel);
+
+            let far_point = || {
+                let direction = ray.xy().try_normalize(f32::EPSILON).unwrap_or_default();
+                Point2::from(camera_position.xy() + direction * FAR_PLANE_DISTANCE)
+            };
+
+            if ray.z >= 0.0 {
+                return far_point();
+            }
+
+            let offset = (ray * (-camera_position.z / ray.z)).xy();
+            if offset.norm() > FAR_PLANE_DISTANCE {
+                return far_point();
+            }
+
+            Point2::from(camera_position.xy() + offset)
+        })
+        .collect()
+    }
+
     fn compute_field_of_view(focal_lengths: Vector2<f32>, image_dim: Vector2<f32>) -> Vector2<f32> {
         Vector2::new(
-            2.0 * (focal_lengths.x / image_dim.x).atan(),
-            2.0 * (focal_lengths.y / image_dim.y).atan(),
+            2.0 * (image_dim.x / (2.0 * focal_lengths.x)).atan(),
+            2.0 * (image_dim.y / (2.0 * focal_lengths.y)).atan(),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Top;
+
+    /// A camera at the origin of the ground frame, looking along the x-axis.
+    fn camera_matrix() -> CameraMatrix<Top> {
+        CameraMatrix::new(
+            vector![500.0, 500.0],
+            point![320.0, 240.0],
+            vector![640.0, 480.0],
+            Isometry3::identity(),
+            Isometry3::identity(),
+            Isometry3::identity(),
+        )
+    }
+
+    #[test]
+    fn project_segment_in_front() {
+        let matrix = camera_matrix();
+        let (start, end) = matrix
+            .project_segment(point![1.0, 0.5, 0.0], point![2.0, -0.5, 0.0])
+            .expect("segment is in front of the camera");
+
+        assert_eq!(
+            start,
+            matrix.ground_to_pixel(point![1.0, 0.5, 0.0]).unwrap()
+        );
+        assert_eq!(end, matrix.ground_to_pixel(point![2.0, -0.5, 0.0]).unwrap());
+    }
+
+    #[test]
+    fn project_segment_clips_near_plane() {
+        let matrix = camera_matrix();
+
+        assert!(
+            matrix
+                .project_segment(point![-1.0, 0.5, 0.0], point![-2.0, -0.5, 0.0])
+                .is_none()
+        );
+
+        let (start, end) = matrix
+            .project_segment(point![-1.0, 0.0, -0.5], point![1.0, 0.0, -0.5])
+            .expect("segment is partially in front of the camera");
+
+        assert_eq!(end, matrix.ground_to_pixel(point![1.0, 0.0, -0.5]).unwrap());
+        // the clipped endpoint lies far below the end, towards the bottom of the image
+        assert!(start.y > end.y);
+        assert!(start.y.is_finite());
+    }
+
+    #[test]
+    fn ground_fov_polygon_of_tilted_camera() {
+        // half a meter above the ground, pitched down by 45 degrees
+        let matrix = CameraMatrix::<Top>::new(
+            vector![500.0, 500.0],
+            point![320.0, 240.0],
+            vector![640.0, 480.0],
+            Isometry3::rotation(vector![0.0, std::f32::consts::FRAC_PI_4, 0.0]),
+            Isometry3::identity(),
+            Isometry3::translation(0.0, 0.0, 0.5),
+        );
+
+        assert!((matrix.horizontal_fov() - 2.0 * 0.64_f32.atan()).abs() < 1e-6);
+        assert!((matrix.vertical_fov() - 2.0 * 0.48_f32.atan()).abs() < 1e-6);
+
+        let polygon = matrix.ground_fov_polygon();
+        let corners = [
+            point![0.0, 0.0],
+            point![640.0, 0.0],
+            point![640.0, 480.0],
+            point![0.0, 480.0],
+        ];
+        for (vertex, corner) in polygon.iter().zip(corners) {
+            let pixel = matrix
+                .ground_to_pixel(point![vertex.x, vertex.y, 0.0])
+                .unwrap();
+            assert!((pixel - corner).norm() < 1e-2, "{pixel} != {corner}");
+        }
+
+        // the top of the image looks further ahead, and the footprint is symmetric
+        assert!(polygon[0].x > polygon[3].x);
+        assert!((polygon[0].y + polygon[1].y).abs() < 1e-5);
+        assert!(polygon[0].x < FAR_PLANE_DISTANCE);
+
+        // a level camera sees the horizon in the middle of the image
+        let level = CameraMatrix::<Top>::new(
+            vector![500.0, 500.0],
+            point![320.0, 240.0],
+            vector![640.0, 480.0],
+            Isometry3::identity(),
+            Isometry3::identity(),
+            Isometry3::translation(0.0, 0.0, 0.5),
+        );
+        let polygon = level.ground_fov_polygon();
+        assert!((polygon[0].coords.norm() - FAR_PLANE_DISTANCE).abs() < 1e-4);
+        assert!((polygon[1].coords.norm() - FAR_PLANE_DISTANCE).abs() < 1e-4);
+        assert!(polygon[2].coords.norm() < FAR_PLANE_DISTANCE);
+    }
+
+    #[test]
+    fn distorted_ground_projection_round_trip() {
+        // half a meter above the ground, pitched down by 45 degrees, with a barrel distortion
+        let matrix = CameraMatrix::<Top>::new(
+            vector![500.0, 500.0],
+            point![320.0, 240.0],
+            vector![640.0, 480.0],
+            Isometry3::rotation(vector![0.0, std::f32::consts::FRAC_PI_4, 0.0]),
+            Isometry3::identity(),
+            Isometry3::translation(0.0, 0.0, 0.5),
+        )
+        .with_distortion(DistortionCoefficients {
+            k1: -0.1,
+            k2: 0.02,
+            k3: 0.0,
+            p1: 0.001,
+            p2: -0.001,
+        });
+
+        for pixel in [
+            point![320.0, 240.0],
+            point![20.0, 460.0],
+            point![600.0, 120.0],
+            point![100.0, 300.0],
+        ] {
+            let ground = matrix.pixel_to_ground(pixel, 0.0).unwrap();
+            let reprojected = matrix.ground_to_pixel(ground).unwrap();
+            assert!(
+                (reprojected - pixel).norm() < 0.1,
+                "{reprojected} != {pixel}"
+            );
+        }
+
+        // the barrel distortion pulls the raw pixels towards the optical center
+        let undistorted = matrix.undistort_pixel(point![20.0, 460.0]).unwrap();
+        assert!((undistorted - matrix.cc_optical_center).norm() > 372.0);
+        assert!((matrix.distort_pixel(undistorted) - point![20.0, 460.0]).norm() < 0.1);
+    }
+
+    #[test]
+    fn ground_covariance_grows_with_distance() {
+        // half a meter above the ground, pitched down by 45 degrees
+        let matrix = CameraMatrix::<Top>::new(
+            vector![500.0, 500.0],
+            point![320.0, 240.0],
+            vector![640.0, 480.0],
+            Isometry3::rotation(vector![0.0, std::f32::consts::FRAC_PI_4, 0.0]),
+            Isometry3::identity(),
+            Isometry3::translation(0.0, 0.0, 0.5),
+        );
+        let pixel_cov = Matrix2::identity() * 4.0;
+
+        let (near, near_cov) = matrix
+            .pixel_to_ground_with_cov(point![320.0, 400.0], 0.0, pixel_cov, None)
+            .unwrap();
+        let (far, far_cov) = matrix
+            .pixel_to_ground_with_cov(point![320.0, 100.0], 0.0, pixel_cov, None)
+            .unwrap();
+
+        assert_eq!(
+            near,
+            matrix.pixel_to_ground(point![320.0, 400.0], 0.0).unwrap()
+        );
+        assert!(far.x > near.x);
+        // the distance is the most uncertain, and the point stays on the ground plane
+        assert!(far_cov[(0, 0)] > near_cov[(0, 0)]);
+        assert!(far_cov[(0, 0)] > far_cov[(1, 1)]);
+        assert!(far_cov.row(2).norm() < f32::EPSILON);
+
+        // an uncertain orientation of the camera adds to the uncertainty
+        let (_, rotated_cov) = matrix
+            .pixel_to_ground_with_cov(
+                point![320.0, 100.0],
+                0.0,
+                pixel_cov,
+                Some(Matrix3::identity() * 1e-4),
+            )
+            .unwrap();
+        assert!(rotated_cov[(0, 0)] > far_cov[(0, 0)]);
+        assert!(rotated_cov[(1, 1)] > far_cov[(1, 1)]);
+    }
+}
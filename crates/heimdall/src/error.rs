@@ -30,6 +30,16 @@ pub enum Error {
         source: std::io::Error,
     },
 
+    #[error("Failed to read camera property `{property}`")]
+    ReadDeviceProperty {
+        property: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Camera property `{property}` is not supported by the device")]
+    Unsupported { property: String },
+
     #[error("Failed to set the device to video capture mode")]
     VideoCapture(#[source] io::Error),
 
@@ -45,6 +55,9 @@ pub enum Error {
     #[error(transparent)]
     Jpeg(#[from] turbojpeg::Error),
 
+    #[error(transparent)]
+    Png(#[from] png::EncodingError),
+
     #[error(transparent)]
     ImageBuffer(#[from] fir::ImageBufferError),
 
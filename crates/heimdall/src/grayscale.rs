@@ -0,0 +1,116 @@
+//! Bulk extraction of the luma plane of YUYV images.
+//!
+//! In a YUYV image every other byte is a luma value, so the grayscale image can be extracted by
+//! deinterleaving the bytes. With the `simd` feature enabled, this is done 16 pixels at a time
+//! using SSE2 on `x86_64` or NEON on `aarch64`, with a scalar fallback for other targets and the
+//! remaining pixels.
+
+/// Extracts the luma values of a YUYV buffer into `luma`.
+///
+/// # Panics
+///
+/// Panics if `yuyv` is not exactly twice as long as `luma`.
+pub fn extract_luma(yuyv: &[u8], luma: &mut [u8]) {
+    assert_eq!(
+        yuyv.len(),
+        luma.len() * 2,
+        "yuyv buffer must contain two bytes per luma value"
+    );
+
+    let processed = extract_luma_simd(yuyv, luma);
+    extract_luma_scalar(&yuyv[processed * 2..], &mut luma[processed..]);
+}
+
+/// Scalar version of [`extract_luma`], which is used for the pixels that do not fit in a SIMD
+/// register, and when the `simd` feature is disabled.
+///
+/// # Panics
+///
+/// Panics if `yuyv` is not exactly twice as long as `luma`.
+pub fn extract_luma_scalar(yuyv: &[u8], luma: &mut [u8]) {
+    assert_eq!(
+        yuyv.len(),
+        luma.len() * 2,
+        "yuyv buffer must contain two bytes per luma value"
+    );
+
+    for (y, pixel) in luma.iter_mut().zip(yuyv.chunks_exact(2)) {
+        *y = pixel[0];
+    }
+}
+
+/// Extracts the luma values in chunks of 16 pixels, returning the number of extracted pixels.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+fn extract_luma_simd(yuyv: &[u8], luma: &mut [u8]) -> usize {
+    use std::arch::x86_64::{
+        __m128i, _mm_and_si128, _mm_loadu_si128, _mm_packus_epi16, _mm_set1_epi16, _mm_storeu_si128,
+    };
+
+    let chunks = luma.len() / 16;
+
+    // SAFETY: SSE2 is always available on `x86_64`, the loads and stores are unaligned and stay
+    // within the bounds of the buffers, as `yuyv` is twice as long as `luma`.
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+        let mask = _mm_set1_epi16(0x00ff);
+
+        for chunk in 0..chunks {
+            let source = yuyv.as_ptr().add(chunk * 32);
+
+            // keep the low byte (luma) of every 16 bit lane and pack them into a single register
+            let low = _mm_and_si128(_mm_loadu_si128(source.cast::<__m128i>()), mask);
+            let high = _mm_and_si128(_mm_loadu_si128(source.add(16).cast::<__m128i>()), mask);
+
+            _mm_storeu_si128(
+                luma.as_mut_ptr().add(chunk * 16).cast::<__m128i>(),
+                _mm_packus_epi16(low, high),
+            );
+        }
+    }
+
+    chunks * 16
+}
+
+/// Extracts the luma values in chunks of 16 pixels, returning the number of extracted pixels.
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+fn extract_luma_simd(yuyv: &[u8], luma: &mut [u8]) -> usize {
+    use std::arch::aarch64::{vld2q_u8, vst1q_u8};
+
+    let chunks = luma.len() / 16;
+
+    // SAFETY: NEON is always available on `aarch64`, the loads and stores stay within the bounds
+    // of the buffers, as `yuyv` is twice as long as `luma`.
+    unsafe {
+        for chunk in 0..chunks {
+            // deinterleave the even (luma) and odd (chroma) bytes
+            let pixels = vld2q_u8(yuyv.as_ptr().add(chunk * 32));
+            vst1q_u8(luma.as_mut_ptr().add(chunk * 16), pixels.0);
+        }
+    }
+
+    chunks * 16
+}
+
+#[cfg(not(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+fn extract_luma_simd(_yuyv: &[u8], _luma: &mut [u8]) -> usize {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_scalar() {
+        // an odd number of pixels, so the scalar tail is exercised as well
+        let yuyv: Vec<u8> = (0..2 * 101).map(|i| (i * 7 % 256) as u8).collect();
+
+        let mut luma = vec![0; 101];
+        let mut expected = vec![0; 101];
+        extract_luma(&yuyv, &mut luma);
+        extract_luma_scalar(&yuyv, &mut expected);
+
+        assert_eq!(luma, expected);
+        assert!(luma.iter().zip(yuyv.iter().step_by(2)).all(|(a, b)| a == b));
+    }
+}
@@ -2,7 +2,10 @@ mod camera;
 pub use camera::{Bottom, Camera, CameraDevice, CameraLocation, CameraPosition, Top};
 
 mod camera_matrix;
-pub use camera_matrix::CameraMatrix;
+pub use camera_matrix::{CameraMatrix, DistortionCoefficients};
+
+mod grayscale;
+pub use grayscale::{extract_luma, extract_luma_scalar};
 
 mod yuyv_image;
 pub use yuyv_image::{YuvPixel, YuyvImage};
@@ -16,5 +19,8 @@ pub use rgb_image::RgbImage;
 mod exposure_weights;
 pub use exposure_weights::ExposureWeights;
 
+mod auto_exposure;
+pub use auto_exposure::{AutoExposureConvergence, ExposureReading};
+
 mod error;
 pub use error::{Error, Result};
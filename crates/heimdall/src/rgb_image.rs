@@ -1,5 +1,7 @@
 use std::ops::Deref;
 
+use crate::Result;
+
 /// An object that holds an RGB NAO camera image.
 pub struct RgbImage {
     pub(super) frame: Vec<u8>,
@@ -17,6 +19,44 @@ impl RgbImage {
     pub fn height(&self) -> usize {
         self.height
     }
+
+    /// Encode this [`RgbImage`] as a JPEG image.
+    ///
+    /// The quality of the JPEG image is determined by the `quality` parameter. The value should be
+    /// between 1 and 100, where 1 is the worst quality and 100 is the best quality.
+    ///
+    /// # Errors
+    /// This function fails if it cannot compress the image.
+    pub fn encode_jpeg(&self, quality: i32) -> Result<Vec<u8>> {
+        let image = turbojpeg::Image {
+            pixels: &*self.frame,
+            width: self.width,
+            pitch: self.width * 3,
+            height: self.height,
+            format: turbojpeg::PixelFormat::RGB,
+        };
+
+        let jpeg = turbojpeg::compress(image, quality, turbojpeg::Subsamp::Sub2x2)?;
+        Ok(jpeg.to_vec())
+    }
+
+    /// Encode this [`RgbImage`] as a lossless PNG image, e.g. for datasets.
+    ///
+    /// # Errors
+    /// This function fails if it cannot encode the image.
+    pub fn encode_png(&self) -> Result<Vec<u8>> {
+        let mut png = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut png, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.frame)?;
+        writer.finish()?;
+
+        Ok(png)
+    }
 }
 
 impl Deref for RgbImage {
@@ -26,3 +66,56 @@ impl Deref for RgbImage {
         &self.frame
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A smooth gradient, which compresses well as a JPEG.
+    fn gradient(width: usize, height: usize) -> RgbImage {
+        let mut frame = Vec::with_capacity(width * height * 3);
+        for y in 0..height {
+            for x in 0..width {
+                frame.extend([(x * 4) as u8, (y * 4) as u8, 128]);
+            }
+        }
+
+        RgbImage {
+            frame,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn jpeg_round_trip() {
+        let image = gradient(64, 48);
+
+        let jpeg = image.encode_jpeg(90).unwrap();
+        let decoded = turbojpeg::decompress(&jpeg, turbojpeg::PixelFormat::RGB).unwrap();
+
+        assert_eq!((decoded.width, decoded.height), (64, 48));
+        assert_eq!(decoded.pixels.len(), image.len());
+
+        let total_error: u32 = image
+            .iter()
+            .zip(&decoded.pixels)
+            .map(|(&a, &b)| u32::from(a.abs_diff(b)))
+            .sum();
+        let mean_error = total_error as f32 / image.len() as f32;
+        assert!(mean_error < 4.0, "mean error of {mean_error} is too large");
+    }
+
+    #[test]
+    fn png_is_lossless() {
+        let image = gradient(64, 48);
+
+        let png = image.encode_png().unwrap();
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut decoded = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut decoded).unwrap();
+
+        assert_eq!((info.width, info.height), (64, 48));
+        assert_eq!(&decoded[..info.buffer_size()], &*image);
+    }
+}
@@ -1,7 +1,9 @@
 use std::{io::Write, ops::Deref};
 
 use crate::Result;
+use crate::grayscale::extract_luma;
 use crate::rgb_image::RgbImage;
+use crate::yuv_planar_image::YuvPlanarImage;
 
 use fast_image_resize::{self as fir, ResizeOptions};
 use itertools::Itertools;
@@ -149,6 +151,35 @@ impl YuyvImage {
         })
     }
 
+    /// Encode this [`YuyvImage`] as a JPEG image, see [`RgbImage::encode_jpeg`].
+    ///
+    /// This compresses the YUV data directly, without converting it to RGB first.
+    ///
+    /// # Errors
+    /// This function fails if it cannot compress the image.
+    pub fn encode_jpeg(&self, quality: i32) -> Result<Vec<u8>> {
+        let jpeg = YuvPlanarImage::from_yuyv(self).to_jpeg(quality)?;
+        Ok(jpeg.to_vec())
+    }
+
+    /// Encode this [`YuyvImage`] as a lossless PNG image, see [`RgbImage::encode_png`].
+    ///
+    /// # Errors
+    /// This function fails if it cannot convert or encode the image.
+    pub fn encode_png(&self) -> Result<Vec<u8>> {
+        self.to_rgb()?.encode_png()
+    }
+
+    /// Extracts the luma plane of this [`YuyvImage`], as a grayscale image of `width * height`
+    /// bytes in row-major order.
+    #[must_use]
+    pub fn to_grayscale(&self) -> Vec<u8> {
+        let mut grayscale = vec![0; self.width * self.height];
+        extract_luma(self, &mut grayscale);
+
+        grayscale
+    }
+
     /// Resizes the image to the given width and height.
     ///
     /// Returns a *YUV444* vec of bytes.
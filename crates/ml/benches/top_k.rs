@@ -0,0 +1,40 @@
+//! Compares selecting the highest scoring candidates against sorting all of them.
+//!
+//! Run with `cargo bench -p ml --bench top_k`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use ml::util::top_k;
+
+const CANDIDATES: usize = 10_000;
+
+fn full_sort(scores: &[f32], k: usize) -> Vec<usize> {
+    let mut indices = (0..scores.len()).collect::<Vec<_>>();
+    indices.sort_unstable_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+    indices.truncate(k);
+    indices
+}
+
+fn bench_top_k(c: &mut Criterion) {
+    // pseudo-random scores, so the selection does not hit a presorted best case
+    let scores = (0..CANDIDATES)
+        .map(|i| ((i * 7919) % CANDIDATES) as f32 / CANDIDATES as f32)
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("top_k");
+    for k in [10, 100, 1000] {
+        assert_eq!(top_k(&scores, k), full_sort(&scores, k));
+
+        group.bench_with_input(BenchmarkId::new("select", k), &k, |b, &k| {
+            b.iter(|| top_k(black_box(&scores), k));
+        });
+        group.bench_with_input(BenchmarkId::new("full_sort", k), &k, |b, &k| {
+            b.iter(|| full_sort(black_box(&scores), k));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_top_k);
+criterion_main!(benches);
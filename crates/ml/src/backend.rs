@@ -6,7 +6,43 @@ use super::{
 };
 use bevy::prelude::*;
 use openvino::{Node, RwPropertyKey, Tensor};
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    marker::PhantomData,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Sentinel stored in [`InferenceTime`] until the first inference has completed.
+const NOT_RECORDED: u64 = u64::MAX;
+
+/// Wall-clock duration of the most recent inference, shared between a
+/// [`ModelExecutor`] and the [`InferRequest`]s it creates.
+#[derive(Debug)]
+struct InferenceTime(AtomicU64);
+
+impl Default for InferenceTime {
+    fn default() -> Self {
+        Self(AtomicU64::new(NOT_RECORDED))
+    }
+}
+
+impl InferenceTime {
+    fn store(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(NOT_RECORDED - 1);
+        self.0.store(nanos, Ordering::Relaxed);
+    }
+
+    fn load(&self) -> Option<Duration> {
+        match self.0.load(Ordering::Relaxed) {
+            NOT_RECORDED => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+}
 
 /// Wrapper around [`openvino::Core`], i.e. the `OpenVINO` engine.
 /// It's used for creating and using ML models.
@@ -58,6 +94,7 @@ pub struct ModelExecutor<M: MlModel> {
     // Descriptions of in- and output layer tensors
     input_descriptions: Arc<[TensorDescription]>,
     output_descriptions: Arc<[TensorDescription]>,
+    last_inference_time: Arc<InferenceTime>,
     _marker: PhantomData<M>,
 }
 
@@ -65,10 +102,15 @@ impl<M: MlModel> ModelExecutor<M> {
     /// # Errors
     ///
     /// Fails if:
+    /// - The model file does not exist.
     /// - The model cannot be loaded.
     /// - An inference request cannot be created, which
     ///   is needed to load relevant model settings.
     pub fn new(core: &mut Core) -> Result<Self> {
+        if !Path::new(M::ONNX_PATH).exists() {
+            return Err(Error::ModelNotFound { path: M::ONNX_PATH });
+        }
+
         let compiled_model = {
             // weights_path parameter is unused for ONNX
             let model =
@@ -88,6 +130,7 @@ impl<M: MlModel> ModelExecutor<M> {
             compiled_model,
             input_descriptions,
             output_descriptions,
+            last_inference_time: Arc::default(),
             _marker: PhantomData,
         })
     }
@@ -132,7 +175,12 @@ impl<M: MlModel> ModelExecutor<M> {
             let node = model.get_output_by_index(i)?;
             let tensor = TensorDescription::new(node)?;
 
-            if dtype != tensor.dtype {
+            // quantized outputs are dequantized to `f32` when they are fetched
+            let dequantized = M::OUTPUT_QUANTIZATION.is_some()
+                && dtype == openvino::ElementType::F32
+                && tensor.dtype == openvino::ElementType::U8;
+
+            if dtype != tensor.dtype && !dequantized {
                 return Err(Error::OutputType {
                     path: M::ONNX_PATH,
                     expected: dtype,
@@ -185,10 +233,19 @@ impl<M: MlModel> ModelExecutor<M> {
         Ok(InferRequest {
             request,
             output_descriptions,
+            last_inference_time: self.last_inference_time.clone(),
             _marker: PhantomData,
         })
     }
 
+    /// Wall-clock duration of the most recently completed inference of this model.
+    ///
+    /// Returns [`None`] if no inference has completed yet.
+    #[must_use]
+    pub fn last_inference_time(&self) -> Option<Duration> {
+        self.last_inference_time.load()
+    }
+
     /// Iterator over the input tensors.
     pub fn input_descriptions(&self) -> std::slice::Iter<TensorDescription> {
         self.input_descriptions.iter()
@@ -207,6 +264,7 @@ impl<M: MlModel> ModelExecutor<M> {
 pub struct InferRequest<M: MlModel> {
     request: openvino::InferRequest,
     output_descriptions: Arc<[TensorDescription]>,
+    last_inference_time: Arc<InferenceTime>,
     // note `fn() -> M` as opposed to just `M`, such that
     // `Self` implements Send, even though `M` does not
     //
@@ -217,42 +275,68 @@ pub struct InferRequest<M: MlModel> {
 impl<M: MlModel> InferRequest<M> {
     /// Runs inference.
     ///
+    /// On success the wall-clock duration of the inference is recorded, which can be
+    /// queried using [`ModelExecutor::last_inference_time`].
+    ///
     /// # Errors
     ///
     /// Returns an error if the inference fails for any reason.
     /// See [`Error`] for more details.
     pub fn run(mut self) -> Result<Self> {
+        let start = Instant::now();
         self.request.infer().map_err(Error::RunInference)?;
+        let elapsed = start.elapsed();
+
+        self.last_inference_time.store(elapsed);
+        tracing::debug!(model = M::ONNX_PATH, ?elapsed, "finished inference");
+
         Ok(self)
     }
 
     /// Fetches the output tensor.
     ///
-    /// # Panics
+    /// Quantized `u8` outputs of a model that declares [`MlModel::OUTPUT_QUANTIZATION`] are
+    /// dequantized to the `f32` outputs of the model.
     ///
-    /// - If the output tensor does not have the expected number of elements.
-    /// - If the output tensor is not found, which should never happen.
-    #[must_use]
-    pub fn fetch_output(self) -> M::Outputs {
-        let iter = self.output_descriptions.iter().map(|description| {
-            let output = self
-                .request
-                .get_tensor(description.name())
-                .expect("Cannot find output tensor!");
-
-            assert_eq!(
-                output.get_size().unwrap(),
-                description.num_elements(),
-                "Output does not have the expected number of elements!"
-            );
-
-            output
-        });
+    /// # Errors
+    ///
+    /// Fails if an output tensor cannot be read, or does not have the expected number of
+    /// elements.
+    pub fn fetch_output(self) -> Result<M::Outputs> {
+        let outputs = self
+            .output_descriptions
+            .iter()
+            .zip(M::Outputs::data_types())
+            .map(|(description, dtype)| {
+                let output = self.request.get_tensor(description.name())?;
+
+                let actual = output.get_size()?;
+                if actual != description.num_elements() {
+                    return Err(Error::OutputSize {
+                        path: M::ONNX_PATH,
+                        expected: description.num_elements(),
+                        actual,
+                    });
+                }
+
+                match M::OUTPUT_QUANTIZATION {
+                    Some(quantization) if dtype != description.dtype() => {
+                        let mut dequantized = Tensor::new(dtype, &description.shape)?;
+                        dequantized
+                            .get_data_mut::<f32>()?
+                            .copy_from_slice(&quantization.dequantize(output.get_data::<u8>()?));
+
+                        Ok(dequantized)
+                    }
+                    _ => Ok(output),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         // # Safety:
         //
         // If this fails I blame the openvino-rs developers
-        unsafe { M::Outputs::from_tensors(iter) }
+        Ok(unsafe { M::Outputs::from_tensors(outputs.into_iter()) })
     }
 }
 
@@ -307,3 +391,44 @@ impl TensorDescription {
         Tensor::new(self.dtype, &self.shape).expect("Failed to create tensor from description")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct BallClassifier;
+
+    impl MlModel for BallClassifier {
+        type Inputs = Vec<u8>;
+        type Outputs = f32;
+
+        const ONNX_PATH: &'static str = "../../deploy/models/ball_classifier.onnx";
+    }
+
+    #[test]
+    #[ignore = "requires the OpenVINO runtime"]
+    fn records_inference_time() {
+        let mut core = Core::new().expect("failed to create the OpenVINO core");
+        let mut executor = ModelExecutor::<BallClassifier>::new(&mut core)
+            .expect("failed to load the ball classifier");
+        assert_eq!(executor.last_inference_time(), None);
+
+        let input = executor
+            .input_descriptions()
+            .next()
+            .expect("the model has an input");
+        let input = vec![0; input.num_elements()];
+
+        executor
+            .request_infer(&input)
+            .and_then(InferRequest::run)
+            .and_then(InferRequest::fetch_output)
+            .expect("inference failed");
+
+        assert!(
+            executor
+                .last_inference_time()
+                .is_some_and(|duration| duration > Duration::ZERO)
+        );
+    }
+}
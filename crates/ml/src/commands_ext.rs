@@ -160,7 +160,7 @@ where
             .spawn_blocking(async move {
                 let output = request
                     .run()
-                    .map(InferRequest::fetch_output)
+                    .and_then(InferRequest::fetch_output)
                     .expect("failed to fetch output");
 
                 f(output)
@@ -193,7 +193,7 @@ where
             .to_resource()
             .spawn({
                 async move {
-                    let output = request.run().map(InferRequest::fetch_output).ok()?;
+                    let output = request.run().and_then(InferRequest::fetch_output).ok()?;
 
                     f(output)
                 }
@@ -221,7 +221,7 @@ where
             .to_entities()
             .spawn({
                 std::iter::once(async move {
-                    let output = request.run().map(InferRequest::fetch_output).ok()?;
+                    let output = request.run().and_then(InferRequest::fetch_output).ok()?;
 
                     f(output)
                 })
@@ -270,7 +270,7 @@ where
             .spawn({
                 requests.into_iter().map(move |request| async move {
                     let output = InferRequest::run(request)
-                        .map(InferRequest::fetch_output)
+                        .and_then(InferRequest::fetch_output)
                         .ok()?;
                     f(output)
                 })
@@ -9,6 +9,9 @@ pub enum Error {
     #[error("Failed to load OpenVINO core engine")]
     LoadCore(#[from] openvino::SetupError),
 
+    #[error("Model file `{path}` does not exist")]
+    ModelNotFound { path: &'static str },
+
     #[error("Failed to load model from `{path}`")]
     LoadModel {
         path: &'static str,
@@ -45,6 +48,13 @@ pub enum Error {
     #[error("Failed to run inference")]
     RunInference(#[source] openvino::InferenceError),
 
+    #[error("Output of `{path}` has {actual} elements, but {expected} were expected")]
+    OutputSize {
+        path: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+
     #[error("OpenVINO threw an unexpected error")]
     UnexpectedOpenvino(#[from] openvino::InferenceError),
 }
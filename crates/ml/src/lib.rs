@@ -5,18 +5,21 @@ mod backend;
 mod commands_ext;
 mod element;
 mod error;
+mod loader;
 pub mod util;
 
 use bevy::prelude::*;
 
-use backend::{Core, ModelExecutor};
+use backend::Core;
 use element::Parameters;
+use loader::{ModelLoader, ModelRegistry};
 
 #[allow(missing_docs)]
 pub mod prelude {
     pub use crate::backend::ModelExecutor;
     pub use crate::commands_ext::MlTaskCommandsExt;
     pub use crate::error::Error;
+    pub use crate::loader::{ModelLoadSummary, ModelStatus};
     pub use crate::util;
     pub use crate::{MlArray, MlModel, MlModelResourceExt, MlPlugin};
 }
@@ -26,14 +29,37 @@ pub type MlArray<E> = ndarray::ArrayD<E>;
 
 /// Plugin offering a high level API for ML inference,
 /// using the [OpenVINO](https://docs.openvino.ai/2023.3/home.html) runtime.
+///
+/// The models registered with [`MlModelResourceExt::init_ml_model`] are loaded concurrently once
+/// all plugins have been built, the results are stored in the
+/// [`ModelLoadSummary`](prelude::ModelLoadSummary) resource.
 pub struct MlPlugin;
 
 impl Plugin for MlPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.insert_resource(
             Core::new().expect("failed to initialize `MlCore` using the provided configuration!"),
-        );
+        )
+        .init_resource::<ModelRegistry>();
     }
+
+    fn finish(&self, app: &mut App) {
+        let Some(registry) = app.world_mut().remove_resource::<ModelRegistry>() else {
+            return;
+        };
+
+        let summary = loader::load_models(app.world_mut(), registry.0);
+        app.insert_resource(summary);
+    }
+}
+
+/// Run condition that is true if the [`ModelExecutor`](prelude::ModelExecutor) of the model
+/// has been loaded.
+///
+/// Systems that run inference should use this, so they are skipped when the model failed to load.
+#[must_use]
+pub fn model_loaded<M: MlModel>(executor: Option<Res<backend::ModelExecutor<M>>>) -> bool {
+    executor.is_some()
 }
 
 /// A machine learning model.
@@ -68,15 +94,26 @@ pub trait MlModel: Send + Sync + 'static {
 
     /// Path to the model's ONNX file.
     const ONNX_PATH: &'static str;
+
+    /// Quantization parameters of the model outputs, for models that produce quantized `u8` output.
+    ///
+    /// When set, the `u8` outputs of the model are dequantized when they are fetched, so the model
+    /// can declare `f32` [`MlModel::Outputs`].
+    const OUTPUT_QUANTIZATION: Option<util::Quantization> = None;
 }
 
 pub trait MlModelResourceExt {
-    /// Adds a [`ModelExecutor`] to the app that can run the given model type.
+    /// Registers the given model type, so a [`ModelExecutor`](prelude::ModelExecutor) that can
+    /// run it is added to the app.
+    ///
+    /// The model is loaded by the [`MlPlugin`] once all plugins have been built, concurrently
+    /// with the other registered models. If the model fails to load, the error is logged and the
+    /// model executor is not added, see [`model_loaded`].
     ///
     /// # Panics
     ///
-    /// - If the plugin is not initialized, this function will panic.
-    /// - If the model executor cannot be created, this function will panic.
+    /// Panics if the [`MlPlugin`] has not been added, or if the plugins have already been finished,
+    /// as the models are loaded at that point.
     fn init_ml_model<M>(&mut self) -> &mut Self
     where
         Self: Sized,
@@ -89,18 +126,15 @@ impl MlModelResourceExt for App {
         Self: Sized,
         M: MlModel + Send + Sync + 'static,
     {
-        let mut ml_core = self
-            .world_mut()
-            .get_resource_mut::<Core>()
-            .expect("the `MlCore` resource does not exist. Did you forget to add the `MlPlugin`?");
-
-        let model_executor = ModelExecutor::<M>::new(&mut ml_core).unwrap_or_else(|err| {
-            panic!(
-                "failed to create model executor for `{}`: {err:?  }",
-                M::ONNX_PATH
+        self.world_mut()
+            .get_resource_mut::<ModelRegistry>()
+            .expect(
+                "the `ModelRegistry` resource does not exist. Did you forget to add the `MlPlugin`, \
+                 or register the model after the plugins were finished?",
             )
-        });
+            .0
+            .push(ModelLoader::new::<M>());
 
-        self.insert_resource(model_executor)
+        self
     }
 }
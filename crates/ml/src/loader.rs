@@ -0,0 +1,201 @@
+//! Concurrent loading of the models registered with
+//! [`init_ml_model`](crate::MlModelResourceExt::init_ml_model), see [`ModelLoadSummary`].
+
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, TaskPool},
+};
+
+use super::{
+    MlModel,
+    backend::{Core, ModelExecutor},
+    error::Result,
+};
+
+/// Inserts a loaded model into the world.
+type InsertModel = Box<dyn FnOnce(&mut World) + Send>;
+
+/// A model that is loaded by the [`MlPlugin`](crate::MlPlugin) once all plugins have been built.
+pub(crate) struct ModelLoader {
+    path: &'static str,
+    load: Box<dyn FnOnce() -> Result<InsertModel> + Send + Sync>,
+}
+
+impl ModelLoader {
+    /// Creates a loader that compiles the model `M` and inserts its [`ModelExecutor`].
+    pub(crate) fn new<M: MlModel>() -> Self {
+        Self::from_fn(M::ONNX_PATH, || {
+            // every model gets its own core, so models can be compiled in parallel
+            let mut core = Core::new()?;
+            let executor = ModelExecutor::<M>::new(&mut core)?;
+
+            Ok(Box::new(move |world: &mut World| {
+                world.insert_resource(executor);
+            }))
+        })
+    }
+
+    fn from_fn(
+        path: &'static str,
+        load: impl FnOnce() -> Result<InsertModel> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            path,
+            load: Box::new(load),
+        }
+    }
+}
+
+/// The models that have been registered, but not loaded yet.
+#[derive(Resource, Default)]
+pub(crate) struct ModelRegistry(pub(crate) Vec<ModelLoader>);
+
+/// The result of loading a single model.
+#[derive(Debug, Clone)]
+pub struct ModelStatus {
+    /// Path to the ONNX file of the model.
+    pub path: &'static str,
+    /// How long it took to load the model, or to fail loading it.
+    pub load_time: Duration,
+    /// Why the model failed to load, or `None` if the model is live.
+    pub error: Option<String>,
+}
+
+impl ModelStatus {
+    /// Whether the model has been loaded, and can be used for inference.
+    #[must_use]
+    pub fn is_live(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Summary of loading all registered models at startup.
+///
+/// Models are loaded concurrently on the [`IoTaskPool`], so the total load time is usually a lot
+/// shorter than the sum of the load times of the individual models. A model that fails to load
+/// does not prevent the others from loading, its [`ModelExecutor`] is simply not inserted.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ModelLoadSummary {
+    /// The status of every registered model, in the order in which they were registered.
+    pub models: Vec<ModelStatus>,
+    /// How long it took to load all models.
+    pub total_load_time: Duration,
+}
+
+impl ModelLoadSummary {
+    /// The models that failed to load.
+    pub fn failed(&self) -> impl Iterator<Item = &ModelStatus> {
+        self.models.iter().filter(|model| !model.is_live())
+    }
+}
+
+/// Loads the models concurrently, and inserts the ones that loaded successfully into the world.
+pub(crate) fn load_models(world: &mut World, loaders: Vec<ModelLoader>) -> ModelLoadSummary {
+    let start = Instant::now();
+
+    let results = IoTaskPool::get_or_init(TaskPool::new).scope(|scope| {
+        for ModelLoader { path, load } in loaders {
+            scope.spawn(async move {
+                let start = Instant::now();
+                let result = load().map_err(|error| error_chain(&error));
+
+                (path, result, start.elapsed())
+            });
+        }
+    });
+
+    let mut models = Vec::with_capacity(results.len());
+    for (path, result, load_time) in results {
+        let error = match result {
+            Ok(insert) => {
+                insert(world);
+                tracing::info!(model = path, ?load_time, "loaded model");
+                None
+            }
+            Err(error) => {
+                tracing::error!(model = path, ?load_time, "failed to load model: {error}");
+                Some(error)
+            }
+        };
+
+        models.push(ModelStatus {
+            path,
+            load_time,
+            error,
+        });
+    }
+
+    let summary = ModelLoadSummary {
+        models,
+        total_load_time: start.elapsed(),
+    };
+    tracing::info!(
+        total_load_time = ?summary.total_load_time,
+        "loaded {} of {} models",
+        summary.models.len() - summary.failed().count(),
+        summary.models.len(),
+    );
+
+    summary
+}
+
+/// Formats an error together with all of its sources.
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+
+    let mut source = error.source();
+    while let Some(error) = source {
+        let _ = write!(message, ": {error}");
+        source = error.source();
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[derive(Resource)]
+    struct BallModel;
+
+    #[test]
+    fn missing_model_does_not_prevent_others_from_loading() {
+        let mut world = World::new();
+
+        let loaders = vec![
+            ModelLoader::from_fn("models/ball.onnx", || {
+                Ok(Box::new(|world: &mut World| {
+                    world.insert_resource(BallModel);
+                }))
+            }),
+            ModelLoader::from_fn("models/missing.onnx", || {
+                Err(Error::ModelNotFound {
+                    path: "models/missing.onnx",
+                })
+            }),
+        ];
+
+        let summary = load_models(&mut world, loaders);
+
+        assert!(world.contains_resource::<BallModel>());
+        assert_eq!(summary.models.len(), 2);
+        assert!(summary.models[0].is_live());
+
+        let failed = summary.failed().collect::<Vec<_>>();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].path, "models/missing.onnx");
+        assert!(
+            failed[0]
+                .error
+                .as_ref()
+                .is_some_and(|error| error.contains("models/missing.onnx"))
+        );
+    }
+}
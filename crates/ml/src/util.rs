@@ -2,6 +2,8 @@
 
 use fast_image_resize::{self as fir, ResizeOptions};
 
+use crate::MlArray;
+
 /// Returns the index of the maximum element in a [`Vec`].
 ///
 /// # Panics
@@ -17,20 +19,137 @@ pub fn argmax(v: &[f32]) -> usize {
 }
 
 /// Returns the softmax of [`Vec`].
+///
+/// The maximum is subtracted from every element before exponentiating, so large logits do not
+/// overflow.
 #[must_use]
 pub fn softmax(v: &[f32]) -> Vec<f32> {
-    let exps = v.iter().map(|f| f.exp()).collect::<Vec<_>>();
+    let max = v.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exps = v.iter().map(|f| (f - max).exp()).collect::<Vec<_>>();
 
     let sum: f32 = exps.iter().sum();
     exps.iter().map(|x| x / sum).collect()
 }
 
+/// Returns the flat index of the maximum element in an [`MlArray`].
+///
+/// Multi-dimensional arrays are treated as a flattened view in logical (row-major) order, so the
+/// returned index can be converted back using the shape of the array.
+///
+/// # Panics
+///
+/// If the input array is empty this function will panic.
+#[must_use]
+pub fn argmax_array(arr: &MlArray<f32>) -> usize {
+    arr.iter()
+        .enumerate()
+        .max_by(|(_, v1), (_, v2)| v1.total_cmp(v2))
+        .expect("argmax: empty array")
+        .0
+}
+
+/// Returns the softmax of an [`MlArray`], with the same shape as the input.
+///
+/// The softmax is taken over all elements of the array, multi-dimensional arrays are not
+/// normalized per axis. The maximum is subtracted from every element before exponentiating, so
+/// large logits do not overflow.
+#[must_use]
+pub fn softmax_array(arr: &MlArray<f32>) -> MlArray<f32> {
+    let max = arr.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exps = arr.mapv(|f| (f - max).exp());
+
+    let sum = exps.sum();
+    exps / sum
+}
+
+/// Returns the indices of the `k` highest elements in a [`Vec`], in descending order of value.
+///
+/// Only the selected elements are sorted, which is significantly cheaper than sorting all
+/// elements when `k` is much smaller than the number of elements.
+///
+/// If `k` is larger than the number of elements, all indices are returned.
+#[must_use]
+pub fn top_k(v: &[f32], k: usize) -> Vec<usize> {
+    let mut indices = (0..v.len()).collect::<Vec<_>>();
+    let descending = |a: &usize, b: &usize| v[*b].total_cmp(&v[*a]);
+
+    if k == 0 {
+        return Vec::new();
+    }
+
+    if k < indices.len() {
+        indices.select_nth_unstable_by(k - 1, descending);
+        indices.truncate(k);
+    }
+
+    indices.sort_unstable_by(descending);
+    indices
+}
+
+/// Returns the `k` highest elements of an [`MlArray`] with their flat index, in descending order.
+///
+/// Multi-dimensional arrays are treated as a flattened view in logical (row-major) order.
+/// If `k` is larger than the number of elements, all elements are returned.
+#[must_use]
+pub fn top_k_array(arr: &MlArray<f32>, k: usize) -> Vec<(usize, f32)> {
+    let elements = arr.iter().copied().collect::<Vec<_>>();
+
+    top_k(&elements, k)
+        .into_iter()
+        .map(|index| (index, elements[index]))
+        .collect()
+}
+
 /// Computes the sigmoid score of the provided logit.
 #[must_use]
 pub fn sigmoid(logit: f32) -> f32 {
     1.0 / (1.0 + (-logit).exp())
 }
 
+/// Calibrates logits using temperature scaling.
+///
+/// Each logit is divided by the temperature, which softens (`temperature > 1.0`) or sharpens
+/// (`temperature < 1.0`) the resulting confidences without changing their order. A temperature of
+/// `1.0` leaves the logits unchanged.
+///
+/// The scores are expected to be logits, use [`calibrate_probabilities`] for scores that already
+/// went through a sigmoid.
+///
+/// # Panics
+///
+/// This function panics if the temperature is not strictly positive.
+#[must_use]
+pub fn calibrate_scores(scores: &[f32], temperature: f32) -> Vec<f32> {
+    assert!(temperature > 0.0, "temperature must be strictly positive");
+
+    scores.iter().map(|score| score / temperature).collect()
+}
+
+/// Calibrates probabilities using temperature scaling.
+///
+/// The probabilities are converted back to logits, scaled using [`calibrate_scores`], and mapped
+/// to probabilities again using a [`sigmoid`]. This is equivalent to Platt scaling without a bias.
+///
+/// Probabilities of exactly `0.0` or `1.0` have an infinite logit, so the probabilities are clamped
+/// to be at least [`f32::EPSILON`] away from those.
+///
+/// # Panics
+///
+/// This function panics if the temperature is not strictly positive.
+#[must_use]
+pub fn calibrate_probabilities(probabilities: &[f32], temperature: f32) -> Vec<f32> {
+    let logits = probabilities
+        .iter()
+        .map(|probability| probability.clamp(f32::EPSILON, 1.0 - f32::EPSILON))
+        .map(|probability| (probability / (1.0 - probability)).ln())
+        .collect::<Vec<_>>();
+
+    calibrate_scores(&logits, temperature)
+        .into_iter()
+        .map(sigmoid)
+        .collect()
+}
+
 /// Resizes a grayscale patch to the target size.
 ///
 /// # Panics
@@ -65,3 +184,174 @@ pub fn resize_patch(original: (usize, usize), target: (usize, usize), patch: Vec
 
     dst_image.buffer().to_vec()
 }
+
+/// Affine quantization parameters of an 8-bit quantized tensor.
+///
+/// A real value `x` is represented by the quantized value `q` as `x = scale * (q - zero_point)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantization {
+    /// Step size between two consecutive quantized values.
+    pub scale: f32,
+    /// Quantized value that represents the real value `0.0`.
+    pub zero_point: u8,
+}
+
+impl Quantization {
+    /// Creates new quantization parameters.
+    #[must_use]
+    pub const fn new(scale: f32, zero_point: u8) -> Self {
+        Self { scale, zero_point }
+    }
+
+    /// Quantizes the values, see [`quantize`].
+    #[must_use]
+    pub fn quantize(&self, values: &[f32]) -> Vec<u8> {
+        quantize(values, self.scale, self.zero_point)
+    }
+
+    /// Dequantizes the values, see [`dequantize`].
+    #[must_use]
+    pub fn dequantize(&self, values: &[u8]) -> Vec<f32> {
+        dequantize(values, self.scale, self.zero_point)
+    }
+}
+
+/// Quantizes real values to `u8` using the provided scale and zero point.
+///
+/// Values are rounded to the nearest quantized value, with ties rounded away from zero,
+/// and clamped to the `u8` range.
+#[must_use]
+pub fn quantize(values: &[f32], scale: f32, zero_point: u8) -> Vec<u8> {
+    values
+        .iter()
+        .map(|value| {
+            let quantized = (value / scale).round() + f32::from(zero_point);
+            quantized.clamp(f32::from(u8::MIN), f32::from(u8::MAX)) as u8
+        })
+        .collect()
+}
+
+/// Dequantizes `u8` values to real values using the provided scale and zero point.
+#[must_use]
+pub fn dequantize(values: &[u8], scale: f32, zero_point: u8) -> Vec<f32> {
+    values
+        .iter()
+        .map(|&value| (f32::from(value) - f32::from(zero_point)) * scale)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{ArrayD, IxDyn};
+
+    use super::*;
+
+    #[test]
+    fn softmax_large_logits() {
+        let logits =
+            ArrayD::from_shape_vec(IxDyn(&[2, 2]), vec![1000.0, 1001.0, 999.0, 1000.0]).unwrap();
+        let probabilities = softmax_array(&logits);
+
+        assert_eq!(probabilities.shape(), logits.shape());
+        assert!(probabilities.iter().all(|p| p.is_finite()));
+        assert!((probabilities.sum() - 1.0).abs() < 1e-6);
+        assert_eq!(argmax_array(&probabilities), 1);
+        assert_eq!(softmax(&[1000.0, 1000.0]), [0.5, 0.5]);
+    }
+
+    #[test]
+    fn top_k_bounds() {
+        let logits = ArrayD::from_shape_vec(IxDyn(&[4]), vec![0.5, 3.0, -1.0, 2.0]).unwrap();
+
+        assert_eq!(top_k_array(&logits, 2), [(1, 3.0), (3, 2.0)]);
+        assert_eq!(top_k_array(&logits, 10).len(), 4);
+        assert!(top_k_array(&logits, 0).is_empty());
+        assert!(top_k_array(&ArrayD::zeros(IxDyn(&[0])), 3).is_empty());
+        assert_eq!(softmax_array(&ArrayD::zeros(IxDyn(&[0]))).len(), 0);
+    }
+
+    #[test]
+    fn top_k_descending() {
+        let scores = [0.1, 0.9, 0.4, 0.7, 0.3];
+
+        assert_eq!(top_k(&scores, 3), [1, 3, 2]);
+        assert_eq!(top_k(&scores, 1), [1]);
+        assert!(top_k(&scores, 0).is_empty());
+    }
+
+    #[test]
+    fn top_k_larger_than_input() {
+        let scores = [0.2, 0.5, 0.1];
+
+        assert_eq!(top_k(&scores, 10), [1, 0, 2]);
+        assert!(top_k(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn calibrate_scores_identity() {
+        let logits = [-3.5, -0.25, 0.0, 1.0, 7.0];
+
+        assert_eq!(calibrate_scores(&logits, 1.0), logits);
+    }
+
+    #[test]
+    fn calibrate_scores_monotonic() {
+        let logits = [-3.5, -0.25, 0.0, 1.0, 7.0];
+        let probabilities = logits.map(sigmoid);
+
+        for temperature in [0.5, 1.0, 2.5] {
+            let calibrated = calibrate_scores(&logits, temperature);
+            assert!(calibrated.is_sorted());
+
+            let calibrated = calibrate_probabilities(&probabilities, temperature);
+            assert!(calibrated.is_sorted());
+        }
+    }
+
+    #[test]
+    fn calibrate_probabilities_saturated() {
+        for temperature in [0.5, 1.0, 2.5] {
+            let calibrated = calibrate_probabilities(&[0.0, 0.5, 1.0], temperature);
+
+            assert!(calibrated.iter().all(|p| (0.0..=1.0).contains(p)));
+            assert!(calibrated.is_sorted());
+            assert!((calibrated[1] - 0.5).abs() < 1e-6);
+
+            // saturated probabilities are treated as the closest probabilities with a finite logit
+            assert_eq!(
+                calibrate_probabilities(&[0.0, 1.0], temperature),
+                calibrate_probabilities(&[f32::EPSILON, 1.0 - f32::EPSILON], temperature)
+            );
+        }
+    }
+
+    #[test]
+    fn quantize_rounds_to_nearest() {
+        assert_eq!(
+            quantize(&[0.0, 0.1, 0.24, 0.25, -0.25], 0.1, 10),
+            [10, 11, 12, 13, 7]
+        );
+    }
+
+    #[test]
+    fn quantize_clamps_to_range() {
+        assert_eq!(quantize(&[-100.0, 100.0], 0.5, 128), [0, 255]);
+        assert_eq!(quantize(&[-64.0, 63.5], 0.5, 128), [0, 255]);
+    }
+
+    #[test]
+    fn dequantize_known_values() {
+        assert_eq!(dequantize(&[0, 128, 255], 0.5, 128), [-64.0, 0.0, 63.5]);
+    }
+
+    #[test]
+    fn quantization_roundtrip() {
+        let quantization = Quantization::new(0.25, 100);
+        let values = [-25.0, -1.0, 0.0, 0.75, 38.75];
+
+        assert_eq!(
+            quantization.dequantize(&quantization.quantize(&values)),
+            values
+        );
+    }
+}
@@ -112,7 +112,7 @@ pub trait DisconnectExt {
 }
 
 /// High level representation of the `LoLA` state message.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Resource))]
 pub struct NaoState {
@@ -63,8 +63,33 @@ pub enum ErrorKind {
         main_value: Value,
         overlay_value: Value,
     },
+    #[error("Overlay changes locked key `{key}`, which can only be set in the main config")]
+    LockedKey {
+        key: String,
+        main_value: Value,
+        overlay_value: Value,
+    },
     #[error("Failed to parse subtable `{key}` in overlay")]
     Subtable { key: String, source: Box<ErrorKind> },
+    #[error("Failed to validate config")]
+    Validation(#[from] ValidationError),
+}
+
+/// Error returned by [`Config::validate`] when a config violates one of its invariants
+#[derive(Debug, Error, Diagnostic)]
+#[error("{message}")]
+pub struct ValidationError {
+    pub message: String,
+}
+
+impl ValidationError {
+    /// Create a validation error with a message describing the violated invariant
+    #[must_use]
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
 }
 
 /// Error type for an odal config
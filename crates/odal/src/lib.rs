@@ -8,53 +8,156 @@ pub use error::*;
 use std::{
     any::type_name,
     fs::{self, read_to_string},
+    io,
     path::Path,
 };
 
 use serde::{Deserialize, Serialize};
-use toml::Table;
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, Item};
 
 /// Trait that defines a configuration file for the implementor
 pub trait Config: for<'de> Deserialize<'de> + Serialize {
     /// The relative path from which the configuration should be loaded
     const PATH: &'static str;
 
+    /// Keys that may not be changed by an overlay
+    ///
+    /// This is meant for structural keys that other parts of the system rely on, such as a camera
+    /// resolution, whereas tunable parameters should remain overlayable. Keys in nested tables are
+    /// written with dots, e.g. `camera.width`, and locking a table locks all keys within it.
+    /// The keys within a locked table are checked one by one, so an overlay may still repeat part
+    /// of the table with the same values.
+    const LOCKED_KEYS: &'static [&'static str] = &[];
+
     /// The name of the configuration
     #[must_use]
     fn name() -> &'static str {
         type_name::<Self>()
     }
 
+    /// Whether the dotted `key` is locked by [`Config::LOCKED_KEYS`], either directly or because
+    /// it is within a locked table
+    #[must_use]
+    fn is_locked(key: &str) -> bool {
+        Self::LOCKED_KEYS.iter().any(|locked| {
+            key.strip_prefix(locked)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    }
+
+    /// Checks invariants of the configuration that cannot be expressed in its type
+    ///
+    /// This is called after a configuration is loaded or merged with an overlay.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error describing the violated invariant.
+    fn validate(&self) -> std::result::Result<(), ValidationError> {
+        Ok(())
+    }
+
     /// Loads a configuration from a path
     ///
     /// # Errors
     ///
-    /// This function will return an error if the configuration cannot be loaded.
+    /// This function will return an error if the configuration cannot be loaded or is invalid.
     fn load(path: impl AsRef<Path>) -> Result<Self> {
         let main = load_table::<Self>(path.as_ref(), ConfigKind::Main)?;
 
-        main.try_into()
-            .map_err(|e| Error::deserialize::<Self>(path.as_ref(), &e))
+        let config = main
+            .try_into()
+            .map_err(|e| Error::deserialize::<Self>(path.as_ref(), &e))?;
+
+        validate(config)
+    }
+
+    /// Loads a configuration from a path, or creates it from [`Default`] if the file does not exist
+    ///
+    /// The default configuration is written to the path, so it can be edited afterwards.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration exists but cannot be loaded,
+    /// or if the default configuration cannot be stored.
+    fn load_or_default(path: impl AsRef<Path>) -> Result<Self>
+    where
+        Self: Default,
+    {
+        let path = path.as_ref();
+
+        match Self::load(path) {
+            Err(Error {
+                kind: ErrorKind::Load { source, .. },
+                ..
+            }) if source.kind() == io::ErrorKind::NotFound => {
+                let full_path = path.join(Self::PATH);
+
+                if let Some(parent) = full_path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| {
+                        Error::from_kind::<Self>(ErrorKind::Store {
+                            path: parent.display().to_string(),
+                            source: e,
+                        })
+                    })?;
+                }
+
+                let config = Self::default();
+                config.store(&full_path)?;
+
+                tracing::info!(
+                    "`{}`: Created default config at `{}`",
+                    Self::name(),
+                    full_path.display()
+                );
+
+                Ok(config)
+            }
+            result => result,
+        }
     }
 
     /// Loads a configuration from two paths and overlays values from the second over the first
     ///
     /// # Errors
     ///
-    /// This function will return an error if the configuration cannot be loaded or merged.
+    /// This function will return an error if the configuration cannot be loaded or merged,
+    /// or if the merged configuration is invalid.
     fn load_with_overlay(
         main_path: impl AsRef<Path>,
         overlay_path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        Self::load_with_overlays(main_path, [overlay_path])
+    }
+
+    /// Loads a configuration from a path and overlays the values from every overlay path over it
+    ///
+    /// The overlays are applied in order, so a value in a later overlay takes precedence over the
+    /// same value in an earlier one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration or any of the overlays cannot be
+    /// loaded or merged, or if the merged configuration is invalid.
+    fn load_with_overlays<P: AsRef<Path>>(
+        main_path: impl AsRef<Path>,
+        overlay_paths: impl IntoIterator<Item = P>,
     ) -> Result<Self> {
         let mut main = load_table::<Self>(main_path, ConfigKind::Main)?;
-        let mut overlay = load_table::<Self>(overlay_path, ConfigKind::Overlay)?;
 
-        merge_tables::<Self>(&mut main, &mut overlay)?;
-        from_table::<Self>(main)
+        for overlay_path in overlay_paths {
+            let mut overlay = load_table::<Self>(overlay_path, ConfigKind::Overlay)?;
+            merge_tables::<Self>(&mut main, &mut overlay, "")?;
+        }
+
+        validate(from_table::<Self>(main)?)
     }
 
     /// Stores the configuration in a file at the specified path
     ///
+    /// If the file already exists, only the values that changed are rewritten, so comments and
+    /// the order of the keys in the file are preserved.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the configuration cannot be serialized or written to the file.
@@ -64,6 +167,11 @@ pub trait Config: for<'de> Deserialize<'de> + Serialize {
         let config_string = toml::to_string_pretty(self)
             .map_err(|e| Error::from_kind::<Self>(ErrorKind::Serialize(e)))?;
 
+        let config_string = match read_to_string(path) {
+            Ok(existing) => update_document::<Self>(&existing, &config_string),
+            Err(_) => config_string,
+        };
+
         fs::write(path, config_string).map_err(|e| {
             Error::from_kind::<Self>(ErrorKind::Store {
                 path: path.display().to_string(),
@@ -94,9 +202,12 @@ fn load_table<T: Config>(path: impl AsRef<Path>, config_kind: ConfigKind) -> Res
 
 /// Overlay values from the overlay into the main table.
 ///
+/// The `table_path` is the dotted path of the main table within the config, which is used to look
+/// up the [`Config::LOCKED_KEYS`].
+///
 /// # Warning ⚠️
 /// This function swaps values between tables and therefore leaves the overlay table in a garbage state.
-fn merge_tables<T: Config>(main: &mut Table, overlay: &mut Table) -> Result<()> {
+fn merge_tables<T: Config>(main: &mut Table, overlay: &mut Table, table_path: &str) -> Result<()> {
     // check if the overlay doesn't contain any keys that don't exist in the main overlay,
     // which might be indicative of an error made when configuring the overlay
     for (key, value) in overlay.iter() {
@@ -123,11 +234,27 @@ fn merge_tables<T: Config>(main: &mut Table, overlay: &mut Table) -> Result<()>
             }));
         }
 
+        let key_path = if table_path.is_empty() {
+            key.clone()
+        } else {
+            format!("{table_path}.{key}")
+        };
+
+        // locked values may only be repeated, not changed
+        if !value.is_table() && T::is_locked(&key_path) && value != overlay_value {
+            return Err(Error::from_kind::<T>(ErrorKind::LockedKey {
+                key: key_path,
+                main_value: value.clone(),
+                overlay_value: overlay_value.clone(),
+            }));
+        }
+
         if value.is_table() {
             // recursively merge tables
             merge_tables::<T>(
                 value.as_table_mut().unwrap(),
                 overlay_value.as_table_mut().unwrap(),
+                &key_path,
             )
             .map_err(|e| {
                 Error::from_kind::<T>(ErrorKind::Subtable {
@@ -144,9 +271,289 @@ fn merge_tables<T: Config>(main: &mut Table, overlay: &mut Table) -> Result<()>
     Ok(())
 }
 
+/// Updates the existing toml document with the values of the new one, keeping the comments and
+/// formatting of the existing document for all values that did not change.
+///
+/// Falls back to the new document if either of them cannot be parsed.
+fn update_document<T: Config>(existing: &str, new: &str) -> String {
+    let parsed = (
+        existing.parse::<DocumentMut>(),
+        existing.parse::<Table>(),
+        new.parse::<DocumentMut>(),
+        new.parse::<Table>(),
+    );
+
+    let (Ok(mut document), Ok(old_values), Ok(new_document), Ok(new_values)) = parsed else {
+        tracing::warn!(
+            "`{}`: Failed to parse existing config, overwriting it",
+            T::name()
+        );
+        return new.to_string();
+    };
+
+    update_table(
+        document.as_table_mut(),
+        &old_values,
+        &new_values,
+        new_document.as_table(),
+    );
+    document.to_string()
+}
+
+/// Recursively replaces the values in `document` that differ between `old` and `new` with the
+/// formatted value from `formatted`.
+fn update_table(
+    document: &mut toml_edit::Table,
+    old: &Table,
+    new: &Table,
+    formatted: &toml_edit::Table,
+) {
+    document.retain(|key, _| new.contains_key(key));
+
+    for (key, new_value) in new {
+        let Some(formatted_item) = formatted.get(key) else {
+            continue;
+        };
+
+        if !document.contains_key(key) {
+            document.insert(key, formatted_item.clone());
+            continue;
+        }
+
+        match (document.get_mut(key).unwrap(), old.get(key), new_value) {
+            (Item::Table(table), Some(Value::Table(old_table)), Value::Table(new_table))
+                if formatted_item.is_table() =>
+            {
+                let formatted_table = formatted_item.as_table().unwrap();
+                update_table(table, old_table, new_table, formatted_table);
+            }
+            (_, Some(old_value), _) if old_value == new_value => {}
+            (Item::Value(value), ..) if formatted_item.is_value() => {
+                // keep the comment after the value
+                let decor = value.decor().clone();
+                *value = formatted_item.as_value().unwrap().clone();
+                *value.decor_mut() = decor;
+            }
+            (item, ..) => *item = formatted_item.clone(),
+        }
+    }
+}
+
 /// Parses a [`Table`] into [`Self`]
 fn from_table<T: Config>(table: Table) -> Result<T> {
     table
         .try_into()
         .map_err(|e| Error::from_kind::<T>(ErrorKind::Parse(e)))
 }
+
+/// Runs [`Config::validate`] on a loaded config, returning it if it is valid
+fn validate<T: Config>(config: T) -> Result<T> {
+    config
+        .validate()
+        .map_err(|e| Error::from_kind::<T>(ErrorKind::Validation(e)))?;
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ops::Deref, path::PathBuf};
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct ThresholdConfig {
+        threshold: f32,
+    }
+
+    impl Config for ThresholdConfig {
+        const PATH: &'static str = "threshold.toml";
+
+        fn validate(&self) -> std::result::Result<(), ValidationError> {
+            if (0.0..=1.0).contains(&self.threshold) {
+                Ok(())
+            } else {
+                Err(ValidationError::new(format!(
+                    "`threshold` must be in [0, 1], got {}",
+                    self.threshold
+                )))
+            }
+        }
+    }
+
+    /// A temporary config directory, which is removed again when dropped.
+    struct ConfigDir(PathBuf);
+
+    impl Deref for ConfigDir {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl AsRef<Path> for ConfigDir {
+        fn as_ref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for ConfigDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Creates a temporary config directory containing the config file of `T`.
+    fn config_dir<T: Config>(name: &str, contents: &str) -> ConfigDir {
+        let dir = std::env::temp_dir().join(format!("odal-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(T::PATH), contents).unwrap();
+        ConfigDir(dir)
+    }
+
+    #[test]
+    fn validate_accepts_valid_config() {
+        let dir = config_dir::<ThresholdConfig>("valid", "threshold = 0.5\n");
+
+        let config = ThresholdConfig::load(&dir).unwrap();
+        assert!((config.threshold - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_value() {
+        let dir = config_dir::<ThresholdConfig>("invalid", "threshold = 1.5\n");
+
+        let error = ThresholdConfig::load(&dir).unwrap_err();
+        let ErrorKind::Validation(validation_error) = error.kind else {
+            panic!("expected a validation error, got {:?}", error.kind);
+        };
+
+        assert_eq!(
+            validation_error.to_string(),
+            "`threshold` must be in [0, 1], got 1.5"
+        );
+    }
+
+    #[test]
+    fn later_overlays_take_precedence() {
+        let main = config_dir::<ThresholdConfig>("layers-main", "threshold = 0.1\n");
+        let first = config_dir::<ThresholdConfig>("layers-first", "threshold = 0.2\n");
+        let second = config_dir::<ThresholdConfig>("layers-second", "threshold = 0.3\n");
+
+        let config = ThresholdConfig::load_with_overlays(&main, [&first, &second]).unwrap();
+        assert!((config.threshold - 0.3).abs() < f32::EPSILON);
+
+        let config = ThresholdConfig::load_with_overlays(&main, [&second, &first]).unwrap();
+        assert!((config.threshold - 0.2).abs() < f32::EPSILON);
+
+        let config = ThresholdConfig::load_with_overlays(&main, [] as [&Path; 0]).unwrap();
+        assert!((config.threshold - 0.1).abs() < f32::EPSILON);
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct CameraConfig {
+        camera: CameraSettings,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct CameraSettings {
+        width: u32,
+        exposure: u32,
+    }
+
+    impl Config for CameraConfig {
+        const PATH: &'static str = "camera.toml";
+        const LOCKED_KEYS: &'static [&'static str] = &["camera.width"];
+    }
+
+    #[test]
+    fn overlay_cannot_change_locked_key() {
+        let main =
+            config_dir::<CameraConfig>("locked-main", "[camera]\nwidth = 640\nexposure = 100\n");
+        let tunable = config_dir::<CameraConfig>("locked-tunable", "[camera]\nexposure = 150\n");
+        let structural = config_dir::<CameraConfig>("locked-structural", "[camera]\nwidth = 320\n");
+        let repeated = config_dir::<CameraConfig>("locked-repeated", "[camera]\nwidth = 640\n");
+
+        let config = CameraConfig::load_with_overlay(&main, &tunable).unwrap();
+        assert_eq!(config.camera.exposure, 150);
+        assert!(CameraConfig::is_locked("camera.width"));
+        assert!(!CameraConfig::is_locked("camera.width_scale"));
+        assert!(!CameraConfig::is_locked("camera"));
+
+        // repeating the value of a locked key does not change it
+        let config = CameraConfig::load_with_overlay(&main, &repeated).unwrap();
+        assert_eq!(config.camera.width, 640);
+
+        let error = CameraConfig::load_with_overlay(&main, &structural).unwrap_err();
+        let ErrorKind::Subtable { source, .. } = error.kind else {
+            panic!("expected a subtable error, got {:?}", error.kind);
+        };
+        let ErrorKind::LockedKey { key, .. } = *source else {
+            panic!("expected a locked key error, got {source:?}");
+        };
+        assert_eq!(key, "camera.width");
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct LockedTableConfig {
+        camera: CameraSettings,
+    }
+
+    impl Config for LockedTableConfig {
+        const PATH: &'static str = "camera.toml";
+        const LOCKED_KEYS: &'static [&'static str] = &["camera"];
+    }
+
+    #[test]
+    fn overlay_can_repeat_part_of_locked_table() {
+        let main = config_dir::<LockedTableConfig>(
+            "table-main",
+            "[camera]\nwidth = 640\nexposure = 100\n",
+        );
+        let repeated =
+            config_dir::<LockedTableConfig>("table-repeated", "[camera]\nexposure = 100\n");
+        let changed = config_dir::<LockedTableConfig>(
+            "table-changed",
+            "[camera]\nwidth = 640\nexposure = 150\n",
+        );
+
+        let config = LockedTableConfig::load_with_overlay(&main, &repeated).unwrap();
+        assert_eq!(config.camera.exposure, 100);
+
+        // every key within a locked table is locked
+        let error = LockedTableConfig::load_with_overlay(&main, &changed).unwrap_err();
+        let ErrorKind::Subtable { source, .. } = error.kind else {
+            panic!("expected a subtable error, got {:?}", error.kind);
+        };
+        let ErrorKind::LockedKey { key, .. } = *source else {
+            panic!("expected a locked key error, got {source:?}");
+        };
+        assert_eq!(key, "camera.exposure");
+    }
+
+    #[test]
+    fn store_preserves_comments() {
+        let dir = config_dir::<ThresholdConfig>(
+            "comments",
+            "# how confident we need to be\nthreshold = 0.5 # tuned at RoboCup\n",
+        );
+        let path = dir.join(ThresholdConfig::PATH);
+
+        let mut config = ThresholdConfig::load(&dir).unwrap();
+        config.store(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# how confident we need to be\nthreshold = 0.5 # tuned at RoboCup\n"
+        );
+
+        config.threshold = 0.75;
+        config.store(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# how confident we need to be\nthreshold = 0.75 # tuned at RoboCup\n"
+        );
+    }
+}
@@ -5,7 +5,7 @@ use petgraph::{
     visit::IntoNodeReferences,
 };
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{ToTokens, quote};
 use syn::{
     Data, DeriveInput, Error, Field, Fields, GenericArgument, Ident, Path, PathArguments, Type,
     parse_macro_input,
@@ -55,17 +55,12 @@ fn build_transform_graph<'a>(
     let mut spaces = HashMap::new();
 
     for field in fields {
-        let Some((s1, s2)) = infer_spaces_from_type(&field.ty) else {
-            return Err(Error::new_spanned(
-                field.ty.clone(),
-                "Cannot infer spaces from type",
-            ));
-        };
+        let ident = field.ident.as_ref().unwrap();
+        let (s1, s2) = infer_spaces_from_type(ident, &field.ty)?;
+
         let s1 = *spaces.entry(s1).or_insert_with(|| graph.add_node(s1));
         let s2 = *spaces.entry(s2).or_insert_with(|| graph.add_node(s2));
 
-        let ident = field.ident.as_ref().unwrap();
-
         graph.add_edge(s1, s2, (ident, &field.ty, false));
         graph.add_edge(s2, s1, (ident, &field.ty, true));
     }
@@ -73,32 +68,70 @@ fn build_transform_graph<'a>(
     Ok(graph)
 }
 
-fn infer_spaces_from_type(ty: &Type) -> Option<(&Path, &Path)> {
-    let segment = match ty {
-        Type::Path(path) => path.path.segments.last().unwrap(),
-        _ => return None,
+/// Infers the spaces a field transforms between, which are the last two generic arguments of its
+/// type, e.g. `S1` and `S2` in `BetweenSpaces<T, S1, S2>`.
+fn infer_spaces_from_type<'a>(field: &Ident, ty: &'a Type) -> Result<(&'a Path, &'a Path), Error> {
+    let Type::Path(path) = ty else {
+        return Err(spaces_error(
+            ty,
+            field,
+            "expected a transform between two spaces, like `BetweenSpaces<T, S1, S2>`",
+        ));
     };
 
-    let args = match &segment.arguments {
-        PathArguments::AngleBracketed(args) => &args.args,
-        _ => return None,
+    let segment = path.path.segments.last().unwrap();
+    let name = &segment.ident;
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return Err(spaces_error(
+            ty,
+            field,
+            &format!(
+                "`{name}` has no generic arguments, expected a transform between two spaces, like \
+                 `BetweenSpaces<T, S1, S2>` (spaces cannot be inferred through type aliases)"
+            ),
+        ));
     };
 
-    let mut args = args.iter().rev();
-    let s2 = args.next()?;
-    let s1 = args.next()?;
-
-    let s1 = match s1 {
-        GenericArgument::Type(Type::Path(path)) => &path.path,
-        _ => return None,
+    let mut spaces = args.args.iter().rev();
+    let (Some(s2), Some(s1)) = (spaces.next(), spaces.next()) else {
+        return Err(spaces_error(
+            args,
+            field,
+            &format!(
+                "expected the last two generic arguments of `{name}` to be spaces, like \
+                 `BetweenSpaces<T, S1, S2>`, found {}",
+                if args.args.is_empty() {
+                    "none"
+                } else {
+                    "only one"
+                }
+            ),
+        ));
     };
 
-    let s2 = match s2 {
-        GenericArgument::Type(Type::Path(path)) => &path.path,
-        _ => return None,
-    };
+    Ok((
+        space_from_argument(field, s1)?,
+        space_from_argument(field, s2)?,
+    ))
+}
+
+fn space_from_argument<'a>(field: &Ident, arg: &'a GenericArgument) -> Result<&'a Path, Error> {
+    match arg {
+        GenericArgument::Type(Type::Path(path)) => Ok(&path.path),
+        _ => Err(spaces_error(
+            arg,
+            field,
+            "expected a space type, like `S1` in `BetweenSpaces<T, S1, S2>`",
+        )),
+    }
+}
 
-    Some((s1, s2))
+fn spaces_error(tokens: impl ToTokens, field: &Ident, reason: &str) -> Error {
+    Error::new_spanned(
+        tokens,
+        format!("cannot infer spaces of field `{field}`: {reason}"),
+    )
 }
 
 fn implement_transforms(
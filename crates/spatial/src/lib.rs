@@ -33,6 +33,17 @@
 //! impl SpaceOver<na::Point3<f32>> for WorldSpace {}
 //! ```
 //!
+//! Spaces usually contain several types, the [`impl_space_over!`] macro implements
+//! [`SpaceOver<T>`] for all of them at once:
+//!
+//! ```rust
+//! # use nalgebra as na;
+//! # use spatial::Space;
+//! # struct WorldSpace;
+//! # impl Space for WorldSpace {}
+//! spatial::impl_space_over!(WorldSpace: na::Point3<f32>, na::Vector3<f32>, na::Isometry3<f32>);
+//! ```
+//!
 //! ## [`InSpace<T, S>`] and [`BetweenSpaces<T, S1, S2>`]
 //!
 //! spatial introduces the [`InSpace<T, S>`] to denote which space a value is expressed in.
@@ -106,5 +117,8 @@ pub use space::*;
 pub mod transform;
 pub use transform::*;
 
+pub mod tree;
+pub use tree::*;
+
 #[macro_use]
 pub mod types;
@@ -14,7 +14,92 @@ impl<T, S: SpaceOver<T>> SpaceOver<&T> for S {}
 
 impl<T, S: SpaceOver<T>> SpaceOver<&mut T> for S {}
 
+/// Implements [`SpaceOver<T>`] for a space, for each of the given types.
+///
+/// The space must implement [`Space`] separately.
+///
+/// ```rust
+/// use nalgebra::{Isometry3, Point2, Point3, Vector3};
+/// use spatial::{Space, SpaceOver};
+///
+/// struct WorldSpace;
+///
+/// impl Space for WorldSpace {}
+/// spatial::impl_space_over!(WorldSpace: Point2<f32>, Point3<f32>, Vector3<f32>, Isometry3<f32>);
+///
+/// fn assert_space_over<T, S: SpaceOver<T>>() {}
+///
+/// assert_space_over::<Point2<f32>, WorldSpace>();
+/// assert_space_over::<Point3<f32>, WorldSpace>();
+/// assert_space_over::<Vector3<f32>, WorldSpace>();
+/// assert_space_over::<Isometry3<f32>, WorldSpace>();
+/// ```
+///
+/// Generic spaces are supported by passing the concrete space type:
+///
+/// ```rust
+/// # use std::marker::PhantomData;
+/// # use nalgebra as na;
+/// # use spatial::Space;
+/// struct Left;
+/// struct Foot<Side>(PhantomData<Side>);
+///
+/// impl Space for Foot<Left> {}
+/// spatial::impl_space_over!(Foot<Left>: na::Point3<f32>, na::Vector3<f32>);
+/// ```
+#[macro_export]
+macro_rules! impl_space_over {
+    ($space:ty: $($inner:ty),+ $(,)?) => {
+        $(impl $crate::SpaceOver<$inner> for $space {})+
+    };
+}
+
 /// Wrapper type for tagging a `T` as existing in space `S`.
+///
+/// Arithmetic operators are implemented for values in the same space, and preserve the space
+/// of the operands. For example, adding a vector to a point gives a point, and subtracting two
+/// points gives a vector:
+///
+/// ```rust
+/// # use nalgebra as na;
+/// # use spatial::{Space, SpaceOver};
+/// use spatial::types::{Point3, Vector3};
+///
+/// struct WorldSpace;
+///
+/// impl Space for WorldSpace {}
+/// impl SpaceOver<na::Point3<f32>> for WorldSpace {}
+/// impl SpaceOver<na::Vector3<f32>> for WorldSpace {}
+///
+/// let p1 = spatial::point3!(WorldSpace, 1., 2., 3.);
+/// let v = spatial::vector3!(WorldSpace, 1., 0., 0.);
+///
+/// let p2: Point3<WorldSpace> = p1 + v;
+/// let difference: Vector3<WorldSpace> = p2 - p1;
+///
+/// assert_eq!(p2.inner, na::point![2., 2., 3.]);
+/// assert_eq!(difference, v);
+/// ```
+///
+/// Values in different spaces cannot be combined, they must first be transformed into the same space:
+///
+/// ```compile_fail
+/// # use nalgebra as na;
+/// # use spatial::{Space, SpaceOver};
+/// # struct LocalSpace;
+/// # impl Space for LocalSpace {}
+/// # impl SpaceOver<na::Point3<f32>> for LocalSpace {}
+/// # impl SpaceOver<na::Vector3<f32>> for LocalSpace {}
+/// # struct WorldSpace;
+/// # impl Space for WorldSpace {}
+/// # impl SpaceOver<na::Point3<f32>> for WorldSpace {}
+/// # impl SpaceOver<na::Vector3<f32>> for WorldSpace {}
+/// let p = spatial::point3!(WorldSpace, 1., 2., 3.);
+/// let v = spatial::vector3!(LocalSpace, 1., 0., 0.);
+///
+/// // ERROR: `v` is in local space!
+/// let _ = p + v;
+/// ```
 pub struct InSpace<T, S: SpaceOver<T>> {
     pub inner: T,
     phantom: PhantomData<S>,
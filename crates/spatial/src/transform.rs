@@ -138,6 +138,60 @@ where
     }
 }
 
+/// Composes two transforms, `S2 -> S3` after `S1 -> S2`, into a transform `S1 -> S3`.
+///
+/// This is equivalent to [`BetweenSpaces::chain`], but follows the order of [`nalgebra`] multiplication:
+///
+/// ```rust
+/// # use nalgebra as na;
+/// # use spatial::Space;
+/// use spatial::types::Isometry3;
+///
+/// # struct Head;
+/// # struct Torso;
+/// # struct Robot;
+/// # impl Space for Head {}
+/// # impl Space for Torso {}
+/// # impl Space for Robot {}
+/// let head_to_torso: Isometry3<Head, Torso> = na::Isometry3::translation(0., 0., 0.1).into();
+/// let torso_to_robot: Isometry3<Torso, Robot> = na::Isometry3::translation(0., 0., 0.2).into();
+///
+/// let head_to_robot: Isometry3<Head, Robot> = torso_to_robot * head_to_torso;
+/// assert_eq!(head_to_robot, head_to_torso.chain(torso_to_robot));
+/// ```
+///
+/// Transforms between spaces that do not line up cannot be composed:
+///
+/// ```compile_fail
+/// # use nalgebra as na;
+/// # use spatial::Space;
+/// # use spatial::types::Isometry3;
+/// # struct Head;
+/// # struct Torso;
+/// # struct Robot;
+/// # impl Space for Head {}
+/// # impl Space for Torso {}
+/// # impl Space for Robot {}
+/// let head_to_torso: Isometry3<Head, Torso> = na::Isometry3::identity().into();
+/// let head_to_robot: Isometry3<Head, Robot> = na::Isometry3::identity().into();
+///
+/// // ERROR: `head_to_robot` does not start in `Torso`!
+/// let _ = head_to_robot * head_to_torso;
+/// ```
+impl<T0, T, S0, S1, S2> Mul<BetweenSpaces<T0, S0, S1>> for BetweenSpaces<T, S1, S2>
+where
+    T: Mul<T0>,
+    S0: Space,
+    S1: Space,
+    S2: Space,
+{
+    type Output = BetweenSpaces<T::Output, S0, S2>;
+
+    fn mul(self, rhs: BetweenSpaces<T0, S0, S1>) -> Self::Output {
+        rhs.chain(self)
+    }
+}
+
 macro_rules! impl_transform {
     ($transform:ty, $inner:ty, $forward:ident, $inverse:ident) => {
         impl<S1, S2> Transform<$inner, $inner, S1, S2> for BetweenSpaces<$transform, S1, S2>
@@ -0,0 +1,223 @@
+//! Runtime lookup of transformations between frames that are not known at compile time.
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::Mul;
+
+use nalgebra as na;
+use thiserror::Error;
+
+use super::space::Space;
+use super::transform::BetweenSpaces;
+
+/// Errors that can occur when inserting into or resolving from a [`TransformTree`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TransformTreeError {
+    #[error("Frame `{0}` does not exist in the transform tree")]
+    UnknownFrame(String),
+    #[error("Frames `{from}` and `{to}` are not connected")]
+    Disconnected { from: String, to: String },
+    #[error("Cannot insert a transform from frame `{0}` to itself")]
+    SelfLoop(String),
+    #[error("Frames `{from}` and `{to}` are already connected, inserting would create a cycle")]
+    Cycle { from: String, to: String },
+}
+
+/// A transformation that can be composed and inverted, and can therefore be stored in a [`TransformTree`].
+pub trait TreeTransform: Clone + Mul<Output = Self> {
+    /// The transform that maps every frame onto itself.
+    #[must_use]
+    fn identity() -> Self;
+
+    /// The transform in the opposite direction.
+    #[must_use]
+    fn inverse(&self) -> Self;
+}
+
+impl TreeTransform for na::Isometry2<f32> {
+    fn identity() -> Self {
+        na::Isometry2::identity()
+    }
+
+    fn inverse(&self) -> Self {
+        na::Isometry2::inverse(self)
+    }
+}
+
+impl TreeTransform for na::Isometry3<f32> {
+    fn identity() -> Self {
+        na::Isometry3::identity()
+    }
+
+    fn inverse(&self) -> Self {
+        na::Isometry3::inverse(self)
+    }
+}
+
+/// A tree of transformations between frames identified at runtime, similar to ROS' `tf`.
+///
+/// This complements the type-level [`Transform`](super::Transform) derive for frames that
+/// are not known at compile time, such as calibration frames loaded from a config.
+///
+/// Each edge stores the transform that maps coordinates from one frame into another.
+/// Inserting an edge between two frames that are already connected is rejected, which
+/// guarantees that there is exactly one path between any two connected frames.
+///
+/// # Example
+///
+/// ```rust
+/// # use nalgebra as na;
+/// use spatial::TransformTree;
+///
+/// let mut tree = TransformTree::<na::Isometry3<f32>>::new();
+/// tree.insert("camera", "head", na::Isometry3::translation(0.0, 0.0, 0.1)).unwrap();
+/// tree.insert("head", "torso", na::Isometry3::translation(0.0, 0.0, 0.2)).unwrap();
+///
+/// let camera_to_torso = tree.lookup("camera", "torso").unwrap();
+/// assert_eq!(camera_to_torso.translation.vector, na::vector![0.0, 0.0, 0.3]);
+///
+/// // this would create a cycle
+/// assert!(tree.insert("torso", "camera", na::Isometry3::identity()).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransformTree<T: TreeTransform> {
+    /// Adjacency list, every edge is stored in both directions.
+    edges: HashMap<String, Vec<(String, T)>>,
+}
+
+impl<T: TreeTransform> Default for TransformTree<T> {
+    fn default() -> Self {
+        Self {
+            edges: HashMap::new(),
+        }
+    }
+}
+
+impl<T: TreeTransform> TransformTree<T> {
+    /// Creates an empty transform tree.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the tree contains the frame.
+    #[must_use]
+    pub fn contains(&self, frame: &str) -> bool {
+        self.edges.contains_key(frame)
+    }
+
+    /// Iterator over all frames in the tree.
+    pub fn frames(&self) -> impl Iterator<Item = &str> {
+        self.edges.keys().map(String::as_str)
+    }
+
+    /// Inserts the transform that maps coordinates in frame `from` into frame `to`.
+    ///
+    /// Frames that do not exist yet are added to the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `from` and `to` are the same frame, or if they are already
+    /// connected through other transforms.
+    pub fn insert(
+        &mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        transform: T,
+    ) -> Result<(), TransformTreeError> {
+        let from = from.into();
+        let to = to.into();
+
+        if from == to {
+            return Err(TransformTreeError::SelfLoop(from));
+        }
+
+        if self.path(&from, &to).is_some() {
+            return Err(TransformTreeError::Cycle { from, to });
+        }
+
+        let inverse = transform.inverse();
+        self.edges
+            .entry(from.clone())
+            .or_default()
+            .push((to.clone(), transform));
+        self.edges.entry(to).or_default().push((from, inverse));
+
+        Ok(())
+    }
+
+    /// Inserts a typed transform between two spaces, see [`TransformTree::insert`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `from` and `to` are the same frame, or if they are already
+    /// connected through other transforms.
+    pub fn insert_between<S1: Space, S2: Space>(
+        &mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        transform: BetweenSpaces<T, S1, S2>,
+    ) -> Result<(), TransformTreeError> {
+        self.insert(from, to, transform.inner)
+    }
+
+    /// Resolves the transform that maps coordinates in frame `from` into frame `to`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either frame does not exist, or if the frames are not connected.
+    pub fn lookup(&self, from: &str, to: &str) -> Result<T, TransformTreeError> {
+        for frame in [from, to] {
+            if !self.contains(frame) {
+                return Err(TransformTreeError::UnknownFrame(frame.to_string()));
+            }
+        }
+
+        self.path(from, to)
+            .ok_or_else(|| TransformTreeError::Disconnected {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+    }
+
+    /// Resolves a transform between frames into a typed transform between two spaces,
+    /// see [`TransformTree::lookup`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either frame does not exist, or if the frames are not connected.
+    pub fn lookup_between<S1: Space, S2: Space>(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<BetweenSpaces<T, S1, S2>, TransformTreeError> {
+        self.lookup(from, to).map(BetweenSpaces::new)
+    }
+
+    /// Finds the composed transform from `from` to `to` using a breadth-first search.
+    fn path(&self, from: &str, to: &str) -> Option<T> {
+        let mut visited: HashMap<&str, T> = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        visited.insert(from, T::identity());
+        queue.push_back(from);
+
+        while let Some(frame) = queue.pop_front() {
+            let transform = visited[frame].clone();
+
+            if frame == to {
+                return Some(transform);
+            }
+
+            for (neighbour, edge) in self.edges.get(frame).into_iter().flatten() {
+                if visited.contains_key(neighbour.as_str()) {
+                    continue;
+                }
+
+                visited.insert(neighbour, edge.clone() * transform.clone());
+                queue.push_back(neighbour);
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,12 @@
+struct Pelvis;
+impl spatial::Space for Pelvis {}
+
+struct Torso;
+impl spatial::Space for Torso {}
+
+#[derive(spatial::Transform)]
+struct Kinematics {
+    pelvis_to_torso: (Pelvis, Torso),
+}
+
+fn main() {}
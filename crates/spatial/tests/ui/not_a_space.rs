@@ -0,0 +1,13 @@
+use std::marker::PhantomData;
+
+struct Pelvis;
+impl spatial::Space for Pelvis {}
+
+struct Borrowed<'a, S>(PhantomData<&'a S>);
+
+#[derive(spatial::Transform)]
+struct Kinematics {
+    pelvis: Borrowed<'static, Pelvis>,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use spatial::types::Isometry3;
+
+struct Pelvis;
+impl spatial::Space for Pelvis {}
+
+struct Torso;
+impl spatial::Space for Torso {}
+
+type PelvisToTorso = Isometry3<Pelvis, Torso>;
+
+#[derive(spatial::Transform)]
+struct Kinematics {
+    pelvis_to_torso: PelvisToTorso,
+}
+
+fn main() {}
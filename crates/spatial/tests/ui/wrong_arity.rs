@@ -0,0 +1,13 @@
+use std::marker::PhantomData;
+
+struct Pelvis;
+impl spatial::Space for Pelvis {}
+
+struct InPelvis<T>(PhantomData<T>);
+
+#[derive(spatial::Transform)]
+struct Kinematics {
+    pelvis: InPelvis<Pelvis>,
+}
+
+fn main() {}
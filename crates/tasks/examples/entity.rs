@@ -37,7 +37,7 @@ fn query_foo_entity(foo: Query<&Foo, Added<Foo>>) {
 
 fn main() {
     App::new()
-        .add_plugins((MinimalPlugins, TaskPlugin))
+        .add_plugins((TaskPlugin::default(), MinimalPlugins))
         .add_systems(
             Update,
             (
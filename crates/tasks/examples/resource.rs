@@ -38,7 +38,7 @@ fn query_foo_resource(foo: Res<Foo>) {
 
 fn main() {
     App::new()
-        .add_plugins((MinimalPlugins, TaskPlugin))
+        .add_plugins((TaskPlugin::default(), MinimalPlugins))
         .init_resource::<Foo>()
         .add_systems(
             Update,
@@ -32,7 +32,7 @@ fn run_blocking_task(
 
 fn main() {
     App::new()
-        .add_plugins((MinimalPlugins, TaskPlugin))
+        .add_plugins((TaskPlugin::default(), MinimalPlugins))
         .add_systems(Update, run_blocking_task)
         .run();
 }
@@ -10,3 +10,136 @@ pub trait Combinators<T>: Sized {
 }
 
 impl<T, F: Future<Output = T>> Combinators<T> for F {}
+
+/// The output of a task that may fail, see [`retry`].
+pub trait Fallible<T> {
+    /// Returns the output of a successful attempt, or `None` if the attempt failed.
+    fn success(self) -> Option<T>;
+}
+
+impl<T> Fallible<T> for Option<T> {
+    fn success(self) -> Option<T> {
+        self
+    }
+}
+
+impl<T, E> Fallible<T> for Result<T, E> {
+    fn success(self) -> Option<T> {
+        self.ok()
+    }
+}
+
+/// Runs the task created by `task` until it succeeds, at most `max_attempts` times.
+///
+/// An attempt fails if its task returns `None` or an error. After every failed attempt the retry
+/// waits before creating the next task, starting at `backoff` and doubling after every attempt.
+/// The retry resolves to the output of the first successful attempt, or `None` once all attempts
+/// have failed.
+///
+/// The closure is called once for every attempt, so it must be able to create the task again,
+/// e.g. by cloning the data it needs rather than moving it into the task. The backoff is awaited
+/// rather than slept, so a retry spawned on a [`TaskPool`](crate::TaskPool) never blocks the main
+/// loop.
+///
+/// ```ignore
+/// commands
+///     .prepare_task(TaskPool::Io)
+///     .to_resource()
+///     .spawn(retry(3, Duration::from_millis(100), move || {
+///         let address = address.clone();
+///         async move { sync_time(&address).await }
+///     }));
+/// ```
+pub async fn retry<T, R, F, Fut>(max_attempts: u32, backoff: Duration, mut task: F) -> Option<T>
+where
+    R: Fallible<T>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = R>,
+{
+    let mut delay = backoff;
+
+    for attempt in 1..=max_attempts {
+        if let Some(output) = task().await.success() {
+            return Some(output);
+        }
+
+        if attempt < max_attempts {
+            tracing::debug!(attempt, ?delay, "task failed, retrying");
+            async_std::task::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Instant,
+    };
+
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::{CommandsExt, TaskPool};
+
+    #[test]
+    fn retries_until_success() {
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        let backoff = Duration::from_millis(20);
+
+        let task = retry(5, backoff, {
+            let attempts = attempts.clone();
+            move || {
+                let attempts = attempts.clone();
+                async move {
+                    let mut attempts = attempts.lock().unwrap();
+                    attempts.push(Instant::now());
+
+                    if attempts.len() < 3 {
+                        Err("connection refused")
+                    } else {
+                        Ok(attempts.len())
+                    }
+                }
+            }
+        });
+
+        let mut world = World::new();
+        let handle = world
+            .commands()
+            .prepare_task(TaskPool::AsyncCompute)
+            .spawn_awaitable(task);
+
+        assert_eq!(handle.block_until_ready(), Some(3));
+
+        let attempts = attempts.lock().unwrap();
+        assert_eq!(attempts.len(), 3);
+        assert!(attempts[1] - attempts[0] >= backoff);
+        assert!(attempts[2] - attempts[1] >= backoff * 2);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let attempts = Arc::new(Mutex::new(0));
+
+        let task = retry(2, Duration::ZERO, {
+            let attempts = attempts.clone();
+            move || {
+                *attempts.lock().unwrap() += 1;
+                async { None::<()> }
+            }
+        });
+
+        let mut world = World::new();
+        let handle = world
+            .commands()
+            .prepare_task(TaskPool::AsyncCompute)
+            .spawn_awaitable(task);
+
+        assert_eq!(handle.block_until_ready(), None);
+        assert_eq!(*attempts.lock().unwrap(), 2);
+    }
+}
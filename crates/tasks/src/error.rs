@@ -0,0 +1,15 @@
+//! Result and Error types for the crate.
+use thiserror::Error;
+
+/// Result containing an error variant from this module.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Task error variants
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The maximum number of in-flight tasks has been reached.
+    ///
+    /// Callers should drop the work instead of queueing it, e.g. skip processing a frame.
+    #[error("Task queue is full, {limit} tasks are already in flight")]
+    QueueFull { limit: usize },
+}
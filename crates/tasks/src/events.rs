@@ -0,0 +1,103 @@
+use std::any::{Any, TypeId, type_name};
+
+use bevy::prelude::*;
+
+use crate::Generation;
+
+/// Information about a spawned task, used to identify it in [`TaskCompleted`] and [`TaskFailed`] events.
+#[derive(Debug, Clone)]
+pub(crate) struct TaskInfo {
+    tag: TypeId,
+    tag_name: &'static str,
+    generation: Generation,
+}
+
+impl TaskInfo {
+    pub(crate) fn new<T: 'static>(generation: Generation) -> Self {
+        Self {
+            tag: TypeId::of::<T>(),
+            tag_name: type_name::<T>(),
+            generation,
+        }
+    }
+
+    pub(crate) fn generation(&self) -> &Generation {
+        &self.generation
+    }
+}
+
+/// Event that is sent when a task has finished and its output has been applied to the world.
+#[derive(Event, Debug, Clone)]
+pub struct TaskCompleted {
+    /// The entity of the task.
+    ///
+    /// Depending on the output strategy, this entity might have been despawned already.
+    pub entity: Entity,
+    /// The generation of the task.
+    pub generation: Generation,
+    tag: TypeId,
+    /// The type name of the task output.
+    pub tag_name: &'static str,
+}
+
+impl TaskCompleted {
+    pub(crate) fn new(entity: Entity, info: &TaskInfo) -> Self {
+        Self {
+            entity,
+            generation: info.generation.clone(),
+            tag: info.tag,
+            tag_name: info.tag_name,
+        }
+    }
+
+    /// Whether the completed task produced a `T`.
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.tag == TypeId::of::<T>()
+    }
+}
+
+/// Event that is sent when a task has panicked.
+///
+/// The entity of a failed task is despawned, so a new task can be spawned.
+#[derive(Event, Debug, Clone)]
+pub struct TaskFailed {
+    /// The entity of the task, which has been despawned.
+    pub entity: Entity,
+    /// The generation of the task.
+    pub generation: Generation,
+    tag: TypeId,
+    /// The type name of the task output.
+    pub tag_name: &'static str,
+    /// The panic message of the task.
+    pub message: String,
+}
+
+impl TaskFailed {
+    pub(crate) fn new(entity: Entity, info: &TaskInfo, payload: &(dyn Any + Send)) -> Self {
+        Self {
+            entity,
+            generation: info.generation.clone(),
+            tag: info.tag,
+            tag_name: info.tag_name,
+            message: panic_message(payload),
+        }
+    }
+
+    /// Whether the failed task would have produced a `T`.
+    #[must_use]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.tag == TypeId::of::<T>()
+    }
+}
+
+/// Extracts the message from the payload of a panic.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
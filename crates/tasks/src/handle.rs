@@ -0,0 +1,66 @@
+//! Handles to await the output of a task, intended for tests.
+
+use std::{panic, thread};
+
+use bevy::tasks::{Task, block_on};
+
+/// Handle to the output of a task spawned with
+/// [`TaskBuilder::spawn_awaitable`](crate::TaskBuilder::spawn_awaitable).
+///
+/// This lets unit tests drive a task to completion without running the app, so it is only
+/// available in tests or with the `test-util` feature.
+#[must_use = "the output of the task is only available through the handle"]
+pub struct TaskHandle<T> {
+    task: Task<thread::Result<Option<T>>>,
+}
+
+impl<T> TaskHandle<T> {
+    pub(crate) fn new(task: Task<thread::Result<Option<T>>>) -> Self {
+        Self { task }
+    }
+
+    /// Blocks the calling thread until the task has finished, and returns its output.
+    ///
+    /// # Panics
+    ///
+    /// If the task panicked, the panic is resumed on the calling thread.
+    #[must_use]
+    pub fn block_until_ready(self) -> Option<T> {
+        match block_on(self.task) {
+            Ok(output) => output,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{CommandsExt, TaskPool};
+
+    #[test]
+    fn returns_output() {
+        let mut world = World::new();
+
+        let handle = world
+            .commands()
+            .prepare_task(TaskPool::AsyncCompute)
+            .spawn_awaitable(async { Some(42) });
+
+        assert_eq!(handle.block_until_ready(), Some(42));
+    }
+
+    #[test]
+    #[should_panic(expected = "task failed")]
+    fn resumes_panic() {
+        let mut world = World::new();
+
+        let handle = world
+            .commands()
+            .prepare_task(TaskPool::Compute)
+            .spawn_awaitable::<()>(async { panic!("task failed") });
+
+        let _ = handle.block_until_ready();
+    }
+}
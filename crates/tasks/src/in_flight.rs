@@ -0,0 +1,115 @@
+//! Bookkeeping of the number of tasks that are in flight.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use bevy::prelude::*;
+
+use crate::{Error, Result};
+
+/// The tasks that are currently in flight, limited by
+/// [`TaskConfig::max_in_flight_tasks`](crate::TaskConfig::max_in_flight_tasks).
+///
+/// The resource is added by the [`TaskPlugin`](crate::TaskPlugin), so every app keeps its own
+/// count. Cloning the resource shares the count.
+#[derive(Resource, Debug, Clone)]
+pub struct InFlightTasks(Arc<Limiter>);
+
+#[derive(Debug)]
+struct Limiter {
+    in_flight: AtomicUsize,
+    limit: usize,
+}
+
+impl InFlightTasks {
+    /// Creates a limiter for at most `limit` in-flight tasks, or without a limit if `None`.
+    #[must_use]
+    pub fn new(limit: Option<usize>) -> Self {
+        Self(Arc::new(Limiter {
+            in_flight: AtomicUsize::new(0),
+            limit: limit.unwrap_or(usize::MAX),
+        }))
+    }
+
+    /// The number of tasks that are currently in flight.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.0.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Registers a new in-flight task, regardless of the limit.
+    pub(crate) fn acquire(&self) -> InFlight {
+        self.0.in_flight.fetch_add(1, Ordering::AcqRel);
+        InFlight(self.0.clone())
+    }
+
+    /// Registers a new in-flight task, unless the limit has been reached.
+    pub(crate) fn try_acquire(&self) -> Result<InFlight> {
+        let limit = self.0.limit;
+
+        self.0
+            .in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |in_flight| {
+                (in_flight < limit).then_some(in_flight + 1)
+            })
+            .map(|_| InFlight(self.0.clone()))
+            .map_err(|_| Error::QueueFull { limit })
+    }
+}
+
+/// Guard of a single in-flight task, which releases its slot when dropped.
+#[derive(Debug)]
+pub(crate) struct InFlight(Arc<Limiter>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_when_full() {
+        let limiter = InFlightTasks::new(Some(3));
+
+        let guards = (0..3)
+            .map(|_| limiter.try_acquire())
+            .collect::<Result<Vec<_>>>()
+            .expect("should accept tasks up to the limit");
+        assert_eq!(limiter.in_flight(), 3);
+
+        assert_eq!(
+            limiter.try_acquire().unwrap_err(),
+            Error::QueueFull { limit: 3 }
+        );
+        assert_eq!(limiter.in_flight(), 3);
+
+        drop(guards);
+        assert_eq!(limiter.in_flight(), 0);
+        assert!(limiter.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn unchecked_tasks_count_towards_limit() {
+        let limiter = InFlightTasks::new(Some(1));
+
+        let _guard = limiter.acquire();
+        assert!(limiter.try_acquire().is_err());
+    }
+
+    #[test]
+    fn limiters_are_independent() {
+        let first = InFlightTasks::new(Some(1));
+        let second = InFlightTasks::new(Some(1));
+
+        let _guard = first.acquire();
+        assert!(first.try_acquire().is_err());
+        assert!(second.try_acquire().is_ok());
+        assert_eq!(first.clone().in_flight(), 1);
+    }
+}
@@ -1,29 +1,93 @@
 pub mod combinators;
 pub mod conditions;
+pub mod error;
+pub mod events;
+#[cfg(any(test, feature = "test-util"))]
+pub mod handle;
+mod in_flight;
+pub mod map;
+pub mod progress;
 pub mod strategy;
 
-use std::{future::Future, marker::PhantomData, sync::atomic::AtomicU32};
+pub use error::{Error, Result};
+pub use in_flight::InFlightTasks;
+
+use std::{
+    future::Future, marker::PhantomData, panic::AssertUnwindSafe, sync::atomic::AtomicU32, thread,
+};
 
 use bevy::{
+    app::{TaskPoolOptions, TaskPoolThreadAssignmentPolicy},
     ecs::world::CommandQueue,
     prelude::*,
     tasks::futures::check_ready,
+    tasks::futures_lite::FutureExt,
     tasks::{AsyncComputeTaskPool, ComputeTaskPool, IoTaskPool, Task, block_on},
 };
+use events::{TaskCompleted, TaskFailed, TaskInfo};
+use in_flight::InFlight;
+use progress::{ProgressReporter, TaskProgress};
 use strategy::{entity::EntityStrategy, resource::ResourceStrategy};
 
 /// A tag that marks an entity as a running task.
 #[derive(Component)]
-pub struct YggdrasilTask(Task<CommandQueue>);
+pub struct YggdrasilTask {
+    task: Task<thread::Result<CommandQueue>>,
+    info: TaskInfo,
+    /// Releases the in-flight slot of the task once the component is removed.
+    in_flight: Option<InFlight>,
+}
+
+impl YggdrasilTask {
+    /// Spawns the future on the pool, catching any panic so it can't take down the worker thread.
+    ///
+    /// The future is wrapped in [`AssertUnwindSafe`], which is sound because a panicked task is
+    /// despawned in [`handle_tasks`] and its state is never observed again. Data shared with the
+    /// task through locks may still be left poisoned, which the owner of that data has to handle.
+    fn spawn<T: 'static>(
+        pool: &bevy::tasks::TaskPool,
+        generation: Generation,
+        in_flight: Option<InFlight>,
+        future: impl Future<Output = CommandQueue> + Send + 'static,
+    ) -> Self {
+        Self {
+            task: pool.spawn(AssertUnwindSafe(future).catch_unwind()),
+            info: TaskInfo::new::<T>(generation),
+            in_flight,
+        }
+    }
+}
+
+/// Registers a task that was spawned without checking the limit with the [`InFlightTasks`] of
+/// the world, so it counts towards the limit of tasks spawned with [`TaskBuilder::try_spawn`].
+fn count_in_flight(mut entity: EntityWorldMut) {
+    let Some(in_flight) = entity
+        .world()
+        .get_resource::<InFlightTasks>()
+        .map(InFlightTasks::acquire)
+    else {
+        return;
+    };
+
+    if let Some(mut task) = entity.get_mut::<YggdrasilTask>() {
+        task.in_flight.get_or_insert(in_flight);
+    }
+}
 
 /// A tag that provides the type annotation for a running task.
 #[derive(Component)]
 pub struct Tag<T>(PhantomData<T>);
 
 /// The generation of a task.
-#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Component, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Generation(u32);
 
+impl Generation {
+    fn next() -> Self {
+        Self(CURRENT_GEN.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+}
+
 /// The current generation of tasks.
 ///
 /// The generation is incremented whenever a new set of tasks is spawned.
@@ -110,31 +174,141 @@ impl<'a, 'w, 's> TaskBuilder<'a, 'w, 's, UnsetTask> {
         let pool = self.pool.get();
         block_on(pool.spawn(fut))
     }
+
+    /// Spawns the task and returns a [`TaskHandle`](handle::TaskHandle) to its output.
+    ///
+    /// The output is not written to the world, it is only returned by
+    /// [`TaskHandle::block_until_ready`](handle::TaskHandle::block_until_ready), which blocks the
+    /// calling thread until the task has finished. This is meant for unit tests that need the
+    /// result of a task without running the app, so the default task pools are created if they
+    /// do not exist yet.
+    ///
+    /// ```ignore
+    /// let mut world = World::new();
+    ///
+    /// let handle = world
+    ///     .commands()
+    ///     .prepare_task(TaskPool::AsyncCompute)
+    ///     .spawn_awaitable(async { Some(detect(&image)) });
+    ///
+    /// assert!(handle.block_until_ready().is_some());
+    /// ```
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn spawn_awaitable<T: Send + 'static>(
+        &self,
+        task: impl TaskFuture<T>,
+    ) -> handle::TaskHandle<T> {
+        TaskPoolOptions::default().create_default_pools();
+
+        let pool = self.pool.get();
+        handle::TaskHandle::new(pool.spawn(AssertUnwindSafe(task).catch_unwind()))
+    }
 }
 
 pub trait TaskFuture<T>: Future<Output = Option<T>> + Send + 'static {}
 impl<F: Future<Output = Option<T>> + Send + 'static, T> TaskFuture<T> for F {}
 
 impl TaskBuilder<'_, '_, '_, ResourceTask> {
+    /// Spawns the task and returns the entity that tracks it.
     pub fn spawn_with_strategy<T: Resource, F: Future<Output = CommandQueue> + Send + 'static>(
         &mut self,
         strategy: impl ResourceStrategy<T, F> + 'static,
         task: impl TaskFuture<T>,
-    ) {
+    ) -> Entity {
+        let entity = self.spawn_in_flight(None, strategy, task);
+        self.commands.entity(entity).queue(count_in_flight);
+
+        entity
+    }
+
+    /// Spawns the task, unless the maximum number of in-flight tasks has been reached.
+    ///
+    /// This gives callers backpressure, so they can drop work instead of piling up tasks when
+    /// they are spawned faster than they complete. The limit is configured with
+    /// [`TaskConfig::max_in_flight_tasks`], and tracked by the `in_flight` resource.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::QueueFull`] if the maximum number of in-flight tasks has been reached.
+    pub fn try_spawn_with_strategy<
+        T: Resource,
+        F: Future<Output = CommandQueue> + Send + 'static,
+    >(
+        &mut self,
+        in_flight: &InFlightTasks,
+        strategy: impl ResourceStrategy<T, F> + 'static,
+        task: impl TaskFuture<T>,
+    ) -> Result<Entity> {
+        let in_flight = in_flight.try_acquire()?;
+        Ok(self.spawn_in_flight(Some(in_flight), strategy, task))
+    }
+
+    /// See [`TaskBuilder::try_spawn_with_strategy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::QueueFull`] if the maximum number of in-flight tasks has been reached.
+    pub fn try_spawn<T: Resource>(
+        &mut self,
+        in_flight: &InFlightTasks,
+        task: impl TaskFuture<T>,
+    ) -> Result<()> {
+        self.try_spawn_with_strategy(in_flight, strategy::resource::to_resource, task)
+            .map(|_| ())
+    }
+
+    fn spawn_in_flight<T: Resource, F: Future<Output = CommandQueue> + Send + 'static>(
+        &mut self,
+        in_flight: Option<InFlight>,
+        strategy: impl ResourceStrategy<T, F> + 'static,
+        task: impl TaskFuture<T>,
+    ) -> Entity {
         let task_pool = self.pool.get();
 
         let entity = self.commands.spawn_empty().id();
 
-        let task = task_pool.spawn(async move { strategy(entity, task.await).await });
+        let task =
+            YggdrasilTask::spawn::<T>(task_pool, Generation::next(), in_flight, async move {
+                strategy(entity, task.await).await
+            });
 
         self.commands
             .entity(entity)
-            .insert((Tag(PhantomData::<T>), YggdrasilTask(task)));
+            .insert((Tag(PhantomData::<T>), task));
+
+        entity
     }
 
     pub fn spawn<T: Resource>(&mut self, task: impl TaskFuture<T>) {
         self.spawn_with_strategy(strategy::resource::to_resource, task);
     }
+
+    /// Spawns a task that reports its progress through the provided [`ProgressReporter`].
+    ///
+    /// The progress can be read from the returned [`TaskProgress`], which is also added as a
+    /// component to the entity of the task. Progress is best-effort and not synchronized with
+    /// completion, see [`TaskProgress`].
+    ///
+    /// ```ignore
+    /// commands
+    ///     .prepare_task(TaskPool::AsyncCompute)
+    ///     .to_resource()
+    ///     .spawn_with_progress(|reporter| async move {
+    ///         reporter.set(0.5);
+    ///         Some(calibrate().await)
+    ///     });
+    /// ```
+    pub fn spawn_with_progress<T: Resource, Fut: TaskFuture<T>>(
+        &mut self,
+        task: impl FnOnce(ProgressReporter) -> Fut,
+    ) -> TaskProgress {
+        let (reporter, progress) = progress::channel();
+
+        let entity = self.spawn_with_strategy(strategy::resource::to_resource, task(reporter));
+        self.commands.entity(entity).insert(progress.clone());
+
+        progress
+    }
 }
 
 impl TaskBuilder<'_, '_, '_, EntityTask> {
@@ -148,7 +322,7 @@ impl TaskBuilder<'_, '_, '_, EntityTask> {
     ) {
         let pool = AsyncComputeTaskPool::get();
 
-        let generation = Generation(CURRENT_GEN.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+        let generation = Generation::next();
 
         let tasks = tasks
             .into_iter()
@@ -165,10 +339,11 @@ impl TaskBuilder<'_, '_, '_, EntityTask> {
             .collect::<Vec<_>>();
 
         for (entity, future) in tasks {
-            let task = pool.spawn(future);
+            let task = YggdrasilTask::spawn::<T>(pool, generation.clone(), None, future);
             self.commands
                 .entity(entity)
-                .insert((Tag(PhantomData::<T>), YggdrasilTask(task)));
+                .insert((Tag(PhantomData::<T>), task))
+                .queue(count_in_flight);
         }
     }
 
@@ -190,19 +365,219 @@ impl<'a, 'w, 's> CommandsExt<'a, 'w, 's> for Commands<'w, 's> {
     }
 }
 
-fn handle_tasks(mut commands: Commands, mut query: Query<&mut YggdrasilTask>) {
-    for mut task in &mut query {
-        if let Some(mut command_queue) = check_ready(&mut task.0) {
-            commands.append(&mut command_queue);
+fn handle_tasks(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut YggdrasilTask)>,
+    mut completed: EventWriter<TaskCompleted>,
+    mut failed: EventWriter<TaskFailed>,
+) {
+    for (entity, mut task) in &mut query {
+        match check_ready(&mut task.task) {
+            None => {}
+            Some(Ok(mut command_queue)) => {
+                commands.append(&mut command_queue);
+                completed.write(TaskCompleted::new(entity, &task.info));
+            }
+            Some(Err(payload)) => {
+                // the task panicked, so the output strategy never ran
+                commands.entity(entity).despawn();
+
+                let event = TaskFailed::new(entity, &task.info, payload.as_ref());
+                tracing::error!(task = event.tag_name, "Task panicked: {}", event.message);
+                failed.write(event);
+            }
+        }
+    }
+}
+
+/// The number of threads for each of the task pools.
+///
+/// Pools without a thread count use bevy's default assignment, which is based on the number of available cores.
+/// The default config caps the pools to the four cores of the NAO, see [`TaskConfig::NAO_THREADS`].
+#[derive(Debug, Clone)]
+pub struct TaskConfig {
+    pub compute_threads: Option<usize>,
+    pub async_compute_threads: Option<usize>,
+    pub io_threads: Option<usize>,
+    /// The maximum number of tasks that can be in flight at the same time when spawned with
+    /// [`TaskBuilder::try_spawn`], or unbounded if `None`.
+    ///
+    /// Tasks spawned with [`TaskBuilder::spawn`] are never rejected, but do count towards the limit.
+    pub max_in_flight_tasks: Option<usize>,
+}
+
+impl Default for TaskConfig {
+    fn default() -> Self {
+        let [compute, async_compute, io] = Self::NAO_THREADS;
+
+        Self {
+            compute_threads: Some(compute),
+            async_compute_threads: Some(async_compute),
+            io_threads: Some(io),
+            max_in_flight_tasks: None,
+        }
+    }
+}
+
+impl TaskConfig {
+    /// The number of compute, async compute and io threads on the NAO.
+    ///
+    /// Together these use one thread per core, as more threads than cores cause jitter in the
+    /// cycle time.
+    pub const NAO_THREADS: [usize; 3] = [2, 1, 1];
+
+    fn task_pool_options(&self) -> TaskPoolOptions {
+        let mut options = TaskPoolOptions::default();
+
+        for (policy, threads) in [
+            (&mut options.compute, self.compute_threads),
+            (&mut options.async_compute, self.async_compute_threads),
+            (&mut options.io, self.io_threads),
+        ] {
+            if let Some(threads) = threads {
+                *policy = TaskPoolThreadAssignmentPolicy {
+                    min_threads: threads,
+                    max_threads: threads,
+                    ..policy.clone()
+                };
+            }
         }
+
+        options
     }
 }
 
 /// Plugin that provides the task system.
-pub struct TaskPlugin;
+///
+/// Sends a [`TaskCompleted`] or [`TaskFailed`] event whenever a task finishes.
+///
+/// The task pools are created with the thread counts from the [`TaskConfig`].
+/// The pools can only be created once, so this plugin has to be added before bevy's
+/// [`TaskPoolPlugin`](bevy::app::TaskPoolPlugin), which is part of the `MinimalPlugins`.
+#[derive(Default)]
+pub struct TaskPlugin {
+    pub config: TaskConfig,
+}
+
+impl TaskPlugin {
+    #[must_use]
+    pub fn new(config: TaskConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl Plugin for TaskPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostUpdate, handle_tasks);
+        if ComputeTaskPool::try_get().is_some()
+            || AsyncComputeTaskPool::try_get().is_some()
+            || IoTaskPool::try_get().is_some()
+        {
+            tracing::warn!(
+                "Task pools have already been created, the thread counts of {:?} are ignored",
+                self.config
+            );
+        }
+
+        self.config.task_pool_options().create_default_pools();
+        app.insert_resource(InFlightTasks::new(self.config.max_in_flight_tasks))
+            .add_event::<TaskCompleted>()
+            .add_event::<TaskFailed>()
+            .add_systems(PostUpdate, handle_tasks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    #[derive(Resource)]
+    struct Healthy(u32);
+
+    #[derive(Resource)]
+    struct Buggy;
+
+    #[test]
+    fn panicking_task_does_not_affect_others() {
+        let mut app = App::new();
+        app.add_plugins(TaskPlugin::default());
+
+        let mut commands = app.world_mut().commands();
+        commands
+            .prepare_task(TaskPool::AsyncCompute)
+            .to_resource()
+            .spawn::<Buggy>(async { panic!("buggy task") });
+        commands
+            .prepare_task(TaskPool::AsyncCompute)
+            .to_resource()
+            .spawn(async { Some(Healthy(42)) });
+        app.world_mut().flush();
+
+        // a hung task fails the test instead of blocking it forever
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut failed = Vec::new();
+        while failed.is_empty() || !app.world().contains_resource::<Healthy>() {
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for the tasks to finish"
+            );
+            app.update();
+            failed.extend(
+                app.world()
+                    .resource::<Events<TaskFailed>>()
+                    .iter_current_update_events()
+                    .cloned(),
+            );
+            thread::yield_now();
+        }
+
+        assert_eq!(app.world().resource::<Healthy>().0, 42);
+        assert!(!app.world().contains_resource::<Buggy>());
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].is::<Buggy>());
+        assert_eq!(failed[0].message, "buggy task");
+    }
+
+    #[test]
+    fn in_flight_tasks_are_limited_per_app() {
+        let config = TaskConfig {
+            max_in_flight_tasks: Some(1),
+            ..Default::default()
+        };
+        let mut app = App::new();
+        app.add_plugins(TaskPlugin::new(config.clone()));
+        let in_flight = app.world().resource::<InFlightTasks>().clone();
+
+        let mut commands = app.world_mut().commands();
+        commands
+            .prepare_task(TaskPool::AsyncCompute)
+            .to_resource()
+            .spawn(std::future::pending::<Option<Healthy>>());
+        app.world_mut().flush();
+        assert_eq!(in_flight.in_flight(), 1);
+
+        let mut commands = app.world_mut().commands();
+        assert_eq!(
+            commands
+                .prepare_task(TaskPool::AsyncCompute)
+                .to_resource()
+                .try_spawn(&in_flight, async { Some(Healthy(42)) }),
+            Err(Error::QueueFull { limit: 1 })
+        );
+
+        // another app does not share the limit
+        let mut other = App::new();
+        other.add_plugins(TaskPlugin::new(config));
+        let other_in_flight = other.world().resource::<InFlightTasks>().clone();
+
+        let mut commands = other.world_mut().commands();
+        assert!(
+            commands
+                .prepare_task(TaskPool::AsyncCompute)
+                .to_resource()
+                .try_spawn(&other_in_flight, async { Some(Healthy(42)) })
+                .is_ok()
+        );
     }
 }
@@ -0,0 +1,157 @@
+//! Keyed tasks whose outputs are collected as they complete.
+
+use std::{panic::AssertUnwindSafe, thread};
+
+use bevy::tasks::{Task, futures::check_ready, futures_lite::FutureExt};
+
+use crate::{TaskFuture, TaskPool, events::panic_message};
+
+/// A set of tasks identified by a key, whose outputs can be collected as soon as each task has
+/// finished, instead of waiting for the slowest one.
+///
+/// This is useful when running many small tasks, such as one inference per candidate, where the
+/// results can be processed incrementally within the time budget of a cycle.
+///
+/// ```ignore
+/// let mut classifications = TaskMap::new();
+///
+/// for (id, patch) in candidates {
+///     classifications.spawn(&TaskPool::AsyncCompute, id, async move { Some(classify(patch)) });
+/// }
+///
+/// for (id, confidence) in classifications.poll_completed() {
+///     // handle the results that are already available
+/// }
+/// ```
+pub struct TaskMap<K, T> {
+    tasks: Vec<(K, Task<thread::Result<Option<T>>>)>,
+}
+
+impl<K, T> Default for TaskMap<K, T> {
+    fn default() -> Self {
+        Self { tasks: Vec::new() }
+    }
+}
+
+impl<K, T: Send + 'static> TaskMap<K, T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns a task on the given pool, identified by `key`.
+    ///
+    /// Keys do not have to be unique, every task is returned separately by
+    /// [`TaskMap::poll_completed`].
+    pub fn spawn(&mut self, pool: &TaskPool, key: K, task: impl TaskFuture<T>) {
+        let task = pool.get().spawn(AssertUnwindSafe(task).catch_unwind());
+        self.tasks.push((key, task));
+    }
+
+    /// The number of tasks that have not been collected yet.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Whether all tasks have been collected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Returns the outputs of the tasks that have finished since the last poll, with their keys.
+    ///
+    /// This never blocks. Every task is returned at most once, and the completed tasks are
+    /// returned in the order they were spawned, not in the order in which they finished.
+    ///
+    /// Tasks that returned [`None`] are removed without being returned, and tasks that panicked
+    /// are removed and logged.
+    pub fn poll_completed(&mut self) -> Vec<(K, T)> {
+        let mut completed = Vec::new();
+        let tasks = std::mem::take(&mut self.tasks);
+
+        for (key, mut task) in tasks {
+            match check_ready(&mut task) {
+                None => self.tasks.push((key, task)),
+                Some(Ok(Some(output))) => completed.push((key, output)),
+                Some(Ok(None)) => {}
+                Some(Err(payload)) => {
+                    tracing::error!("Task in map panicked: {}", panic_message(payload.as_ref()));
+                }
+            }
+        }
+
+        completed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::{Duration, Instant},
+    };
+
+    use bevy::{app::TaskPoolOptions, tasks::futures_lite::future::yield_now};
+
+    use super::*;
+
+    /// Polls the map until at least one task has been collected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no task completes within five seconds, so a hung task fails the test instead of
+    /// blocking it forever.
+    fn wait_for_completed(map: &mut TaskMap<usize, usize>) -> Vec<(usize, usize)> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let completed = map.poll_completed();
+            if !completed.is_empty() {
+                return completed;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for a task to complete"
+            );
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn collects_incrementally() {
+        TaskPoolOptions::default().create_default_pools();
+
+        let gates = (0..3)
+            .map(|_| Arc::new(AtomicBool::new(false)))
+            .collect::<Vec<_>>();
+
+        let mut map = TaskMap::new();
+        for (key, gate) in gates.iter().enumerate() {
+            let gate = gate.clone();
+            map.spawn(&TaskPool::AsyncCompute, key, async move {
+                while !gate.load(Ordering::Acquire) {
+                    yield_now().await;
+                }
+                Some(key * 10)
+            });
+        }
+
+        assert!(map.poll_completed().is_empty());
+        assert_eq!(map.len(), 3);
+
+        gates[1].store(true, Ordering::Release);
+        assert_eq!(wait_for_completed(&mut map), [(1, 10)]);
+        assert_eq!(map.len(), 2);
+
+        gates[2].store(true, Ordering::Release);
+        assert_eq!(wait_for_completed(&mut map), [(2, 20)]);
+
+        gates[0].store(true, Ordering::Release);
+        assert_eq!(wait_for_completed(&mut map), [(0, 0)]);
+        assert!(map.is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+//! Progress reporting for long-running tasks.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU32, Ordering},
+};
+
+use bevy::prelude::*;
+
+/// Creates a connected reporter and progress handle, starting at zero progress.
+pub(crate) fn channel() -> (ProgressReporter, TaskProgress) {
+    let progress = Arc::new(AtomicU32::new(0.0_f32.to_bits()));
+
+    (ProgressReporter(progress.clone()), TaskProgress(progress))
+}
+
+/// Handle passed into a task to report its progress, see
+/// [`TaskBuilder::spawn_with_progress`](crate::TaskBuilder::spawn_with_progress).
+#[derive(Debug, Clone)]
+pub struct ProgressReporter(Arc<AtomicU32>);
+
+impl ProgressReporter {
+    /// Sets the progress of the task, as a fraction between 0 and 1.
+    ///
+    /// Values outside this range are clamped.
+    pub fn set(&self, progress: f32) {
+        self.0
+            .store(progress.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// The progress of a running task, as reported by its [`ProgressReporter`].
+///
+/// This component is added to the entity of a task spawned with progress reporting.
+///
+/// Progress is best-effort: it is only as accurate as the task reports it, and it is not
+/// synchronized with completion. A task can finish before reporting full progress, and the
+/// last reported value may be observed after the output of the task has already been applied.
+#[derive(Component, Debug, Clone)]
+pub struct TaskProgress(Arc<AtomicU32>);
+
+impl TaskProgress {
+    /// The last reported progress, as a fraction between 0 and 1.
+    #[must_use]
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
@@ -1,4 +1,8 @@
-use std::future::Future;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    time::{Duration, Instant},
+};
 
 use crate::{Generation, Tag, YggdrasilTask};
 use bevy::{ecs::world::CommandQueue, prelude::*, tasks::BoxedFuture};
@@ -101,3 +105,232 @@ pub fn latest_n<T: Send + Component>(
         Box::pin(to_entity_latest_n_inner(n, generation, entity, value))
     }
 }
+
+/// Results of entity tasks that are held back by [`in_order`], until the results of all earlier
+/// generations have been applied.
+#[derive(Resource)]
+pub struct OrderedResults<T> {
+    held: BTreeMap<Generation, Vec<HeldResult<T>>>,
+    last_applied: Option<Generation>,
+}
+
+struct HeldResult<T> {
+    entity: Entity,
+    value: Option<T>,
+    deadline: Instant,
+}
+
+impl<T> Default for OrderedResults<T> {
+    fn default() -> Self {
+        Self {
+            held: BTreeMap::new(),
+            last_applied: None,
+        }
+    }
+}
+
+impl<T> OrderedResults<T> {
+    /// The number of results that are currently held back.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.held.values().map(Vec::len).sum()
+    }
+
+    /// Whether no results are currently held back.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.held.is_empty()
+    }
+}
+
+/// Strategy that applies the results of entity tasks in ascending [`Generation`] order, without
+/// dropping any of them.
+///
+/// Unlike [`latest`], a completed result is held back as long as a task of an earlier generation
+/// is still running. Held results are applied once the earlier tasks have finished, or once they
+/// have been held for longer than `timeout`. A result that arrives after a later generation has
+/// already been applied is dropped, so results are never applied out of order. Applied results
+/// are kept like with [`keep_all`].
+///
+/// Held results are stored in the [`OrderedResults`] resource, so memory usage grows with the
+/// number of results that complete while an earlier task is running. A slow task can hold back
+/// up to `timeout` worth of results, so the timeout should be kept short for tasks that are
+/// spawned often or produce large outputs.
+///
+/// Results are only checked when a task completes, add [`apply_in_order`] to the schedule to
+/// also apply results whose timeout expired in the meantime.
+pub fn in_order<T: Component>(
+    timeout: Duration,
+) -> impl Fn(Generation, Entity, Option<T>) -> BoxedFuture<'static, CommandQueue> + Clone {
+    #[allow(clippy::unused_async)]
+    async fn in_order_inner<T: Component>(
+        timeout: Duration,
+        generation: Generation,
+        entity: Entity,
+        value: Option<T>,
+    ) -> CommandQueue {
+        let mut queue = CommandQueue::default();
+
+        queue.push(move |world: &mut World| {
+            // the task has finished, but keeps its tag until its result is applied
+            world.entity_mut(entity).remove::<YggdrasilTask>();
+
+            let late = world
+                .get_resource_or_init::<OrderedResults<T>>()
+                .last_applied
+                .as_ref()
+                .is_some_and(|last_applied| generation < *last_applied);
+            if late {
+                world.entity_mut(entity).despawn();
+                return;
+            }
+
+            world
+                .resource_mut::<OrderedResults<T>>()
+                .held
+                .entry(generation)
+                .or_default()
+                .push(HeldResult {
+                    entity,
+                    value,
+                    deadline: Instant::now() + timeout,
+                });
+
+            apply_in_order::<T>(world);
+        });
+
+        queue
+    }
+
+    move |generation, entity, value| Box::pin(in_order_inner(timeout, generation, entity, value))
+}
+
+/// Applies the results held back by [`in_order`] that are no longer waiting for an earlier
+/// generation.
+pub fn apply_in_order<T: Component>(world: &mut World) {
+    if !world.contains_resource::<OrderedResults<T>>() {
+        return;
+    }
+
+    let oldest_running = world
+        .query_filtered::<&YggdrasilTask, With<Tag<T>>>()
+        .iter(world)
+        .map(|task| task.info.generation().clone())
+        .min();
+
+    world.resource_scope(|world, mut results: Mut<OrderedResults<T>>| {
+        let now = Instant::now();
+
+        while let Some(entry) = results.held.first_entry() {
+            let waiting = oldest_running
+                .as_ref()
+                .is_some_and(|oldest_running| oldest_running < entry.key());
+            let timed_out = entry.get().iter().any(|result| result.deadline <= now);
+            if waiting && !timed_out {
+                break;
+            }
+
+            let (generation, held) = entry.remove_entry();
+            for HeldResult { entity, value, .. } in held {
+                if let Some(value) = value {
+                    world
+                        .entity_mut(entity)
+                        .insert((value, generation.clone()))
+                        .remove::<Tag<T>>();
+                } else {
+                    world.entity_mut(entity).despawn();
+                }
+            }
+            results.last_applied = Some(generation);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use async_std::task;
+
+    use super::*;
+    use crate::{CommandsExt, TaskPlugin, TaskPool};
+
+    #[derive(Component)]
+    struct Step(u32);
+
+    fn spawn_step(app: &mut App, step: u32, delay: Duration, timeout: Duration) {
+        app.world_mut()
+            .commands()
+            .prepare_task(TaskPool::AsyncCompute)
+            .to_entities()
+            .spawn_with_strategy(
+                in_order(timeout),
+                [async move {
+                    task::sleep(delay).await;
+                    Some(Step(step))
+                }],
+            );
+        app.world_mut().flush();
+    }
+
+    fn applied_steps(app: &mut App) -> Vec<u32> {
+        let mut steps = app
+            .world_mut()
+            .query::<(&Step, &Generation)>()
+            .iter(app.world())
+            .map(|(step, generation)| (generation.clone(), step.0))
+            .collect::<Vec<_>>();
+        steps.sort_by(|(a, _), (b, _)| a.cmp(b));
+        steps.into_iter().map(|(_, step)| step).collect()
+    }
+
+    #[test]
+    fn out_of_order_results_are_applied_in_order() {
+        let mut app = App::new();
+        app.add_plugins(TaskPlugin::default())
+            .add_systems(Last, apply_in_order::<Step>);
+
+        // the first step takes the longest, so the results complete in reverse order
+        let timeout = Duration::from_secs(10);
+        spawn_step(&mut app, 0, Duration::from_millis(150), timeout);
+        spawn_step(&mut app, 1, Duration::from_millis(75), timeout);
+        spawn_step(&mut app, 2, Duration::ZERO, timeout);
+
+        let mut applied = Vec::new();
+        while applied.len() < 3 {
+            app.update();
+
+            let steps = applied_steps(&mut app);
+            assert_eq!(
+                steps,
+                (0..steps.len() as u32).collect::<Vec<_>>(),
+                "a later step was applied before an earlier one"
+            );
+            applied = steps;
+            thread::yield_now();
+        }
+        assert!(app.world().resource::<OrderedResults<Step>>().is_empty());
+
+        // a step that takes too long no longer holds back the next one, and is dropped once
+        // it completes
+        spawn_step(
+            &mut app,
+            3,
+            Duration::from_millis(300),
+            Duration::from_millis(50),
+        );
+        spawn_step(&mut app, 4, Duration::ZERO, Duration::from_millis(50));
+        while applied_steps(&mut app).len() < 4 {
+            app.update();
+            thread::yield_now();
+        }
+        assert_eq!(applied_steps(&mut app), [0, 1, 2, 4]);
+
+        let mut tasks = app.world_mut().query::<&Tag<Step>>();
+        while tasks.iter(app.world()).next().is_some() {
+            app.update();
+            thread::yield_now();
+        }
+        assert_eq!(applied_steps(&mut app), [0, 1, 2, 4]);
+    }
+}
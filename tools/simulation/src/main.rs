@@ -34,33 +34,40 @@ use bifrost::communication::{
     CompetitionPhase, CompetitionType, GameControllerMessage, GamePhase, GameState, Half, Penalty,
     RobotInfo, SetPlay, TeamColor, TeamInfo,
 };
-use egui::{emath::RectTransform, Pos2, Rect};
+use bifrost::serialization::{Decode, Encode};
+use clap::Parser;
 use egui::{
-    Color32, Direction, Image, Layout, Painter, Response, RichText, Sense, Stroke, Ui, Vec2,
+    Color32, Direction, Image, Layout, Painter, Response, RichText, Sense, Slider, Stroke, Ui, Vec2,
 };
+use egui::{Pos2, Rect, emath::RectTransform};
 use nalgebra::{Isometry2, Point2, Vector2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
 use std::time::Duration;
+use yggdrasil::behavior::BehaviorConfig;
 use yggdrasil::behavior::behaviors::ObserveBehaviorConfig;
 use yggdrasil::behavior::engine::{BehaviorKind, Context};
-use yggdrasil::behavior::primary_state::{next_primary_state, PrimaryStateConfig};
-use yggdrasil::behavior::BehaviorConfig;
+use yggdrasil::behavior::primary_state::{PrimaryStateConfig, next_primary_state};
 use yggdrasil::core::config::showtime::PlayerConfig;
 use yggdrasil::core::config::yggdrasil::YggdrasilConfig;
 use yggdrasil::core::debug::DebugContext;
 use yggdrasil::core::whistle::WhistleState;
 use yggdrasil::game_controller::GameControllerConfig;
-use yggdrasil::localization::{next_robot_pose, RobotPose};
+use yggdrasil::localization::{RobotPose, next_robot_pose};
 use yggdrasil::motion::odometry::{Odometry, OdometryConfig};
 use yggdrasil::motion::step_planner::StepPlanner;
 use yggdrasil::motion::walk::engine::WalkRequest;
 use yggdrasil::prelude::Config;
 use yggdrasil::sensor::orientation::OrientationFilterConfig;
 use yggdrasil::sensor::{ButtonConfig, FootBumperConfig, FsrConfig, SensorConfig};
+use yggdrasil::vision::VisionConfig;
 use yggdrasil::vision::camera::{CameraConfig, CameraSettings};
 use yggdrasil::vision::field_marks::FieldMarksConfig;
-use yggdrasil::vision::VisionConfig;
 use yggdrasil::{
-    behavior::{engine::Control, primary_state::PrimaryState, BehaviorEngine},
+    behavior::{BehaviorEngine, engine::Control, primary_state::PrimaryState},
     core::config::layout::LayoutConfig,
     motion::walk::engine::WalkingEngine,
 };
@@ -71,8 +78,111 @@ struct OccupiedScreenSpace {
     bottom: f32,
 }
 
+/// Tunable physics parameters of the simulated ball and robots.
+///
+/// These can be edited at runtime from the UI, to calibrate the simulation against measured ball roll distances.
+struct SimPhysicsConfig {
+    /// Fraction of the ball velocity that is kept every frame.
+    friction: f32,
+    /// Speed in m/s that the ball gets when a robot walks into it.
+    ///
+    /// This is disabled by default, so the ball is only pushed out of the robot. Set it to let the
+    /// ball roll away after a collision.
+    kick_impulse: f32,
+    /// Fraction of the incoming ball velocity that is reflected when the ball hits a robot.
+    restitution: f32,
+    /// Collision radius of a robot in meters.
+    robot_radius: f32,
+    /// Collision radius of the ball in meters.
+    ball_radius: f32,
+}
+
+impl Default for SimPhysicsConfig {
+    fn default() -> Self {
+        Self {
+            friction: 0.98,
+            kick_impulse: 0.0,
+            restitution: 0.0,
+            robot_radius: 0.1,
+            ball_radius: 0.05,
+        }
+    }
+}
+
+/// Gaussian noise that is added to the simulated sensors of the robots.
+///
+/// This is used to test how robust the behavior is against imperfect measurements.
+/// The noise is sampled from a seeded RNG, so runs with the same seed are reproducible.
+struct SensorNoiseConfig {
+    /// Standard deviation of the ball position measurement in meters.
+    ball_stddev: f32,
+    /// Standard deviation of the odometry translation per step in meters.
+    ///
+    /// The odometry noise only affects the pose the robot believes it has, so it drifts away from
+    /// the actual pose of the robot in the simulation.
+    odometry_translation_stddev: f32,
+    /// Standard deviation of the odometry rotation per step in radians.
+    odometry_rotation_stddev: f32,
+    seed: u64,
+}
+
+impl Default for SensorNoiseConfig {
+    fn default() -> Self {
+        Self {
+            ball_stddev: 0.0,
+            odometry_translation_stddev: 0.0,
+            odometry_rotation_stddev: 0.0,
+            seed: 42,
+        }
+    }
+}
+
+/// Samples from a normal distribution with zero mean using the Box-Muller transform.
+fn sample_gaussian(rng: &mut StdRng, stddev: f32) -> f32 {
+    if stddev <= 0.0 {
+        return 0.0;
+    }
+
+    let u1: f32 = rng.random_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.random();
+
+    stddev * (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+/// A single frame of a recorded scenario.
+///
+/// This contains the inputs of the simulation for that frame, and the robot poses
+/// which are used to reset the robots when a replay starts.
+#[derive(Clone, Encode, Decode)]
+struct ScenarioFrame {
+    game_state: GameState,
+    penalties: [bool; NUMBER_OF_PLAYERS],
+    ball: [f32; 2],
+    ball_velocity: [f32; 2],
+    /// Robot poses as `[x, y, angle]`
+    robot_poses: [[f32; 3]; NUMBER_OF_PLAYERS],
+}
+
+/// Whether the simulation is running live, recording, or replaying a scenario.
+enum ScenarioMode {
+    Live,
+    Recording,
+    Replaying { frame: usize, paused: bool },
+}
+
+const DEFAULT_SCENARIO_PATH: &str = "scenario.bin";
+
+#[derive(Parser, Debug)]
+#[clap(name = "simulation")]
+struct Cli {
+    /// Path of the file that recorded scenarios are saved to and loaded from
+    #[clap(short, long, default_value = DEFAULT_SCENARIO_PATH)]
+    scenario: PathBuf,
+}
+
 fn main() -> eframe::Result<()> {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+    let cli = Cli::parse();
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_fullscreen(true),
         ..Default::default()
@@ -84,7 +194,10 @@ fn main() -> eframe::Result<()> {
             // Provide image support:
             egui_extras::install_image_loaders(&cc.egui_ctx);
 
-            Ok(Box::<Simulation>::default())
+            Ok(Box::new(Simulation {
+                scenario_path: cli.scenario,
+                ..Default::default()
+            }))
         }),
     )
 }
@@ -100,6 +213,15 @@ struct Simulation {
     robots: Vec<Robot>,
     layout_config: LayoutConfig,
     global_ball: Option<Point2<f32>>,
+    ball_velocity: Vector2<f32>,
+    physics: SimPhysicsConfig,
+    noise: SensorNoiseConfig,
+    rng: StdRng,
+    scenario_mode: ScenarioMode,
+    scenario: Vec<ScenarioFrame>,
+    scenario_path: PathBuf,
+    scenario_status: String,
+    step_replay: bool,
 }
 
 impl Default for Simulation {
@@ -133,7 +255,6 @@ impl Default for Simulation {
                 single_shots: Default::default(),
                 message_budget: Default::default(),
             }; 2],
-            header: Default::default(),
             version: Default::default(),
             packet_number: Default::default(),
             players_per_team: Default::default(),
@@ -165,11 +286,181 @@ impl Default for Simulation {
             robots,
             layout_config,
             global_ball: Some(Point2::new(0.0, 0.0)),
+            ball_velocity: Vector2::zeros(),
+            physics: SimPhysicsConfig::default(),
+            noise: SensorNoiseConfig::default(),
+            rng: StdRng::seed_from_u64(SensorNoiseConfig::default().seed),
+            scenario_mode: ScenarioMode::Live,
+            scenario: Vec::new(),
+            scenario_path: PathBuf::from(DEFAULT_SCENARIO_PATH),
+            scenario_status: String::new(),
+            step_replay: false,
         }
     }
 }
 
 impl Simulation {
+    fn scenario_frame(&self) -> ScenarioFrame {
+        let ball = self.global_ball.unwrap_or_else(Point2::origin);
+
+        ScenarioFrame {
+            game_state: self.game_state,
+            penalties: self.penalties,
+            ball: [ball.x, ball.y],
+            ball_velocity: [self.ball_velocity.x, self.ball_velocity.y],
+            robot_poses: std::array::from_fn(|i| {
+                let pose = &self.robots[i].true_pose.inner;
+                [
+                    pose.translation.x,
+                    pose.translation.y,
+                    pose.rotation.angle(),
+                ]
+            }),
+        }
+    }
+
+    fn apply_scenario_frame(&mut self, frame: &ScenarioFrame) {
+        self.game_state = frame.game_state;
+        self.penalties = frame.penalties;
+        self.global_ball = Some(Point2::new(frame.ball[0], frame.ball[1]));
+        self.ball_velocity = Vector2::new(frame.ball_velocity[0], frame.ball_velocity[1]);
+    }
+
+    /// Restarts the noise of the sensors from the configured seed.
+    fn reseed(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.noise.seed);
+    }
+
+    /// Starts recording a new scenario, from a freshly seeded RNG.
+    fn start_recording(&mut self) {
+        self.scenario.clear();
+        self.reseed();
+        self.scenario_mode = ScenarioMode::Recording;
+    }
+
+    /// Resets the robots to the poses of the first recorded frame, and reseeds the RNG, so the
+    /// replay starts from the same state and samples the same noise every time.
+    fn start_replay(&mut self) {
+        let Some(first_frame) = self.scenario.first().cloned() else {
+            self.scenario_status = "Nothing to replay".to_string();
+            return;
+        };
+
+        self.robots = self
+            .robots
+            .iter()
+            .zip(first_frame.robot_poses)
+            .map(|(robot, [x, y, angle])| {
+                Robot::new(
+                    robot.player_config.clone(),
+                    Isometry2::new(Vector2::new(x, y), angle),
+                )
+            })
+            .collect();
+
+        self.apply_scenario_frame(&first_frame);
+        self.reseed();
+        self.scenario_mode = ScenarioMode::Replaying {
+            frame: 0,
+            paused: true,
+        };
+    }
+
+    fn save_scenario(&mut self) {
+        let result = File::create(&self.scenario_path)
+            .map_err(bifrost::Error::from)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                self.scenario.encode(&mut writer)?;
+                writer.flush().map_err(bifrost::Error::from)
+            });
+
+        self.scenario_status = match result {
+            Ok(()) => format!(
+                "Saved {} frames to `{}`",
+                self.scenario.len(),
+                self.scenario_path.display()
+            ),
+            Err(error) => format!("Failed to save scenario: {error}"),
+        };
+    }
+
+    fn load_scenario(&mut self) {
+        let result = File::open(&self.scenario_path)
+            .map_err(bifrost::Error::from)
+            .and_then(|file| Vec::<ScenarioFrame>::decode(BufReader::new(file)));
+
+        self.scenario_status = match result {
+            Ok(scenario) => {
+                self.scenario = scenario;
+                format!(
+                    "Loaded {} frames from `{}`",
+                    self.scenario.len(),
+                    self.scenario_path.display()
+                )
+            }
+            Err(error) => format!("Failed to load scenario: {error}"),
+        };
+    }
+
+    /// Returns whether the robots should be updated this frame.
+    fn update_scenario(&mut self, step: bool) -> bool {
+        match self.scenario_mode {
+            ScenarioMode::Live => true,
+            ScenarioMode::Recording => {
+                self.scenario.push(self.scenario_frame());
+                true
+            }
+            ScenarioMode::Replaying { frame, paused } => {
+                if paused && !step {
+                    return false;
+                }
+
+                let Some(scenario_frame) = self.scenario.get(frame).cloned() else {
+                    self.scenario_mode = ScenarioMode::Replaying {
+                        frame,
+                        paused: true,
+                    };
+                    return false;
+                };
+
+                self.apply_scenario_frame(&scenario_frame);
+                self.scenario_mode = ScenarioMode::Replaying {
+                    frame: frame + 1,
+                    paused,
+                };
+                true
+            }
+        }
+    }
+
+    /// Advances the scenario and the robots by a frame, `step` advances a paused replay.
+    fn update_robots(&mut self, step: bool) {
+        if !self.update_scenario(step) {
+            return;
+        }
+
+        self.gamecontrollermessage.state = self.game_state;
+
+        for (i, penalty) in self.penalties.iter().enumerate() {
+            if *penalty {
+                self.gamecontrollermessage.teams[0].players[i].penalty = Penalty::Manual;
+            } else {
+                self.gamecontrollermessage.teams[0].players[i].penalty = Penalty::None;
+            }
+        }
+
+        for robot in &mut self.robots {
+            robot.update(
+                &self.gamecontrollermessage,
+                &self.global_ball,
+                &self.layout_config,
+                &self.noise,
+                &mut self.rng,
+            );
+        }
+    }
+
     fn absolute_to_simulation(image_response: &Response, point: Point2<f32>) -> Pos2 {
         let to_screen = RectTransform::from_to(
             Rect::from_min_size(Pos2::ZERO, image_response.rect.size()),
@@ -200,21 +491,38 @@ impl Simulation {
     }
 
     fn check_ball_collisions(&mut self) {
-        let ball = self.global_ball.unwrap();
+        let Some(mut ball) = self.global_ball else {
+            return;
+        };
+
+        let collision_distance = self.physics.robot_radius + self.physics.ball_radius;
 
         for robot in self.robots.iter() {
-            let robot_pos = robot.pose.world_position();
-            let robot_radius = 0.1; // Robot radius
-            let ball_radius = 0.05; // Ball radius
+            let robot_pos = robot.true_pose.world_position();
 
             let distance = (robot_pos - ball).norm();
-            if distance < robot_radius + ball_radius {
+            if distance < collision_distance {
                 // Move the ball to the edge of the robot
                 let direction = (ball - robot_pos).normalize();
-                let new_ball_pos = robot_pos + direction * (robot_radius + ball_radius);
-                self.global_ball = Some(new_ball_pos);
+                ball = robot_pos + direction * collision_distance;
+
+                // Reflect the incoming velocity and push the ball away from the robot
+                let incoming_speed = self.ball_velocity.dot(&direction).min(0.0);
+                self.ball_velocity -= (1.0 + self.physics.restitution) * incoming_speed * direction;
+                self.ball_velocity += direction * self.physics.kick_impulse;
             }
         }
+
+        self.global_ball = Some(ball);
+    }
+
+    fn update_ball_motion(&mut self) {
+        let Some(ball) = self.global_ball.as_mut() else {
+            return;
+        };
+
+        *ball += self.ball_velocity / FRAMES_PER_SECOND as f32;
+        self.ball_velocity *= self.physics.friction;
     }
 
     fn draw_ball(&self, painter: &Painter, image_response: &Response) {
@@ -230,6 +538,9 @@ impl Simulation {
     fn update_global_ball(&mut self, response: &Response) {
         if let Some(pointer_pos) = response.interact_pointer_pos() {
             self.global_ball = Some(Simulation::simulation_to_absolute(response, pointer_pos));
+            self.ball_velocity = Vector2::zeros();
+        } else {
+            self.update_ball_motion();
         }
         self.check_ball_collisions();
     }
@@ -275,6 +586,79 @@ impl Simulation {
                 column.checkbox(&mut self.penalties[i], "");
             }
         });
+
+        ui.separator();
+        ui.label(RichText::new("Physics").heading());
+        ui.add(Slider::new(&mut self.physics.friction, 0.9..=1.0).text("Friction"));
+        ui.add(Slider::new(&mut self.physics.kick_impulse, 0.0..=5.0).text("Kick impulse (m/s)"));
+        ui.add(Slider::new(&mut self.physics.restitution, 0.0..=1.0).text("Restitution"));
+        ui.add(Slider::new(&mut self.physics.robot_radius, 0.0..=0.5).text("Robot radius (m)"));
+        ui.add(Slider::new(&mut self.physics.ball_radius, 0.0..=0.2).text("Ball radius (m)"));
+
+        ui.separator();
+        ui.label(RichText::new("Sensor noise").heading());
+        ui.add(Slider::new(&mut self.noise.ball_stddev, 0.0..=0.5).text("Ball stddev (m)"));
+        ui.add(
+            Slider::new(&mut self.noise.odometry_translation_stddev, 0.0..=0.01)
+                .text("Odometry translation stddev (m)"),
+        );
+        ui.add(
+            Slider::new(&mut self.noise.odometry_rotation_stddev, 0.0..=0.05)
+                .text("Odometry rotation stddev (rad)"),
+        );
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.noise.seed).prefix("Seed: "));
+            if ui.button("Reseed").clicked() {
+                self.reseed();
+            }
+        });
+
+        ui.separator();
+        ui.label(RichText::new("Scenario").heading());
+        self.ui_scenario_controls(ui);
+    }
+
+    fn ui_scenario_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| match self.scenario_mode {
+            ScenarioMode::Live => {
+                if ui.button("Record").clicked() {
+                    self.start_recording();
+                }
+                if ui.button("Replay").clicked() {
+                    self.start_replay();
+                }
+                if ui.button("Save").clicked() {
+                    self.save_scenario();
+                }
+                if ui.button("Load").clicked() {
+                    self.load_scenario();
+                }
+            }
+            ScenarioMode::Recording => {
+                if ui.button("Stop recording").clicked() {
+                    self.scenario_mode = ScenarioMode::Live;
+                }
+                ui.label(format!("{} frames", self.scenario.len()));
+            }
+            ScenarioMode::Replaying { frame, paused } => {
+                let label = if paused { "Play" } else { "Pause" };
+                if ui.button(label).clicked() {
+                    self.scenario_mode = ScenarioMode::Replaying {
+                        frame,
+                        paused: !paused,
+                    };
+                }
+                if ui.button("Step").clicked() {
+                    self.step_replay = true;
+                }
+                if ui.button("Stop replay").clicked() {
+                    self.scenario_mode = ScenarioMode::Live;
+                }
+                ui.label(format!("{frame}/{}", self.scenario.len()));
+            }
+        });
+
+        ui.label(&self.scenario_status);
     }
 
     fn ui_panel_bottom(&mut self, ui: &mut Ui) {
@@ -339,25 +723,17 @@ impl Simulation {
 
             let painter = ui.painter_at(image_response.rect);
 
-            self.gamecontrollermessage.state = self.game_state;
+            let step = std::mem::take(&mut self.step_replay);
+            self.update_robots(step);
 
-            for (i, penalty) in self.penalties.iter().enumerate() {
-                if *penalty {
-                    self.gamecontrollermessage.teams[0].players[i].penalty = Penalty::Manual;
-                } else {
-                    self.gamecontrollermessage.teams[0].players[i].penalty = Penalty::None;
-                }
+            for robot in &self.robots {
+                robot.draw(ui, &painter, &image_response, &self.global_ball);
             }
 
-            for robot in self.robots.iter_mut() {
-                robot.update(
-                    &self.gamecontrollermessage,
-                    &self.global_ball,
-                    &self.layout_config,
-                );
-                robot.draw(ui, &painter, &image_response, &self.global_ball);
+            // during a replay the ball is driven by the recorded scenario
+            if !matches!(self.scenario_mode, ScenarioMode::Replaying { .. }) {
+                self.update_global_ball(&image_response);
             }
-            self.update_global_ball(&image_response);
             self.draw_ball(&painter, &image_response);
         });
     }
@@ -376,6 +752,9 @@ impl eframe::App for Simulation {
 struct Robot {
     player_config: PlayerConfig,
     primary_state: PrimaryState,
+    /// The actual pose of the robot in the simulation.
+    true_pose: RobotPose,
+    /// The pose the robot believes it has, which is integrated from the noisy odometry.
     pose: RobotPose,
     engine: BehaviorEngine,
     walking_engine: WalkingEngine,
@@ -390,6 +769,7 @@ impl Robot {
             step_planner: StepPlanner::default(),
             engine: BehaviorEngine::default(),
             primary_state: PrimaryState::Initial,
+            true_pose: RobotPose { inner: isometry },
             pose: RobotPose { inner: isometry },
             sees_ball: false,
             player_config,
@@ -401,7 +781,21 @@ impl Robot {
         gamecontrollermessage: &GameControllerMessage,
         ball: &Option<Point2<f32>>,
         layout_config: &LayoutConfig,
+        noise: &SensorNoiseConfig,
+        rng: &mut StdRng,
     ) {
+        // the robot measures a noisy ball position relative to itself, and places it on the field
+        // using the pose it believes it has
+        let perceived_ball = &ball.map(|ball| {
+            let relative_ball = self.true_pose.world_to_robot(&ball)
+                + Vector2::new(
+                    sample_gaussian(rng, noise.ball_stddev),
+                    sample_gaussian(rng, noise.ball_stddev),
+                );
+
+            self.pose.robot_to_world(&relative_ball)
+        });
+
         self.primary_state = next_primary_state(
             &self.primary_state,
             &Some(gamecontrollermessage.clone()),
@@ -435,13 +829,17 @@ impl Robot {
             game_controller_message: Some(gamecontrollermessage),
             pose: &self.pose,
             current_behavior: BehaviorKind::Stand(Default::default()),
-            ball_position: if self.sees_ball { ball } else { &None },
+            ball_position: if self.sees_ball {
+                perceived_ball
+            } else {
+                &None
+            },
         };
 
         self.engine.step(context, &mut control);
 
         self.update_ball(ball);
-        self.walk(0.1, layout_config, gamecontrollermessage);
+        self.walk(0.1, layout_config, gamecontrollermessage, noise, rng);
     }
 
     fn walk(
@@ -449,6 +847,8 @@ impl Robot {
         walk_scalar: f32,
         layout_config: &LayoutConfig,
         gamecontrollermessage: &GameControllerMessage,
+        noise: &SensorNoiseConfig,
+        rng: &mut StdRng,
     ) {
         let step = match self.walking_engine.request {
             WalkRequest::Walk(step) => Some(step),
@@ -464,6 +864,26 @@ impl Robot {
             Isometry2::identity()
         };
 
+        // the robot moves by the actual step
+        self.true_pose = next_robot_pose(
+            &self.true_pose,
+            &odometry,
+            &self.primary_state,
+            layout_config,
+            &Some(gamecontrollermessage.clone()),
+        );
+
+        // but only the reported odometry drifts while walking
+        if step.is_some() {
+            odometry.offset_to_last *= Isometry2::new(
+                Vector2::new(
+                    sample_gaussian(rng, noise.odometry_translation_stddev),
+                    sample_gaussian(rng, noise.odometry_translation_stddev),
+                ),
+                sample_gaussian(rng, noise.odometry_rotation_stddev),
+            );
+        }
+
         self.pose = next_robot_pose(
             &self.pose,
             &odometry,
@@ -479,8 +899,8 @@ impl Robot {
             return;
         };
 
-        let relative_ball = self.pose.world_to_robot(ball);
-        let angle = self.pose.angle_to(&ball);
+        let relative_ball = self.true_pose.world_to_robot(ball);
+        let angle = self.true_pose.angle_to(&ball);
 
         self.sees_ball = relative_ball.coords.norm() < 3.0 && angle.abs() < 45.0f32.to_radians();
     }
@@ -492,10 +912,10 @@ impl Robot {
         image_response: &Response,
         ball: &Option<Point2<f32>>,
     ) {
-        let robot_rotation = self.pose.inner.rotation.inverse().angle();
+        let robot_rotation = self.true_pose.inner.rotation.inverse().angle();
 
         let robot_pos_screen =
-            Simulation::absolute_to_simulation(image_response, self.pose.world_position());
+            Simulation::absolute_to_simulation(image_response, self.true_pose.world_position());
 
         painter.circle_filled(robot_pos_screen, 13.0f32, Color32::RED);
         painter.text(
@@ -616,3 +1036,59 @@ fn create_default_configs() -> (YggdrasilConfig, BehaviorConfig, GameControllerC
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays the whole recorded scenario, and returns the poses the robots believe they have.
+    fn replay(simulation: &mut Simulation) -> Vec<[f32; 3]> {
+        simulation.start_replay();
+        for _ in 0..simulation.scenario.len() {
+            simulation.update_robots(true);
+        }
+
+        simulation
+            .robots
+            .iter()
+            .map(|robot| {
+                let pose = &robot.pose.inner;
+                [
+                    pose.translation.x,
+                    pose.translation.y,
+                    pose.rotation.angle(),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn noisy_replay_is_reproducible() {
+        let mut simulation = Simulation {
+            noise: SensorNoiseConfig {
+                ball_stddev: 0.2,
+                odometry_translation_stddev: 0.005,
+                odometry_rotation_stddev: 0.02,
+                seed: 7,
+            },
+            game_state: GameState::Playing,
+            ..Default::default()
+        };
+
+        simulation.start_recording();
+        for _ in 0..200 {
+            simulation.update_robots(false);
+        }
+        simulation.scenario_mode = ScenarioMode::Live;
+
+        // the live robots keep drawing noise after the recording, which must not leak into the
+        // replays
+        for _ in 0..10 {
+            simulation.update_robots(false);
+        }
+
+        let first = replay(&mut simulation);
+        let second = replay(&mut simulation);
+        assert_eq!(first, second);
+    }
+}
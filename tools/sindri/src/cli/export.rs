@@ -0,0 +1,60 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result, bail};
+
+/// Exports scalar timelines of a recorded rrd file to CSV, e.g. `stats/cycle_time`.
+#[derive(Parser, Debug)]
+pub struct ExportCommand {
+    /// Path to the recorded rrd file.
+    pub rrd: PathBuf,
+    /// Entity paths of the scalars to export, paths without scalars are skipped.
+    #[clap(required = true)]
+    pub entity_paths: Vec<String>,
+    /// File to write the CSV to, instead of stdout.
+    #[clap(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl ExportCommand {
+    /// Writes one column per entity path, indexed by the cycle they were logged in.
+    ///
+    /// Entity paths without scalars are reported on stderr, so they do not end up in the CSV
+    /// that is written to stdout. The export fails if none of the entity paths contain scalars.
+    pub fn export(self) -> Result<()> {
+        let skipped = match self.output {
+            Some(output) => yggdrasil::core::debug::export_scalars_to_csv(
+                &self.rrd,
+                &self.entity_paths,
+                BufWriter::new(File::create(output).into_diagnostic()?),
+            ),
+            None => yggdrasil::core::debug::export_scalars_to_csv(
+                &self.rrd,
+                &self.entity_paths,
+                io::stdout().lock(),
+            ),
+        }?;
+
+        for entity_path in &skipped {
+            eprintln!(
+                "{}: {}",
+                "warning".bold().yellow(),
+                format!("no scalars found for `{entity_path}`, skipping").white()
+            );
+        }
+
+        if skipped.len() == self.entity_paths.len() {
+            bail!(
+                "None of the entity paths contain scalars in `{}`",
+                self.rrd.display()
+            );
+        }
+
+        Ok(())
+    }
+}
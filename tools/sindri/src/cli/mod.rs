@@ -5,6 +5,7 @@ use crate::Sindri;
 
 pub mod change_network;
 pub mod config;
+pub mod export;
 pub mod flash;
 pub mod robot_ops;
 pub mod run;
@@ -67,4 +68,5 @@ pub enum Commands {
     Config(config::ConfigCommand),
     Update(update::UpdateCommand),
     Stop(stop::StopCommand),
+    Export(export::ExportCommand),
 }
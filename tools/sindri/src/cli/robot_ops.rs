@@ -11,10 +11,13 @@ use tokio::{
     io::{AsyncBufReadExt, BufReader},
     process::Command,
 };
-use yggdrasil::core::config::showtime::ShowtimeConfig;
+use yggdrasil::core::config::{ConfigProfile, showtime::ShowtimeConfig};
 use yggdrasil::prelude::*;
 
-use build_utils::cargo::{self, Profile, find_bin_manifest};
+use build_utils::{
+    cache::{BuildCache, BuildKey},
+    cargo::{self, Profile, find_bin_manifest},
+};
 
 use crate::{
     config::{Robot, SindriConfig},
@@ -27,6 +30,7 @@ const ROBOT_TARGET: &str = "x86_64-unknown-linux-gnu";
 const RELEASE_PATH_REMOTE: &str = "./target/x86_64-unknown-linux-gnu/release/yggdrasil";
 const RELEASE_PATH_LOCAL: &str = "./target/release/yggdrasil";
 const DEPLOY_PATH: &str = "./deploy/yggdrasil";
+const TARGET_DIR: &str = "./target";
 
 const LOCAL_ROBOT_ID_STR: &str = "local";
 
@@ -155,6 +159,17 @@ pub struct ConfigOptsRobotOps {
     /// [Tracy]: https://github.com/wolfpld/tracy
     #[clap(long)]
     pub timings: bool,
+
+    /// Always run cargo, even if a binary built from the same sources is cached [default: false]
+    #[clap(long)]
+    pub rebuild: bool,
+
+    /// Config profile to run with, such as `competition-field`
+    ///
+    /// The profile overlays the configs in `deploy/config/profile/<PROFILE>/` on top of the main
+    /// configs, before the overlay of the robot.
+    #[clap(long)]
+    pub profile: Option<String>,
 }
 
 impl ConfigOptsRobotOps {
@@ -194,9 +209,14 @@ impl ConfigOptsRobotOps {
                 robot_assignments.insert((robot.number).to_string(), DEFAULT_PLAYER_NUMBER);
             }
         }
+        if let Some(profile) = &self.profile {
+            ConfigProfile::resolve(Path::new("./deploy/config/"), profile)?;
+        }
+
         let showtime_config = ShowtimeConfig {
             team_number: self.team.unwrap_or(config.team_number),
             robot_numbers_map: robot_assignments,
+            profile: self.profile.clone(),
         };
 
         showtime_config
@@ -552,6 +572,32 @@ pub(crate) async fn compile(config: ConfigOptsRobotOps, output: Output) -> miett
     ));
     pb.set_prefix("Compiling");
 
+    let release_path = if config.local {
+        RELEASE_PATH_LOCAL
+    } else {
+        RELEASE_PATH_REMOTE
+    };
+
+    let cache = BuildCache::new(TARGET_DIR);
+    let key = build_key(&config, target, &features).await?;
+
+    if let Some(cached) = cache.get(&config.bin, key).filter(|_| !config.rebuild) {
+        if output.should_print() {
+            pb.println(format!(
+                "{} {} {}",
+                "      Cached".green().bold(),
+                "yggdrasil".bold(),
+                "(sources unchanged, skipping build)".dimmed()
+            ));
+        }
+
+        fs::copy(cached, DEPLOY_PATH)
+            .into_diagnostic()
+            .wrap_err("Failed to copy cached binary to deploy directory!")?;
+
+        return Ok(());
+    }
+
     cargo::build(
         &config.bin,
         Profile::Release,
@@ -561,6 +607,13 @@ pub(crate) async fn compile(config: ConfigOptsRobotOps, output: Output) -> miett
     )
     .await?;
 
+    if let Err(error) = cache.store(&config.bin, key, release_path) {
+        pb.println(format!(
+            "{} failed to cache binary: {error}",
+            "     Warning".yellow().bold()
+        ));
+    }
+
     if output.should_print() {
         pb.println(format!(
             "{} {} {}{}, {}{}{}",
@@ -580,12 +633,6 @@ pub(crate) async fn compile(config: ConfigOptsRobotOps, output: Output) -> miett
         pb.reset_elapsed();
     }
 
-    let release_path = if config.local {
-        RELEASE_PATH_LOCAL
-    } else {
-        RELEASE_PATH_REMOTE
-    };
-
     // Copy over the files that need to be deployed
     fs::copy(release_path, DEPLOY_PATH)
         .into_diagnostic()
@@ -594,6 +641,33 @@ pub(crate) async fn compile(config: ConfigOptsRobotOps, output: Output) -> miett
     Ok(())
 }
 
+/// Computes the key of a build of yggdrasil with the given options, see [`BuildKey`].
+///
+/// The key covers all sources in the workspace, the toolchain and all options passed to cargo.
+async fn build_key(
+    config: &ConfigOptsRobotOps,
+    target: Option<&str>,
+    features: &[&str],
+) -> miette::Result<BuildKey> {
+    let rustc_version = cargo::rustc_version().await?;
+
+    let mut options = vec![
+        config.bin.as_str(),
+        target.unwrap_or("host"),
+        rustc_version.as_str(),
+    ];
+    options.extend(features);
+    options.extend(
+        cross::ENV_VARS
+            .iter()
+            .flat_map(|(key, value)| [*key, *value]),
+    );
+
+    BuildKey::new(&["."], &options)
+        .into_diagnostic()
+        .wrap_err("Failed to hash the sources of the build")
+}
+
 /// Start the yggdrasil service on a specific robot
 pub(crate) async fn start_single_yggdrasil_service(robot: &Robot, output: Output) -> Result<()> {
     match &output {
@@ -17,8 +17,10 @@ use rerun::external::{
 };
 use strum::{EnumIter, IntoEnumIterator};
 use yggdrasil_rerun_comms::{
-    debug_system::DebugEnabledSystems,
-    protocol::{CONTROL_PORT, RobotMessage, control::RobotControlMessage},
+    protocol::{
+        CONTROL_PORT, RobotMessage,
+        control::{LoadedModel, RobotControlMessage},
+    },
     viewer::ControlViewer,
 };
 
@@ -27,9 +29,11 @@ use crate::{
     state::{HandleState, SharedHandleState},
     ui::{
         camera_calibration::{CameraState, camera_calibration_ui},
+        config_dump::{ConfigValueState, config_dump_ui},
         debug_systems::{DebugEnabledState, debug_enabled_systems_ui},
         extra_title_bar_connection_ui,
         field_color::{FieldColorState, field_color_ui},
+        models::models_ui,
         resource::{ResourcesState, resource_ui},
         selection_ui,
         visual_referee::visual_referee_ui,
@@ -49,14 +53,19 @@ pub struct ControlViewerData {
     pub debug_enabled_state: DebugEnabledState,
     pub camera_state: CameraState,
     pub field_color: FieldColorState,
+    pub config_dump: Option<String>,
+    pub config_value: ConfigValueState,
+    pub models: Vec<LoadedModel>,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, EnumIter)]
 enum ControlViewerSection {
     CameraCalibration,
+    ConfigDump,
     #[default]
     DebugEnabledSystems,
     FieldColor,
+    Models,
     Resources,
     VisualReferee,
 }
@@ -182,9 +191,15 @@ A view to control the robot",
                 // Camera calibration section
                 camera_calibration_ui(ui, Arc::clone(&state.data), handle);
             }
+            ControlViewerSection::ConfigDump => {
+                config_dump_ui(ui, Arc::clone(&state.data), handle);
+            }
             ControlViewerSection::FieldColor => {
                 field_color_ui(ui, Arc::clone(&state.data), handle);
             }
+            ControlViewerSection::Models => {
+                models_ui(ui, Arc::clone(&state.data));
+            }
             ControlViewerSection::VisualReferee => {
                 visual_referee_ui(ui, Arc::clone(&state.data), handle);
             }
@@ -253,8 +268,7 @@ impl HandleState for ControlViewerData {
         if let RobotMessage::RobotControlMessage(message) = message {
             match message {
                 RobotControlMessage::DebugEnabledSystems(enabled_systems) => {
-                    self.debug_enabled_state
-                        .update(DebugEnabledSystems::from(enabled_systems.clone()));
+                    self.debug_enabled_state.update(enabled_systems.clone());
                 }
                 RobotControlMessage::Resources(_resources) => {
                     tracing::warn!("Got a resource update but is unhandled")
@@ -276,6 +290,20 @@ impl HandleState for ControlViewerData {
                 RobotControlMessage::FieldColor { config } => {
                     self.field_color.config = config.clone();
                 }
+                RobotControlMessage::ConfigDump(dump) => {
+                    self.config_dump = Some(dump.clone());
+                }
+                RobotControlMessage::ConfigValueSet {
+                    config,
+                    path,
+                    result,
+                } => {
+                    self.config_value.last_result =
+                        Some((config.clone(), path.clone(), result.clone()));
+                }
+                RobotControlMessage::ModelStatus(models) => {
+                    self.models = models.clone();
+                }
             }
         }
     }
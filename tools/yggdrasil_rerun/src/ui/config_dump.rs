@@ -0,0 +1,114 @@
+use std::sync::{Arc, RwLock};
+
+use rerun::external::{egui, re_ui::UiExt};
+use yggdrasil_rerun_comms::{
+    protocol::{
+        ViewerMessage,
+        control::{ConfigValueResult, ViewerControlMessage},
+    },
+    viewer::ControlViewerHandle,
+};
+
+use crate::control_view::ControlViewerData;
+
+use super::view_section;
+
+/// Input of the form to set a single config value on the robot.
+#[derive(Default)]
+pub struct ConfigValueState {
+    pub config: String,
+    pub path: String,
+    pub value: String,
+    /// The result of the most recently set value, as `(config, path, result)`.
+    pub last_result: Option<(String, String, ConfigValueResult)>,
+}
+
+pub fn config_dump_ui(
+    ui: &mut egui::Ui,
+    viewer_data: Arc<RwLock<ControlViewerData>>,
+    handle: &ControlViewerHandle,
+) {
+    view_section(ui, "Config dump".to_string(), |ui| {
+        let Ok(viewer_data) = &mut viewer_data.write() else {
+            tracing::error!("Failed to lock viewer data");
+            return;
+        };
+
+        config_value_ui(ui, &mut viewer_data.config_value, handle);
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui.button("Dump configs").clicked() {
+                if let Err(error) = handle.send(ViewerMessage::ViewerControlMessage(
+                    ViewerControlMessage::DumpConfigs,
+                )) {
+                    tracing::error!(?error, "Failed to send message");
+                }
+            }
+
+            if let Some(dump) = &viewer_data.config_dump {
+                if ui.button("Copy").clicked() {
+                    ui.ctx().copy_text(dump.clone());
+                }
+            }
+        });
+
+        ui.separator();
+
+        match &viewer_data.config_dump {
+            Some(dump) => {
+                ui.add(
+                    egui::TextEdit::multiline(&mut dump.as_str())
+                        .code_editor()
+                        .desired_width(f32::INFINITY),
+                );
+            }
+            None => {
+                ui.label("No configs received yet");
+            }
+        }
+    });
+}
+
+fn config_value_ui(ui: &mut egui::Ui, state: &mut ConfigValueState, handle: &ControlViewerHandle) {
+    egui::Grid::new("config value")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Config");
+            ui.text_edit_singleline(&mut state.config)
+                .on_hover_text("Type name or path of the config, e.g. `robot_detection.toml`");
+            ui.end_row();
+
+            ui.label("Path");
+            ui.text_edit_singleline(&mut state.path)
+                .on_hover_text("Dotted path of the value, e.g. `tracker.min_iou`");
+            ui.end_row();
+
+            ui.label("Value");
+            ui.text_edit_singleline(&mut state.value)
+                .on_hover_text("TOML value, strings need to be quoted");
+            ui.end_row();
+        });
+
+    if ui.button("Set value").clicked() {
+        if let Err(error) = handle.send(ViewerMessage::ViewerControlMessage(
+            ViewerControlMessage::SetConfigValue {
+                config: state.config.clone(),
+                path: state.path.clone(),
+                value: state.value.clone(),
+            },
+        )) {
+            tracing::error!(?error, "Failed to send message");
+        }
+    }
+
+    match &state.last_result {
+        Some((config, path, ConfigValueResult::Applied)) => {
+            ui.label(format!("Set `{path}` of `{config}`"));
+        }
+        Some((_, _, ConfigValueResult::Rejected(error))) => {
+            ui.warning_label(error);
+        }
+        None => {}
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::{Arc, RwLock};
 use rerun::external::{egui, re_ui::UiExt};
 
 use yggdrasil_rerun_comms::{
-    debug_system::DebugEnabledSystems,
+    debug_system::{DebugEnabledSystems, DebugLevel},
     protocol::{ViewerMessage, control::ViewerControlMessage},
     viewer::ControlViewerHandle,
 };
@@ -16,12 +16,17 @@ use super::view_section;
 pub struct DebugEnabledState {
     debug_enabled_systems: DebugEnabledSystems,
     key_sequence: Vec<String>,
+    categories: Vec<String>,
 }
 
 impl DebugEnabledState {
     pub fn update(&mut self, debug_enabled_systems: DebugEnabledSystems) {
         let mut key_sequence: Vec<_> = debug_enabled_systems.systems.keys().cloned().collect();
         key_sequence.sort();
+        self.categories = debug_enabled_systems
+            .category_names()
+            .map(ToString::to_string)
+            .collect();
         self.debug_enabled_systems = debug_enabled_systems;
         self.key_sequence = key_sequence;
     }
@@ -62,7 +67,64 @@ fn debug_enabled_systems_control_ui(
             return;
         }
 
+        let mut verbosity = debug_enabled_state.debug_enabled_systems.verbosity;
+        egui::ComboBox::from_label("Verbosity")
+            .selected_text(format!("{verbosity:?}"))
+            .show_ui(ui, |ui| {
+                for level in DebugLevel::ALL {
+                    ui.selectable_value(&mut verbosity, level, format!("{level:?}"));
+                }
+            });
+
+        if verbosity != debug_enabled_state.debug_enabled_systems.verbosity {
+            debug_enabled_state.debug_enabled_systems.verbosity = verbosity;
+
+            let message =
+                ViewerMessage::ViewerControlMessage(ViewerControlMessage::UpdateDebugVerbosity {
+                    verbosity,
+                });
+
+            if let Err(error) = handle.send(message) {
+                tracing::error!(?error, "Failed to send update debug verbosity message")
+            }
+        }
+
+        for category in &debug_enabled_state.categories {
+            let systems = &mut debug_enabled_state.debug_enabled_systems;
+            let mut enabled = key_sequence
+                .iter()
+                .filter(|system_name| systems.category(system_name) == Some(category.as_str()))
+                .all(|system_name| systems.systems.get(*system_name).copied().unwrap_or(false));
+
+            if ui
+                .checkbox(&mut enabled, format!("All {category}"))
+                .changed()
+            {
+                systems.set_category_enabled(category, enabled);
+
+                let message = ViewerMessage::ViewerControlMessage(
+                    ViewerControlMessage::UpdateEnabledDebugCategory {
+                        category: category.clone(),
+                        enabled,
+                    },
+                );
+
+                if let Err(error) = handle.send(message) {
+                    tracing::error!(?error, "Failed to send update debug category message")
+                }
+            }
+        }
+
+        ui.separator();
+
         for system_name in key_sequence {
+            let level = debug_enabled_state
+                .debug_enabled_systems
+                .levels
+                .get(system_name)
+                .copied()
+                .unwrap_or_default();
+
             let Some(enabled) = debug_enabled_state
                 .debug_enabled_systems
                 .systems
@@ -72,7 +134,10 @@ fn debug_enabled_systems_control_ui(
                 continue;
             };
 
-            if ui.checkbox(enabled, system_name).changed() {
+            if ui
+                .checkbox(enabled, format!("{system_name} ({level:?})"))
+                .changed()
+            {
                 let message = ViewerMessage::ViewerControlMessage(
                     ViewerControlMessage::UpdateEnabledDebugSystem {
                         system_name: system_name.clone(),
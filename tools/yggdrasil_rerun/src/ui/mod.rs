@@ -5,9 +5,11 @@ use rerun::external::egui::{
 use crate::connection::ConnectionState;
 
 pub mod camera_calibration;
+pub mod config_dump;
 pub mod debug_systems;
 pub mod field_color;
 pub mod game_controller;
+pub mod models;
 pub mod resource;
 pub mod selection_ui;
 pub mod visual_referee;
@@ -0,0 +1,46 @@
+use std::sync::{Arc, RwLock};
+
+use rerun::external::{egui, re_ui::UiExt};
+use yggdrasil_rerun_comms::protocol::control::ModelLoadStatus;
+
+use crate::control_view::ControlViewerData;
+
+use super::view_section;
+
+pub fn models_ui(ui: &mut egui::Ui, viewer_data: Arc<RwLock<ControlViewerData>>) {
+    view_section(ui, "Models".to_string(), |ui| {
+        let Ok(viewer_data) = viewer_data.read() else {
+            tracing::error!("Failed to lock viewer data");
+            return;
+        };
+
+        if viewer_data.models.is_empty() {
+            ui.label("No models received yet");
+            return;
+        }
+
+        egui::Grid::new("models")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Model");
+                ui.strong("Load time");
+                ui.strong("Status");
+                ui.end_row();
+
+                for model in &viewer_data.models {
+                    ui.label(&model.path);
+                    ui.label(format!("{} ms", model.load_time_ms));
+                    match &model.status {
+                        ModelLoadStatus::Live => {
+                            ui.label("Live");
+                        }
+                        ModelLoadStatus::Failed(error) => {
+                            ui.warning_label(error);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    });
+}
@@ -149,9 +149,15 @@ impl ControlApp {
                     // Keep decoding bytes to messages until we read the whole
                     // buffer
                     while bytes_read < n {
-                        let message = ViewerMessage::decode(&buf[bytes_read..n])
-                            .map_err(|error| format!("Failed to decode message: {error:?}"))
-                            .unwrap();
+                        let message = match ViewerMessage::decode(&buf[bytes_read..n]) {
+                            Ok(message) => message,
+                            Err(error) => {
+                                // e.g. a viewer that is built from another version of the
+                                // messages, which we can't talk to
+                                tracing::error!(%error, "Failed to decode message, disconnecting");
+                                return;
+                            }
+                        };
 
                         let handlers = handlers.read().expect("failed to lock handlers");
 
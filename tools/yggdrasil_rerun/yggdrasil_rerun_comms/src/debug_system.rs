@@ -1,7 +1,30 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
 
 use bevy::prelude::*;
 use bifrost::serialization::{Decode, Encode};
+use miette::IntoDiagnostic;
+
+/// Verbosity level of a debug system, ordered from least to most verbose.
+#[derive(Encode, Decode, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DebugLevel {
+    /// Essential visualizations that are cheap to log.
+    Info,
+    /// Regular debug visualizations.
+    #[default]
+    Debug,
+    /// Detailed visualizations that are expensive or produce a lot of data.
+    Trace,
+}
+
+impl DebugLevel {
+    /// All levels, ordered from least to most verbose.
+    pub const ALL: [DebugLevel; 3] = [DebugLevel::Info, DebugLevel::Debug, DebugLevel::Trace];
+}
 
 /// [`DebugEnabledSystems`] keeps track whether a certain system should run,
 /// or as the name implies, is enabled.
@@ -9,14 +32,37 @@ use bifrost::serialization::{Decode, Encode};
 /// [`DebugEnabledSystems`] only keeps the name of a system and not the actual
 /// system.
 ///
+/// Systems are grouped into categories (e.g. "vision"), which can be toggled
+/// at once, and have a [`DebugLevel`]. A system only runs when it is enabled
+/// and its level does not exceed the current verbosity.
+///
 /// Since it derives [`Resource`], it can be used in any system to read or
 /// update the enabled state of systems.
 #[derive(Resource, Encode, Decode, Debug, Default, Clone)]
 pub struct DebugEnabledSystems {
     pub systems: HashMap<String, bool>,
+    /// The category of each system.
+    pub categories: HashMap<String, String>,
+    /// The verbosity level of each system.
+    pub levels: HashMap<String, DebugLevel>,
+    /// The most verbose level of systems that are allowed to run.
+    pub verbosity: DebugLevel,
 }
 
 impl DebugEnabledSystems {
+    /// Register a system with its category and level.
+    pub fn insert(
+        &mut self,
+        system_name: String,
+        category: String,
+        level: DebugLevel,
+        enabled: bool,
+    ) {
+        self.categories.insert(system_name.clone(), category);
+        self.levels.insert(system_name.clone(), level);
+        self.systems.insert(system_name, enabled);
+    }
+
     pub fn set_system(&mut self, system_name: String, enabled: bool) {
         if let Some(current_enabled) = self.systems.get_mut(&system_name) {
             *current_enabled = enabled;
@@ -24,10 +70,179 @@ impl DebugEnabledSystems {
             tracing::error!("System `{}` does not exist", system_name);
         }
     }
+
+    /// Enable or disable all systems in a category at once.
+    pub fn set_category_enabled(&mut self, category: &str, enabled: bool) {
+        let mut found = false;
+
+        let system_names = self
+            .categories
+            .iter()
+            .filter(|(_, system_category)| *system_category == category)
+            .map(|(system_name, _)| system_name);
+
+        for system_name in system_names {
+            if let Some(current_enabled) = self.systems.get_mut(system_name) {
+                *current_enabled = enabled;
+                found = true;
+            }
+        }
+
+        if !found {
+            tracing::error!("Category `{}` does not exist", category);
+        }
+    }
+
+    /// Set the most verbose level of systems that are allowed to run.
+    pub fn set_verbosity(&mut self, verbosity: DebugLevel) {
+        self.verbosity = verbosity;
+    }
+
+    /// Whether the system is enabled and allowed to run at the current verbosity.
+    #[must_use]
+    pub fn is_enabled(&self, system_name: &str) -> bool {
+        let enabled = self.systems.get(system_name).copied().unwrap_or(false);
+        let level = self.levels.get(system_name).copied().unwrap_or_default();
+
+        enabled && level <= self.verbosity
+    }
+
+    /// The category of a system, if it exists.
+    #[must_use]
+    pub fn category(&self, system_name: &str) -> Option<&str> {
+        self.categories.get(system_name).map(String::as_str)
+    }
+
+    /// Iterator over all distinct categories.
+    pub fn category_names(&self) -> impl Iterator<Item = &str> {
+        let mut categories: Vec<_> = self.categories.values().map(String::as_str).collect();
+        categories.sort_unstable();
+        categories.dedup();
+        categories.into_iter()
+    }
+
+    /// Apply the enabled flags and verbosity of a previously persisted set.
+    ///
+    /// Systems that no longer exist are ignored, and new systems keep their default.
+    pub fn restore(&mut self, persisted: &DebugEnabledSystems) {
+        for (system_name, enabled) in &persisted.systems {
+            if let Some(current_enabled) = self.systems.get_mut(system_name) {
+                *current_enabled = *enabled;
+            }
+        }
+
+        self.verbosity = persisted.verbosity;
+    }
+
+    /// Load a persisted set from a file.
+    pub fn load(path: impl AsRef<Path>) -> miette::Result<Self> {
+        let file = File::open(path).into_diagnostic()?;
+        Self::decode(BufReader::new(file)).into_diagnostic()
+    }
+
+    /// Persist the set to a file, so it can be restored with [`DebugEnabledSystems::restore`].
+    pub fn save(&self, path: impl AsRef<Path>) -> miette::Result<()> {
+        let file = File::create(path).into_diagnostic()?;
+        self.encode(BufWriter::new(file)).into_diagnostic()
+    }
 }
 
 impl From<HashMap<String, bool>> for DebugEnabledSystems {
     fn from(value: HashMap<String, bool>) -> Self {
-        DebugEnabledSystems { systems: value }
+        DebugEnabledSystems {
+            systems: value,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn systems() -> DebugEnabledSystems {
+        let mut systems = DebugEnabledSystems::default();
+        systems.insert("scan_lines".into(), "vision".into(), DebugLevel::Info, true);
+        systems.insert("lines".into(), "vision".into(), DebugLevel::Trace, true);
+        systems.insert("step".into(), "motion".into(), DebugLevel::Debug, true);
+        systems.insert("contour".into(), "vision".into(), DebugLevel::Debug, false);
+        systems
+    }
+
+    #[test]
+    fn toggles_categories_at_once() {
+        let mut systems = systems();
+        systems.set_verbosity(DebugLevel::Trace);
+        assert_eq!(
+            systems.category_names().collect::<Vec<_>>(),
+            ["motion", "vision"]
+        );
+
+        systems.set_category_enabled("vision", true);
+        assert!(systems.is_enabled("scan_lines"));
+        assert!(systems.is_enabled("lines"));
+        assert!(systems.is_enabled("contour"));
+
+        systems.set_category_enabled("vision", false);
+        assert!(!systems.is_enabled("scan_lines"));
+        assert!(!systems.is_enabled("lines"));
+        assert!(!systems.is_enabled("contour"));
+        // other categories are left untouched
+        assert!(systems.is_enabled("step"));
+
+        // the per-system toggle still works within a disabled category
+        systems.set_system("lines".into(), true);
+        assert!(systems.is_enabled("lines"));
+        assert!(!systems.is_enabled("scan_lines"));
+
+        // unknown categories do not change anything
+        systems.set_category_enabled("audio", false);
+        assert!(systems.is_enabled("step"));
+    }
+
+    #[test]
+    fn verbosity_limits_levels() {
+        let mut systems = systems();
+        assert_eq!(systems.verbosity, DebugLevel::Debug);
+        assert!(systems.is_enabled("scan_lines"));
+        assert!(systems.is_enabled("step"));
+        assert!(!systems.is_enabled("lines"));
+
+        systems.set_verbosity(DebugLevel::Info);
+        assert!(systems.is_enabled("scan_lines"));
+        assert!(!systems.is_enabled("step"));
+        assert!(!systems.is_enabled("lines"));
+
+        systems.set_verbosity(DebugLevel::Trace);
+        assert!(systems.is_enabled("scan_lines"));
+        assert!(systems.is_enabled("step"));
+        assert!(systems.is_enabled("lines"));
+        // a disabled system does not run at any verbosity
+        assert!(!systems.is_enabled("contour"));
+        assert!(!systems.is_enabled("unknown"));
+    }
+
+    #[test]
+    fn restores_persisted_set() {
+        let mut persisted = systems();
+        persisted.set_category_enabled("vision", false);
+        persisted.set_verbosity(DebugLevel::Trace);
+        persisted.insert("removed".into(), "audio".into(), DebugLevel::Info, true);
+
+        let mut encoded = Vec::new();
+        persisted.encode(&mut encoded).unwrap();
+        let persisted = DebugEnabledSystems::decode(encoded.as_slice()).unwrap();
+
+        let mut systems = systems();
+        systems.insert("new".into(), "motion".into(), DebugLevel::Info, true);
+        systems.restore(&persisted);
+
+        assert_eq!(systems.verbosity, DebugLevel::Trace);
+        assert!(!systems.is_enabled("scan_lines"));
+        assert!(!systems.is_enabled("lines"));
+        assert!(systems.is_enabled("step"));
+        // new systems keep their default, and removed systems are not added back
+        assert!(systems.is_enabled("new"));
+        assert!(!systems.systems.contains_key("removed"));
     }
 }
@@ -4,6 +4,8 @@ use bifrost::serialization::{Decode, Encode};
 use heimdall::CameraPosition;
 use nalgebra::Vector3;
 
+use crate::debug_system::{DebugEnabledSystems, DebugLevel};
+
 #[derive(Encode, Decode, Debug, Clone, Default)]
 pub struct FieldColorConfig {
     pub min_edge_luminance_difference: f32,
@@ -28,7 +30,7 @@ pub struct FieldColorConfig {
 #[derive(Encode, Decode, Debug, Clone)]
 pub enum RobotControlMessage {
     Resources(HashMap<String, String>),
-    DebugEnabledSystems(HashMap<String, bool>),
+    DebugEnabledSystems(DebugEnabledSystems),
     CameraExtrinsic {
         camera_position: CameraPosition,
         extrinsic_rotation: Vector3<f32>,
@@ -36,6 +38,42 @@ pub enum RobotControlMessage {
     FieldColor {
         config: FieldColorConfig,
     },
+    /// Current value of all loaded configs, as a TOML document.
+    ConfigDump(String),
+    /// Result of a [`ViewerControlMessage::SetConfigValue`].
+    ConfigValueSet {
+        config: String,
+        path: String,
+        result: ConfigValueResult,
+    },
+    /// Status of the ML models that have been loaded at startup.
+    ModelStatus(Vec<LoadedModel>),
+}
+
+/// Whether a config value sent by the viewer has been applied by the robot.
+#[derive(Encode, Decode, Debug, Clone)]
+pub enum ConfigValueResult {
+    Applied,
+    /// The value was rejected, with the reason why.
+    Rejected(String),
+}
+
+/// The result of loading a single ML model on the robot.
+#[derive(Encode, Decode, Debug, Clone)]
+pub struct LoadedModel {
+    /// Path to the ONNX file of the model.
+    pub path: String,
+    /// How long it took to load the model, in milliseconds.
+    pub load_time_ms: u64,
+    pub status: ModelLoadStatus,
+}
+
+/// Whether an ML model can be used for inference.
+#[derive(Encode, Decode, Debug, Clone)]
+pub enum ModelLoadStatus {
+    Live,
+    /// The model failed to load, with the reason why.
+    Failed(String),
 }
 
 /// Possible message that the viewer can send in the "control" panel
@@ -50,6 +88,13 @@ pub enum ViewerControlMessage {
         system_name: String,
         enabled: bool,
     },
+    UpdateEnabledDebugCategory {
+        category: String,
+        enabled: bool,
+    },
+    UpdateDebugVerbosity {
+        verbosity: DebugLevel,
+    },
     CameraExtrinsic {
         camera_position: CameraPosition,
         extrinsic_rotation: Vector3<f32>,
@@ -58,4 +103,12 @@ pub enum ViewerControlMessage {
         config: FieldColorConfig,
     },
     VisualRefereeRecognition,
+    DumpConfigs,
+    /// Sets the value at the dotted `path` of a config, e.g. `tracker.min_iou`. The config is
+    /// named by its type or path, and the `value` is parsed as a TOML value.
+    SetConfigValue {
+        config: String,
+        path: String,
+        value: String,
+    },
 }
@@ -17,14 +17,216 @@ pub type HandlerFn<T> = Box<dyn Fn(&T) + Send + Sync + 'static>;
 
 pub const CONTROL_PORT: u16 = 1337;
 
+/// Version of the layout of the messages, which is sent with every message and checked when it
+/// is decoded, so a robot and a viewer built from different versions of the messages refuse to
+/// talk to each other, instead of decoding garbage.
+///
+/// This has to be bumped whenever the layout of any of the messages changes, which is checked by
+/// a snapshot test of the encoded messages.
+pub const PROTOCOL_VERSION: u32 = 1;
+
 #[derive(Encode, Decode, Debug, Clone)]
+#[bifrost(version = PROTOCOL_VERSION)]
 pub enum RobotMessage {
     RobotControlMessage(RobotControlMessage),
     RobotGameController(RobotGameController),
 }
 
 #[derive(Encode, Decode, Debug, Clone)]
+#[bifrost(version = PROTOCOL_VERSION)]
 pub enum ViewerMessage {
     ViewerControlMessage(ViewerControlMessage),
     ViewerGameController(ViewerGameControllerMessage),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use heimdall::CameraPosition;
+    use nalgebra::Vector3;
+
+    use super::*;
+    use crate::debug_system::{DebugEnabledSystems, DebugLevel};
+    use control::{ConfigValueResult, FieldColorConfig, LoadedModel, ModelLoadStatus};
+    use game_controller::Player;
+
+    /// The protocol version and hash of the layout of the messages when it was last bumped.
+    ///
+    /// When the snapshot test fails, bump [`PROTOCOL_VERSION`] and update this snapshot to the
+    /// values in the failure message.
+    const LAYOUT_SNAPSHOT: (u32, u64) = (1, 0xb2c7_191e_aadb_95c4);
+
+    fn field_color() -> FieldColorConfig {
+        FieldColorConfig {
+            min_edge_luminance_difference: 1.0,
+            max_field_luminance: 2.0,
+            min_field_saturation: 3.0,
+            min_field_hue: 4.0,
+            max_field_hue: 5.0,
+            min_white_luminance: 6.0,
+            max_white_saturation: 7.0,
+            max_black_luminance: 8.0,
+            max_black_saturation: 9.0,
+            green_chromaticity_threshold: 10.0,
+            red_chromaticity_threshold: 11.0,
+            blue_chromaticity_threshold: 12.0,
+        }
+    }
+
+    /// A message of every variant, with distinct values so that reordering fields changes the
+    /// encoded layout as well.
+    ///
+    /// The messages containing a [`GameControllerMessage`](bifrost::communication::GameControllerMessage)
+    /// are left out, its layout is defined by the `GameController` and has its own version.
+    fn sample_messages() -> (Vec<RobotMessage>, Vec<ViewerMessage>) {
+        let mut debug_systems = DebugEnabledSystems::default();
+        debug_systems.insert(
+            "ball".to_string(),
+            "vision".to_string(),
+            DebugLevel::Trace,
+            true,
+        );
+
+        let robot = [
+            RobotControlMessage::Resources(HashMap::from([("Cycle".to_string(), "1".to_string())])),
+            RobotControlMessage::DebugEnabledSystems(debug_systems),
+            RobotControlMessage::CameraExtrinsic {
+                camera_position: CameraPosition::Bottom,
+                extrinsic_rotation: Vector3::new(1.0, 2.0, 3.0),
+            },
+            RobotControlMessage::FieldColor {
+                config: field_color(),
+            },
+            RobotControlMessage::ConfigDump("[cycle]".to_string()),
+            RobotControlMessage::ConfigValueSet {
+                config: "tracker".to_string(),
+                path: "min_iou".to_string(),
+                result: ConfigValueResult::Rejected("not a float".to_string()),
+            },
+            RobotControlMessage::ModelStatus(vec![
+                LoadedModel {
+                    path: "ball.onnx".to_string(),
+                    load_time_ms: 12,
+                    status: ModelLoadStatus::Live,
+                },
+                LoadedModel {
+                    path: "robot.onnx".to_string(),
+                    load_time_ms: 34,
+                    status: ModelLoadStatus::Failed("missing".to_string()),
+                },
+            ]),
+        ]
+        .into_iter()
+        .map(RobotMessage::RobotControlMessage)
+        .chain([
+            RobotMessage::RobotGameController(RobotGameController::GameControllerMessageInit {
+                team_number: 8,
+            }),
+            RobotMessage::RobotGameController(RobotGameController::PlayerInfo {
+                player: Player {
+                    player_number: 3,
+                    team_number: 8,
+                },
+            }),
+        ])
+        .collect();
+
+        let viewer = [
+            ViewerControlMessage::UpdateResource {
+                resource_name: "Cycle".to_string(),
+                value: "2".to_string(),
+            },
+            ViewerControlMessage::SendResourcesNow,
+            ViewerControlMessage::UpdateEnabledDebugSystem {
+                system_name: "ball".to_string(),
+                enabled: true,
+            },
+            ViewerControlMessage::UpdateEnabledDebugCategory {
+                category: "vision".to_string(),
+                enabled: false,
+            },
+            ViewerControlMessage::UpdateDebugVerbosity {
+                verbosity: DebugLevel::Info,
+            },
+            ViewerControlMessage::CameraExtrinsic {
+                camera_position: CameraPosition::Top,
+                extrinsic_rotation: Vector3::new(4.0, 5.0, 6.0),
+            },
+            ViewerControlMessage::FieldColor {
+                config: field_color(),
+            },
+            ViewerControlMessage::VisualRefereeRecognition,
+            ViewerControlMessage::DumpConfigs,
+            ViewerControlMessage::SetConfigValue {
+                config: "tracker".to_string(),
+                path: "min_iou".to_string(),
+                value: "0.5".to_string(),
+            },
+        ]
+        .into_iter()
+        .map(ViewerMessage::ViewerControlMessage)
+        .collect();
+
+        (robot, viewer)
+    }
+
+    /// Hashes the encoded messages without their protocol version, using FNV-1a, which unlike
+    /// the hasher of the standard library is stable across Rust versions.
+    fn layout_hash() -> u64 {
+        let (robot, viewer) = sample_messages();
+
+        let mut encoded = Vec::new();
+        for message in robot {
+            let mut buf = Vec::new();
+            message.encode(&mut buf).unwrap();
+            encoded.extend_from_slice(&buf[std::mem::size_of::<u32>()..]);
+        }
+        for message in viewer {
+            let mut buf = Vec::new();
+            message.encode(&mut buf).unwrap();
+            encoded.extend_from_slice(&buf[std::mem::size_of::<u32>()..]);
+        }
+
+        encoded.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    #[test]
+    fn protocol_version_is_bumped_when_layout_changes() {
+        let (snapshot_version, snapshot_hash) = LAYOUT_SNAPSHOT;
+        let hash = layout_hash();
+
+        if hash == snapshot_hash {
+            assert_eq!(
+                PROTOCOL_VERSION, snapshot_version,
+                "the layout of the messages did not change, so the protocol version should not either"
+            );
+            return;
+        }
+
+        assert_ne!(
+            PROTOCOL_VERSION, snapshot_version,
+            "the layout of the messages changed, bump `PROTOCOL_VERSION`"
+        );
+        panic!(
+            "the layout of the messages changed, update `LAYOUT_SNAPSHOT` to \
+            ({PROTOCOL_VERSION}, {hash:#018x})"
+        );
+    }
+
+    #[test]
+    fn message_with_other_version_is_rejected() {
+        let (robot, _) = sample_messages();
+
+        let mut encoded = Vec::new();
+        robot[0].encode(&mut encoded).unwrap();
+        encoded[..4].copy_from_slice(&(PROTOCOL_VERSION + 1).to_le_bytes());
+
+        assert!(matches!(
+            RobotMessage::decode(encoded.as_slice()),
+            Err(bifrost::Error::SchemaMismatch { .. })
+        ));
+    }
+}
@@ -165,8 +165,15 @@ impl ControlViewer {
                     // Keep decoding bytes to messages until we read the whole
                     // buffer
                     while bytes_read < n {
-                        let message = RobotMessage::decode(&buf[bytes_read..n])
-                            .expect("Failed to decode message");
+                        let message = match RobotMessage::decode(&buf[bytes_read..n]) {
+                            Ok(message) => message,
+                            Err(error) => {
+                                // e.g. a robot that is built from another version of the
+                                // messages, which we can't talk to
+                                tracing::error!(%error, "Failed to decode message, disconnecting");
+                                return;
+                            }
+                        };
 
                         let handlers = handlers.read().expect("failed to get reader");
                         for handler in handlers.iter() {
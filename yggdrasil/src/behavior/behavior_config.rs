@@ -2,7 +2,11 @@ use bevy::prelude::*;
 use odal::Config;
 use serde::{Deserialize, Serialize};
 
-use super::behaviors::{ObserveBehaviorConfig, RlStrikerSearchBehaviorConfig};
+use super::{
+    behaviors::{ObserveBehaviorConfig, RlStrikerSearchBehaviorConfig, SearchForBallConfig},
+    kick_target::KickTargetConfig,
+    positioning::PositioningConfig,
+};
 
 /// Config that contains information about the layout of the field and
 /// robot positions.
@@ -11,8 +15,15 @@ use super::behaviors::{ObserveBehaviorConfig, RlStrikerSearchBehaviorConfig};
 pub struct BehaviorConfig {
     pub observe: ObserveBehaviorConfig,
     pub rl_striker_search: RlStrikerSearchBehaviorConfig,
+    pub search_for_ball: SearchForBallConfig,
+    pub kick_target: KickTargetConfig,
+    pub positioning: PositioningConfig,
 }
 
 impl Config for BehaviorConfig {
     const PATH: &'static str = "behavior.toml";
+
+    fn validate(&self) -> std::result::Result<(), odal::ValidationError> {
+        self.search_for_ball.validate()
+    }
 }
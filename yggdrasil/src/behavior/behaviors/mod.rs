@@ -1,7 +1,7 @@
 mod catchfall;
-mod lost_ball_search;
 mod observe;
 mod rl_striker_search;
+mod search_for_ball;
 mod sitting;
 mod stand;
 mod stand_look;
@@ -14,9 +14,9 @@ mod walk_to_ball;
 mod walk_to_set;
 
 pub use catchfall::*;
-pub use lost_ball_search::*;
 pub use observe::*;
 pub use rl_striker_search::*;
+pub use search_for_ball::*;
 pub use sitting::*;
 pub use stand::*;
 pub use stand_look::*;
@@ -1,12 +1,19 @@
 use bevy::prelude::*;
+use nalgebra::Point3;
 use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
-use std::time::Instant;
+use serde_with::{DurationMilliSeconds, serde_as};
+use std::time::{Duration, Instant};
 
 use crate::{
-    behavior::engine::{Behavior, BehaviorState, in_behavior},
+    behavior::{
+        BehaviorConfig,
+        engine::{Behavior, BehaviorState, in_behavior},
+    },
+    core::config::layout::LayoutConfig,
+    localization::{RobotPose, confidence::PoseConfidence},
     motion::walking_engine::{StandingHeight, step::Step, step_context::StepContext},
-    nao::HeadMotionManager,
+    nao::{Clock, HeadMotionManager, LookAt},
+    vision::ball_detection::hypothesis::Ball,
 };
 
 /// Config struct containing parameters for the initial behavior.
@@ -26,6 +33,60 @@ pub struct ObserveBehaviorConfig {
     pub look_at_head_stiffness: f32,
     // The look around head stiffness
     pub look_around_head_stiffness: f32,
+    // Controls which scan pattern is used while observing
+    pub scan_pattern: ScanPatternConfig,
+}
+
+/// Config for choosing the [`ScanPattern`] of the [`Observe`] behavior.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ScanPatternConfig {
+    /// How long after the ball was last seen it is still tracked, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub ball_lost_timeout: Duration,
+    /// Pose covariance trace above which the robot looks at landmarks to relocalize.
+    pub localization_threshold: f32,
+    /// How long to look at each landmark during a landmark sweep, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub landmark_dwell_time: Duration,
+}
+
+/// Where the robot looks while observing.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScanPattern {
+    /// Keep looking at the ball, which has been seen recently.
+    BallTracking,
+    /// Sweep the head along the horizon to find the ball.
+    #[default]
+    HorizonSweep,
+    /// Look at the landmarks in view one by one, to improve the localization.
+    LandmarkSweep,
+}
+
+impl ScanPattern {
+    /// Selects the most useful pattern, given when the ball was last seen according to the
+    /// `clock` and how well the robot is localized.
+    ///
+    /// Tracking a recently seen ball takes precedence, otherwise a poorly localized robot looks
+    /// at landmarks before it sweeps the horizon.
+    #[must_use]
+    pub fn select(
+        ball_last_seen: Option<Instant>,
+        clock: &Clock,
+        confidence: &PoseConfidence,
+        config: &ScanPatternConfig,
+    ) -> Self {
+        if ball_last_seen
+            .is_some_and(|last_seen| clock.elapsed(last_seen) < config.ball_lost_timeout)
+        {
+            ScanPattern::BallTracking
+        } else if confidence.is_localized_well(config.localization_threshold) {
+            ScanPattern::HorizonSweep
+        } else {
+            ScanPattern::LandmarkSweep
+        }
+    }
 }
 
 #[derive(Resource, Deref)]
@@ -58,22 +119,88 @@ pub struct ObserveBehaviorPlugin;
 
 impl Plugin for ObserveBehaviorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, observe.run_if(in_behavior::<Observe>))
+        app.init_resource::<ScanPattern>()
+            .add_systems(
+                Update,
+                (select_scan_pattern, observe)
+                    .chain()
+                    .run_if(in_behavior::<Observe>),
+            )
             .add_systems(OnEnter(BehaviorState::Observe), reset_observe_starting_time)
             .insert_resource(ObserveStartingTime(Instant::now()));
     }
 }
 
-fn reset_observe_starting_time(mut observe_starting_time: ResMut<ObserveStartingTime>) {
-    observe_starting_time.0 = Instant::now();
+fn reset_observe_starting_time(
+    mut observe_starting_time: ResMut<ObserveStartingTime>,
+    clock: Res<Clock>,
+) {
+    observe_starting_time.0 = clock.now();
+}
+
+fn select_scan_pattern(
+    mut scan_pattern: ResMut<ScanPattern>,
+    ball: Res<Ball>,
+    clock: Res<Clock>,
+    confidence: Res<PoseConfidence>,
+    behavior_config: Res<BehaviorConfig>,
+) {
+    let ball_last_seen = ball.as_option().map(|ball| ball.last_update);
+    let pattern = ScanPattern::select(
+        ball_last_seen,
+        &clock,
+        &confidence,
+        &behavior_config.observe.scan_pattern,
+    );
+
+    scan_pattern.set_if_neq(pattern);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn observe(
     observe: Res<Observe>,
+    scan_pattern: Res<ScanPattern>,
+    pose: Res<RobotPose>,
+    ball: Res<Ball>,
+    layout: Res<LayoutConfig>,
+    behavior_config: Res<BehaviorConfig>,
+    observe_starting_time: Res<ObserveStartingTime>,
+    clock: Res<Clock>,
     mut step_context: ResMut<StepContext>,
     mut head_motion_manager: ResMut<HeadMotionManager>,
 ) {
-    head_motion_manager.request_look_around();
+    let config = &behavior_config.observe;
+
+    let look_at = match *scan_pattern {
+        ScanPattern::BallTracking => ball
+            .as_option()
+            .map(|ball| pose.robot_to_world(&ball.position)),
+        ScanPattern::LandmarkSweep => {
+            let mut landmarks = layout
+                .field
+                .landmarks()
+                .into_iter()
+                .filter(|landmark| pose.angle_to(landmark).abs() <= config.head_yaw_max)
+                .collect::<Vec<_>>();
+            landmarks.sort_by(|a, b| pose.angle_to(a).total_cmp(&pose.angle_to(b)));
+
+            let dwell_time = config.scan_pattern.landmark_dwell_time.as_secs_f32();
+            let index =
+                (clock.elapsed(**observe_starting_time).as_secs_f32() / dwell_time) as usize;
+
+            (!landmarks.is_empty()).then(|| landmarks[index % landmarks.len()])
+        }
+        ScanPattern::HorizonSweep => None,
+    };
+
+    if let Some(point) = look_at {
+        head_motion_manager.request_look_at(LookAt {
+            pose: *pose,
+            point: Point3::new(point.x, point.y, 0.0),
+        });
+    } else {
+        head_motion_manager.request_look_around();
+    }
 
     if let Some(step) = observe.step {
         step_context.request_walk(step);
@@ -81,3 +208,38 @@ fn observe(
         step_context.request_stand_with_height(StandingHeight::MAX);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use filter::CovarianceMatrix;
+
+    use super::*;
+
+    #[test]
+    fn switches_pattern_when_ball_is_lost() {
+        let config = ScanPatternConfig {
+            ball_lost_timeout: Duration::from_secs(2),
+            localization_threshold: 0.5,
+            landmark_dwell_time: Duration::from_secs(1),
+        };
+        let localized = PoseConfidence::new(CovarianceMatrix::from_diagonal_element(0.01));
+        let lost = PoseConfidence::new(CovarianceMatrix::from_diagonal_element(1.0));
+
+        let mut clock = Clock::simulated(Duration::from_secs(5));
+        let last_seen = clock.now();
+
+        assert_eq!(
+            ScanPattern::select(Some(last_seen), &clock, &localized, &config),
+            ScanPattern::BallTracking
+        );
+        clock.step();
+        assert_eq!(
+            ScanPattern::select(Some(last_seen), &clock, &localized, &config),
+            ScanPattern::HorizonSweep
+        );
+        assert_eq!(
+            ScanPattern::select(None, &clock, &lost, &config),
+            ScanPattern::LandmarkSweep
+        );
+    }
+}
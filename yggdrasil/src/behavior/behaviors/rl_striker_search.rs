@@ -34,7 +34,8 @@ impl Plugin for RlStrikerSearchBehaviorPlugin {
                 PreUpdate,
                 run_inference
                     .run_if(in_behavior::<RlStrikerSearchBehavior>.and(task_finished::<Output>))
-                    .run_if(on_event::<FootSwitchedEvent>.or(in_state(Gait::Standing))),
+                    .run_if(on_event::<FootSwitchedEvent>.or(in_state(Gait::Standing)))
+                    .run_if(ml::model_loaded::<RlStrikerSearchBehaviorModel>),
             )
             .add_systems(
                 OnEnter(BehaviorState::RlStrikerSearchBehavior),
@@ -0,0 +1,332 @@
+use bevy::prelude::*;
+use nalgebra::{Point2, Point3};
+use serde::{Deserialize, Serialize};
+use serde_with::{DurationMilliSeconds, serde_as};
+use std::time::{Duration, Instant};
+
+use crate::{
+    behavior::{
+        BehaviorConfig,
+        engine::{Behavior, BehaviorState, in_behavior},
+    },
+    localization::RobotPose,
+    motion::walking_engine::{StandingHeight, step::Step, step_context::StepContext},
+    nao::{Clock, HeadMotionManager, LookAt},
+    vision::ball_detection::communication::TeammateBall,
+};
+
+/// Config struct containing parameters for the search for ball behavior.
+#[serde_as]
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SearchForBallConfig {
+    /// How long to scan with the head while standing, before turning, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub head_scan_duration: Duration,
+    /// How long to turn on the spot, before walking a spiral, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub rotate_duration: Duration,
+    /// How long the search may take in total before another search takes over, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub timeout: Duration,
+    /// How far to turn per step while rotating, in radians.
+    pub turn_speed: f32,
+    /// How far to walk forward per step while walking a spiral, in meters.
+    pub spiral_forward: f32,
+    /// How fast the radius of the spiral grows, as a fraction of the initial radius per second.
+    pub spiral_growth: f32,
+    /// Uncertainty of the last known ball position below which the robot looks at that position
+    /// during the head scan, in meters.
+    pub max_look_at_uncertainty: f32,
+    /// How long a ball reported by a teammate is used to choose the search direction, in
+    /// milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub teammate_ball_timeout: Duration,
+}
+
+impl SearchForBallConfig {
+    /// Checks that the search reaches the spiral before it times out.
+    pub fn validate(&self) -> Result<(), odal::ValidationError> {
+        let phases = self.head_scan_duration + self.rotate_duration;
+        if phases >= self.timeout {
+            return Err(odal::ValidationError::new(format!(
+                "`search_for_ball.head_scan_duration` and `search_for_ball.rotate_duration` take \
+                 {}ms together, which must be shorter than `search_for_ball.timeout` ({}ms)",
+                phases.as_millis(),
+                self.timeout.as_millis()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The phase of a [`SearchForBall`], which widens the search the longer the ball is not found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchPhase {
+    /// Stand still and scan with the head, the ball is likely still close to where it was seen.
+    HeadScan,
+    /// Turn on the spot in the search direction.
+    Rotate,
+    /// Walk an outward spiral in the search direction.
+    Spiral,
+}
+
+impl SearchPhase {
+    /// The phase of a search that started `elapsed` ago.
+    #[must_use]
+    pub fn at(elapsed: Duration, config: &SearchForBallConfig) -> Self {
+        if elapsed < config.head_scan_duration {
+            SearchPhase::HeadScan
+        } else if elapsed < config.head_scan_duration + config.rotate_duration {
+            SearchPhase::Rotate
+        } else {
+            SearchPhase::Spiral
+        }
+    }
+}
+
+/// What the robot should do at some point during a [`SearchForBall`].
+#[derive(Debug, Clone, Copy)]
+pub struct SearchPlan {
+    pub phase: SearchPhase,
+    /// The requested step, or `None` to stand still.
+    pub step: Option<Step>,
+    /// The point in world coordinates to look at, or `None` to look around.
+    pub look_at: Option<Point2<f32>>,
+}
+
+/// When the current search started, and in which direction it searches.
+#[derive(Resource)]
+struct SearchState {
+    started_at: Instant,
+    direction: f32,
+}
+
+/// This behavior makes the robot systematically search for a ball that has been lost.
+///
+/// The robot first scans with its head, looking at the last known ball position if that is still
+/// accurate. If the ball is not found, it turns on the spot and finally walks an outward spiral.
+/// The search turns towards a ball reported by a teammate, or otherwise towards the last known
+/// ball position. The role stops the search once a ball is confirmed again, as only confirmed
+/// detections result in a [`Ball`](crate::vision::ball_detection::hypothesis::Ball).
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct SearchForBall {
+    /// The last known position of the ball in world coordinates, if any.
+    pub last_ball: Option<Point2<f32>>,
+    /// The uncertainty of the last known ball position, in meters.
+    pub uncertainty: f32,
+}
+
+impl SearchForBall {
+    /// The direction to search in, `1.0` to turn left and `-1.0` to turn right.
+    ///
+    /// A recent ball reported by a teammate takes precedence over the last known ball position.
+    /// The direction is chosen once at the start of the search, as the side on which the ball is
+    /// expected changes while turning.
+    #[must_use]
+    pub fn direction(&self, pose: &RobotPose, teammate_ball: Option<Point2<f32>>) -> f32 {
+        teammate_ball
+            .or(self.last_ball)
+            .map_or(1.0, |ball| pose.angle_to(&ball).signum())
+    }
+
+    /// What to do when the search in `direction` started `elapsed` ago.
+    #[must_use]
+    pub fn plan(
+        &self,
+        elapsed: Duration,
+        direction: f32,
+        config: &SearchForBallConfig,
+    ) -> SearchPlan {
+        let phase = SearchPhase::at(elapsed, config);
+
+        let (step, look_at) = match phase {
+            SearchPhase::HeadScan => (
+                None,
+                self.last_ball
+                    .filter(|_| self.uncertainty <= config.max_look_at_uncertainty),
+            ),
+            SearchPhase::Rotate => (
+                Some(Step {
+                    turn: direction * config.turn_speed,
+                    ..Default::default()
+                }),
+                None,
+            ),
+            SearchPhase::Spiral => {
+                // turning less over time grows the radius of the spiral
+                let spiral_elapsed =
+                    elapsed.saturating_sub(config.head_scan_duration + config.rotate_duration);
+                let turn = config.turn_speed
+                    / config
+                        .spiral_growth
+                        .mul_add(spiral_elapsed.as_secs_f32(), 1.0);
+
+                (
+                    Some(Step {
+                        forward: config.spiral_forward,
+                        turn: direction * turn,
+                        ..Default::default()
+                    }),
+                    None,
+                )
+            }
+        };
+
+        SearchPlan {
+            phase,
+            step,
+            look_at,
+        }
+    }
+}
+
+impl Behavior for SearchForBall {
+    const STATE: BehaviorState = BehaviorState::SearchForBall;
+}
+
+pub struct SearchForBallBehaviorPlugin;
+
+impl Plugin for SearchForBallBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, search_for_ball.run_if(in_behavior::<SearchForBall>))
+            .add_systems(OnEnter(BehaviorState::SearchForBall), reset_search_state)
+            .insert_resource(SearchState {
+                started_at: Instant::now(),
+                direction: 1.0,
+            });
+    }
+}
+
+fn reset_search_state(
+    mut search_state: ResMut<SearchState>,
+    search: Res<SearchForBall>,
+    pose: Res<RobotPose>,
+    teammate_ball: Res<TeammateBall>,
+    clock: Res<Clock>,
+    behavior_config: Res<BehaviorConfig>,
+) {
+    let config = &behavior_config.search_for_ball;
+    let teammate_ball = teammate_ball.recent(&clock, config.teammate_ball_timeout);
+
+    *search_state = SearchState {
+        started_at: clock.now(),
+        direction: search.direction(&pose, teammate_ball),
+    };
+}
+
+fn search_for_ball(
+    search: Res<SearchForBall>,
+    search_state: Res<SearchState>,
+    pose: Res<RobotPose>,
+    clock: Res<Clock>,
+    behavior_config: Res<BehaviorConfig>,
+    mut step_context: ResMut<StepContext>,
+    mut head_motion_manager: ResMut<HeadMotionManager>,
+) {
+    let plan = search.plan(
+        clock.elapsed(search_state.started_at),
+        search_state.direction,
+        &behavior_config.search_for_ball,
+    );
+
+    if let Some(point) = plan.look_at {
+        head_motion_manager.request_look_at(LookAt {
+            pose: *pose,
+            point: Point3::new(point.x, point.y, 0.0),
+        });
+    } else {
+        head_motion_manager.request_look_around();
+    }
+
+    if let Some(step) = plan.step {
+        step_context.request_walk(step);
+    } else {
+        step_context.request_stand_with_height(StandingHeight::MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use super::*;
+    use crate::core::config::load_deployed;
+
+    /// Duration of a single step of the simulated walk.
+    const STEP_DURATION: Duration = Duration::from_millis(250);
+    /// Angle around the heading of the robot in which the simulated cameras see the ball,
+    /// including the head scan.
+    const FIELD_OF_VIEW: f32 = 1.0;
+    /// Distance up to which the simulated cameras see the ball.
+    const MAX_VISIBLE_DISTANCE: f32 = 3.0;
+
+    fn config() -> SearchForBallConfig {
+        load_deployed::<BehaviorConfig>().search_for_ball
+    }
+
+    /// Simulates the search until the ball at `ball` is in view, returns how long that took.
+    fn simulate_search(
+        search: SearchForBall,
+        teammate_ball: Option<Point2<f32>>,
+        ball: Point2<f32>,
+    ) -> Option<Duration> {
+        let config = config();
+        let mut pose = RobotPose::from_translation_and_rotation(Vector2::zeros(), 0.0);
+        let mut elapsed = Duration::ZERO;
+        let direction = search.direction(&pose, teammate_ball);
+
+        while elapsed < config.timeout {
+            if pose.distance_to(&ball) <= MAX_VISIBLE_DISTANCE
+                && pose.angle_to(&ball).abs() <= FIELD_OF_VIEW
+            {
+                return Some(elapsed);
+            }
+
+            let plan = search.plan(elapsed, direction, &config);
+            if let Some(step) = plan.step {
+                let rotation = pose.world_rotation();
+                let translation = pose.world_position().coords
+                    + nalgebra::Rotation2::new(rotation) * Vector2::new(step.forward, step.left);
+                pose = RobotPose::from_translation_and_rotation(translation, rotation + step.turn);
+            }
+            elapsed += STEP_DURATION;
+        }
+
+        None
+    }
+
+    #[test]
+    fn lost_ball_is_reacquired() {
+        // the ball rolled just out of view, and nobody knows where it is
+        let search = SearchForBall {
+            last_ball: Some(Point2::new(1.0, 0.5)),
+            uncertainty: 2.0,
+        };
+        let ball = Point2::new(0.5, 3.0);
+        let found = simulate_search(search, None, ball).expect("ball was never found");
+        assert_eq!(
+            SearchPhase::at(found, &config()),
+            SearchPhase::Spiral,
+            "ball should only be found by walking the spiral"
+        );
+
+        // a teammate that sees the ball makes the robot turn the right way
+        let teammate_ball = Some(Point2::new(-2.5, -1.5));
+        let ball = Point2::new(-2.5, -1.5);
+        let without_teammate = simulate_search(search, None, ball).expect("ball was never found");
+        let with_teammate =
+            simulate_search(search, teammate_ball, ball).expect("ball was never found");
+        assert!(with_teammate < without_teammate);
+
+        // a ball that was just lost is looked at
+        let search = SearchForBall {
+            last_ball: Some(Point2::new(1.0, 0.5)),
+            uncertainty: 0.1,
+        };
+        let plan = search.plan(Duration::ZERO, 1.0, &config());
+        assert_eq!(plan.look_at, search.last_ball);
+        assert!(plan.step.is_none());
+    }
+}
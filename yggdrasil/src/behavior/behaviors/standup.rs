@@ -3,20 +3,22 @@ use nalgebra::Vector2;
 
 use crate::{
     behavior::engine::{Behavior, BehaviorState, in_behavior},
-    motion::keyframe::{KeyframeExecutor, MotionType},
+    motion::{
+        get_up::{GetUp, GetUpConfig, GetUpState},
+        keyframe::KeyframeExecutor,
+    },
     nao::{NaoManager, Priority},
     prelude::PreWrite,
     sensor::{
-        falling::{FallState, LyingDirection},
-        imu::IMUValues,
-        low_pass_filter::ExponentialLpf,
+        falling::FallState, fsr::Contacts, imu::IMUValues, low_pass_filter::ExponentialLpf,
+        orientation::RobotOrientation,
     },
 };
 
 /// Behavior dedicated to handling the getup sequence of the robot.
 /// The behavior will be entered once the robot is confirmed to be lying down,
-/// this will execute the appropriate standup motion after which the robot will return
-/// to the appropriate next behavior.
+/// this will execute the standup motion selected by [`GetUp`] until the robot is upright,
+/// after which the robot will return to the appropriate next behavior.
 #[derive(Resource, Default)]
 pub struct Standup {
     completed: bool,
@@ -50,23 +52,41 @@ impl Plugin for StandupBehaviorPlugin {
 
 fn standup(
     mut standup: ResMut<Standup>,
+    mut get_up: ResMut<GetUp>,
     fall_state: Res<FallState>,
+    orientation: Res<RobotOrientation>,
+    contacts: Res<Contacts>,
+    config: Res<GetUpConfig>,
     mut keyframe_executor: ResMut<KeyframeExecutor>,
 ) {
-    // check the direction the robot is lying and execute the appropriate motion
-    match fall_state.as_ref() {
-        FallState::Lying(LyingDirection::FacingDown) => {
-            keyframe_executor.start_new_motion(MotionType::StandupStomach, Priority::High);
+    let lying = match fall_state.as_ref() {
+        FallState::Lying(direction) => Some(direction),
+        _ => None,
+    };
+
+    match get_up.state() {
+        // start a new get-up sequence if we are lying down, a failed sequence is reset after a
+        // backoff by the get-up tracking
+        GetUpState::Idle | GetUpState::Succeeded { .. } => {
+            if let Some(lying) = lying {
+                let motion = get_up.start(lying);
+                keyframe_executor.start_new_motion(motion, Priority::High);
+            }
         }
-        FallState::Lying(LyingDirection::FacingUp) => {
-            keyframe_executor.start_new_motion(MotionType::StandupBack, Priority::High);
+        // verify the motion once it has finished, and retry if the robot is not upright
+        GetUpState::Executing { .. } if !keyframe_executor.is_motion_active() => {
+            let (roll, pitch, _) = orientation.euler_angles();
+            let upright =
+                roll.abs() < config.max_upright_angle && pitch.abs() < config.max_upright_angle;
+
+            if let Some(motion) = get_up.verify(upright, contacts.ground, lying, &config) {
+                keyframe_executor.start_new_motion(motion, Priority::High);
+            }
         }
-        // if we are not lying down anymore, either standing up or falling, we do not execute any motion
-        _ => {}
+        GetUpState::Executing { .. } | GetUpState::Failed { .. } => {}
     }
 
-    // Update completed status based on motion activity
-    standup.completed = !keyframe_executor.is_motion_active();
+    standup.completed = !get_up.is_executing();
 }
 
 #[derive(Resource)]
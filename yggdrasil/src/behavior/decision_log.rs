@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::core::debug::DebugContext;
+
+use super::{
+    engine::{BehaviorState, RoleState, role_base},
+    primary_state::PrimaryState,
+};
+
+/// Plugin that annotates the rerun logs with the decisions of the robot.
+///
+/// The active behavior, primary state and role are logged as a text entry on the cycle timeline
+/// whenever one of them changes, so the viewer shows the decision context alongside the vision
+/// and localization data.
+pub(super) struct DecisionLogPlugin;
+
+impl Plugin for DecisionLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, log_decision_context.after(role_base));
+    }
+}
+
+/// The decisions of the robot in a single cycle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionContext {
+    pub behavior: BehaviorState,
+    pub primary_state: PrimaryState,
+    pub role: RoleState,
+}
+
+impl DecisionContext {
+    /// Returns the annotation for this context, if it differs from the `last` logged context.
+    ///
+    /// The `last` context is updated, so every change produces exactly one annotation.
+    fn annotation(self, last: &mut Option<Self>) -> Option<String> {
+        if last.as_ref() == Some(&self) {
+            return None;
+        }
+
+        let annotation = format!(
+            "behavior: {:?}, primary state: {:?}, role: {:?}",
+            self.behavior, self.primary_state, self.role
+        );
+        *last = Some(self);
+
+        Some(annotation)
+    }
+}
+
+fn log_decision_context(
+    dbg: DebugContext,
+    behavior: Res<State<BehaviorState>>,
+    role: Res<State<RoleState>>,
+    primary_state: Res<PrimaryState>,
+    mut last: Local<Option<DecisionContext>>,
+) {
+    let context = DecisionContext {
+        behavior: behavior.get().clone(),
+        primary_state: *primary_state,
+        role: *role.get(),
+    };
+
+    if let Some(annotation) = context.annotation(&mut last) {
+        dbg.log(
+            "behavior/decision",
+            &rerun::TextLog::new(annotation).with_level(rerun::TextLogLevel::INFO),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn behavior_change_produces_annotation() {
+        let mut last = None;
+        let context = DecisionContext {
+            behavior: BehaviorState::Stand,
+            primary_state: PrimaryState::Initial,
+            role: RoleState::Striker,
+        };
+
+        assert!(context.clone().annotation(&mut last).is_some());
+        // nothing is logged while the decisions stay the same
+        assert!(context.clone().annotation(&mut last).is_none());
+
+        let walking = DecisionContext {
+            behavior: BehaviorState::WalkToBall,
+            ..context
+        };
+        let annotation = walking.annotation(&mut last).unwrap();
+        assert!(annotation.contains("WalkToBall"));
+        assert_eq!(
+            last.map(|context| context.behavior),
+            Some(BehaviorState::WalkToBall)
+        );
+    }
+}
@@ -18,8 +18,8 @@ use crate::{
 
 use super::{
     behaviors::{
-        CatchFall, CatchFallBehaviorPlugin, LostBallSearchBehaviorPlugin, ObserveBehaviorPlugin,
-        RlStrikerSearchBehaviorPlugin, Sitting, SittingBehaviorPlugin, Stand, StandBehaviorPlugin,
+        CatchFall, CatchFallBehaviorPlugin, ObserveBehaviorPlugin, RlStrikerSearchBehaviorPlugin,
+        SearchForBallBehaviorPlugin, Sitting, SittingBehaviorPlugin, Stand, StandBehaviorPlugin,
         StandLookAt, StandLookAtBehaviorPlugin, Standup, StandupBehaviorPlugin,
         StartUpBehaviorPlugin, VisualReferee, VisualRefereeBehaviorPlugin, WalkBehaviorPlugin,
         WalkToBallBehaviorPlugin, WalkToBehaviorPlugin, WalkToSet, WalkToSetBehaviorPlugin,
@@ -27,6 +27,7 @@ use super::{
     primary_state::PrimaryState,
     roles::{
         Defender, DefenderRolePlugin, Goalkeeper, GoalkeeperRolePlugin, Striker, StrikerRolePlugin,
+        Supporter, SupporterRolePlugin,
     },
 };
 
@@ -38,6 +39,9 @@ const BACKWARD_LEANING_THRESHOLD: f32 = -0.2;
 /// This is the robot ID of ken, which has a broken head joint.
 const BROKEN_ROBOT_ID: u32 = 29;
 
+/// Player numbers of the robots that are striker by default, see [`RoleState::by_player_number`].
+const STRIKER_PLAYER_NUMBERS: [u8; 2] = [4, 5];
+
 pub(super) struct BehaviorEnginePlugin;
 
 impl Plugin for BehaviorEnginePlugin {
@@ -45,11 +49,17 @@ impl Plugin for BehaviorEnginePlugin {
         // StatesPlugin should be added before init_state
         app.init_state::<BehaviorState>()
             .init_state::<RoleState>()
-            .add_plugins((DefenderRolePlugin, GoalkeeperRolePlugin, StrikerRolePlugin))
+            .add_plugins((
+                DefenderRolePlugin,
+                GoalkeeperRolePlugin,
+                StrikerRolePlugin,
+                SupporterRolePlugin,
+            ))
             .add_plugins((
                 CatchFallBehaviorPlugin,
                 ObserveBehaviorPlugin,
                 RlStrikerSearchBehaviorPlugin,
+                SearchForBallBehaviorPlugin,
                 SittingBehaviorPlugin,
                 StandBehaviorPlugin,
                 StandLookAtBehaviorPlugin,
@@ -60,7 +70,6 @@ impl Plugin for BehaviorEnginePlugin {
                 WalkToBallBehaviorPlugin,
                 WalkToBehaviorPlugin,
                 WalkToSetBehaviorPlugin,
-                LostBallSearchBehaviorPlugin,
             ))
             .add_systems(PostUpdate, role_base);
     }
@@ -129,7 +138,7 @@ pub enum BehaviorState {
     WalkToSet,
     WalkToBall,
     RlStrikerSearchBehavior,
-    LostBallSearch,
+    SearchForBall,
 }
 
 #[must_use]
@@ -176,6 +185,7 @@ pub enum RoleState {
     Striker,
     Goalkeeper,
     Defender,
+    Supporter,
 }
 
 impl RoleState {
@@ -184,20 +194,45 @@ impl RoleState {
         match player_number {
             1 => commands.set_role(Goalkeeper),
             5 | 4 => commands.set_role(Striker),
+            3 => commands.set_role(Supporter),
             _ => commands.set_role(Defender),
         }
     }
 
+    /// Whether a teammate of `player_number` is playing striker, which is the case as long as one
+    /// of the robots that are striker by default is not penalized.
+    ///
+    /// Without a game controller message all robots are assumed to be playing.
+    #[must_use]
+    pub fn striker_active(
+        game_controller_message: Option<&GameControllerMessage>,
+        team_number: u8,
+        player_number: u8,
+    ) -> bool {
+        let Some(team) = game_controller_message.and_then(|message| message.team(team_number))
+        else {
+            return true;
+        };
+
+        STRIKER_PLAYER_NUMBERS
+            .iter()
+            .any(|&number| number != player_number && !team.is_penalized(number))
+    }
+
     pub fn assign_role(
         commands: &mut Commands,
         player_number: u8,
         possible_ball_distance: Option<f32>,
+        striker_active: bool,
         role_state: Res<State<RoleState>>,
         defender_switch_timer: Option<ResMut<DefenderSwitchTimer>>,
         time: Res<Time>,
     ) {
+        // the supporter positions itself close to the ball on purpose, so it should not take over
+        // the ball from the striker, unless there is no striker to take it over from
+        let supports_striker = *role_state == RoleState::Supporter && striker_active;
         if let Some(distance) = possible_ball_distance {
-            if distance < 3.0 {
+            if distance < 3.0 && !supports_striker {
                 commands.set_role(Striker);
                 return;
             }
@@ -209,11 +244,7 @@ impl RoleState {
                 timer.timer.tick(time.delta());
                 if timer.timer.finished() {
                     commands.remove_resource::<DefenderSwitchTimer>();
-                    if player_number == 1 {
-                        commands.set_role(Goalkeeper);
-                    } else {
-                        commands.set_role(Defender);
-                    }
+                    Self::by_player_number(commands, player_number);
                 } else {
                     commands.set_role(Striker);
                 }
@@ -311,7 +342,7 @@ pub fn role_base(
         return;
     }
 
-    if let Some(message) = game_controller_message {
+    if let Some(message) = &game_controller_message {
         if message.game_phase == GamePhase::PenaltyShoot {
             if message.kicking_team == player_config.team_number {
                 commands.set_role(Striker);
@@ -351,11 +382,17 @@ pub fn role_base(
         }),
         PrimaryState::Playing { .. } => {
             let possible_ball_distance = ball.as_option().map(|b| b.position.coords.norm());
+            let striker_active = RoleState::striker_active(
+                game_controller_message.as_deref(),
+                player_config.team_number,
+                player_config.player_number,
+            );
 
             RoleState::assign_role(
                 &mut commands,
                 player_config.player_number,
                 possible_ball_distance,
+                striker_active,
                 role_state,
                 defender_switch_timer,
                 time,
@@ -0,0 +1,250 @@
+//! Selection of the target the striker kicks the ball towards, see [`KickTarget`].
+
+use std::{net::SocketAddr, time::Duration};
+
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use serde_with::{DurationMilliSeconds, serde_as};
+
+use crate::motion::path_finding::Obstacle;
+
+/// Config struct containing the weights used to score the possible kick targets.
+///
+/// The score of a shot at the goal is `goal_value`, the score of a pass is `pass_value` plus
+/// `forward_progress` per meter the pass moves the ball towards the opponent goal. Both are
+/// lowered by `distance_penalty` per meter of kick distance, and by `blocked_penalty` for every
+/// obstacle in the kick lane.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KickTargetConfig {
+    /// Score of a shot at the goal, before the penalties.
+    pub goal_value: f32,
+    /// Score of a pass to a teammate, before the forward progress and the penalties.
+    pub pass_value: f32,
+    /// Score per meter that a pass moves the ball towards the opponent goal.
+    pub forward_progress: f32,
+    /// Penalty per meter of kick distance.
+    pub distance_penalty: f32,
+    /// Penalty for every obstacle that blocks the kick lane.
+    pub blocked_penalty: f32,
+    /// Half the width of the kick lane, in meters.
+    pub lane_width: f32,
+    /// Teammates closer to the ball than this are not passed to, in meters.
+    pub min_pass_distance: f32,
+    /// Teammates further from the ball than this are not passed to, in meters.
+    pub max_pass_distance: f32,
+    /// How far the robot may be aimed next to a teammate to be aligned for a pass, in meters.
+    pub pass_alignment_width: f32,
+    /// How much higher the score of another target has to be to switch to it.
+    pub hysteresis: f32,
+    /// How long the position reported by a teammate is used, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub teammate_timeout: Duration,
+}
+
+/// The target the ball is kicked towards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KickTarget {
+    /// A shot at the center of the opponent goal.
+    Goal { position: Point2<f32> },
+    /// A pass to the teammate at `teammate`.
+    Pass {
+        teammate: SocketAddr,
+        position: Point2<f32>,
+    },
+}
+
+impl KickTarget {
+    /// The position the ball is kicked towards, in world coordinates.
+    #[must_use]
+    pub fn position(&self) -> Point2<f32> {
+        match self {
+            KickTarget::Goal { position } | KickTarget::Pass { position, .. } => *position,
+        }
+    }
+
+    /// Whether both targets are the goal, or a pass to the same teammate.
+    #[must_use]
+    pub fn is_same(&self, other: &KickTarget) -> bool {
+        match (self, other) {
+            (KickTarget::Goal { .. }, KickTarget::Goal { .. }) => true,
+            (
+                KickTarget::Pass { teammate, .. },
+                KickTarget::Pass {
+                    teammate: other, ..
+                },
+            ) => teammate == other,
+            _ => false,
+        }
+    }
+}
+
+/// Scores a kick from `ball` to `target`, see [`KickTargetConfig`].
+fn score(
+    ball: Point2<f32>,
+    target: &KickTarget,
+    goal: Point2<f32>,
+    obstacles: &[Obstacle],
+    config: &KickTargetConfig,
+) -> f32 {
+    let position = target.position();
+    let value = match target {
+        KickTarget::Goal { .. } => config.goal_value,
+        KickTarget::Pass { .. } => {
+            let progress = (ball - goal).norm() - (position - goal).norm();
+            config.pass_value + config.forward_progress * progress
+        }
+    };
+
+    let blocking = obstacles
+        .iter()
+        .filter(|obstacle| blocks_lane(ball, position, obstacle, config.lane_width))
+        .count();
+
+    value
+        - config.distance_penalty * (position - ball).norm()
+        - config.blocked_penalty * blocking as f32
+}
+
+/// Whether the obstacle lies within `lane_width` of the line segment from `from` to `to`.
+fn blocks_lane(from: Point2<f32>, to: Point2<f32>, obstacle: &Obstacle, lane_width: f32) -> bool {
+    let center = Point2::new(obstacle.x.0, obstacle.y.0);
+    let lane = to - from;
+    let length = lane.norm();
+    if length <= f32::EPSILON {
+        return false;
+    }
+
+    let direction = lane / length;
+    let offset = center - from;
+    let along = offset.dot(&direction);
+    if !(0.0..=length).contains(&along) {
+        return false;
+    }
+
+    let across = Vector2::new(-direction.y, direction.x).dot(&offset).abs();
+    across <= lane_width + obstacle.radius.0
+}
+
+/// Selects the kick target with the highest score.
+///
+/// Every teammate within the pass distances of [`KickTargetConfig`] is a pass candidate, and an
+/// obstacle for the kick lanes to the other targets. The `teammates` must not contain the robot
+/// itself, see [`TeammatePositions`](crate::localization::communication::TeammatePositions). The
+/// `previous` target is kept unless another
+/// target scores at least [`KickTargetConfig::hysteresis`] higher, so the robot does not keep
+/// turning between two targets with similar scores.
+#[must_use]
+pub fn select_kick_target(
+    ball: Point2<f32>,
+    goal: Point2<f32>,
+    teammates: &[(SocketAddr, Point2<f32>)],
+    obstacles: &[Obstacle],
+    previous: Option<&KickTarget>,
+    config: &KickTargetConfig,
+) -> KickTarget {
+    let passes: Vec<_> = teammates
+        .iter()
+        .filter(|(_, position)| {
+            (config.min_pass_distance..=config.max_pass_distance)
+                .contains(&(position - ball).norm())
+        })
+        .map(|&(teammate, position)| KickTarget::Pass { teammate, position })
+        .collect();
+
+    let scored: Vec<_> = std::iter::once(KickTarget::Goal { position: goal })
+        .chain(passes.iter().copied())
+        .map(|target| {
+            let mut lane_obstacles = obstacles.to_vec();
+            lane_obstacles.extend(
+                passes
+                    .iter()
+                    .filter(|pass| !pass.is_same(&target))
+                    .map(|pass| Obstacle::new(pass.position().x, pass.position().y, 0.0)),
+            );
+
+            let score = score(ball, &target, goal, &lane_obstacles, config);
+            (target, score)
+        })
+        .collect();
+
+    let (best, best_score) = scored
+        .iter()
+        .copied()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .expect("the goal is always a candidate");
+
+    previous
+        .and_then(|previous| {
+            scored
+                .iter()
+                .find(|(target, _)| target.is_same(previous))
+                .filter(|(_, score)| score + config.hysteresis > best_score)
+        })
+        .map_or(best, |(target, _)| *target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> KickTargetConfig {
+        KickTargetConfig {
+            goal_value: 1.0,
+            pass_value: 0.3,
+            forward_progress: 0.2,
+            distance_penalty: 0.1,
+            blocked_penalty: 1.0,
+            lane_width: 0.3,
+            min_pass_distance: 1.0,
+            max_pass_distance: 4.0,
+            pass_alignment_width: 0.3,
+            hysteresis: 0.1,
+            teammate_timeout: Duration::from_secs(3),
+        }
+    }
+
+    #[test]
+    fn clear_pass_is_preferred_over_blocked_shot() {
+        let config = config();
+        let ball = Point2::new(2.0, 0.0);
+        let goal = Point2::new(4.5, 0.0);
+        let forward = SocketAddr::from(([10, 0, 8, 22], 10008));
+        let backward = SocketAddr::from(([10, 0, 8, 23], 10008));
+        let teammates = [
+            (forward, Point2::new(3.5, 1.5)),
+            (backward, Point2::new(0.0, -1.0)),
+        ];
+
+        // nothing in the way, so the robot shoots
+        let target = select_kick_target(ball, goal, &teammates, &[], None, &config);
+        assert_eq!(target, KickTarget::Goal { position: goal });
+
+        // an opponent in front of the goal blocks the shot, but not the pass lane
+        let opponent = [Obstacle::new(3.2, 0.1, 0.2)];
+        let target = select_kick_target(ball, goal, &teammates, &opponent, None, &config);
+        assert_eq!(
+            target,
+            KickTarget::Pass {
+                teammate: forward,
+                position: Point2::new(3.5, 1.5),
+            }
+        );
+
+        // a clear shot takes over from a previous pass with a much lower score
+        let target = select_kick_target(ball, goal, &teammates, &[], Some(&target), &config);
+        assert_eq!(target, KickTarget::Goal { position: goal });
+
+        let config = KickTargetConfig {
+            hysteresis: 1.0,
+            ..config
+        };
+        let pass = KickTarget::Pass {
+            teammate: forward,
+            position: Point2::new(3.5, 1.5),
+        };
+        let target = select_kick_target(ball, goal, &teammates, &[], Some(&pass), &config);
+        assert_eq!(target, pass);
+    }
+}
@@ -1,8 +1,12 @@
 pub mod behavior_config;
 pub mod behaviors;
+mod decision_log;
 pub mod engine;
+pub mod kick_target;
+pub mod positioning;
 pub mod primary_state;
 pub mod roles;
+pub mod tree;
 
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
@@ -16,5 +20,7 @@ impl PluginGroup for BehaviorPlugins {
         PluginGroupBuilder::start::<Self>()
             .add(engine::BehaviorEnginePlugin)
             .add(primary_state::PrimaryStatePlugin)
+            .add(decision_log::DecisionLogPlugin)
+            .add(positioning::PositioningPlugin)
     }
 }
@@ -0,0 +1,501 @@
+//! Positioning of the robots that do not play the ball, see [`PositioningTarget`].
+
+use bevy::prelude::*;
+use nalgebra::{Point2, Vector2, point};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    behavior::{
+        BehaviorConfig,
+        behaviors::{LookMode, Observe, WalkTo},
+        engine::{CommandsBehaviorExt, RoleState},
+    },
+    core::{
+        config::layout::{FieldConfig, LayoutConfig},
+        debug::DebugContext,
+    },
+    localization::RobotPose,
+    motion::step_planner::{StepPlanner, Target},
+    nao::Cycle,
+    vision::ball_detection::hypothesis::Ball,
+};
+
+/// Plugin that computes the position on the field the robot should take for its role.
+///
+/// This module provides the following resources to the application:
+/// - [`PositioningTarget`]
+pub(super) struct PositioningPlugin;
+
+impl Plugin for PositioningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PositioningTarget>().add_systems(
+            Update,
+            (
+                update_positioning_target,
+                log_positioning_target.run_if(resource_changed::<PositioningTarget>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Config struct containing the parameters used to position the robots that do not play the ball.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PositioningConfig {
+    /// How far the supporter stays behind the ball, towards the own goal, in meters.
+    pub support_distance: f32,
+    /// How far the supporter stays next to the ball, towards the center of the field, in meters.
+    pub support_offset: f32,
+    /// How far the ball has to be past the center line of the field before the supporter switches
+    /// sides, in meters.
+    pub side_margin: f32,
+    /// Fraction of the way from the own goal to the ball at which the defender stands.
+    pub defense_ratio: f32,
+    /// How far the targets stay away from the field lines, in meters.
+    pub field_margin: f32,
+    /// How far a new target has to be from the current one before the robot walks to it, in
+    /// meters.
+    pub hysteresis_distance: f32,
+    /// How far a new target has to be rotated from the current one before the robot turns to it,
+    /// in radians.
+    pub hysteresis_angle: f32,
+}
+
+/// The pose the robot walks to for its role, in world coordinates.
+///
+/// The target is re-evaluated every cycle as the ball moves, but only replaced once it moved
+/// further than the hysteresis of the [`PositioningConfig`], so the robot does not jitter.
+/// There is no target if the role does not position itself, or if the ball is not known.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PositioningTarget {
+    pub pose: Option<RobotPose>,
+    /// The side of the ball the supporter stays on, `1.0` for the left and `-1.0` for the right.
+    support_side: f32,
+}
+
+impl Default for PositioningTarget {
+    fn default() -> Self {
+        Self {
+            pose: None,
+            support_side: 1.0,
+        }
+    }
+}
+
+impl PositioningTarget {
+    /// Updates the side of the ball the supporter stays on for the `ball` position in world
+    /// coordinates, and returns it.
+    ///
+    /// The supporter stays towards the center of the field, but only switches sides once the ball
+    /// is further than the side margin past the center line. Otherwise the target would jump
+    /// between both sides of the ball while it moves along the center line.
+    pub fn update_support_side(&mut self, ball: Point2<f32>, config: &PositioningConfig) -> f32 {
+        if ball.y > config.side_margin {
+            self.support_side = -1.0;
+        } else if ball.y < -config.side_margin {
+            self.support_side = 1.0;
+        }
+
+        self.support_side
+    }
+
+    /// Replaces the current target with `target`, unless both are within the hysteresis.
+    ///
+    /// Returns whether the target changed.
+    pub fn update(&mut self, target: Option<RobotPose>, config: &PositioningConfig) -> bool {
+        match (self.pose, target) {
+            (None, None) => return false,
+            (Some(current), Some(target)) => {
+                let distance = (current.world_position() - target.world_position()).norm();
+                let angle = current
+                    .inner
+                    .rotation
+                    .angle_to(&target.inner.rotation)
+                    .abs();
+
+                if distance < config.hysteresis_distance && angle < config.hysteresis_angle {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+
+        self.pose = target;
+        true
+    }
+
+    /// The target to walk to, falling back to `default` if there is no target.
+    #[must_use]
+    pub fn walk_target(&self, default: Target) -> Target {
+        self.pose.map_or(default, |pose| Target {
+            position: pose.world_position(),
+            rotation: Some(pose.inner.rotation),
+        })
+    }
+}
+
+/// Computes the pose the robot should take for its `role`, given the `ball` position in world
+/// coordinates.
+///
+/// - The supporter stays behind the ball and next to it on the `support_side`, so it can take
+///   over the ball when the striker loses it, see [`PositioningTarget::update_support_side`].
+/// - The defender stands on the line between the own goal and the ball, just outside the penalty
+///   area so it does not get in the way of the goalkeeper.
+///
+/// Both face the ball, and stay within the field. Other roles do not position themselves.
+#[must_use]
+pub fn target_pose(
+    role: RoleState,
+    ball: Point2<f32>,
+    support_side: f32,
+    field: &FieldConfig,
+    config: &PositioningConfig,
+) -> Option<RobotPose> {
+    let own_goal = point![-field.length / 2.0, 0.0];
+    let max_x = field.length / 2.0 - config.field_margin;
+    let max_y = field.width / 2.0 - config.field_margin;
+
+    let position = match role {
+        RoleState::Supporter => {
+            let position = ball
+                + Vector2::new(
+                    -config.support_distance,
+                    support_side * config.support_offset,
+                );
+
+            point![
+                position.x.clamp(-max_x, max_x),
+                position.y.clamp(-max_y, max_y)
+            ]
+        }
+        RoleState::Defender => {
+            let position = own_goal + (ball - own_goal) * config.defense_ratio;
+            let min_x = own_goal.x + field.penalty_area_length + config.field_margin;
+
+            point![
+                position.x.clamp(min_x, max_x),
+                position.y.clamp(-max_y, max_y)
+            ]
+        }
+        RoleState::Disabled | RoleState::Striker | RoleState::Goalkeeper => return None,
+    };
+
+    let to_ball = ball - position;
+    let angle = if to_ball.norm() > f32::EPSILON {
+        to_ball.y.atan2(to_ball.x)
+    } else {
+        0.0
+    };
+
+    Some(RobotPose::from_translation_and_rotation(
+        position.coords,
+        angle,
+    ))
+}
+
+/// Walks to the `target` of a role, and observes the field once it is reached.
+pub fn walk_to_position(commands: &mut Commands, target: Target, step_planner: &StepPlanner) {
+    // the target may have moved since the robot reached the previous one
+    if step_planner.current_absolute_target() == Some(&target) && step_planner.reached_target() {
+        commands.set_behavior(Observe::with_turning(-0.4));
+    } else {
+        commands.set_behavior(WalkTo {
+            target,
+            look_mode: LookMode::Observe,
+        });
+    }
+}
+
+fn update_positioning_target(
+    mut positioning_target: ResMut<PositioningTarget>,
+    role: Res<State<RoleState>>,
+    ball: Res<Ball>,
+    pose: Res<RobotPose>,
+    layout_config: Res<LayoutConfig>,
+    behavior_config: Res<BehaviorConfig>,
+) {
+    let config = &behavior_config.positioning;
+    // only mark the resource as changed if the target changes
+    let target = ball.as_option().and_then(|ball| {
+        let ball = pose.robot_to_world(&ball.position);
+        let support_side = positioning_target
+            .bypass_change_detection()
+            .update_support_side(ball, config);
+
+        target_pose(
+            *role.get(),
+            ball,
+            support_side,
+            &layout_config.field,
+            config,
+        )
+    });
+
+    if positioning_target
+        .bypass_change_detection()
+        .update(target, config)
+    {
+        positioning_target.set_changed();
+    }
+}
+
+fn log_positioning_target(dbg: DebugContext, cycle: Res<Cycle>, target: Res<PositioningTarget>) {
+    let Some(pose) = target.pose else {
+        dbg.log_with_cycle("field/positioning_target", *cycle, &rerun::Clear::flat());
+        return;
+    };
+
+    let position = pose.world_position();
+    let direction = pose.inner.rotation * Vector2::new(0.3, 0.0);
+
+    dbg.log_with_cycle(
+        "field/positioning_target",
+        *cycle,
+        &rerun::Arrows3D::from_vectors([(direction.x, direction.y, 0.0)])
+            .with_origins([(position.x, position.y, 0.05)])
+            .with_colors([(245, 179, 66)]),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::{
+        behavior::engine::DefenderSwitchTimer, core::config::load_deployed,
+        vision::ball_detection::hypothesis::BallState,
+    };
+
+    fn field() -> FieldConfig {
+        FieldConfig {
+            length: 9.0,
+            width: 6.0,
+            line_width: 0.05,
+            penalty_mark_size: 0.1,
+            goal_area_length: 0.6,
+            goal_area_width: 2.2,
+            penalty_area_length: 1.65,
+            penalty_area_width: 4.0,
+            penalty_mark_distance: 1.3,
+            centre_circle_diameter: 1.5,
+            border_strip_width: 0.7,
+        }
+    }
+
+    fn config() -> PositioningConfig {
+        PositioningConfig {
+            support_distance: 1.5,
+            support_offset: 1.0,
+            side_margin: 0.5,
+            defense_ratio: 0.4,
+            field_margin: 0.3,
+            hysteresis_distance: 0.3,
+            hysteresis_angle: 0.3,
+        }
+    }
+
+    #[test]
+    fn supporter_keeps_offset_from_ball() {
+        let field = field();
+        let config = config();
+
+        // the striker dribbles the ball from the own half up the left side of the field
+        let mut striker = point![-3.0, 0.5];
+        let mut supporter = PositioningTarget::default();
+        let mut changes = 0;
+
+        for step in 0..100 {
+            striker += Vector2::new(0.06, 0.02);
+            let ball = striker + Vector2::new(0.1, 0.0);
+
+            let side = supporter.update_support_side(ball, &config);
+            let target = target_pose(RoleState::Supporter, ball, side, &field, &config);
+            if supporter.update(target, &config) {
+                changes += 1;
+            }
+            let pose = supporter.pose.expect("the supporter has a target");
+
+            // the supporter stays behind the ball, on the side of the field center
+            let offset = ball - pose.world_position();
+            assert!(
+                offset.x > 1.0,
+                "step {step}: {offset} is not behind the ball"
+            );
+            assert!(
+                offset.y > 0.5,
+                "step {step}: {offset} is not towards the center"
+            );
+            assert!((1.2..2.2).contains(&offset.norm()), "step {step}: {offset}");
+            assert!(pose.world_position().y.abs() < field.width / 2.0);
+        }
+
+        // small movements of the ball do not move the target every cycle
+        assert!(changes < 25, "the target changed {changes} times");
+
+        // the defender stays outside of the penalty area, between the ball and the goal
+        let defender = target_pose(RoleState::Defender, point![-2.0, 1.0], 1.0, &field, &config)
+            .unwrap()
+            .world_position();
+        assert!(defender.x >= -field.length / 2.0 + field.penalty_area_length);
+        assert!(defender.y > 0.0 && defender.y < 1.0);
+
+        assert!(target_pose(RoleState::Striker, striker, 1.0, &field, &config).is_none());
+    }
+
+    #[test]
+    fn supporter_keeps_side_near_center_line() {
+        let field = field();
+        let config = config();
+        let mut supporter = PositioningTarget::default();
+
+        // the ball was on the left side, so the supporter stays to the right of it
+        supporter.update_support_side(point![0.0, 1.0], &config);
+        let ball = point![0.0, 0.1];
+        let side = supporter.update_support_side(ball, &config);
+        supporter.update(
+            target_pose(RoleState::Supporter, ball, side, &field, &config),
+            &config,
+        );
+        assert!(supporter.pose.unwrap().world_position().y < ball.y);
+
+        // the ball moves back and forth over the center line, within the side margin
+        for step in 0..50 {
+            let ball = point![0.0, if step % 2 == 0 { -0.1 } else { 0.1 }];
+            let side = supporter.update_support_side(ball, &config);
+            assert!(
+                !supporter.update(
+                    target_pose(RoleState::Supporter, ball, side, &field, &config),
+                    &config,
+                ),
+                "step {step}: the target jumped to the other side of the ball"
+            );
+        }
+
+        // once the ball is far enough on the right side, the supporter switches sides
+        let ball = point![0.0, -1.0];
+        let side = supporter.update_support_side(ball, &config);
+        supporter.update(
+            target_pose(RoleState::Supporter, ball, side, &field, &config),
+            &config,
+        );
+        assert!(supporter.pose.unwrap().world_position().y > ball.y);
+    }
+
+    /// The player number of a robot in [`robot`], and whether it has a teammate playing striker.
+    #[derive(Resource)]
+    struct Player {
+        number: u8,
+        striker_active: bool,
+    }
+
+    fn assign_role(
+        mut commands: Commands,
+        player: Res<Player>,
+        ball: Res<Ball>,
+        role_state: Res<State<RoleState>>,
+        defender_switch_timer: Option<ResMut<DefenderSwitchTimer>>,
+        time: Res<Time>,
+    ) {
+        RoleState::assign_role(
+            &mut commands,
+            player.number,
+            ball.as_option().map(|ball| ball.position.coords.norm()),
+            player.striker_active,
+            role_state,
+            defender_switch_timer,
+            time,
+        );
+    }
+
+    /// A headless robot that assigns its role and positions itself for it, with the deployed
+    /// configs.
+    fn robot(number: u8, x: f32, y: f32) -> App {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin)
+            .init_state::<RoleState>()
+            .init_resource::<Time>()
+            .init_resource::<Ball>()
+            .init_resource::<PositioningTarget>()
+            .insert_resource(Player {
+                number,
+                striker_active: true,
+            })
+            .insert_resource(RobotPose::from_translation_and_rotation(
+                Vector2::new(x, y),
+                0.0,
+            ))
+            .insert_resource(load_deployed::<LayoutConfig>())
+            .insert_resource(load_deployed::<BehaviorConfig>())
+            .add_systems(Update, (assign_role, update_positioning_target).chain());
+        app
+    }
+
+    /// Runs a cycle in which the robot sees the `ball` in world coordinates.
+    fn update(robot: &mut App, ball: Point2<f32>) {
+        let pose = *robot.world().resource::<RobotPose>();
+        robot.insert_resource(Ball::Some(BallState::at(pose.world_to_robot(&ball))));
+        robot.update();
+    }
+
+    fn target(robot: &App) -> Option<RobotPose> {
+        robot.world().resource::<PositioningTarget>().pose
+    }
+
+    /// Moves the robot to its positioning target, if it has one.
+    fn walk_to_target(robot: &mut App) {
+        if let Some(pose) = target(robot) {
+            robot.insert_resource(pose);
+        }
+    }
+
+    fn role(robot: &App) -> RoleState {
+        *robot.world().resource::<State<RoleState>>().get()
+    }
+
+    #[test]
+    fn striker_plays_ball_while_supporter_follows() {
+        let config = load_deployed::<BehaviorConfig>().positioning;
+        let mut ball = point![0.0, 1.0];
+        let mut striker = robot(4, -0.4, 0.8);
+        let mut supporter = robot(3, -3.0, -2.0);
+
+        // the striker walks along with the ball as it dribbles it forward
+        for _ in 0..40 {
+            ball += Vector2::new(0.05, 0.0);
+            striker.insert_resource(RobotPose::from_translation_and_rotation(
+                ball.coords - Vector2::new(0.2, 0.0),
+                0.0,
+            ));
+            update(&mut striker, ball);
+            update(&mut supporter, ball);
+            walk_to_target(&mut supporter);
+        }
+
+        assert_eq!(role(&striker), RoleState::Striker);
+        assert!(target(&striker).is_none());
+
+        // the supporter reached its target close to the ball, but leaves the ball to the striker
+        assert_eq!(role(&supporter), RoleState::Supporter);
+        let position = supporter.world().resource::<RobotPose>().world_position();
+        let offset = ball - position;
+        assert!(offset.norm() < 3.0, "{offset} is too far from the ball");
+        assert!(
+            (offset.x - config.support_distance).abs() <= config.hysteresis_distance,
+            "{offset} is not behind the ball"
+        );
+        assert!(offset.y > 0.0, "{offset} is not towards the center");
+
+        // once the striker is penalized, the supporter takes over the ball
+        supporter.insert_resource(Player {
+            number: 3,
+            striker_active: false,
+        });
+        for _ in 0..3 {
+            update(&mut supporter, ball);
+        }
+        assert_eq!(role(&supporter), RoleState::Striker);
+        assert!(target(&supporter).is_none());
+    }
+}
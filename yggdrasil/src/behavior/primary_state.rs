@@ -3,7 +3,6 @@ use crate::{
     game_controller::{GameControllerMessageEvent, penalty::PenaltyState},
     kinematics::Kinematics,
     motion::walking_engine::config::WalkingEngineConfig,
-    nao::{NaoManager, Priority},
     sensor::button::{ChestButton, HeadButtons},
     vision::referee::{
         RefereePose, communication::ReceivedRefereePose, recognize::RefereePoseRecognized,
@@ -16,12 +15,12 @@ use serde_with::{DurationMilliSeconds, serde_as};
 use std::time::Duration;
 
 use bifrost::communication::{GameControllerMessage, GameState};
-use nidhogg::types::color;
 
 #[serde_as]
 #[derive(Resource, Serialize, Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct PrimaryStateConfig {
+    /// Time between two blinks of the chest while sitting.
     #[serde_as(as = "DurationMilliSeconds<u64>")]
     pub chest_blink_interval: Duration,
 }
@@ -93,20 +92,15 @@ fn update_gamecontroller_message(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
 pub fn update_primary_state(
     mut primary_state: ResMut<PrimaryState>,
     game_controller_message: Option<Res<GameControllerMessage>>,
-    mut nao_manager: ResMut<NaoManager>,
     (head_buttons, chest_button): (Res<HeadButtons>, Res<ChestButton>),
-    config: Res<PrimaryStateConfig>,
     whistle: Res<Whistle>,
     penalty_state: Res<PenaltyState>,
     mut recognized_pose: EventReader<RefereePoseRecognized>,
     mut received_pose: EventReader<ReceivedRefereePose>,
 ) {
-    use PrimaryState as PS;
-
     let next_state = next_primary_state(
         primary_state.as_mut(),
         game_controller_message.as_deref(),
@@ -122,22 +116,6 @@ pub fn update_primary_state(
                 .any(|event| event.pose == RefereePose::Ready),
     );
 
-    match next_state {
-        PS::Sitting => nao_manager.set_chest_blink_led(
-            color::f32::BLUE,
-            config.chest_blink_interval,
-            Priority::Medium,
-        ),
-        PS::Standby => nao_manager.set_chest_led(color::f32::CYAN, Priority::Critical),
-        PS::Initial => nao_manager.set_chest_led(color::f32::GRAY, Priority::Critical),
-        PS::Ready { .. } => nao_manager.set_chest_led(color::f32::BLUE, Priority::Critical),
-        PS::Set => nao_manager.set_chest_led(color::f32::YELLOW, Priority::Critical),
-        PS::Playing { .. } => nao_manager.set_chest_led(color::f32::GREEN, Priority::Critical),
-        PS::Penalized => nao_manager.set_chest_led(color::f32::RED, Priority::Critical),
-        PS::Finished => nao_manager.set_chest_led(color::f32::GRAY, Priority::Critical),
-        PS::Calibration => nao_manager.set_chest_led(color::f32::PURPLE, Priority::Critical),
-    };
-
     *primary_state = next_state;
 }
 
@@ -3,8 +3,8 @@ use nalgebra::Point2;
 
 use crate::{
     behavior::{
-        behaviors::{LookMode, Observe, WalkTo},
-        engine::{CommandsBehaviorExt, RoleState, Roles, in_role},
+        engine::{RoleState, Roles, in_role},
+        positioning::{PositioningTarget, walk_to_position},
     },
     core::config::{layout::LayoutConfig, showtime::PlayerConfig},
     motion::step_planner::{StepPlanner, Target},
@@ -20,7 +20,8 @@ impl Plugin for DefenderRolePlugin {
 }
 
 /// The [`Defender`] role is held by any robot that does not see the ball.
-/// It's job is to observe it's set position depending on player number.
+/// It's job is to cover the own goal from the ball, see [`PositioningTarget`].
+/// If the ball is not known, it observes it's set position depending on player number.
 #[derive(Resource)]
 pub struct Defender;
 impl Roles for Defender {
@@ -31,32 +32,18 @@ pub fn defender_role(
     mut commands: Commands,
     player_config: Res<PlayerConfig>,
     layout_config: Res<LayoutConfig>,
-    step_planner: ResMut<StepPlanner>,
+    positioning_target: Res<PositioningTarget>,
+    step_planner: Res<StepPlanner>,
 ) {
     let set_robot_position = layout_config
         .set_positions
         .player(player_config.player_number);
     let set_position = set_robot_position.isometry.translation.vector;
     let set_point = Point2::new(set_position.x, set_position.y);
-    let defend_target = Target {
+    let defend_target = positioning_target.walk_target(Target {
         position: set_point,
         rotation: Some(set_robot_position.isometry.rotation),
-    };
+    });
 
-    if !step_planner.has_target() {
-        commands.set_behavior(WalkTo {
-            target: defend_target,
-            look_mode: LookMode::Observe,
-        });
-        return;
-    }
-
-    if step_planner.reached_target() {
-        commands.set_behavior(Observe::with_turning(-0.4));
-    } else {
-        commands.set_behavior(WalkTo {
-            target: defend_target,
-            look_mode: LookMode::Observe,
-        });
-    }
+    walk_to_position(&mut commands, defend_target, &step_planner);
 }
@@ -3,7 +3,9 @@
 mod defender;
 mod goalkeeper;
 mod striker;
+mod supporter;
 
 pub use defender::{Defender, DefenderRolePlugin};
 pub use goalkeeper::{Goalkeeper, GoalkeeperRolePlugin};
 pub use striker::{LostBallSearchTimer, Striker, StrikerRolePlugin};
+pub use supporter::{Supporter, SupporterRolePlugin};
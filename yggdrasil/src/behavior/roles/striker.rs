@@ -1,24 +1,28 @@
 use bevy::prelude::*;
 use bifrost::communication::{GameControllerMessage, SetPlay};
-use nalgebra::{Point2, Point3};
+use nalgebra::{Point2, Point3, Vector2};
 use nidhogg::types::{FillExt, RightEye, color};
 
 use crate::{
     behavior::{
+        BehaviorConfig,
         behaviors::{
-            LookMode, LostBallSearch, RlStrikerSearchBehavior, StandLookAt, Walk, WalkTo,
-            WalkToBall,
+            LookMode, RlStrikerSearchBehavior, SearchForBall, StandLookAt, Walk, WalkTo, WalkToBall,
         },
         engine::{BehaviorState, CommandsBehaviorExt, RoleState, Roles, in_role},
+        kick_target::{KickTarget, select_kick_target},
         primary_state::PrimaryState,
     },
     core::config::{
         layout::{FieldConfig, LayoutConfig},
         showtime::PlayerConfig,
     },
-    localization::RobotPose,
-    motion::{step_planner::Target, walking_engine::step::Step},
-    nao::{NaoManager, Priority},
+    localization::{RobotPose, communication::TeammatePositions},
+    motion::{
+        step_planner::{StepPlanner, Target},
+        walking_engine::step::Step,
+    },
+    nao::{Clock, NaoManager, Priority},
     vision::ball_detection::hypothesis::{Ball, BallState},
 };
 
@@ -62,30 +66,33 @@ fn in_set_play(
     false
 }
 
-/// The `Striker` role has five substates, each indicated by the right eye LED color:
+/// The `Striker` role has six substates, each indicated by the right eye LED color:
+///
+/// | LED Color | Substate            | Description                                                        |
+/// |-----------|---------------------|--------------------------------------------------------------------|
+/// | Blue      | Search for Ball     | Ball lost recently; search around its last known position.         |
+/// | Green     | RL Striker Search   | No ball detected for a while; search for the ball.                 |
+/// | Yellow    | Walk to Ball        | Ball is far; walk straight towards it.                             |
+/// | Orange    | Align with Target   | Close to the ball but not aligned with the target; circle step.    |
+/// | Purple    | Align with Ball     | Aligned with the target but not with the ball; side step to align. |
+/// | Red       | Walk with Ball      | Aligned with both target and ball; walk straight forward.          |
 ///
-/// | LED Color | Substate            | Description                                                      |
-/// |-----------|---------------------|------------------------------------------------------------------|
-/// | Green     | RL Striker Search   | No ball detected; search for the ball.                           |
-/// | Yellow    | Walk to Ball        | Ball is far; walk straight towards it.                           |
-/// | Orange    | Align with Goal     | Close to the ball but not aligned with the goal; circle step.    |
-/// | Purple    | Align with Ball     | Aligned with the goal but not with the ball; side step to align. |
-/// | Red       | Walk with Ball      | Aligned with both goal and ball; walk straight forward.          |
+/// The target is either the goal or a teammate to pass to, see [`select_kick_target`].
 #[derive(Resource, Default, Debug)]
 pub struct Striker;
 
 #[derive(Resource)]
 pub struct LostBallSearchTimer {
     timer: Timer,
-    last_ball: Point2<f32>,
+    search: SearchForBall,
 }
 
 impl LostBallSearchTimer {
     #[must_use]
-    pub fn new(duration: Duration, last_ball: Point2<f32>) -> Self {
+    pub fn new(duration: Duration, search: SearchForBall) -> Self {
         LostBallSearchTimer {
             timer: Timer::new(duration, TimerMode::Once),
-            last_ball,
+            search,
         }
     }
 }
@@ -98,6 +105,7 @@ fn reset_striker_role(mut nao_manager: ResMut<NaoManager>) {
     nao_manager.set_right_eye_led(RightEye::fill(color::f32::EMPTY), Priority::default());
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn striker_role(
     mut commands: Commands,
     pose: Res<RobotPose>,
@@ -106,13 +114,13 @@ pub fn striker_role(
     mut nao_manager: ResMut<NaoManager>,
     lost_ball_timer: Option<ResMut<LostBallSearchTimer>>,
     time: Res<Time>,
+    behavior_config: Res<BehaviorConfig>,
+    teammate_positions: Res<TeammatePositions>,
+    step_planner: Res<StepPlanner>,
+    clock: Res<Clock>,
+    mut kick_target: Local<Option<KickTarget>>,
 ) {
-    let Ball::Some(BallState {
-        position: relative_ball,
-        last_update,
-        ..
-    }) = ball.as_ref()
-    else {
+    let Ball::Some(ball_state) = ball.as_ref() else {
         if let Some(mut timer) = lost_ball_timer {
             timer.timer.tick(time.delta()); // <- tick the timer
 
@@ -120,13 +128,9 @@ pub fn striker_role(
                 commands.remove_resource::<LostBallSearchTimer>();
             } else {
                 nao_manager
-                    .set_right_eye_led(RightEye::fill(color::f32::BLUE), Priority::default()); // TEMP LED
+                    .set_right_eye_led(RightEye::fill(color::f32::BLUE), Priority::default());
 
-                // determine the side we need to turn to by using timer.last_ball
-                let relative_last_ball = &timer.last_ball;
-                commands.set_behavior(LostBallSearch::with_turning(
-                    relative_last_ball.y.signum() * 0.6, //TODO test
-                ));
+                commands.set_behavior(timer.search);
             }
         } else {
             nao_manager.set_right_eye_led(RightEye::fill(color::f32::GREEN), Priority::default());
@@ -134,14 +138,22 @@ pub fn striker_role(
         }
         return;
     };
+    let BallState {
+        position: relative_ball,
+        last_update,
+        ..
+    } = ball_state;
     let absolute_ball: nalgebra::OPoint<f32, nalgebra::Const<2>> =
         pose.robot_to_world(relative_ball);
 
-    if last_update.elapsed().as_secs_f32() > 0.5 {
+    if clock.elapsed(*last_update).as_secs_f32() > 0.5 {
         if lost_ball_timer.is_none() {
             commands.insert_resource(LostBallSearchTimer::new(
-                Duration::from_secs(9),
-                *relative_ball,
+                behavior_config.search_for_ball.timeout,
+                SearchForBall {
+                    last_ball: Some(absolute_ball),
+                    uncertainty: ball_state.position_uncertainty(),
+                },
             ));
         }
     } else {
@@ -153,20 +165,47 @@ pub fn striker_role(
     let ball_target: nalgebra::OPoint<f32, nalgebra::Const<3>> =
         Point3::new(absolute_ball.x, absolute_ball.y, 0.2);
 
-    let relative_goalpost_left =
-        pose.world_to_robot(&Point2::new(layout_config.field.length / 2., 0.8));
-    let relative_goalpost_right =
-        pose.world_to_robot(&Point2::new(layout_config.field.length / 2., -0.8));
+    let config = &behavior_config.kick_target;
+    let teammates: Vec<_> = teammate_positions
+        .recent(&clock, config.teammate_timeout)
+        .collect();
+    let target = select_kick_target(
+        absolute_ball,
+        Point2::new(layout_config.field.length / 2., 0.),
+        &teammates,
+        &step_planner.obstacles(&pose),
+        kick_target.as_ref(),
+        config,
+    );
+    *kick_target = Some(target);
+
+    let (target_left, target_right) = match target {
+        KickTarget::Goal { .. } => (
+            Point2::new(layout_config.field.length / 2., 0.8),
+            Point2::new(layout_config.field.length / 2., -0.8),
+        ),
+        KickTarget::Pass { position, .. } => {
+            // the edges of the teammate, as seen from the ball
+            let direction = (position - absolute_ball).normalize();
+            let left = Vector2::new(-direction.y, direction.x) * config.pass_alignment_width;
+            (position + left, position - left)
+        }
+    };
+    let relative_target_left = pose.world_to_robot(&target_left);
+    let relative_target_right = pose.world_to_robot(&target_right);
 
-    let goal_aligned = goal_aligned(pose.as_ref(), &layout_config.as_ref().field);
+    let target_aligned = match target {
+        KickTarget::Goal { .. } => goal_aligned(pose.as_ref(), &layout_config.as_ref().field),
+        KickTarget::Pass { .. } => relative_target_left.y > 0. && relative_target_right.y < 0.,
+    };
 
     if ball_distance > ALIGN_WITH_BALL_DISTANCE {
         nao_manager.set_right_eye_led(RightEye::fill(color::f32::YELLOW), Priority::default());
 
         commands.set_behavior(WalkToBall);
-    } else if !goal_aligned {
+    } else if !target_aligned {
         nao_manager.set_right_eye_led(RightEye::fill(color::f32::ORANGE), Priority::default());
-        if relative_goalpost_left.y < 0. && relative_goalpost_right.y < 0. {
+        if relative_target_left.y < 0. && relative_target_right.y < 0. {
             commands.set_behavior(Walk {
                 step: Step {
                     forward: 0.00,
@@ -177,7 +216,7 @@ pub fn striker_role(
             });
             return;
         }
-        if relative_goalpost_left.y > 0. && relative_goalpost_right.y > 0. {
+        if relative_target_left.y > 0. && relative_target_right.y > 0. {
             commands.set_behavior(Walk {
                 step: Step {
                     forward: 0.00,
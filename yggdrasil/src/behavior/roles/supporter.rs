@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use nalgebra::Point2;
+
+use crate::{
+    behavior::{
+        engine::{RoleState, Roles, in_role},
+        positioning::{PositioningTarget, walk_to_position},
+    },
+    core::config::{layout::LayoutConfig, showtime::PlayerConfig},
+    motion::step_planner::{StepPlanner, Target},
+};
+
+/// Plugin for the Supporter role
+pub struct SupporterRolePlugin;
+
+impl Plugin for SupporterRolePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, supporter_role.run_if(in_role::<Supporter>));
+    }
+}
+
+/// The [`Supporter`] role is held by a robot that does not see the ball, but stays close to it.
+/// It's job is to stay behind the ball next to the striker, so it can take over the ball when the
+/// striker loses it, see [`PositioningTarget`].
+/// If the ball is not known, it observes it's set position depending on player number.
+#[derive(Resource)]
+pub struct Supporter;
+impl Roles for Supporter {
+    const STATE: RoleState = RoleState::Supporter;
+}
+
+pub fn supporter_role(
+    mut commands: Commands,
+    player_config: Res<PlayerConfig>,
+    layout_config: Res<LayoutConfig>,
+    positioning_target: Res<PositioningTarget>,
+    step_planner: Res<StepPlanner>,
+) {
+    let set_robot_position = layout_config
+        .set_positions
+        .player(player_config.player_number);
+    let set_position = set_robot_position.isometry.translation.vector;
+    let support_target = positioning_target.walk_target(Target {
+        position: Point2::new(set_position.x, set_position.y),
+        rotation: Some(set_robot_position.isometry.rotation),
+    });
+
+    walk_to_position(&mut commands, support_target, &step_planner);
+}
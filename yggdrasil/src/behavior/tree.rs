@@ -0,0 +1,228 @@
+//! Behavior-tree combinators for composing behaviors declaratively.
+//!
+//! Roles decide which [`Behavior`] to run by setting it every cycle with
+//! [`CommandsBehaviorExt::set_behavior`]. For larger decision structures this quickly becomes a
+//! deep nest of `if`/`else` branches, so this module provides a small set of behavior-tree nodes
+//! that can be combined instead:
+//!
+//! ```ignore
+//! let tree = selector(vec![
+//!     guard(|data: &StrikerData| data.ball_seen, behavior(|_| WalkToBall)),
+//!     behavior(|_| Observe::default()),
+//! ]);
+//!
+//! tree.tick(&mut Context::new(&mut commands, &data));
+//! ```
+//!
+//! The tree is evaluated from the root on every tick and does not remember which node was
+//! running, just like the imperative roles.
+
+use bevy::prelude::*;
+
+use super::engine::{Behavior, CommandsBehaviorExt};
+
+/// The result of ticking a [`Node`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Failure,
+    Running,
+}
+
+/// The context that is passed to every node of a tree.
+///
+/// The data is shared by all nodes, and contains whatever the tree needs to make its decisions,
+/// such as resources that were queried by the system ticking the tree.
+pub struct Context<'a, 'w, 's, D> {
+    pub commands: &'a mut Commands<'w, 's>,
+    pub data: &'a D,
+}
+
+impl<'a, 'w, 's, D> Context<'a, 'w, 's, D> {
+    #[must_use]
+    pub fn new(commands: &'a mut Commands<'w, 's>, data: &'a D) -> Self {
+        Self { commands, data }
+    }
+}
+
+/// A node in a behavior tree.
+pub trait Node<D>: Send + Sync {
+    fn tick(&self, ctx: &mut Context<'_, '_, '_, D>) -> Status;
+}
+
+pub type BoxedNode<D> = Box<dyn Node<D>>;
+
+impl<D, F> Node<D> for F
+where
+    F: Fn(&mut Context<'_, '_, '_, D>) -> Status + Send + Sync,
+{
+    fn tick(&self, ctx: &mut Context<'_, '_, '_, D>) -> Status {
+        self(ctx)
+    }
+}
+
+/// A leaf node that runs an arbitrary action.
+pub fn action<D: 'static>(
+    action: impl Fn(&mut Context<'_, '_, '_, D>) -> Status + Send + Sync + 'static,
+) -> BoxedNode<D> {
+    Box::new(action)
+}
+
+/// A leaf node that succeeds if the condition holds, and fails otherwise.
+pub fn condition<D: 'static>(
+    condition: impl Fn(&D) -> bool + Send + Sync + 'static,
+) -> BoxedNode<D> {
+    action(move |ctx| {
+        if condition(ctx.data) {
+            Status::Success
+        } else {
+            Status::Failure
+        }
+    })
+}
+
+/// A leaf node that sets the behavior created by `behavior`, and is always running.
+pub fn behavior<D: 'static, B: Behavior>(
+    behavior: impl Fn(&D) -> B + Send + Sync + 'static,
+) -> BoxedNode<D> {
+    action(move |ctx| {
+        ctx.commands.set_behavior(behavior(ctx.data));
+        Status::Running
+    })
+}
+
+/// Ticks the children in order until one of them does not succeed, and returns its status.
+///
+/// Succeeds if all children succeed.
+pub fn sequence<D: 'static>(children: Vec<BoxedNode<D>>) -> BoxedNode<D> {
+    action(move |ctx| {
+        children
+            .iter()
+            .map(|child| child.tick(ctx))
+            .find(|status| *status != Status::Success)
+            .unwrap_or(Status::Success)
+    })
+}
+
+/// Ticks the children in order until one of them does not fail, and returns its status.
+///
+/// Fails if all children fail.
+pub fn selector<D: 'static>(children: Vec<BoxedNode<D>>) -> BoxedNode<D> {
+    action(move |ctx| {
+        children
+            .iter()
+            .map(|child| child.tick(ctx))
+            .find(|status| *status != Status::Failure)
+            .unwrap_or(Status::Failure)
+    })
+}
+
+/// Ticks all children, and succeeds once at least `success_threshold` of them succeed.
+///
+/// Fails as soon as the threshold can no longer be reached, and is running otherwise.
+pub fn parallel<D: 'static>(success_threshold: usize, children: Vec<BoxedNode<D>>) -> BoxedNode<D> {
+    action(move |ctx| {
+        let statuses = children
+            .iter()
+            .map(|child| child.tick(ctx))
+            .collect::<Vec<_>>();
+
+        let successes = statuses.iter().filter(|s| **s == Status::Success).count();
+        let failures = statuses.iter().filter(|s| **s == Status::Failure).count();
+
+        if successes >= success_threshold {
+            Status::Success
+        } else if children.len() - failures < success_threshold {
+            Status::Failure
+        } else {
+            Status::Running
+        }
+    })
+}
+
+/// Ticks the child only if the condition holds, and fails otherwise.
+pub fn guard<D: 'static>(
+    condition: impl Fn(&D) -> bool + Send + Sync + 'static,
+    child: BoxedNode<D>,
+) -> BoxedNode<D> {
+    action(move |ctx| {
+        if condition(ctx.data) {
+            child.tick(ctx)
+        } else {
+            Status::Failure
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behavior::{
+        behaviors::{Observe, Stand},
+        engine::BehaviorState,
+    };
+
+    struct Data {
+        ball_seen: bool,
+    }
+
+    fn tick(tree: &BoxedNode<Data>, data: &Data) -> (Status, World) {
+        let mut world = World::new();
+        world.init_resource::<NextState<BehaviorState>>();
+
+        let status = tree.tick(&mut Context::new(&mut world.commands(), data));
+        world.flush();
+
+        (status, world)
+    }
+
+    #[test]
+    fn selector_runs_first_applicable_child() {
+        let tree = selector(vec![
+            guard(|data: &Data| data.ball_seen, behavior(|_| Stand)),
+            behavior(|_| Observe::default()),
+        ]);
+
+        let (status, world) = tick(&tree, &Data { ball_seen: true });
+        assert_eq!(status, Status::Running);
+        assert!(world.contains_resource::<Stand>());
+        assert!(!world.contains_resource::<Observe>());
+
+        let (status, world) = tick(&tree, &Data { ball_seen: false });
+        assert_eq!(status, Status::Running);
+        assert!(world.contains_resource::<Observe>());
+        assert!(!world.contains_resource::<Stand>());
+        assert!(matches!(
+            world.resource::<NextState<BehaviorState>>(),
+            NextState::Pending(BehaviorState::Observe)
+        ));
+    }
+
+    #[test]
+    fn sequence_and_parallel_combine_statuses() {
+        let succeed = || action(|_: &mut Context<'_, '_, '_, Data>| Status::Success);
+        let fail = || action(|_: &mut Context<'_, '_, '_, Data>| Status::Failure);
+        let data = Data { ball_seen: false };
+
+        assert_eq!(
+            tick(&sequence(vec![succeed(), succeed()]), &data).0,
+            Status::Success
+        );
+        assert_eq!(
+            tick(&sequence(vec![succeed(), fail(), succeed()]), &data).0,
+            Status::Failure
+        );
+        assert_eq!(
+            tick(&parallel(1, vec![fail(), succeed()]), &data).0,
+            Status::Success
+        );
+        assert_eq!(
+            tick(&parallel(2, vec![fail(), succeed()]), &data).0,
+            Status::Failure
+        );
+        assert_eq!(
+            tick(&parallel(1, vec![behavior(|_| Stand), fail()]), &data).0,
+            Status::Running
+        );
+    }
+}
@@ -10,7 +10,7 @@ use crate::core::config::showtime::ShowtimeConfig;
 use crate::prelude::Result;
 use crate::vision::referee::RefereePose;
 
-use bifrost::broadcast::{Deadline, Inbound, Message, Outbound, Rate};
+use bifrost::broadcast::{Coalesce, Deadline, Inbound, Message, Outbound, Rate, Staged, Stamped};
 use bifrost::communication::{GameControllerMessage, GameState, Half};
 use bifrost::serialization::{Decode, Encode};
 
@@ -46,9 +46,14 @@ fn sync_budget(mut tc: ResMut<TeamCommunication>, message: Option<Res<GameContro
             // For now, make sure to never send messages faster than can be maintained.
             tc.rate_mut().late_threshold = threshold;
             tc.rate_mut().automatic_deadline = threshold;
+            tc.staged.set_interval(threshold);
         }
     }
 
+    if let Err(err) = tc.release_staged() {
+        warn!(?err, "unable to encode staged update");
+    }
+
     match tc.try_send() {
         Ok(true) => debug!("successfully sent out a new packet."),
         Ok(false) => (),
@@ -64,8 +69,8 @@ fn sync_budget(mut tc: ResMut<TeamCommunication>, message: Option<Res<GameContro
 
 fn ping_response(mut tc: ResMut<TeamCommunication>) {
     // If we have received a ping...
-    let msg = tc.inbound_mut().take_map(|_, _, msg| match msg {
-        TeamMessage::Ping => Some(TeamMessage::Pong),
+    let msg = tc.inbound_mut().take_map(|_, _, msg| match &msg.message {
+        TeamMessage::Ping => Some(Stamped::now(TeamMessage::Pong)),
         _ => None,
     });
 
@@ -86,8 +91,9 @@ pub struct TeamCommunication {
     port: u16,
     team_number: u8,
     socket: UdpSocket,
-    inbound: Inbound<SocketAddr, TeamMessage>,
-    outbound: Outbound<TeamMessage>,
+    inbound: Inbound<SocketAddr, Stamped<TeamMessage>>,
+    outbound: Outbound<Stamped<TeamMessage>>,
+    staged: Staged<TeamUpdate>,
 }
 
 impl TeamCommunication {
@@ -108,16 +114,17 @@ impl TeamCommunication {
             port,
             team_number,
             socket,
+            staged: Staged::new(rate.late_threshold),
             inbound: Inbound::new(),
             outbound: Outbound::new(rate),
         })
     }
 
-    pub fn inbound_mut(&mut self) -> &mut Inbound<SocketAddr, TeamMessage> {
+    pub fn inbound_mut(&mut self) -> &mut Inbound<SocketAddr, Stamped<TeamMessage>> {
         &mut self.inbound
     }
 
-    pub fn outbound_mut(&mut self) -> &mut Outbound<TeamMessage> {
+    pub fn outbound_mut(&mut self) -> &mut Outbound<Stamped<TeamMessage>> {
         &mut self.outbound
     }
 
@@ -125,6 +132,38 @@ impl TeamCommunication {
         &mut self.outbound.rate
     }
 
+    /// Stages a message, replacing the staged message of the same kind if there is one.
+    ///
+    /// The staged messages of all systems are released together at most once per
+    /// [`TeamCommunication::staged_interval`], so systems that update their message every cycle
+    /// do not exceed the message budget. Messages that have to be sent out as soon as possible
+    /// should be pushed into the [`TeamCommunication::outbound_mut`] buffer directly instead.
+    pub fn stage(&mut self, message: TeamMessage) {
+        self.staged.stage(TeamUpdate {
+            messages: vec![Stamped::now(message)],
+        });
+    }
+
+    /// The minimum interval between two releases of the staged messages, which follows the
+    /// message budget once it has been calibrated.
+    #[must_use]
+    pub fn staged_interval(&self) -> Duration {
+        self.staged.interval()
+    }
+
+    /// Moves the staged messages into the outbound buffer, if the staged interval has passed.
+    fn release_staged(&mut self) -> Result<()> {
+        let Some(update) = self.staged.try_take() else {
+            return Ok(());
+        };
+
+        for message in update.messages {
+            self.outbound.update_or_push(message).into_diagnostic()?;
+        }
+
+        Ok(())
+    }
+
     fn try_send(&mut self) -> Result<bool> {
         if let Some(packet) = self.outbound.try_pack() {
             match self
@@ -183,21 +222,94 @@ impl TeamCommunication {
     }
 }
 
+/// Header of a [`TeamMessage`], which distinguishes it from other messages such as the
+/// [`GameControllerMessage`].
+///
+/// Kept to a single byte, as it is sent with every message.
+const TEAM_MESSAGE_HEADER: [u8; 1] = *b"Y";
+
 #[derive(Debug, Encode, Decode)]
+#[bifrost(header = TEAM_MESSAGE_HEADER)]
 #[non_exhaustive]
 pub enum TeamMessage {
     Ping,
     Pong,
     DetectedWhistle,
     RecognizedRefereePose(RefereePose),
+    /// Position of the ball in world coordinates, as detected by the sender.
+    DetectedBall([f32; 2]),
+    /// Position of the sender in world coordinates.
+    RobotPosition {
+        /// Player number of the sender, so a robot can recognize its own broadcast.
+        player_number: u8,
+        position: [f32; 2],
+    },
 }
 
 impl Message for TeamMessage {
     const MAX_PACKET_SIZE: usize = 128;
-    const EXPECTED_SIZE: usize = 1;
+    const EXPECTED_SIZE: usize = 2;
     const DEAD_SPACE: usize = 64;
 
     fn try_merge(&mut self, old: &Self) -> bool {
         std::mem::discriminant(self) == std::mem::discriminant(old)
     }
 }
+
+/// The combined update of the staged [`TeamMessage`]s, containing the latest message of each
+/// kind.
+#[derive(Debug, Default)]
+struct TeamUpdate {
+    messages: Vec<Stamped<TeamMessage>>,
+}
+
+impl Coalesce for TeamUpdate {
+    fn coalesce(&mut self, newer: Self) {
+        for mut message in newer.messages {
+            match self.messages.iter_mut().find(|old| message.try_merge(old)) {
+                Some(old) => *old = message,
+                None => self.messages.push(message),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staged_updates_are_coalesced() {
+        let mut staged = Staged::new(Duration::from_secs(1));
+
+        for n in 0..5 {
+            let n = n as f32;
+            staged.stage(TeamUpdate {
+                messages: vec![
+                    Stamped::now(TeamMessage::RobotPosition {
+                        player_number: 1,
+                        position: [n, 0.0],
+                    }),
+                    Stamped::now(TeamMessage::DetectedBall([0.0, n])),
+                ],
+            });
+        }
+
+        // all updates of a single cycle are released once, with the latest message of each kind
+        let update = staged.try_take().expect("staged update should be released");
+        let messages: Vec<_> = update.messages.into_iter().map(|m| m.message).collect();
+        let [
+            TeamMessage::RobotPosition { position, .. },
+            TeamMessage::DetectedBall(ball),
+        ] = messages.as_slice()
+        else {
+            panic!("unexpected staged messages: {messages:?}");
+        };
+        assert_eq!(*position, [4.0, 0.0]);
+        assert_eq!(*ball, [0.0, 4.0]);
+
+        staged.stage(TeamUpdate::default());
+        assert!(staged.try_take().is_none());
+    }
+}
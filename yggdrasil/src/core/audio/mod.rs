@@ -3,10 +3,12 @@ use crate::prelude::*;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_with::{DurationMilliSeconds, serde_as};
+use sound_direction::SoundDirectionConfig;
 use std::time::Duration;
 
 pub mod audio_input;
 pub mod battery_sound;
+pub mod sound_direction;
 pub mod sound_manager;
 pub mod wee_sound;
 pub mod whistle_detection;
@@ -30,6 +32,8 @@ pub struct AudioConfig {
 
     #[serde_as(as = "DurationMilliSeconds<u64>")]
     pub whistle_timeout: Duration,
+
+    pub sound_direction: SoundDirectionConfig,
 }
 
 impl Config for AudioConfig {
@@ -42,6 +46,7 @@ impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
         app.init_config::<AudioConfig>().add_plugins((
             audio_input::AudioInputPlugin,
+            sound_direction::SoundDirectionPlugin,
             sound_manager::SoundManagerPlugin,
             whistle_detection::WhistleDetectionPlugin,
         ));
@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AudioConfig,
+    audio_input::{AudioSamplesEvent, SAMPLE_RATE},
+};
+
+/// The speed of sound in air, in meters per second.
+const SPEED_OF_SOUND: f32 = 343.0;
+
+/// Estimates the direction of incoming sound from the time difference of arrival between the
+/// microphones.
+///
+/// This module provides the following resources to the application:
+/// - [`SoundDirection`]
+pub struct SoundDirectionPlugin;
+
+impl Plugin for SoundDirectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoundDirection>()
+            .add_systems(Update, update_sound_direction);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SoundDirectionConfig {
+    /// Distance between the left and right microphone, in meters.
+    pub microphone_distance: f32,
+}
+
+/// The estimated direction of the most recent audio samples, relative to the head.
+///
+/// With only a left and right microphone, sound from the front and back cannot be distinguished,
+/// so the angle is always between -90° (right) and 90° (left). This makes it a weak cue that
+/// should be combined with other information, such as the expected position of the referee.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct SoundDirection {
+    /// The angle of the sound in radians, positive to the left, or [`None`] if the direction
+    /// could not be estimated.
+    pub angle: Option<f32>,
+    /// The normalized cross-correlation of the channels at the estimated delay, between 0 and 1.
+    pub confidence: f32,
+}
+
+impl SoundDirection {
+    /// Estimates the direction of a sound from the samples of each microphone channel, ordered
+    /// left to right.
+    ///
+    /// The delay between the channels is found by cross-correlating them over all physically
+    /// possible delays, refined to sub-sample precision with a parabolic fit around the peak.
+    ///
+    /// Returns no direction if there are fewer than two channels or the channels are silent.
+    #[must_use]
+    pub fn estimate(channels: &[&[f32]], microphone_distance: f32, sample_rate: f32) -> Self {
+        let [left, right, ..] = channels else {
+            return Self::default();
+        };
+
+        let max_lag = (microphone_distance / SPEED_OF_SOUND * sample_rate).ceil() as isize;
+        let correlations = (-max_lag..=max_lag)
+            .map(|lag| cross_correlation(left, right, lag))
+            .collect::<Vec<_>>();
+
+        let energy = left.iter().map(|s| s * s).sum::<f32>().sqrt()
+            * right.iter().map(|s| s * s).sum::<f32>().sqrt();
+
+        let Some((peak, &peak_correlation)) = correlations
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        else {
+            return Self::default();
+        };
+
+        if energy <= f32::EPSILON || peak_correlation <= 0.0 {
+            return Self::default();
+        }
+
+        let offset = if peak > 0 && peak + 1 < correlations.len() {
+            let (before, after) = (correlations[peak - 1], correlations[peak + 1]);
+            let curvature = before - 2.0 * peak_correlation + after;
+
+            if curvature < 0.0 {
+                0.5 * (before - after) / curvature
+            } else {
+                0.0
+            }
+        } else {
+            0.0
+        };
+
+        let delay = (peak as f32 - max_lag as f32 + offset) / sample_rate;
+        let sine = (delay * SPEED_OF_SOUND / microphone_distance).clamp(-1.0, 1.0);
+
+        Self {
+            angle: Some(sine.asin()),
+            confidence: (peak_correlation / energy).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Cross-correlation of two signals, where `right` is shifted back by `lag` samples.
+///
+/// A positive lag means the sound reached the left microphone first.
+fn cross_correlation(left: &[f32], right: &[f32], lag: isize) -> f32 {
+    let shift = lag.unsigned_abs();
+
+    if lag >= 0 {
+        left.iter()
+            .zip(right.iter().skip(shift))
+            .map(|(l, r)| l * r)
+            .sum()
+    } else {
+        left.iter()
+            .skip(shift)
+            .zip(right.iter())
+            .map(|(l, r)| l * r)
+            .sum()
+    }
+}
+
+fn update_sound_direction(
+    mut audio_samples: EventReader<AudioSamplesEvent>,
+    mut sound_direction: ResMut<SoundDirection>,
+    config: Res<AudioConfig>,
+) {
+    let Some(samples) = audio_samples.read().last() else {
+        return;
+    };
+
+    *sound_direction = SoundDirection::estimate(
+        &[samples.left.as_slice(), samples.right.as_slice()],
+        config.sound_direction.microphone_distance,
+        SAMPLE_RATE.0 as f32,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MICROPHONE_DISTANCE: f32 = 0.12;
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    /// Deterministic broadband noise, so the cross-correlation has a single clear peak.
+    fn noise(len: usize) -> Vec<f32> {
+        let mut state = 0x2545_f491_u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as f32 / u32::MAX as f32 - 0.5
+            })
+            .collect()
+    }
+
+    #[test]
+    fn estimates_angle_of_delayed_signal() {
+        let signal = noise(2048 + 16);
+
+        for delay in [-8_isize, 0, 5, 12] {
+            let shift = delay.unsigned_abs();
+            let (left, right) = if delay >= 0 {
+                (&signal[shift..shift + 2048], &signal[..2048])
+            } else {
+                (&signal[..2048], &signal[shift..shift + 2048])
+            };
+
+            let direction =
+                SoundDirection::estimate(&[left, right], MICROPHONE_DISTANCE, SAMPLE_RATE);
+            let expected =
+                (delay as f32 * SPEED_OF_SOUND / (SAMPLE_RATE * MICROPHONE_DISTANCE)).asin();
+
+            let angle = direction.angle.expect("direction should be estimated");
+            assert!(
+                (angle - expected).abs() < 0.02,
+                "delay {delay}: estimated {angle}, expected {expected}"
+            );
+            assert!(direction.confidence > 0.9);
+        }
+    }
+
+    #[test]
+    fn no_direction_without_stereo_signal() {
+        let signal = noise(2048);
+
+        let mono = SoundDirection::estimate(&[&signal], MICROPHONE_DISTANCE, SAMPLE_RATE);
+        assert!(mono.angle.is_none());
+
+        let silence = vec![0.0; 2048];
+        let silent =
+            SoundDirection::estimate(&[&silence, &silence], MICROPHONE_DISTANCE, SAMPLE_RATE);
+        assert!(silent.angle.is_none());
+    }
+}
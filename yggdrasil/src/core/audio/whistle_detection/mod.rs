@@ -8,7 +8,7 @@ use bevy::{
     tasks::{AsyncComputeTaskPool, Task, futures_lite::future},
 };
 
-use bifrost::broadcast::Deadline;
+use bifrost::broadcast::{Deadline, Stamped};
 use fourier::Stft;
 use nidhogg::types::{FillExt, LeftEar, RightEar};
 use serde::{Deserialize, Serialize};
@@ -50,7 +50,11 @@ impl Plugin for WhistleDetectionPlugin {
             .add_systems(Update, spawn_whistle_preprocess_task)
             .add_systems(
                 Update,
-                (update_whistle_state, despawn_whistle_preprocessing_task, spawn_whistle_detection_model)
+                (
+                    update_whistle_state,
+                    despawn_whistle_preprocessing_task,
+                    spawn_whistle_detection_model.run_if(ml::model_loaded::<WhistleDetectionModel>),
+                )
                     .chain()
                     .run_if(task_finished::<WhistleDetections>),
             )
@@ -136,7 +140,7 @@ fn update_whistle_state(
 
     let incoming_msg = tc
         .inbound_mut()
-        .take_map(|_, _, msg| match msg {
+        .take_map(|_, _, msg| match &msg.message {
             TeamMessage::DetectedWhistle => Some(()),
             _ => None,
         })
@@ -166,19 +170,17 @@ fn update_whistle_state(
 
             if *primary_state == PrimaryState::Set {
                 // Send message to all teammates
-                let msg = TeamMessage::DetectedWhistle;
+                let msg = Stamped::now(TeamMessage::DetectedWhistle);
                 tc.outbound_mut().update_or_push_by(msg, Deadline::ASAP)?;
             }
             break;
         }
     }
 
+    // only light up the ears on a whistle, so they show the game controller connection otherwise
     if whistle.detected {
         nao_manager.set_left_ear_led(LeftEar::fill(1.0), Priority::High);
         nao_manager.set_right_ear_led(RightEar::fill(1.0), Priority::High);
-    } else {
-        nao_manager.set_left_ear_led(LeftEar::fill(0.0), Priority::High);
-        nao_manager.set_right_ear_led(RightEar::fill(0.0), Priority::High);
     }
 
     Ok(())
@@ -186,6 +186,75 @@ impl FieldConfig {
         point.x.abs() < self.length / 2.0 + margin && point.y.abs() < self.width / 2.0 + margin
     }
 
+    /// Returns the distance from the point to the nearest field line.
+    #[must_use]
+    pub fn distance_to_nearest_line(&self, point: Point2<f32>) -> f32 {
+        self.field_lines()
+            .iter()
+            .map(|line| line.project_with_signed_distance(point).1.abs())
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Returns the centre of our own goal, which is the goal we defend.
+    #[must_use]
+    pub fn own_goal(&self) -> Point2<f32> {
+        point![-self.length / 2.0, 0.0]
+    }
+
+    /// Returns the centre of the opponents' goal.
+    #[must_use]
+    pub fn opponent_goal(&self) -> Point2<f32> {
+        point![self.length / 2.0, 0.0]
+    }
+
+    /// Returns if the point is in our own half of the field.
+    #[must_use]
+    pub fn in_own_half(&self, point: Point2<f32>) -> bool {
+        point.x < 0.0 && self.in_field(point)
+    }
+
+    /// Returns if the point is in our own penalty area.
+    #[must_use]
+    pub fn in_own_penalty_area(&self, point: Point2<f32>) -> bool {
+        point.x >= -self.length / 2.0
+            && point.x <= -self.length / 2.0 + self.penalty_area_length
+            && point.y.abs() <= self.penalty_area_width / 2.0
+    }
+
+    /// Returns if the point is in the opponents' penalty area.
+    #[must_use]
+    pub fn in_opponent_penalty_area(&self, point: Point2<f32>) -> bool {
+        self.in_own_penalty_area(point![-point.x, point.y])
+    }
+
+    /// Returns the corners of the field, starting with our own left corner and going clockwise.
+    #[must_use]
+    pub fn corners(&self) -> [Point2<f32>; 4] {
+        let (x, y) = (self.length / 2.0, self.width / 2.0);
+
+        [point![-x, y], point![x, y], point![x, -y], point![-x, -y]]
+    }
+
+    /// Returns the penalty marks, with the mark in front of our own goal first.
+    #[must_use]
+    pub fn penalty_marks(&self) -> [Point2<f32>; 2] {
+        let x = self.length / 2.0 - self.penalty_mark_distance;
+
+        [point![-x, 0.0], point![x, 0.0]]
+    }
+
+    /// Returns the positions of all landmarks on the field.
+    ///
+    /// These are the [corners](Self::corners), the [penalty marks](Self::penalty_marks) and the
+    /// centre of the field.
+    #[must_use]
+    pub fn landmarks(&self) -> [Point2<f32>; 7] {
+        let [c1, c2, c3, c4] = self.corners();
+        let [p1, p2] = self.penalty_marks();
+
+        [c1, c2, c3, c4, p1, p2, Point2::origin()]
+    }
+
     /// Returns the field lines described by the field configuration.
     #[allow(clippy::too_many_lines)]
     #[must_use]
@@ -345,6 +414,11 @@ impl FieldPositionsConfig {
             .find(|elem| elem.player_number == player_num as usize)
             .unwrap_or_else(|| panic!("Player number {player_num:?} not in layout configuration!"))
     }
+
+    /// Iterator over the positions of all players.
+    pub fn iter(&self) -> impl Iterator<Item = &RobotPosition> {
+        self.0.iter()
+    }
 }
 
 /// Contains the coordinates for one robot position.
@@ -365,3 +439,67 @@ pub struct RobotPosition {
 impl Config for LayoutConfig {
     const PATH: &'static str = "layout.toml";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spl_field() -> FieldConfig {
+        FieldConfig {
+            length: 9.0,
+            width: 6.0,
+            line_width: 0.05,
+            penalty_mark_size: 0.1,
+            goal_area_length: 0.6,
+            goal_area_width: 2.2,
+            penalty_area_length: 1.65,
+            penalty_area_width: 4.0,
+            penalty_mark_distance: 1.3,
+            centre_circle_diameter: 1.5,
+            border_strip_width: 0.7,
+        }
+    }
+
+    #[test]
+    fn distance_to_nearest_line() {
+        let field = spl_field();
+
+        assert!((field.distance_to_nearest_line(point![0.0, 0.0])).abs() < 1e-5);
+        assert!((field.distance_to_nearest_line(point![1.0, 0.0]) - 0.25).abs() < 1e-5);
+        assert!((field.distance_to_nearest_line(point![2.0, 2.5]) - 0.5).abs() < 1e-5);
+        assert!((field.distance_to_nearest_line(point![-4.0, 0.0]) - 0.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn goals_and_penalty_areas() {
+        let field = spl_field();
+
+        assert_eq!(field.own_goal(), point![-4.5, 0.0]);
+        assert_eq!(field.opponent_goal(), point![4.5, 0.0]);
+        assert!(field.in_own_half(point![-1.0, 2.0]));
+        assert!(!field.in_own_half(point![1.0, 2.0]));
+
+        assert!(field.in_own_penalty_area(point![-3.0, 1.9]));
+        assert!(!field.in_own_penalty_area(point![-2.8, 0.0]));
+        assert!(!field.in_own_penalty_area(point![3.0, 0.0]));
+        assert!(field.in_opponent_penalty_area(point![3.0, -1.9]));
+        assert!(!field.in_opponent_penalty_area(point![3.0, 2.1]));
+    }
+
+    #[test]
+    fn landmarks() {
+        let field = spl_field();
+
+        assert_eq!(
+            field.corners(),
+            [
+                point![-4.5, 3.0],
+                point![4.5, 3.0],
+                point![4.5, -3.0],
+                point![-4.5, -3.0]
+            ]
+        );
+        assert_eq!(field.penalty_marks(), [point![-3.2, 0.0], point![3.2, 0.0]]);
+        assert_eq!(field.landmarks()[6], point![0.0, 0.0]);
+    }
+}
@@ -1,15 +1,22 @@
 pub mod layout;
+mod profile;
+mod registry;
 pub mod showtime;
 pub mod yggdrasil;
 
 use std::path::{Path, PathBuf};
 
 use crate::{behavior::BehaviorConfig, nao::RobotInfo, prelude::*};
-use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy::{
+    ecs::system::{RunSystemOnce, ScheduleSystem},
+    prelude::*,
+};
 use miette::IntoDiagnostic;
-use odal::{ConfigKind, Error, ErrorKind};
+use odal::{Error, ErrorKind};
 
 use layout::LayoutConfig;
+pub use profile::{ConfigProfile, PROFILE_ENV_NAME};
+pub use registry::{ConfigRegistry, ConfigSource, dump_all_configs, set_config_value};
 use showtime::ShowtimeConfig;
 use yggdrasil::YggdrasilConfig;
 
@@ -18,6 +25,8 @@ use yggdrasil::YggdrasilConfig;
 /// It provides the following resources to the application:
 /// - [`MainConfigDir`]
 /// - [`OverlayConfigDir`]
+/// - [`ConfigRegistry`]
+/// - [`ConfigProfile`], if a profile is selected
 ///
 /// # Example
 ///
@@ -62,18 +71,32 @@ impl Plugin for ConfigPlugin {
             robot_info.robot_name
         );
 
-        app.insert_resource(MainConfigDir(main_dir))
+        app.insert_resource(MainConfigDir(main_dir.clone()))
             .insert_resource(OverlayConfigDir(overlay_dir));
 
-        app.init_config::<ShowtimeConfig>()
-            .init_config::<LayoutConfig>()
+        // the showtime config selects the profile, so it is loaded without one
+        app.init_config::<ShowtimeConfig>();
+
+        let requested_profile =
+            profile::requested_profile(std::env::args(), std::env::var(PROFILE_ENV_NAME).ok())
+                .or_else(|| app.world().resource::<ShowtimeConfig>().profile.clone());
+        if let Some(name) = requested_profile {
+            let profile = ConfigProfile::resolve(&main_dir, &name)
+                .unwrap_or_else(|report| panic!("{report:?}"));
+
+            tracing::info!("Using config profile `{}`", profile.name());
+            app.insert_resource(profile);
+        }
+
+        app.init_config::<LayoutConfig>()
             .init_config::<BehaviorConfig>()
             .init_config::<YggdrasilConfig>();
 
         app.add_systems(
             PreStartup,
             (init_subconfigs, showtime::configure_showtime).chain(),
-        );
+        )
+        .on_config_changed::<YggdrasilConfig, _>(init_subconfigs);
     }
 }
 
@@ -83,6 +106,10 @@ fn init_subconfigs(mut commands: Commands, config: Res<YggdrasilConfig>) {
     commands.insert_resource(config.game_controller.clone());
     commands.insert_resource(config.primary_state.clone());
     commands.insert_resource(config.orientation.clone());
+    commands.insert_resource(config.cycle.clone());
+    commands.insert_resource(config.hardware_health.clone());
+    commands.insert_resource(config.leds.clone());
+    commands.insert_resource(config.debug.clone());
 }
 
 /// Directory where the main configs are stored
@@ -111,6 +138,31 @@ pub trait ConfigExt {
     fn init_config<T: Resource + Config + Send + Sync + 'static>(&mut self) -> &mut Self
     where
         Self: Sized;
+
+    /// Returns the configuration `T`, initializing it if it has not been added to the app yet.
+    ///
+    /// The configuration is resolved in layers: the existing resource, then the config files
+    /// (main and overlay, like [`ConfigExt::init_config`]), and finally [`Default`] if no config
+    /// file exists. Unlike [`ConfigExt::init_config`], this does not panic on a missing file,
+    /// which makes it suitable for optional configs.
+    fn config_or_default<T: Resource + Config + Default + Clone>(&mut self) -> T
+    where
+        Self: Sized;
+
+    /// Adds systems that run when the configuration `T` changes.
+    ///
+    /// The systems run in [`First`], so the new configuration is applied before the rest of
+    /// the cycle. They do not run when the configuration is first inserted.
+    ///
+    /// Any system that mutates the resource counts as a change, such as the config editing in
+    /// the rerun viewer. There is no file watcher that hot-reloads configs from disk; if one is
+    /// added, it should replace the resource so these systems are triggered as well.
+    fn on_config_changed<T: Resource + Config, M>(
+        &mut self,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self
+    where
+        Self: Sized;
 }
 
 impl ConfigExt for App {
@@ -118,43 +170,119 @@ impl ConfigExt for App {
     where
         Self: Sized,
     {
+        self.world_mut().init_resource::<ConfigRegistry>();
         self.world_mut()
             .run_system_once(init_config::<T>)
             .unwrap_or_else(|_| panic!("failed to initialize config at: {}", T::PATH));
         self
     }
+
+    fn config_or_default<T: Resource + Config + Default + Clone>(&mut self) -> T
+    where
+        Self: Sized,
+    {
+        let world = self.world_mut();
+        if let Some(config) = world.get_resource::<T>() {
+            return config.clone();
+        }
+
+        let main_dir = world.resource::<MainConfigDir>();
+        let overlay_dir = world.resource::<OverlayConfigDir>();
+        let profile_dir = world
+            .get_resource::<ConfigProfile>()
+            .map(ConfigProfile::dir);
+
+        let (config, source) = match load_config::<T>(&main_dir.0, profile_dir, &overlay_dir.0) {
+            // failed to load the main config, so fall back to the default
+            Err(Error {
+                name,
+                kind: ErrorKind::Load { path, .. },
+            }) => {
+                tracing::debug!("`{name}`: Failed to read `{path}`, using default config");
+                Ok((T::default(), ConfigSource::Default))
+            }
+            result => result,
+        }
+        .into_diagnostic()
+        .unwrap_or_else(|report| panic!("{report:?}"));
+
+        world.insert_resource(config.clone());
+        world
+            .get_resource_or_init::<ConfigRegistry>()
+            .register::<T>(source);
+        config
+    }
+
+    fn on_config_changed<T: Resource + Config, M>(
+        &mut self,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self
+    where
+        Self: Sized,
+    {
+        self.add_systems(
+            First,
+            systems.run_if(resource_exists_and_changed::<T>.and(not(resource_added::<T>))),
+        )
+    }
+}
+
+/// Loads the config from the main directory, with the profile and then the overlay applied if
+/// they contain the config.
+#[allow(clippy::result_large_err)]
+fn load_config<T: Config>(
+    main_path: &Path,
+    profile_path: Option<&Path>,
+    overlay_path: &Path,
+) -> odal::Result<(T, ConfigSource)> {
+    let contains_config = |path: &&Path| path.join(T::PATH).is_file();
+    let profile_path = profile_path.filter(contains_config);
+    let overlay_path = Some(overlay_path).filter(contains_config).or_else(|| {
+        tracing::debug!(
+            "`{}`: No overlay in `{}`",
+            T::name(),
+            overlay_path.display()
+        );
+        None
+    });
+
+    let source = match (profile_path, overlay_path) {
+        (None, None) => ConfigSource::Main,
+        (None, Some(_)) => ConfigSource::Overlay,
+        (Some(_), None) => ConfigSource::Profile,
+        (Some(_), Some(_)) => ConfigSource::ProfileOverlay,
+    };
+
+    T::load_with_overlays(main_path, profile_path.into_iter().chain(overlay_path))
+        .map(|t| (t, source))
 }
 
 fn init_config<T: Resource + Config + Send + Sync + 'static>(
     mut commands: Commands,
     main_dir: Res<MainConfigDir>,
     overlay_dir: Res<OverlayConfigDir>,
+    profile: Option<Res<ConfigProfile>>,
+    mut registry: ResMut<ConfigRegistry>,
 ) {
     // add config file path to the config roots
     let main_path: &Path = main_dir.0.as_ref();
     let overlay_path: &Path = overlay_dir.0.as_ref();
+    let profile_path = profile.as_deref().map(ConfigProfile::dir);
 
-    let config = match T::load_with_overlay(main_path, overlay_path) {
-        Ok(t) => Ok(t),
-        // failed to load any overlay
-        Err(Error {
-            name,
-            kind:
-                ErrorKind::Load {
-                    path,
-                    config_kind: ConfigKind::Overlay,
-                    ..
-                },
-        }) => {
-            // log and use only main config
-            tracing::debug!("`{name}`: Failed to read overlay from `{path}`");
-            // use only root in that case
-            T::load(main_path)
-        }
-        Err(e) => Err(e),
-    }
-    .into_diagnostic()
-    .unwrap_or_else(|report| panic!("{report:?}"));
+    let (config, source) = load_config::<T>(main_path, profile_path, overlay_path)
+        .into_diagnostic()
+        .unwrap_or_else(|report| panic!("{report:?}"));
 
     commands.insert_resource(config);
+    registry.register::<T>(source);
+}
+
+/// Loads the main config that is deployed to the robots, so tests use the same values.
+#[cfg(test)]
+pub(crate) fn load_deployed<T: Config>() -> T {
+    let main_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../deploy/config");
+
+    T::load(main_path)
+        .into_diagnostic()
+        .unwrap_or_else(|report| panic!("{report:?}"))
 }
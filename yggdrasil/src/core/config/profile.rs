@@ -0,0 +1,229 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use miette::{Result, bail, miette};
+
+/// Name of the environment variable that selects the config profile.
+pub const PROFILE_ENV_NAME: &str = "YGGDRASIL_PROFILE";
+
+/// Name of the directory within the main config directory that contains the profiles.
+const PROFILE_DIR_NAME: &str = "profile";
+
+/// A named set of overlay configs for a specific condition, such as `practice-gym` or
+/// `competition-field`.
+///
+/// The configs of a profile are stored in `config/profile/<name>/`, and are applied on top of the
+/// main configs, before the overlay of the robot. This way a profile can change the configs of
+/// the whole team, while robot specific calibrations still apply.
+///
+/// The profile is selected with the `--profile <name>` flag or the [`PROFILE_ENV_NAME`]
+/// environment variable, and otherwise with the profile in the
+/// [`ShowtimeConfig`](super::showtime::ShowtimeConfig) that is set by `sindri`.
+#[derive(Resource, Debug, Clone)]
+pub struct ConfigProfile {
+    name: String,
+    dir: PathBuf,
+}
+
+impl ConfigProfile {
+    /// Finds the profile `name` in the main config directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a valid profile name, or if the profile does not
+    /// contain any configs.
+    pub fn resolve(main_dir: &Path, name: &str) -> Result<Self> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!(
+                "Invalid config profile `{name}`, a profile name may only contain letters, digits, `-` and `_`"
+            );
+        }
+
+        let profiles_dir = main_dir.join(PROFILE_DIR_NAME);
+        let dir = profiles_dir.join(name);
+        if !contains_configs(&dir) {
+            let available = available_profiles(&profiles_dir);
+            return Err(miette!(
+                help = if available.is_empty() {
+                    format!("No profiles exist in `{}`", profiles_dir.display())
+                } else {
+                    format!("Available profiles: {}", available.join(", "))
+                },
+                "Config profile `{name}` does not exist, expected configs in `{}`",
+                dir.display()
+            ));
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            dir,
+        })
+    }
+
+    /// The name of the profile.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The directory that contains the configs of the profile.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Returns the profile requested with the `--profile` flag in `args`, or else with the `env`
+/// value of [`PROFILE_ENV_NAME`].
+#[must_use]
+pub fn requested_profile(
+    args: impl IntoIterator<Item = String>,
+    env: Option<String>,
+) -> Option<String> {
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next();
+        }
+
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_owned());
+        }
+    }
+
+    env.filter(|name| !name.is_empty())
+}
+
+/// Whether the directory contains at least one config file.
+fn contains_configs(dir: &Path) -> bool {
+    dir.read_dir().is_ok_and(|mut entries| {
+        entries.any(|entry| {
+            entry.is_ok_and(|entry| entry.path().extension().is_some_and(|ext| ext == "toml"))
+        })
+    })
+}
+
+/// The names of all profiles that contain configs, sorted alphabetically.
+fn available_profiles(profiles_dir: &Path) -> Vec<String> {
+    let Ok(entries) = profiles_dir.read_dir() else {
+        return Vec::new();
+    };
+
+    let mut profiles: Vec<_> = entries
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| contains_configs(&entry.path()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    profiles.sort();
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, ops::Deref};
+
+    use odal::Config;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::core::config::{ConfigSource, load_config};
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct LightingConfig {
+        white_luminance: f32,
+        exposure: u32,
+    }
+
+    impl Config for LightingConfig {
+        const PATH: &'static str = "lighting.toml";
+    }
+
+    /// A temporary config directory, which is removed again when dropped.
+    struct ConfigDir(PathBuf);
+
+    impl Deref for ConfigDir {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for ConfigDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write(dir: &Path, contents: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(LightingConfig::PATH), contents).unwrap();
+    }
+
+    #[test]
+    fn profiles_change_effective_config() {
+        let main_dir = ConfigDir(
+            std::env::temp_dir().join(format!("yggdrasil-profile-{}", std::process::id())),
+        );
+        let overlay_dir = main_dir.join("overlay/local");
+        write(&main_dir, "white_luminance = 90.0\nexposure = 200\n");
+        write(
+            &main_dir.join("profile/practice-gym"),
+            "white_luminance = 70.0\n",
+        );
+        write(
+            &main_dir.join("profile/competition-field"),
+            "white_luminance = 110.0\nexposure = 150\n",
+        );
+        write(&overlay_dir, "exposure = 180\n");
+
+        let load = |name| {
+            let profile = ConfigProfile::resolve(&main_dir, name).unwrap();
+            load_config::<LightingConfig>(&main_dir, Some(profile.dir()), &overlay_dir).unwrap()
+        };
+
+        let (gym, source) = load("practice-gym");
+        assert_eq!(source, ConfigSource::ProfileOverlay);
+        assert!((gym.white_luminance - 70.0).abs() < f32::EPSILON);
+        assert_eq!(gym.exposure, 180);
+
+        // the overlay of the robot is applied on top of the profile
+        let (competition, _) = load("competition-field");
+        assert!((competition.white_luminance - 110.0).abs() < f32::EPSILON);
+        assert_eq!(competition.exposure, 180);
+
+        let (main, source) = load_config::<LightingConfig>(&main_dir, None, &overlay_dir).unwrap();
+        assert_eq!(source, ConfigSource::Overlay);
+        assert!((main.white_luminance - 90.0).abs() < f32::EPSILON);
+
+        let error = ConfigProfile::resolve(&main_dir, "outdoor").unwrap_err();
+        let help = error
+            .help()
+            .map(|help| help.to_string())
+            .unwrap_or_default();
+        assert_eq!(help, "Available profiles: competition-field, practice-gym");
+        assert!(ConfigProfile::resolve(&main_dir, "../overlay").is_err());
+    }
+
+    #[test]
+    fn flag_takes_precedence_over_env() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let env = Some("practice-gym".to_owned());
+
+        assert_eq!(
+            requested_profile(args(&["yggdrasil", "--profile", "outdoor"]), env.clone()),
+            Some("outdoor".to_owned())
+        );
+        assert_eq!(
+            requested_profile(args(&["yggdrasil", "--profile=outdoor"]), env.clone()),
+            Some("outdoor".to_owned())
+        );
+        assert_eq!(requested_profile(args(&["yggdrasil"]), env.clone()), env);
+        assert_eq!(requested_profile(args(&["yggdrasil"]), None), None);
+    }
+}
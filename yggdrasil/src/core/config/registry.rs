@@ -0,0 +1,343 @@
+use std::fmt::{self, Display, Write};
+
+use bevy::prelude::*;
+use miette::{IntoDiagnostic, Result, bail, miette};
+use odal::Config;
+use serde::Deserialize;
+
+/// Where the values of a loaded config come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Only the main config was loaded.
+    Main,
+    /// The robot's overlay was applied on top of the main config.
+    Overlay,
+    /// The [`ConfigProfile`](super::ConfigProfile) was applied on top of the main config.
+    Profile,
+    /// The [`ConfigProfile`](super::ConfigProfile) and then the robot's overlay were applied on
+    /// top of the main config.
+    ProfileOverlay,
+    /// No config file exists, so the [`Default`] config is used.
+    Default,
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ConfigSource::Main => "main",
+            ConfigSource::Overlay => "main + overlay",
+            ConfigSource::Profile => "main + profile",
+            ConfigSource::ProfileOverlay => "main + profile + overlay",
+            ConfigSource::Default => "default",
+        };
+
+        f.write_str(name)
+    }
+}
+
+struct RegisteredConfig {
+    name: &'static str,
+    path: &'static str,
+    source: ConfigSource,
+    serialize: fn(&World) -> Option<Result<toml::Value, toml::ser::Error>>,
+    set: fn(&mut World, &str, toml::Value) -> Result<()>,
+}
+
+impl RegisteredConfig {
+    /// Whether `name` refers to this config, by its full name, type name or path.
+    fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.path == name || self.name.rsplit("::").next() == Some(name)
+    }
+}
+
+/// Registry of every config that has been loaded with [`ConfigExt`](super::ConfigExt).
+///
+/// This is used to dump the effective value of all configs at once, see [`dump_all_configs`].
+#[derive(Resource, Default)]
+pub struct ConfigRegistry {
+    configs: Vec<RegisteredConfig>,
+}
+
+impl ConfigRegistry {
+    /// Registers the config `T`, replacing any earlier registration of it.
+    pub fn register<T: Resource + Config>(&mut self, source: ConfigSource) {
+        self.configs.retain(|config| config.name != T::name());
+        self.configs.push(RegisteredConfig {
+            name: T::name(),
+            path: T::PATH,
+            source,
+            serialize: |world| world.get_resource::<T>().map(toml::Value::try_from),
+            set: set_value::<T>,
+        });
+    }
+
+    /// The registered configs, as `(name, path, source)`.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &'static str, ConfigSource)> + '_ {
+        self.configs
+            .iter()
+            .map(|config| (config.name, config.path, config.source))
+    }
+}
+
+/// Sets a single value of a registered config at runtime.
+///
+/// The config is looked up by its name, type name or path, such as `robot_detection.toml`. The
+/// value is set at the dotted `path` within the config, e.g. `tracker.min_iou`, where array
+/// elements are indexed by number. The `value` is parsed as a TOML value, so strings need to be
+/// quoted.
+///
+/// The updated config is validated with [`Config::validate`] and then replaces the config
+/// resource, so systems see the new value in the next run. Configs that are split into
+/// subconfigs, such as the sections of the [`YggdrasilConfig`](super::yggdrasil::YggdrasilConfig),
+/// propagate the new value to their subconfigs with
+/// [`ConfigExt::on_config_changed`](super::ConfigExt::on_config_changed) at the start of the next
+/// cycle.
+///
+/// Like overlays, this cannot change the [`Config::LOCKED_KEYS`] of a config.
+///
+/// # Errors
+///
+/// Returns an error if the config or path does not exist, if the value has a different type
+/// than the current value, if it changes a locked key, or if the updated config is invalid. The
+/// config is left unchanged.
+pub fn set_config_value(world: &mut World, config: &str, path: &str, value: &str) -> Result<()> {
+    let set = world
+        .get_resource::<ConfigRegistry>()
+        .and_then(|registry| {
+            registry
+                .configs
+                .iter()
+                .find(|registered| registered.is_named(config))
+        })
+        .map(|registered| registered.set)
+        .ok_or_else(|| miette!("Unknown config `{config}`"))?;
+
+    let value = toml::Value::deserialize(toml::de::ValueDeserializer::new(value))
+        .map_err(|error| miette!("Failed to parse `{value}` as a TOML value: {error}"))?;
+
+    set(world, path, value)
+}
+
+fn set_value<T: Resource + Config>(
+    world: &mut World,
+    path: &str,
+    value: toml::Value,
+) -> Result<()> {
+    let Some(config) = world.get_resource::<T>() else {
+        bail!("Config `{}` has been removed", T::name());
+    };
+
+    let original = toml::Value::try_from(config).into_diagnostic()?;
+    let mut root = original.clone();
+    let mut current = &mut root;
+    for key in path.split('.') {
+        current = match current {
+            toml::Value::Array(array) => key.parse().ok().and_then(|i: usize| array.get_mut(i)),
+            value => value.get_mut(key),
+        }
+        .ok_or_else(|| miette!("Unknown path `{path}` in `{}`", T::PATH))?;
+    }
+
+    *current = match (&*current, value) {
+        // allow writing `1` instead of `1.0`
+        (toml::Value::Float(_), toml::Value::Integer(integer)) => {
+            toml::Value::Float(integer as f64)
+        }
+        (current, value) if current.same_type(&value) => value,
+        (current, value) => bail!(
+            "Type mismatch for `{path}` in `{}`: expected {}, got {}",
+            T::PATH,
+            current.type_str(),
+            value.type_str()
+        ),
+    };
+
+    // the path may also point to a table that contains locked keys
+    let value_at = |root: &toml::Value, key: &str| {
+        key.split('.')
+            .try_fold(root, |value, key| value.get(key))
+            .cloned()
+    };
+    if let Some(locked) = T::LOCKED_KEYS
+        .iter()
+        .find(|key| value_at(&original, key) != value_at(&root, key))
+    {
+        bail!(
+            "`{locked}` in `{}` is locked and can only be changed in the main config",
+            T::PATH
+        );
+    }
+
+    let config: T = root
+        .try_into()
+        .map_err(|error| miette!("Invalid value for `{path}` in `{}`: {error}", T::PATH))?;
+    config.validate()?;
+
+    world.insert_resource(config);
+    Ok(())
+}
+
+/// Serializes the current value of every registered config to a single TOML document.
+///
+/// Each config is stored in a table named after its file, preceded by a comment with the name of
+/// the config and where its values were loaded from. As the values are taken from the resources,
+/// any changes made at runtime, such as through the control panel, are included.
+#[must_use]
+pub fn dump_all_configs(world: &World) -> String {
+    let mut dump = String::new();
+    if let Some(registry) = world.get_resource::<ConfigRegistry>() {
+        write_configs(&mut dump, world, registry).expect("writing to a string never fails");
+    }
+
+    dump
+}
+
+fn write_configs(dump: &mut String, world: &World, registry: &ConfigRegistry) -> fmt::Result {
+    for config in &registry.configs {
+        writeln!(
+            dump,
+            "# {} ({}, {})",
+            config.name, config.path, config.source
+        )?;
+
+        let value = match (config.serialize)(world) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                writeln!(dump, "# failed to serialize: {error}\n")?;
+                continue;
+            }
+            None => {
+                writeln!(dump, "# resource has been removed\n")?;
+                continue;
+            }
+        };
+
+        let table = toml::Table::from_iter([(config.path.to_string(), value)]);
+        match toml::to_string_pretty(&table) {
+            Ok(serialized) => writeln!(dump, "{serialized}")?,
+            Err(error) => writeln!(dump, "# failed to serialize: {error}\n")?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::core::config::ConfigExt;
+
+    #[derive(Resource, Serialize, Deserialize)]
+    struct WalkConfig {
+        step_height: f32,
+    }
+
+    impl Config for WalkConfig {
+        const PATH: &'static str = "walk.toml";
+    }
+
+    #[derive(Resource, Serialize, Deserialize)]
+    struct KickConfig {
+        power: u32,
+        legs: u32,
+    }
+
+    impl Config for KickConfig {
+        const PATH: &'static str = "kick.toml";
+        const LOCKED_KEYS: &'static [&'static str] = &["legs"];
+
+        fn validate(&self) -> std::result::Result<(), odal::ValidationError> {
+            if self.power > 10 {
+                return Err(odal::ValidationError::new("power must be at most 10"));
+            }
+
+            Ok(())
+        }
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.insert_resource(WalkConfig { step_height: 0.25 });
+        world.insert_resource(KickConfig { power: 3, legs: 2 });
+
+        let mut registry = ConfigRegistry::default();
+        registry.register::<WalkConfig>(ConfigSource::Overlay);
+        registry.register::<KickConfig>(ConfigSource::Main);
+        world.insert_resource(registry);
+
+        world
+    }
+
+    #[test]
+    fn dump_contains_all_configs() {
+        let world = world();
+        let dump = dump_all_configs(&world);
+        assert!(dump.contains("walk.toml, main + overlay)"), "{dump}");
+        assert!(dump.contains("kick.toml, main)"), "{dump}");
+
+        let parsed: toml::Table = dump.parse().expect("dump should be valid toml");
+        assert_eq!(parsed["walk.toml"]["step_height"].as_float(), Some(0.25));
+        assert_eq!(parsed["kick.toml"]["power"].as_integer(), Some(3));
+    }
+
+    #[derive(Resource, Serialize, Deserialize)]
+    struct GaitConfig {
+        swing: SwingConfig,
+    }
+
+    #[derive(Resource, Serialize, Deserialize, Clone)]
+    struct SwingConfig {
+        height: f32,
+    }
+
+    impl Config for GaitConfig {
+        const PATH: &'static str = "gait.toml";
+    }
+
+    fn init_swing(mut commands: Commands, config: Res<GaitConfig>) {
+        commands.insert_resource(config.swing.clone());
+    }
+
+    #[test]
+    fn set_value_propagates_to_subconfigs() {
+        let mut app = App::new();
+        app.insert_resource(GaitConfig {
+            swing: SwingConfig { height: 0.2 },
+        })
+        .init_resource::<ConfigRegistry>()
+        .add_systems(PreStartup, init_swing)
+        .on_config_changed::<GaitConfig, _>(init_swing);
+        app.world_mut()
+            .resource_mut::<ConfigRegistry>()
+            .register::<GaitConfig>(ConfigSource::Main);
+        app.update();
+
+        set_config_value(app.world_mut(), "gait.toml", "swing.height", "0.1").unwrap();
+        app.update();
+        assert!((app.world().resource::<SwingConfig>().height - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn set_value_replaces_config() {
+        let mut world = world();
+
+        set_config_value(&mut world, "walk.toml", "step_height", "0.5").unwrap();
+        set_config_value(&mut world, "KickConfig", "power", "7").unwrap();
+        assert!((world.resource::<WalkConfig>().step_height - 0.5).abs() < 1e-6);
+        assert_eq!(world.resource::<KickConfig>().power, 7);
+
+        for (config, path, value, error) in [
+            ("run.toml", "speed", "1.0", "Unknown config"),
+            ("walk.toml", "step_width", "1.0", "Unknown path"),
+            ("walk.toml", "step_height", "\"high\"", "Type mismatch"),
+            ("kick.toml", "power", "11", "power must be at most 10"),
+            ("kick.toml", "legs", "1", "`legs` in `kick.toml` is locked"),
+        ] {
+            let report = set_config_value(&mut world, config, path, value).unwrap_err();
+            assert!(report.to_string().contains(error), "{report}");
+        }
+        assert_eq!(world.resource::<KickConfig>().power, 7);
+    }
+}
@@ -15,6 +15,8 @@ pub struct ShowtimeConfig {
     /// This field contains mappings from robot ids to player numbers, the
     /// key is a String to make sure default serialization works
     pub robot_numbers_map: HashMap<String, u8>,
+    /// The [`ConfigProfile`](super::ConfigProfile) to run with, if any
+    pub profile: Option<String>,
 }
 
 impl Config for ShowtimeConfig {
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::core::debug::DebugConfig;
 use crate::game_controller::GameControllerConfig;
+use crate::nao::{CycleBudgetConfig, HardwareHealthConfig, LedConfig};
 use crate::prelude::*;
 use crate::sensor::orientation::OrientationFilterConfig;
 use crate::vision::camera::CameraConfig;
@@ -17,8 +19,19 @@ pub struct YggdrasilConfig {
     // TODO: Add this back whenever we have something again
     // pub vision: VisionConfig,
     pub orientation: OrientationFilterConfig,
+    pub cycle: CycleBudgetConfig,
+    pub hardware_health: HardwareHealthConfig,
+    pub leds: LedConfig,
+    pub debug: DebugConfig,
 }
 
 impl Config for YggdrasilConfig {
     const PATH: &'static str = "yggdrasil.toml";
+    // the resolution is structural, as the vision pipeline is tuned for it
+    const LOCKED_KEYS: &'static [&'static str] = &[
+        "camera.top.width",
+        "camera.top.height",
+        "camera.bottom.width",
+        "camera.bottom.height",
+    ];
 }
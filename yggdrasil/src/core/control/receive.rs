@@ -11,6 +11,7 @@ use yggdrasil_rerun_comms::{
 use futures::channel::mpsc::UnboundedReceiver;
 
 use crate::{
+    core::control::transmit::{apply_config_value, send_config_dump},
     game_controller::GameControllerMessageEvent,
     vision::{
         camera::CameraConfig, referee::recognize::RecognizeRefereePose, scan_lines::ScanLinesConfig,
@@ -108,6 +109,7 @@ pub(super) struct ViewerControlMessageEvent(ViewerControlMessage);
 pub(super) struct ViewerGameControllerMessageEvent(ViewerGameControllerMessage);
 
 pub(super) fn handle_viewer_control_message(
+    mut commands: Commands,
     mut message_event: EventReader<ViewerControlMessageEvent>,
     mut debug_enabled_systems: ResMut<DebugEnabledSystems>,
     mut ev_debug_enabled_system_updated: EventWriter<DebugEnabledSystemUpdated>,
@@ -125,6 +127,14 @@ pub(super) fn handle_viewer_control_message(
                 debug_enabled_systems.set_system(system_name.clone(), *enabled);
                 ev_debug_enabled_system_updated.write(DebugEnabledSystemUpdated);
             }
+            ViewerControlMessage::UpdateEnabledDebugCategory { category, enabled } => {
+                debug_enabled_systems.set_category_enabled(category, *enabled);
+                ev_debug_enabled_system_updated.write(DebugEnabledSystemUpdated);
+            }
+            ViewerControlMessage::UpdateDebugVerbosity { verbosity } => {
+                debug_enabled_systems.set_verbosity(*verbosity);
+                ev_debug_enabled_system_updated.write(DebugEnabledSystemUpdated);
+            }
             ViewerControlMessage::CameraExtrinsic {
                 camera_position,
                 extrinsic_rotation: rotation,
@@ -142,6 +152,20 @@ pub(super) fn handle_viewer_control_message(
             ViewerControlMessage::VisualRefereeRecognition => {
                 recognize_pose.write(RecognizeRefereePose);
             }
+            ViewerControlMessage::DumpConfigs => {
+                commands.queue(send_config_dump);
+            }
+            ViewerControlMessage::SetConfigValue {
+                config,
+                path,
+                value,
+            } => {
+                commands.queue(apply_config_value(
+                    config.clone(),
+                    path.clone(),
+                    value.clone(),
+                ));
+            }
             _ => tracing::warn!(?message, "unhandled message"),
         }
     }
@@ -3,18 +3,19 @@ use std::{collections::HashMap, time::Duration};
 use bevy::{ecs::system::SystemId, prelude::*, tasks::IoTaskPool};
 use bifrost::communication::GameControllerMessage;
 use heimdall::CameraPosition;
+use ml::prelude::ModelLoadSummary;
 use yggdrasil_rerun_comms::{
     app::ControlAppHandle,
     debug_system::DebugEnabledSystems,
     protocol::{
         RobotMessage,
-        control::RobotControlMessage,
+        control::{ConfigValueResult, LoadedModel, ModelLoadStatus, RobotControlMessage},
         game_controller::{Player, RobotGameController},
     },
 };
 
 use crate::{
-    core::config::showtime::PlayerConfig,
+    core::config::{dump_all_configs, set_config_value, showtime::PlayerConfig},
     vision::{camera::CameraConfig, scan_lines::ScanLinesConfig},
 };
 
@@ -79,7 +80,7 @@ pub fn send_debug_enabled_systems(
     control_handle: Res<ControlAppHandle>,
 ) {
     let msg = RobotMessage::RobotControlMessage(RobotControlMessage::DebugEnabledSystems(
-        debug_enabled_resources.systems.clone(),
+        debug_enabled_resources.clone(),
     ));
 
     let io = IoTaskPool::get();
@@ -192,6 +193,103 @@ fn send_game_controller_message(
     .detach();
 }
 
+/// Sends the status of the ML models that have been loaded at startup.
+fn send_model_status(
+    control_handle: Res<ControlAppHandle>,
+    summary: Option<Res<ModelLoadSummary>>,
+) {
+    let Some(summary) = summary else {
+        return;
+    };
+
+    let models = summary
+        .models
+        .iter()
+        .map(|model| LoadedModel {
+            path: model.path.to_string(),
+            load_time_ms: u64::try_from(model.load_time.as_millis()).unwrap_or(u64::MAX),
+            status: match &model.error {
+                None => ModelLoadStatus::Live,
+                Some(error) => ModelLoadStatus::Failed(error.clone()),
+            },
+        })
+        .collect();
+
+    let msg = RobotMessage::RobotControlMessage(RobotControlMessage::ModelStatus(models));
+
+    let io = IoTaskPool::get();
+
+    let handle = control_handle.clone();
+    io.spawn(async move {
+        if let Err(error) = handle.broadcast(msg).await {
+            tracing::error!(?error, "Failed to send model status");
+        }
+    })
+    .detach();
+}
+
+/// Sends the current value of all loaded configs to all connected viewers.
+///
+/// This needs access to the whole [`World`], as the configs are looked up through the
+/// [`ConfigRegistry`](crate::core::config::ConfigRegistry).
+pub(super) fn send_config_dump(world: &mut World) {
+    let dump = dump_all_configs(world);
+    broadcast_from_world(world, RobotControlMessage::ConfigDump(dump));
+}
+
+/// Returns a command that sets a config value requested by a viewer, see [`set_config_value`].
+///
+/// The result is sent back to all connected viewers, followed by a new config dump if the value
+/// has been applied.
+pub(super) fn apply_config_value(
+    config: String,
+    path: String,
+    value: String,
+) -> impl FnOnce(&mut World) {
+    move |world| {
+        let result = match set_config_value(world, &config, &path, &value) {
+            Ok(()) => {
+                tracing::info!("Set `{path}` of `{config}` to `{value}`");
+                ConfigValueResult::Applied
+            }
+            Err(report) => {
+                tracing::warn!("Failed to set `{path}` of `{config}`: {report}");
+                ConfigValueResult::Rejected(report.to_string())
+            }
+        };
+
+        let applied = matches!(result, ConfigValueResult::Applied);
+        broadcast_from_world(
+            world,
+            RobotControlMessage::ConfigValueSet {
+                config,
+                path,
+                result,
+            },
+        );
+
+        if applied {
+            send_config_dump(world);
+        }
+    }
+}
+
+fn broadcast_from_world(world: &World, message: RobotControlMessage) {
+    let Some(handle) = world.get_resource::<ControlAppHandle>().cloned() else {
+        return;
+    };
+
+    let msg = RobotMessage::RobotControlMessage(message);
+
+    let io = IoTaskPool::get();
+    io.spawn(async move {
+        if let Err(error) = handle.broadcast(msg).await {
+            tracing::error!(?error, "Failed to send control message");
+        }
+    })
+    .detach();
+}
+
 // This system sends the current [`DebugEnabledSystems`] to all connected
 // clients.
 // When an individual client updates a debug enabled system, the state of
@@ -203,7 +301,7 @@ pub(crate) fn update_debug_systems_for_clients(
 ) {
     for _ev in ev_debug_enabled_system_updated.read() {
         let msg = RobotMessage::RobotControlMessage(RobotControlMessage::DebugEnabledSystems(
-            debug_enabled_resources.systems.clone(),
+            debug_enabled_resources.clone(),
         ));
 
         let io = IoTaskPool::get();
@@ -233,6 +331,7 @@ impl FromWorld for SendRobotInitialDataSystems {
             world.register_system(send_green_chromaticity_threshold),
             world.register_system(send_game_controller_message),
             world.register_system(send_game_controller_player),
+            world.register_system(send_model_status),
         ];
 
         Self { system_ids }
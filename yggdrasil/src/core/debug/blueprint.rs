@@ -0,0 +1,204 @@
+//! The default layout of the rerun viewer, see [`send_default_blueprint`].
+
+use bevy::prelude::*;
+use miette::IntoDiagnostic;
+use rerun::{
+    RecordingStreamBuilder,
+    external::{
+        re_log_types::BlueprintActivationCommand,
+        re_types::blueprint::{
+            archetypes::{ContainerBlueprint, ViewBlueprint, ViewContents, ViewportBlueprint},
+            components::{ContainerKind, RootContainer},
+        },
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::{DebugContext, RerunStream};
+
+/// Configuration of the debugging tools.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DebugConfig {
+    /// Whether to send the default blueprint to viewers that connect to the robot.
+    pub default_blueprint: bool,
+}
+
+/// A part of the blueprint, which is either a container or a view.
+enum Part {
+    Container {
+        kind: ContainerKind,
+        contents: Vec<Part>,
+    },
+    View {
+        class: &'static str,
+        name: &'static str,
+        origin: &'static str,
+        query: &'static [&'static str],
+    },
+}
+
+/// The default layout: the camera images on the left, the field and cycle time on the right.
+fn default_layout() -> Part {
+    let camera = |name, origin, query| Part::View {
+        class: "2D",
+        name,
+        origin,
+        query,
+    };
+
+    Part::Container {
+        kind: ContainerKind::Horizontal,
+        contents: vec![
+            Part::Container {
+                kind: ContainerKind::Vertical,
+                contents: vec![
+                    camera("Top camera", "/top_camera", &["+ $origin/**"]),
+                    camera("Bottom camera", "/bottom_camera", &["+ $origin/**"]),
+                ],
+            },
+            Part::Container {
+                kind: ContainerKind::Vertical,
+                contents: vec![
+                    Part::View {
+                        class: "3D",
+                        name: "Field",
+                        origin: "/",
+                        query: &[
+                            "+ $origin/**",
+                            "- /top_camera/**",
+                            "- /bottom_camera/**",
+                            "- /stats/**",
+                        ],
+                    },
+                    Part::View {
+                        class: "TimeSeries",
+                        name: "Cycle time",
+                        origin: "/stats/cycle_time",
+                        query: &["+ $origin/**"],
+                    },
+                ],
+            },
+        ],
+    }
+}
+
+/// Sends the default blueprint to the viewers that connect to the robot.
+///
+/// The blueprint is only made the default, so viewers that already have a layout for yggdrasil
+/// keep it. Recordings to an rrd file do not get a blueprint, so they keep the layout of whoever
+/// replays them.
+pub(super) fn send_default_blueprint(dbg: DebugContext, config: Res<DebugConfig>) {
+    if !config.default_blueprint || !dbg.is_enabled() || dbg.logging_to_file_sink() {
+        return;
+    }
+
+    if let Err(error) = dbg.stream().send_blueprint(&default_layout()) {
+        tracing::warn!(?error, "Failed to send default rerun blueprint");
+    }
+}
+
+impl RerunStream {
+    /// Sends `layout` as the default blueprint.
+    fn send_blueprint(&self, layout: &Part) -> miette::Result<()> {
+        let (blueprint, storage) = RecordingStreamBuilder::new(super::RECORDING_NAME)
+            .blueprint()
+            .memory()
+            .into_diagnostic()?;
+
+        let mut next_id = 0;
+        let root = log_part(&blueprint, layout, &mut next_id).into_diagnostic()?;
+        blueprint
+            .log(
+                "viewport",
+                &ViewportBlueprint::new()
+                    .with_root_container(RootContainer(root.into()))
+                    .with_auto_layout(false)
+                    .with_auto_views(false),
+            )
+            .into_diagnostic()?;
+
+        let blueprint_id = blueprint
+            .store_info()
+            .ok_or_else(|| miette::miette!("blueprint stream has no store info"))?
+            .store_id;
+
+        self.stream.send_blueprint(
+            storage.take(),
+            BlueprintActivationCommand {
+                blueprint_id,
+                make_active: false,
+                make_default: true,
+            },
+        );
+
+        Ok(())
+    }
+}
+
+/// Logs a part of the blueprint and all of its contents, returns the id of the part.
+fn log_part(
+    blueprint: &rerun::RecordingStream,
+    part: &Part,
+    next_id: &mut u8,
+) -> rerun::RecordingStreamResult<[u8; 16]> {
+    // the ids only need to be unique within the blueprint
+    let id = [*next_id; 16];
+    *next_id += 1;
+
+    match part {
+        Part::Container { kind, contents } => {
+            let contents = contents
+                .iter()
+                .map(|part| {
+                    let id = log_part(blueprint, part, next_id)?;
+                    Ok(entity_path(part, id))
+                })
+                .collect::<rerun::RecordingStreamResult<Vec<_>>>()?;
+
+            blueprint.log(
+                entity_path(part, id),
+                &ContainerBlueprint::new(*kind).with_contents(contents),
+            )?;
+        }
+        Part::View {
+            class,
+            name,
+            origin,
+            query,
+        } => {
+            let path = entity_path(part, id);
+            blueprint.log(
+                path.as_str(),
+                &ViewBlueprint::new(*class)
+                    .with_display_name(*name)
+                    .with_space_origin(*origin),
+            )?;
+            blueprint.log(
+                format!("{path}/ViewContents"),
+                &ViewContents::new(query.iter().copied()),
+            )?;
+        }
+    }
+
+    Ok(id)
+}
+
+/// The entity path of a part of the blueprint, which is how parts refer to each other.
+fn entity_path(part: &Part, id: [u8; 16]) -> String {
+    let kind = match part {
+        Part::Container { .. } => "container",
+        Part::View { .. } => "view",
+    };
+
+    // formatted like a uuid, e.g. `00000000-0000-0000-0000-000000000000`
+    let hex = id.map(|byte| format!("{byte:02x}")).concat();
+    format!(
+        "{kind}/{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
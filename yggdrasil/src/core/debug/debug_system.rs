@@ -4,16 +4,16 @@ use bevy::{
 };
 use yggdrasil_rerun_comms::debug_system::DebugEnabledSystems;
 
+pub use yggdrasil_rerun_comms::debug_system::DebugLevel;
+
 /// Run condition for a specified system (using the system name) based on
-/// the corresponding flag for that systems, stored in the resource
+/// the corresponding flag and verbosity level for that systems, stored in the resource
 /// [`DebugEnabledSystems`]
 fn debug_enabled(system_name: impl ToString) -> impl Condition<()> {
     let name = system_name.to_string();
 
     // Create a system to check the enabled flag for the specified system
-    IntoSystem::into_system(move |enabled: Res<DebugEnabledSystems>| {
-        enabled.systems.get(&name).copied().unwrap_or(false)
-    })
+    IntoSystem::into_system(move |enabled: Res<DebugEnabledSystems>| enabled.is_enabled(&name))
 }
 
 /// Enum describing whether a system will be enabled by default when yggdrasil starts.
@@ -35,15 +35,15 @@ impl From<SystemToggle> for bool {
 /// The trait `DebugAppExt` gives wrapper functions around the bevy `App`.
 ///
 /// Instead of only adding system to the `App`, it also adds:
-/// - Add the system name to the resource [`DebugEnabledSystems`]
+/// - Add the system name, category and [`DebugLevel`] to the resource [`DebugEnabledSystems`]
 /// - Add the run condition `debug_enabled` to the system. This will make it
 ///   that this system will only run when it is flagged as enabled in the
-///   [`DebugEnabledSystems`]
+///   [`DebugEnabledSystems`] and its level does not exceed the verbosity
 ///
 /// # Examples
 ///
 /// ```
-/// # use yggdrasil::core::debug::debug_system::{DebugAppExt, SystemToggle};
+/// # use yggdrasil::core::debug::debug_system::{DebugAppExt, DebugLevel, SystemToggle};
 /// # use yggdrasil::core::audio::wee_sound::wee_sound_system;
 /// use bevy::prelude::*;
 ///
@@ -51,13 +51,21 @@ impl From<SystemToggle> for bool {
 ///
 /// impl Plugin for CustomPlugin {
 ///     fn build(&self, app: &mut App) {
-///         app.add_debug_systems(Update, wee_sound_system, SystemToggle::Enable)
-///             .add_named_debug_systems(
-///                 Update,
-///                 wee_sound_system,
-///                 "Wee the sound",
-///                 SystemToggle::Enable,
-///             );
+///         app.add_debug_systems(
+///             Update,
+///             wee_sound_system,
+///             "audio",
+///             DebugLevel::Debug,
+///             SystemToggle::Enable,
+///         )
+///         .add_named_debug_systems(
+///             Update,
+///             wee_sound_system,
+///             "Wee the sound",
+///             "audio",
+///             DebugLevel::Info,
+///             SystemToggle::Enable,
+///         );
 ///     }
 /// }
 /// ```
@@ -66,21 +74,29 @@ pub trait DebugAppExt {
     /// Add a system to the schedule and add the system name to the
     /// [`DebugEnabledSystems`] resource. The system will only run when the
     /// flag for this system is enabled in the [`DebugEnabledSystems`].
+    ///
+    /// The `category` allows enabling or disabling a group of systems at once.
     fn add_debug_systems<M>(
         &mut self,
         schedule: impl ScheduleLabel,
         systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+        category: impl ToString,
+        level: DebugLevel,
         toggle: SystemToggle,
     ) -> &mut Self;
 
     /// Add a system to the schedule and add the system name to the
     /// [`DebugEnabledSystems`] resource. The system will only run when the
     /// flag for this system is enabled in the [`DebugEnabledSystems`].
+    ///
+    /// The `category` allows enabling or disabling a group of systems at once.
     fn add_named_debug_systems<M>(
         &mut self,
         schedule: impl ScheduleLabel,
         systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
         systems_name: impl ToString,
+        category: impl ToString,
+        level: DebugLevel,
         toggle: SystemToggle,
     ) -> &mut Self;
 }
@@ -90,10 +106,19 @@ impl DebugAppExt for App {
         &mut self,
         schedule: impl ScheduleLabel,
         systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+        category: impl ToString,
+        level: DebugLevel,
         toggle: SystemToggle,
     ) -> &mut Self {
         let system_name = std::any::type_name_of_val(&systems);
-        self.add_named_debug_systems(schedule, systems, system_name.to_string(), toggle)
+        self.add_named_debug_systems(
+            schedule,
+            systems,
+            system_name.to_string(),
+            category,
+            level,
+            toggle,
+        )
     }
 
     fn add_named_debug_systems<M>(
@@ -101,13 +126,18 @@ impl DebugAppExt for App {
         schedule: impl ScheduleLabel,
         systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
         systems_name: impl ToString,
+        category: impl ToString,
+        level: DebugLevel,
         toggle: SystemToggle,
     ) -> &mut Self {
         let world = self.world_mut();
         let mut debug_enabled_systems = world.resource_mut::<DebugEnabledSystems>();
-        debug_enabled_systems
-            .systems
-            .insert(systems_name.to_string(), toggle.into());
+        debug_enabled_systems.insert(
+            systems_name.to_string(),
+            category.to_string(),
+            level,
+            toggle.into(),
+        );
         self.add_systems(schedule, systems.run_if(debug_enabled(systems_name)))
     }
 }
@@ -0,0 +1,163 @@
+//! Export of scalar timelines from a recorded rrd file, for post-game analysis without the viewer.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+use miette::{IntoDiagnostic, Result};
+use rerun::external::re_log_encoding::{VersionPolicy, decoder::Decoder};
+use rerun::log::{Chunk, LogMsg};
+use rerun::{EntityPath, Scalars, TimelineName, components::Scalar};
+
+use super::CYCLE_TIMELINE;
+
+/// Scalar values of a single entity path, keyed by cycle.
+type Timeline = BTreeMap<i64, f64>;
+
+/// Reads the rrd file at `rrd_path` and writes the scalars logged to `entity_paths` on the
+/// `cycle` timeline to `output` as CSV.
+///
+/// The CSV has a `cycle` column followed by one column per entity path, with empty cells for
+/// cycles in which an entity path was not logged. Entity paths that do not contain any scalars
+/// in the recording are skipped, and returned so the caller can report them.
+///
+/// This is exposed through the `sindri export` command.
+pub fn export_scalars_to_csv(
+    rrd_path: impl AsRef<Path>,
+    entity_paths: &[impl AsRef<str>],
+    output: impl Write,
+) -> Result<Vec<EntityPath>> {
+    let entity_paths = entity_paths
+        .iter()
+        .map(|entity_path| EntityPath::from(entity_path.as_ref()))
+        .collect::<Vec<_>>();
+
+    let file = File::open(rrd_path.as_ref()).into_diagnostic()?;
+    let decoder = Decoder::new(VersionPolicy::Warn, BufReader::new(file)).into_diagnostic()?;
+
+    let timeline = TimelineName::new(CYCLE_TIMELINE);
+    let mut timelines: HashMap<EntityPath, Timeline> = HashMap::new();
+
+    for msg in decoder {
+        let LogMsg::ArrowMsg(_, arrow_msg) = msg.into_diagnostic()? else {
+            continue;
+        };
+
+        let chunk = Chunk::from_arrow_msg(&arrow_msg).into_diagnostic()?;
+        if !entity_paths.contains(chunk.entity_path()) {
+            continue;
+        }
+
+        let descriptor = Scalars::descriptor_scalars();
+        let values = chunk
+            .iter_component_indices(&timeline, &descriptor)
+            .zip(chunk.iter_component::<Scalar>(&descriptor));
+
+        let entry = timelines.entry(chunk.entity_path().clone()).or_default();
+        for ((cycle, _), scalars) in values {
+            if let Some(scalar) = scalars.first() {
+                entry.insert(cycle.as_i64(), scalar.0.0);
+            }
+        }
+    }
+
+    let (columns, skipped) = select_columns(&entity_paths, timelines);
+    write_csv(output, &columns)?;
+
+    Ok(skipped)
+}
+
+/// Picks the timelines of the `entity_paths` in order, and returns the entity paths without any
+/// scalars separately.
+fn select_columns(
+    entity_paths: &[EntityPath],
+    mut timelines: HashMap<EntityPath, Timeline>,
+) -> (Vec<(&EntityPath, Timeline)>, Vec<EntityPath>) {
+    let mut columns = Vec::new();
+    let mut skipped = Vec::new();
+
+    for entity_path in entity_paths {
+        match timelines.remove(entity_path).filter(|t| !t.is_empty()) {
+            Some(timeline) => columns.push((entity_path, timeline)),
+            None => skipped.push(entity_path.clone()),
+        }
+    }
+
+    (columns, skipped)
+}
+
+fn write_csv(mut output: impl Write, columns: &[(&EntityPath, Timeline)]) -> Result<()> {
+    write!(output, "{CYCLE_TIMELINE}").into_diagnostic()?;
+    for (entity_path, _) in columns {
+        write!(output, ",{entity_path}").into_diagnostic()?;
+    }
+    writeln!(output).into_diagnostic()?;
+
+    let mut cycles = columns
+        .iter()
+        .flat_map(|(_, timeline)| timeline.keys().copied())
+        .collect::<Vec<_>>();
+    cycles.sort_unstable();
+    cycles.dedup();
+
+    for cycle in cycles {
+        write!(output, "{cycle}").into_diagnostic()?;
+        for (_, timeline) in columns {
+            match timeline.get(&cycle) {
+                Some(value) => write!(output, ",{value}"),
+                None => write!(output, ","),
+            }
+            .into_diagnostic()?;
+        }
+        writeln!(output).into_diagnostic()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entity_paths_without_scalars_are_skipped() {
+        let entity_paths = [
+            EntityPath::from("stats/cycle_time"),
+            EntityPath::from("stats/typo"),
+            EntityPath::from("stats/balls"),
+        ];
+        let timelines = HashMap::from([
+            (entity_paths[0].clone(), Timeline::from([(1, 11.5)])),
+            (entity_paths[2].clone(), Timeline::new()),
+        ]);
+
+        let (columns, skipped) = select_columns(&entity_paths, timelines);
+
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0].0, &entity_paths[0]);
+        assert_eq!(skipped, entity_paths[1..]);
+    }
+
+    #[test]
+    fn missing_cycles_are_empty_cells() {
+        let cycle_time = EntityPath::from("stats/cycle_time");
+        let balls = EntityPath::from("stats/balls");
+
+        let columns = [
+            (
+                &cycle_time,
+                Timeline::from([(1, 11.5), (2, 12.0), (3, 11.0)]),
+            ),
+            (&balls, Timeline::from([(2, 1.0)])),
+        ];
+
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &columns).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "cycle,/stats/cycle_time,/stats/balls\n1,11.5,\n2,12,1\n3,11,\n"
+        );
+    }
+}
@@ -1,4 +1,7 @@
+mod blueprint;
 pub mod debug_system;
+mod export;
+mod scope;
 mod utils;
 
 use bevy::ecs::system::SystemParam;
@@ -13,17 +16,24 @@ use std::convert::Into;
 use std::env;
 use std::f32::consts::PI;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use std::{marker::PhantomData, net::IpAddr};
 use yggdrasil_rerun_comms::debug_system::DebugEnabledSystems;
 
 use crate::nao::{Cycle, CycleTime};
 
+pub use blueprint::DebugConfig;
+pub use export::export_scalars_to_csv;
+pub use scope::CameraScope;
 pub use utils::SerializeComponentBatch;
 
+const RECORDING_NAME: &str = "yggdrasil";
 const DEFAULT_STORAGE_PATH: &str = "/mnt/usb";
 const STORAGE_PATH_ENV_NAME: &str = "RERUN_STORAGE_PATH";
 const DATE_TIME_FORMAT: &str = "%Y_%m_%d-%H_%M_%S";
+const DEBUG_SYSTEMS_FILE_NAME: &str = "yggdrasil_debug_systems";
+const CYCLE_TIMELINE: &str = "cycle";
+const TIME_TIMELINE: &str = "time";
 
 /// Plugin that adds debugging tools for the robot using the [rerun](https://rerun.io) viewer.
 ///
@@ -34,8 +44,45 @@ pub struct DebugPlugin;
 impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DebugEnabledSystems>()
-            .add_systems(Startup, (init_rerun, setup_spl_field).chain())
-            .add_systems(First, sync_cycle_number);
+            .add_systems(
+                Startup,
+                (
+                    init_rerun,
+                    setup_spl_field,
+                    blueprint::send_default_blueprint.run_if(resource_exists::<DebugConfig>),
+                )
+                    .chain(),
+            )
+            .add_systems(First, sync_cycle_number)
+            .add_systems(PostStartup, restore_debug_enabled_systems)
+            .add_systems(
+                Last,
+                persist_debug_enabled_systems.run_if(resource_changed::<DebugEnabledSystems>),
+            );
+    }
+}
+
+/// Path to the file the [`DebugEnabledSystems`] are persisted to, so they survive restarts and
+/// reconnects of the viewer.
+fn debug_systems_path() -> PathBuf {
+    env::temp_dir().join(DEBUG_SYSTEMS_FILE_NAME)
+}
+
+fn restore_debug_enabled_systems(mut debug_enabled_systems: ResMut<DebugEnabledSystems>) {
+    let path = debug_systems_path();
+    if !path.exists() {
+        return;
+    }
+
+    match DebugEnabledSystems::load(&path) {
+        Ok(persisted) => debug_enabled_systems.restore(&persisted),
+        Err(error) => tracing::warn!(?error, "Failed to load persisted debug systems"),
+    }
+}
+
+fn persist_debug_enabled_systems(debug_enabled_systems: Res<DebugEnabledSystems>) {
+    if let Err(error) = debug_enabled_systems.save(debug_systems_path()) {
+        tracing::warn!(?error, "Failed to persist debug systems");
     }
 }
 
@@ -90,10 +137,10 @@ pub fn init_rerun(mut commands: Commands) {
             "Rerun log sink set to file: {}",
             output_rrd_file_path.as_path().display()
         );
-        RerunStream::init_file_sink("yggdrasil", output_rrd_file_path)
+        RerunStream::init_file_sink(RECORDING_NAME, output_rrd_file_path)
             .expect("failed to initialize rerun::RecordingStream")
     } else if let Some(address) = server_address {
-        RerunStream::init_grpc_server("yggdrasil", address)
+        RerunStream::init_grpc_server(RECORDING_NAME, address)
             .expect("failed to initialize rerun::RecordingStream")
     } else {
         tracing::warn!("`RERUN_HOST` not set, rerun debugging is disabled");
@@ -289,9 +336,45 @@ impl RerunStream {
         cycle: Cycle,
         as_components: &AS,
     ) {
-        self.stream.set_time_sequence("cycle", cycle.0 as i64);
+        self.stream
+            .set_time_sequence(CYCLE_TIMELINE, cycle.0 as i64);
         self.log(ent_path, as_components);
-        self.stream.set_time_sequence("cycle", self.cycle.0 as i64);
+        self.stream
+            .set_time_sequence(CYCLE_TIMELINE, self.cycle.0 as i64);
+    }
+
+    /// Log data to Rerun on the wall clock `"time"` timeline.
+    ///
+    /// This allows scrubbing through the data by real time in the viewer, which makes it possible to
+    /// correlate it with events that are not tied to a [`Cycle`], such as audio or network traffic.
+    /// An [`Instant`], such as the one returned by [`Image::timestamp`](crate::vision::camera::Image::timestamp),
+    /// is converted to wall clock time relative to now.
+    ///
+    /// Whether, and in which cycle, the data is also logged on the `"cycle"` timeline is configured
+    /// by `cycle`.
+    pub fn log_with_time<AS: ?Sized + AsComponents>(
+        &self,
+        ent_path: impl Into<EntityPath>,
+        time: impl Into<WallTime>,
+        cycle: CycleTimeline,
+        as_components: &AS,
+    ) {
+        self.stream.set_time(TIME_TIMELINE, time.into().0);
+
+        match cycle {
+            CycleTimeline::Current => {}
+            CycleTimeline::At(cycle) => {
+                self.stream
+                    .set_time_sequence(CYCLE_TIMELINE, cycle.0 as i64);
+            }
+            CycleTimeline::Disabled => self.stream.disable_timeline(CYCLE_TIMELINE),
+        }
+
+        self.log(ent_path, as_components);
+
+        self.stream.disable_timeline(TIME_TIMELINE);
+        self.stream
+            .set_time_sequence(CYCLE_TIMELINE, self.cycle.0 as i64);
     }
 
     /// Lower-level logging API to provide data spanning multiple timepoints.
@@ -314,6 +397,17 @@ impl RerunStream {
         }
     }
 
+    /// Get a handle that logs all data under the entity path of camera `T`.
+    ///
+    /// This avoids repeating [`CameraLocation::make_entity_path`] for every call, see
+    /// [`CameraScope`].
+    ///
+    /// [`CameraLocation::make_entity_path`]: heimdall::CameraLocation::make_entity_path
+    #[must_use]
+    pub fn scoped<T: heimdall::CameraLocation>(&self) -> CameraScope<'_, T> {
+        CameraScope::new(self)
+    }
+
     /// Return whether the [`RerunStream`] is logging to an rrd file.
     #[must_use]
     pub fn logging_to_file_sink(&self) -> bool {
@@ -321,6 +415,60 @@ impl RerunStream {
     }
 }
 
+/// How data logged with [`RerunStream::log_with_time`] is placed on the `"cycle"` timeline.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CycleTimeline {
+    /// Log in the current [`Cycle`].
+    #[default]
+    Current,
+    /// Log in the provided [`Cycle`].
+    At(Cycle),
+    /// Only log on the `"time"` timeline.
+    Disabled,
+}
+
+/// A point in wall clock time, used for the `"time"` timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WallTime(SystemTime);
+
+impl From<SystemTime> for WallTime {
+    fn from(time: SystemTime) -> Self {
+        Self(time)
+    }
+}
+
+impl From<Instant> for WallTime {
+    fn from(instant: Instant) -> Self {
+        Self(SystemTime::now() - instant.elapsed())
+    }
+}
+
+/// Log data to Rerun, only evaluating the data if the [`RerunStream`] is enabled.
+///
+/// Unlike [`RerunStream::log`], the entity path and the archetype expressions are not evaluated at
+/// all when Rerun is disabled, so expensive debug visualizations cost nothing on the robot.
+/// The first argument can be anything that dereferences to a [`RerunStream`], such as a [`DebugContext`].
+///
+/// ```ignore
+/// debug_log!(dbg, "balls", rerun::Points2D::new(expensive_points()));
+/// debug_log!(dbg, "balls", cycle = image.cycle(), rerun::Points2D::new(expensive_points()));
+/// ```
+#[macro_export]
+macro_rules! debug_log {
+    ($dbg:expr, $ent_path:expr, cycle = $cycle:expr, $as_components:expr $(,)?) => {{
+        let stream: &$crate::core::debug::RerunStream = &$dbg;
+        if stream.is_enabled() {
+            stream.log_with_cycle($ent_path, $cycle, &$as_components);
+        }
+    }};
+    ($dbg:expr, $ent_path:expr, $as_components:expr $(,)?) => {{
+        let stream: &$crate::core::debug::RerunStream = &$dbg;
+        if stream.is_enabled() {
+            stream.log($ent_path, &$as_components);
+        }
+    }};
+}
+
 /// Run condition to test whether Rerun is being logged to a [`rerun::sink::FileSink`].
 #[must_use]
 pub fn logging_to_file_sink(dbg: DebugContext) -> bool {
@@ -0,0 +1,81 @@
+use std::marker::PhantomData;
+
+use heimdall::CameraLocation;
+use rerun::AsComponents;
+
+use super::RerunStream;
+use crate::nao::Cycle;
+
+/// A [`RerunStream`] that logs all data under the entity path of camera `T`.
+///
+/// Paths are prefixed using [`CameraLocation::make_entity_path`], or
+/// [`CameraLocation::make_entity_image_path`] after calling [`CameraScope::image`].
+/// Created using [`RerunStream::scoped`].
+///
+/// ```ignore
+/// // logs to `top_camera/image/lines/detected`
+/// dbg.scoped::<Top>()
+///     .image()
+///     .log_with_cycle("lines/detected", cycle, &rerun::LineStrips2D::new(lines));
+/// ```
+pub struct CameraScope<'a, T: CameraLocation> {
+    stream: &'a RerunStream,
+    image: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: CameraLocation> CameraScope<'a, T> {
+    pub(super) fn new(stream: &'a RerunStream) -> Self {
+        Self {
+            stream,
+            image: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Scope the logged data to the image of the camera instead.
+    #[must_use]
+    pub fn image(self) -> Self {
+        Self {
+            image: true,
+            ..self
+        }
+    }
+
+    /// The full entity path for a path relative to this scope.
+    #[must_use]
+    pub fn entity_path(&self, ent_path: impl Into<String>) -> String {
+        if self.image {
+            T::make_entity_image_path(ent_path)
+        } else {
+            T::make_entity_path(ent_path)
+        }
+    }
+
+    /// Log data to Rerun under this scope, see [`RerunStream::log`].
+    pub fn log<AS: ?Sized + AsComponents>(&self, ent_path: impl Into<String>, as_components: &AS) {
+        self.stream.log(self.entity_path(ent_path), as_components);
+    }
+
+    /// Log static data to Rerun under this scope, see [`RerunStream::log_static`].
+    pub fn log_static<AS: ?Sized + AsComponents>(
+        &self,
+        ent_path: impl Into<String>,
+        as_components: &AS,
+    ) {
+        self.stream
+            .log_static(self.entity_path(ent_path), as_components);
+    }
+
+    /// Log data to Rerun under this scope in the provided [`Cycle`], see
+    /// [`RerunStream::log_with_cycle`].
+    pub fn log_with_cycle<AS: ?Sized + AsComponents>(
+        &self,
+        ent_path: impl Into<String>,
+        cycle: Cycle,
+        as_components: &AS,
+    ) {
+        self.stream
+            .log_with_cycle(self.entity_path(ent_path), cycle, as_components);
+    }
+}
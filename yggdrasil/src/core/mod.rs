@@ -4,6 +4,7 @@ pub mod audio;
 pub mod config;
 pub mod control;
 pub mod debug;
+pub mod shutdown;
 
 pub struct CorePlugins;
 
@@ -14,5 +15,6 @@ impl PluginGroup for CorePlugins {
             .add(debug::DebugPlugin)
             .add(audio::AudioPlugin)
             .add(control::ControlPlugin)
+            .add(shutdown::ShutdownPlugin)
     }
 }
@@ -0,0 +1,94 @@
+//! Graceful shutdown of yggdrasil.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bevy::prelude::*;
+
+use crate::prelude::Result;
+
+/// Whether a termination signal has been received.
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Exit code used when the process is killed by a second termination signal.
+const FORCED_EXIT_CODE: i32 = 130;
+
+/// Plugin that shuts down yggdrasil in an orderly fashion.
+///
+/// A handler for `SIGINT` and `SIGTERM` is installed that exits the [`App`]. When the app exits,
+/// for any reason, the hooks registered with [`ShutdownAppExt::add_shutdown_hook`] are run in
+/// reverse order of registration, so hardware handles such as the cameras and the `LoLA` socket
+/// are released cleanly.
+///
+/// A second termination signal exits the process immediately.
+pub struct ShutdownPlugin;
+
+impl Plugin for ShutdownPlugin {
+    fn build(&self, app: &mut App) {
+        let handler = ctrlc::try_set_handler(|| {
+            if SHUTDOWN_REQUESTED.swap(true, Ordering::SeqCst) {
+                tracing::warn!("Received second termination signal, exiting immediately");
+                std::process::exit(FORCED_EXIT_CODE);
+            }
+
+            tracing::info!("Received termination signal, shutting down");
+        });
+
+        if let Err(error) = handler {
+            tracing::error!(?error, "Failed to install termination signal handler");
+        }
+
+        app.init_resource::<ShutdownHooks>()
+            .add_systems(First, exit_on_signal)
+            .add_systems(Last, run_shutdown_hooks.run_if(on_event::<AppExit>));
+    }
+}
+
+type ShutdownHook = Box<dyn FnOnce(&mut World) -> Result<()> + Send + Sync>;
+
+/// The registered shutdown hooks, in order of registration.
+#[derive(Resource, Default)]
+pub struct ShutdownHooks(Vec<(&'static str, ShutdownHook)>);
+
+/// Extension trait for registering shutdown hooks on an [`App`].
+pub trait ShutdownAppExt {
+    /// Register a hook that is run when the app exits.
+    ///
+    /// Hooks are run in reverse order of registration, so a plugin should register its hook when
+    /// it initializes the resources that need to be released.
+    fn add_shutdown_hook(
+        &mut self,
+        name: &'static str,
+        hook: impl FnOnce(&mut World) -> Result<()> + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl ShutdownAppExt for App {
+    fn add_shutdown_hook(
+        &mut self,
+        name: &'static str,
+        hook: impl FnOnce(&mut World) -> Result<()> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<ShutdownHooks>()
+            .0
+            .push((name, Box::new(hook)));
+        self
+    }
+}
+
+fn exit_on_signal(mut exit: EventWriter<AppExit>) {
+    if SHUTDOWN_REQUESTED.load(Ordering::SeqCst) {
+        exit.write(AppExit::Success);
+    }
+}
+
+fn run_shutdown_hooks(world: &mut World) {
+    let hooks = std::mem::take(&mut world.resource_mut::<ShutdownHooks>().0);
+
+    for (name, hook) in hooks.into_iter().rev() {
+        match hook(world) {
+            Ok(()) => tracing::info!("Shut down {name}"),
+            Err(error) => tracing::error!("Failed to shut down {name}: {error:?}"),
+        }
+    }
+}
@@ -1,12 +1,13 @@
 pub mod penalty;
 mod receive;
+pub mod transition;
 mod transmit;
 
 use std::{
     net::{Ipv4Addr, SocketAddr},
     str::FromStr,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use async_std::{
@@ -25,8 +26,11 @@ use penalty::PenaltyStatePlugin;
 use receive::{GameControllerReceiver, handle_messages, receive_loop};
 use serde::{Deserialize, Serialize};
 use serde_with::{DurationMilliSeconds, serde_as};
+use transition::GameTransitionPlugin;
 use transmit::{GameControllerSender, send_loop, send_message};
 
+use crate::nao::Clock;
+
 pub use receive::GameControllerMessageEvent;
 
 /// This module handles the communication with the game controller.
@@ -41,6 +45,9 @@ pub use receive::GameControllerMessageEvent;
 ///
 /// This module provides the following events to the application:
 /// - [`GameControllerMessageEvent`]
+/// - [`GameStateChanged`](transition::GameStateChanged)
+/// - [`SetPlayChanged`](transition::SetPlayChanged)
+/// - [`PenaltyChanged`](penalty::PenaltyChanged)
 ///
 /// This module provides the following resources to the application:
 /// - [`GameControllerConfig`]
@@ -49,7 +56,7 @@ pub struct GameControllerPlugin;
 
 impl Plugin for GameControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(PenaltyStatePlugin)
+        app.add_plugins((PenaltyStatePlugin, GameTransitionPlugin))
             .add_event::<GameControllerMessageEvent>()
             .add_systems(Startup, setup)
             .add_systems(PreUpdate, handle_messages)
@@ -79,30 +86,29 @@ pub struct GameControllerConfig {
     pub game_controller_return_delay: Duration,
 }
 
+/// The connection with the game controller, which only exists while it sends messages.
 #[derive(Resource)]
-struct GameControllerConnection {
+pub(crate) struct GameControllerConnection {
     address: SocketAddr,
-    timer: Timer,
+    timeout: Duration,
+    last_message: Instant,
 }
 
 impl GameControllerConnection {
-    pub fn new(address: SocketAddr, timeout: Duration) -> Self {
+    pub fn new(address: SocketAddr, timeout: Duration, now: Instant) -> Self {
         Self {
             address,
-            timer: Timer::new(timeout, TimerMode::Once),
+            timeout,
+            last_message: now,
         }
     }
 
-    pub fn tick(&mut self, delta: Duration) {
-        self.timer.tick(delta);
-    }
-
-    pub fn reset_timeout(&mut self) {
-        self.timer.reset();
+    pub fn reset_timeout(&mut self, now: Instant) {
+        self.last_message = now;
     }
 
-    pub fn timed_out(&self) -> bool {
-        self.timer.finished()
+    pub fn timed_out(&self, clock: &Clock) -> bool {
+        clock.elapsed(self.last_message) >= self.timeout
     }
 }
 
@@ -3,17 +3,20 @@ use std::time::{Duration, Instant};
 use bevy::prelude::*;
 use bifrost::communication::{GameControllerMessage, Penalty};
 
-use crate::core::config::showtime::PlayerConfig;
+use crate::{core::config::showtime::PlayerConfig, nao::Clock};
 
 use super::receive::handle_messages;
 
 /// Plugin responsible for tracking the penalized state of the robot. With the [`PenaltyState`] resource,
 /// you can check if the robot is penalized, the type of penalty, and if it just entered or left a penalty.
+///
+/// A [`PenaltyChanged`] event is emitted whenever the penalty of the robot changes.
 pub struct PenaltyStatePlugin;
 
 impl Plugin for PenaltyStatePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PenaltyState>()
+            .add_event::<PenaltyChanged>()
             .add_systems(PreUpdate, update_penalty_state.after(handle_messages));
     }
 }
@@ -21,9 +24,9 @@ impl Plugin for PenaltyStatePlugin {
 /// Returns true if the robot became unpenalized less than the given duration ago
 pub fn elapsed_since_penalty_return_less_than(
     duration: Duration,
-) -> impl Fn(Res<PenaltyState>) -> bool {
-    move |penalty: Res<PenaltyState>| {
-        matches!(penalty.current, Penalty::None) && penalty.duration_since_return() < duration
+) -> impl Fn(Res<PenaltyState>, Res<Clock>) -> bool {
+    move |penalty: Res<PenaltyState>, clock: Res<Clock>| {
+        matches!(penalty.current, Penalty::None) && penalty.duration_since_return(&clock) < duration
     }
 }
 
@@ -44,16 +47,32 @@ fn get_penalty(
     .unwrap_or(Penalty::None)
 }
 
+/// The [`Penalty`] of the robot changed.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct PenaltyChanged {
+    pub from: Penalty,
+    pub to: Penalty,
+}
+
 fn update_penalty_state(
     mut penalty: ResMut<PenaltyState>,
     gcm: Option<Res<GameControllerMessage>>,
     player_config: Res<PlayerConfig>,
+    mut penalty_changed: EventWriter<PenaltyChanged>,
+    clock: Res<Clock>,
 ) {
     penalty.previous = penalty.current;
     penalty.current = get_penalty(gcm, player_config);
 
+    if penalty.previous != penalty.current {
+        penalty_changed.write(PenaltyChanged {
+            from: penalty.previous,
+            to: penalty.current,
+        });
+    }
+
     if penalty.left_penalty() {
-        penalty.last_return = Some(Instant::now());
+        penalty.last_return = Some(clock.now());
     }
 }
 
@@ -106,9 +125,96 @@ impl PenaltyState {
 
     /// Duration since the robot has returned from its last penalty
     #[must_use]
-    pub fn duration_since_return(&self) -> Duration {
-        self.last_return.map_or(Duration::MAX, |last_return| {
-            Instant::now().duration_since(last_return)
+    pub fn duration_since_return(&self, clock: &Clock) -> Duration {
+        self.last_return
+            .map_or(Duration::MAX, |last_return| clock.elapsed(last_return))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bifrost::communication::TeamInfo;
+
+    use super::*;
+
+    const TEAM_NUMBER: u8 = 8;
+    const PLAYER_NUMBER: u8 = 2;
+
+    /// A message in which the players of our team have the given penalties.
+    fn message(penalties: &[(u8, Penalty)]) -> GameControllerMessage {
+        let mut team = TeamInfo::invisible();
+        team.team_number = TEAM_NUMBER;
+        for &(player_number, penalty) in penalties {
+            team.players[player_number as usize - 1].penalty = penalty;
+        }
+
+        GameControllerMessage {
+            teams: [team, TeamInfo::invisible()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn emits_each_penalty_change_once() {
+        let mut app = App::new();
+        app.insert_resource(PlayerConfig {
+            player_number: PLAYER_NUMBER,
+            team_number: TEAM_NUMBER,
         })
+        .insert_resource(Clock::simulated(Duration::from_millis(12)))
+        .init_resource::<PenaltyState>()
+        .add_event::<PenaltyChanged>()
+        .add_systems(Update, update_penalty_state);
+
+        let cycles = [
+            None,
+            Some(message(&[])),
+            Some(message(&[(PLAYER_NUMBER, Penalty::PlayerPushing)])),
+            Some(message(&[(PLAYER_NUMBER, Penalty::PlayerPushing)])),
+            // penalties of teammates do not concern this robot
+            Some(message(&[
+                (PLAYER_NUMBER, Penalty::PlayerPushing),
+                (PLAYER_NUMBER + 1, Penalty::IllegalPosition),
+            ])),
+            Some(message(&[(PLAYER_NUMBER, Penalty::RequestForPickup)])),
+            Some(message(&[])),
+            Some(message(&[])),
+        ];
+
+        let mut changes = Vec::new();
+        for message in cycles {
+            if let Some(message) = message {
+                app.insert_resource(message);
+            }
+            app.update();
+
+            changes.extend(
+                app.world_mut()
+                    .resource_mut::<Events<PenaltyChanged>>()
+                    .drain(),
+            );
+        }
+
+        assert_eq!(
+            changes,
+            [
+                PenaltyChanged {
+                    from: Penalty::None,
+                    to: Penalty::PlayerPushing,
+                },
+                PenaltyChanged {
+                    from: Penalty::PlayerPushing,
+                    to: Penalty::RequestForPickup,
+                },
+                PenaltyChanged {
+                    from: Penalty::RequestForPickup,
+                    to: Penalty::None,
+                },
+            ]
+        );
+
+        let penalty = app.world().resource::<PenaltyState>();
+        assert!(!penalty.is_penalized());
+        assert!(penalty.last_return.is_some());
     }
 }
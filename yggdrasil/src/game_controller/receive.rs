@@ -5,6 +5,7 @@ use bifrost::{communication::GameControllerMessage, serialization::Decode};
 use futures::channel::mpsc::{self, UnboundedSender};
 
 use super::{GameControllerConfig, GameControllerConnection, GameControllerSocket};
+use crate::nao::Clock;
 
 /// A new incoming [`GameControllerMessage`].
 ///
@@ -63,16 +64,13 @@ pub fn handle_messages(
     mut commands: Commands,
     mut receiver: ResMut<GameControllerReceiver>,
     mut connection: Option<ResMut<GameControllerConnection>>,
-    time: Res<Time>,
+    clock: Res<Clock>,
     mut ev_message: EventWriter<GameControllerMessageEvent>,
     cfg: Res<GameControllerConfig>,
 ) {
     if let Some(conn) = &mut connection {
-        // Tick the connection timeout
-        conn.tick(time.delta());
-
-        // And remove if it timed out
-        if conn.timed_out() {
+        // Remove the connection if it timed out
+        if conn.timed_out(&clock) {
             tracing::info!("Lost gamecontroller connection with {}", conn.address);
             commands.remove_resource::<GameControllerConnection>();
         }
@@ -87,7 +85,7 @@ pub fn handle_messages(
         match connection.as_mut() {
             // If we already have a connection, reset the timeout
             Some(con) if con.address == address => {
-                con.reset_timeout();
+                con.reset_timeout(clock.now());
                 ev_message.write(GameControllerMessageEvent(message));
             }
             // If we have a connection, but the message is from a different address, ignore
@@ -102,6 +100,7 @@ pub fn handle_messages(
                 commands.insert_resource(GameControllerConnection::new(
                     address,
                     cfg.game_controller_timeout,
+                    clock.now(),
                 ));
                 tracing::info!("Established gamecontroller connection with {}", address);
                 ev_message.write(GameControllerMessageEvent(message));
@@ -109,3 +108,44 @@ pub fn handle_messages(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn connection_times_out_in_simulated_time() {
+        let timeout = Duration::from_millis(100);
+        let clock = Clock::simulated(Duration::from_millis(12));
+        let (_tx, rx) = mpsc::unbounded();
+
+        let mut app = App::new();
+        app.add_event::<GameControllerMessageEvent>()
+            .insert_resource(clock)
+            .insert_resource(GameControllerReceiver { rx })
+            .insert_resource(GameControllerConfig {
+                game_controller_timeout: timeout,
+                game_controller_return_delay: Duration::from_millis(500),
+            })
+            .insert_resource(GameControllerConnection::new(
+                ([10, 0, 0, 1], 3838).into(),
+                timeout,
+                clock.now(),
+            ))
+            .add_systems(Update, handle_messages);
+
+        // the timeout is reached after 9 cycles of 12ms, however long the cycles take
+        for cycle in 1..=9 {
+            app.world_mut().resource_mut::<Clock>().step();
+            app.update();
+
+            assert_eq!(
+                app.world().contains_resource::<GameControllerConnection>(),
+                cycle < 9,
+                "unexpected connection state in cycle {cycle}"
+            );
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bifrost::communication::{GameState, SetPlay};
+
+use super::receive::{GameControllerMessageEvent, handle_messages};
+
+/// Plugin that emits events when the game state or set play changes between received
+/// [`GameControllerMessage`](bifrost::communication::GameControllerMessage)s.
+///
+/// The first received message only sets the baseline, so no events are emitted for it.
+pub struct GameTransitionPlugin;
+
+impl Plugin for GameTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GameStateChanged>()
+            .add_event::<SetPlayChanged>()
+            .add_systems(PreUpdate, emit_transitions.after(handle_messages));
+    }
+}
+
+/// The [`GameState`] in the game controller message changed.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct GameStateChanged {
+    pub from: GameState,
+    pub to: GameState,
+}
+
+/// The [`SetPlay`] in the game controller message changed.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct SetPlayChanged {
+    pub from: SetPlay,
+    pub to: SetPlay,
+}
+
+fn emit_transitions(
+    mut messages: EventReader<GameControllerMessageEvent>,
+    mut previous: Local<Option<(GameState, SetPlay)>>,
+    mut game_state_changed: EventWriter<GameStateChanged>,
+    mut set_play_changed: EventWriter<SetPlayChanged>,
+) {
+    for message in messages.read() {
+        let current = (message.state, message.set_play);
+
+        if let Some((state, set_play)) = previous.replace(current) {
+            if state != message.state {
+                game_state_changed.write(GameStateChanged {
+                    from: state,
+                    to: message.state,
+                });
+            }
+
+            if set_play != message.set_play {
+                set_play_changed.write(SetPlayChanged {
+                    from: set_play,
+                    to: message.set_play,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bifrost::communication::GameControllerMessage;
+
+    use super::*;
+
+    fn message(state: GameState, set_play: SetPlay) -> GameControllerMessageEvent {
+        GameControllerMessageEvent(GameControllerMessage {
+            state,
+            set_play,
+            ..Default::default()
+        })
+    }
+
+    fn drain<E: Event + Clone>(app: &mut App) -> Vec<E> {
+        app.world_mut()
+            .resource_mut::<Events<E>>()
+            .drain()
+            .collect()
+    }
+
+    #[test]
+    fn emits_each_transition_once() {
+        let mut app = App::new();
+        app.add_event::<GameControllerMessageEvent>()
+            .add_event::<GameStateChanged>()
+            .add_event::<SetPlayChanged>()
+            .add_systems(Update, emit_transitions);
+
+        let cycles = [
+            vec![message(GameState::Initial, SetPlay::None)],
+            vec![message(GameState::Ready, SetPlay::None)],
+            vec![
+                message(GameState::Ready, SetPlay::None),
+                message(GameState::Set, SetPlay::None),
+            ],
+            vec![],
+            vec![message(GameState::Playing, SetPlay::None)],
+            vec![message(GameState::Playing, SetPlay::CornerKick)],
+            vec![message(GameState::Playing, SetPlay::CornerKick)],
+            vec![message(GameState::Playing, SetPlay::None)],
+        ];
+
+        let mut game_states = Vec::new();
+        let mut set_plays = Vec::new();
+
+        for messages in cycles {
+            app.world_mut().send_event_batch(messages);
+            app.update();
+
+            game_states.extend(drain::<GameStateChanged>(&mut app));
+            set_plays.extend(drain::<SetPlayChanged>(&mut app));
+        }
+
+        assert_eq!(
+            game_states,
+            [
+                GameStateChanged {
+                    from: GameState::Initial,
+                    to: GameState::Ready,
+                },
+                GameStateChanged {
+                    from: GameState::Ready,
+                    to: GameState::Set,
+                },
+                GameStateChanged {
+                    from: GameState::Set,
+                    to: GameState::Playing,
+                },
+            ]
+        );
+        assert_eq!(
+            set_plays,
+            [
+                SetPlayChanged {
+                    from: SetPlay::None,
+                    to: SetPlay::CornerKick,
+                },
+                SetPlayChanged {
+                    from: SetPlay::CornerKick,
+                    to: SetPlay::None,
+                },
+            ]
+        );
+    }
+}
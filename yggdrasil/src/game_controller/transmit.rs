@@ -9,8 +9,8 @@ use bifrost::{
 use futures::channel::mpsc::{self};
 
 use crate::{
-    core::config::showtime::PlayerConfig, localization::RobotPose, sensor::falling::FallState,
-    vision::ball_detection::hypothesis::Ball,
+    core::config::showtime::PlayerConfig, localization::RobotPose, nao::Clock,
+    sensor::falling::FallState, vision::ball_detection::hypothesis::Ball,
 };
 
 use super::{GameControllerConfig, GameControllerConnection, GameControllerSocket};
@@ -64,7 +64,7 @@ pub fn send_message(
         Res<GameControllerConnection>,
         Res<GameControllerConfig>,
     ),
-    time: Res<Time>,
+    (time, clock): (Res<Time>, Res<Clock>),
     mut delay: Local<GameControllerReturnDelay>,
 ) {
     delay.tick(time.delta());
@@ -73,7 +73,7 @@ pub fn send_message(
         return;
     }
 
-    let (ball_age, ball_pos) = balls_to_game_controller_ball(&ball);
+    let (ball_age, ball_pos) = balls_to_game_controller_ball(&ball, &clock);
     let return_message = GameControllerReturnMessage::new(
         player_config.player_number,
         player_config.team_number,
@@ -102,13 +102,13 @@ fn robot_pose_to_game_controller_pose(robot_pose: &RobotPose) -> [f32; 3] {
     ]
 }
 
-fn balls_to_game_controller_ball(ball: &Ball) -> (f32, [f32; 2]) {
+fn balls_to_game_controller_ball(ball: &Ball, clock: &Clock) -> (f32, [f32; 2]) {
     let Ball::Some(ref ball) = *ball else {
         return NO_BALL_DETECTED_DATA;
     };
 
     (
-        ball.last_update.elapsed().as_secs_f32(),
+        clock.elapsed(ball.last_update).as_secs_f32(),
         [
             ball.position.x * MILLIMETERS_PER_METER,
             ball.position.y * MILLIMETERS_PER_METER,
@@ -1,14 +1,108 @@
-use nalgebra::{Isometry3, Matrix3x1, Vector3};
+use nalgebra::{Isometry3, Matrix3x1, UnitQuaternion, Vector3};
 use nidhogg::types::{LeftLegJoints, RightLegJoints};
-use std::f32::consts::PI;
+use spatial::{Space, types};
+use std::f32::consts::{FRAC_PI_4, PI};
 
 use crate::motion::walking_engine::feet::FootPositions;
 
 use super::{
-    dimensions,
-    spaces::{Left, Right},
+    FootKinematics, dimensions,
+    forward::Kinematics,
+    spaces::{Foot, Left, Right, Robot},
 };
 
+/// Joint angles of a single leg, as computed by [`Kinematics::leg_ik`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SingleLegJoints {
+    pub hip_yaw_pitch: f32,
+    pub hip_roll: f32,
+    pub hip_pitch: f32,
+    pub knee_pitch: f32,
+    pub ankle_pitch: f32,
+    pub ankle_roll: f32,
+}
+
+/// A side of the robot, with the properties of its leg that are used by [`Kinematics::leg_ik`].
+pub trait LegSide: FootKinematics {
+    /// Rotation around the x-axis that aligns the hip yaw-pitch axis of the leg with the z-axis.
+    const HIP_ALIGNMENT: f32;
+    /// Sign of the hip yaw-pitch joint, relative to a yaw in the aligned hip frame.
+    const HIP_YAW_PITCH_SIGN: f32;
+}
+
+impl LegSide for Left {
+    const HIP_ALIGNMENT: f32 = -FRAC_PI_4;
+    const HIP_YAW_PITCH_SIGN: f32 = -1.0;
+}
+
+impl LegSide for Right {
+    const HIP_ALIGNMENT: f32 = FRAC_PI_4;
+    const HIP_YAW_PITCH_SIGN: f32 = 1.0;
+}
+
+impl Kinematics {
+    /// Compute the joint angles of the leg on side `T` that place its foot at `foot_target`.
+    ///
+    /// The target is the pose of the foot (ankle roll) frame relative to the robot frame, as
+    /// returned by e.g. `kinematics.isometry::<LeftFoot, Robot>()`.
+    ///
+    /// The NAO has a single hip yaw-pitch joint that drives both legs, so the returned
+    /// `hip_yaw_pitch` also determines the pelvis of the other leg.
+    ///
+    /// Returns [`None`] if the target is out of reach of the leg.
+    #[must_use]
+    pub fn leg_ik<T>(foot_target: types::Isometry3<Foot<T>, Robot>) -> Option<SingleLegJoints>
+    where
+        T: LegSide,
+        Foot<T>: Space,
+    {
+        let thigh = dimensions::HIP_TO_KNEE.z.abs();
+        let tibia = dimensions::KNEE_TO_ANKLE.z.abs();
+
+        // rotate the pelvis frame such that the hip yaw-pitch axis is aligned with the z-axis,
+        // which turns the hip into a regular yaw, roll, pitch chain
+        let roll_offset = T::HIP_ALIGNMENT;
+        let alignment = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), roll_offset);
+        let foot_to_hip = alignment * T::robot_to_pelvis().inverse() * foot_target.inner;
+
+        // the position of the hip in the foot frame only depends on the knee and ankle
+        let hip_in_foot = foot_to_hip.inverse().translation.vector;
+        let distance = hip_in_foot.norm();
+
+        let knee_cos = (distance.powi(2) - thigh.powi(2) - tibia.powi(2)) / (2.0 * thigh * tibia);
+        if !(-1.0..=1.0).contains(&knee_cos) {
+            return None;
+        }
+        let knee_pitch = knee_cos.acos();
+
+        let ankle_roll = hip_in_foot.y.atan2(hip_in_foot.z);
+        let hip_in_ankle_x = -thigh * knee_pitch.sin();
+        let hip_in_ankle_z = tibia + thigh * knee_pitch.cos();
+        let ankle_pitch = hip_in_ankle_x.atan2(hip_in_ankle_z)
+            - hip_in_foot.x.atan2(hip_in_foot.y.hypot(hip_in_foot.z));
+
+        // the remaining rotation is a yaw, roll, pitch rotation in the aligned hip frame
+        let hip_rotation = (foot_to_hip.rotation
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), -ankle_roll)
+            * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), -(knee_pitch + ankle_pitch)))
+        .to_rotation_matrix();
+        let m = hip_rotation.matrix();
+
+        let yaw = (-m[(0, 1)]).atan2(m[(1, 1)]);
+        let roll = m[(2, 1)].clamp(-1.0, 1.0).asin();
+        let pitch = (-m[(2, 0)]).atan2(m[(2, 2)]);
+
+        Some(SingleLegJoints {
+            hip_yaw_pitch: T::HIP_YAW_PITCH_SIGN * yaw,
+            hip_roll: roll - roll_offset,
+            hip_pitch: pitch,
+            knee_pitch,
+            ankle_pitch,
+            ankle_roll,
+        })
+    }
+}
+
 /// Compute the leg angles for the given foot positions.
 ///
 /// The foot positions are relative to the robot's torso, and the angles are relative to the robot's
@@ -130,3 +224,94 @@ fn compute_joint_angles(
         foot_rotation_c2,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nidhogg::types::JointArray;
+
+    use super::*;
+    use crate::kinematics::spaces::{LeftFoot, RightFoot};
+
+    const TOLERANCE: f32 = 1e-4;
+
+    fn assert_pose_eq(expected: &Isometry3<f32>, actual: &Isometry3<f32>) {
+        let translation = (expected.translation.vector - actual.translation.vector).norm();
+        let rotation = expected.rotation.angle_to(&actual.rotation);
+
+        assert!(
+            translation < TOLERANCE && rotation < TOLERANCE,
+            "poses do not match: {expected} vs {actual}"
+        );
+    }
+
+    fn poses() -> Vec<JointArray<f32>> {
+        [
+            (0.0, 0.0, -0.4, 0.9, -0.5, 0.0),
+            (-0.2, 0.1, -0.6, 1.2, -0.4, -0.1),
+            (0.3, -0.15, -0.2, 0.4, -0.3, 0.12),
+            (-0.5, 0.3, 0.2, 1.8, -1.0, -0.2),
+        ]
+        .into_iter()
+        .map(
+            |(hip_yaw_pitch, roll, hip_pitch, knee, ankle_pitch, ankle_roll)| JointArray {
+                left_hip_yaw_pitch: hip_yaw_pitch,
+                left_hip_roll: roll,
+                left_hip_pitch: hip_pitch,
+                left_knee_pitch: knee,
+                left_ankle_pitch: ankle_pitch,
+                left_ankle_roll: ankle_roll,
+                right_hip_roll: -roll,
+                right_hip_pitch: hip_pitch,
+                right_knee_pitch: knee,
+                right_ankle_pitch: ankle_pitch,
+                right_ankle_roll: -ankle_roll,
+                ..Default::default()
+            },
+        )
+        .collect()
+    }
+
+    #[test]
+    fn leg_ik_round_trip() {
+        for joints in poses() {
+            let kinematics = Kinematics::from(&joints);
+
+            let left = kinematics.isometry::<LeftFoot, Robot>();
+            let angles = Kinematics::leg_ik(left).expect("target should be reachable");
+            let solved = Kinematics::from(&JointArray {
+                left_hip_yaw_pitch: angles.hip_yaw_pitch,
+                left_hip_roll: angles.hip_roll,
+                left_hip_pitch: angles.hip_pitch,
+                left_knee_pitch: angles.knee_pitch,
+                left_ankle_pitch: angles.ankle_pitch,
+                left_ankle_roll: angles.ankle_roll,
+                ..Default::default()
+            });
+            assert_pose_eq(&left.inner, &solved.isometry::<LeftFoot, Robot>().inner);
+
+            let right = kinematics.isometry::<RightFoot, Robot>();
+            let angles = Kinematics::leg_ik(right).expect("target should be reachable");
+            let solved = Kinematics::from(&JointArray {
+                left_hip_yaw_pitch: angles.hip_yaw_pitch,
+                right_hip_roll: angles.hip_roll,
+                right_hip_pitch: angles.hip_pitch,
+                right_knee_pitch: angles.knee_pitch,
+                right_ankle_pitch: angles.ankle_pitch,
+                right_ankle_roll: angles.ankle_roll,
+                ..Default::default()
+            });
+            assert_pose_eq(&right.inner, &solved.isometry::<RightFoot, Robot>().inner);
+        }
+    }
+
+    #[test]
+    fn leg_ik_unreachable() {
+        let too_far =
+            types::Isometry3::<LeftFoot, Robot>::new(Isometry3::translation(0.0, 0.05, -0.3));
+        assert_eq!(Kinematics::leg_ik(too_far), None);
+
+        let too_far =
+            types::Isometry3::<RightFoot, Robot>::new(Isometry3::translation(0.25, -0.05, -0.1));
+        assert_eq!(Kinematics::leg_ik(too_far), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! The geometric Jacobian of the kinematic chains of the robot.
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use nalgebra as na;
+
+use super::forward::Kinematics;
+use super::spaces::{
+    Head, LeftAnkle, LeftElbow, LeftFoot, LeftForearm, LeftHip, LeftPelvis, LeftShoulder, LeftSole,
+    LeftThigh, LeftTibia, LeftUpperArm, LeftWrist, Neck, RightAnkle, RightElbow, RightFoot,
+    RightForearm, RightHip, RightPelvis, RightShoulder, RightSole, RightThigh, RightTibia,
+    RightUpperArm, RightWrist, Robot,
+};
+
+/// A joint of a kinematic chain, expressed in the robot frame.
+#[derive(Debug, Clone, Copy)]
+pub struct Joint {
+    /// The frame that is rotated by the joint, relative to the robot frame.
+    ///
+    /// The origin of this frame is the center of rotation of the joint.
+    pub frame: na::Isometry3<f32>,
+    /// The rotation axis of the joint, in `frame`.
+    pub axis: na::Vector3<f32>,
+}
+
+/// The end-effector of a kinematic chain with `N` joints, starting at the robot frame.
+pub trait EndEffector<const N: usize> {
+    /// The joints of the chain, ordered from the robot frame towards the end-effector.
+    fn joints(kinematics: &Kinematics) -> [Joint; N];
+
+    /// The pose of the end-effector, relative to the robot frame.
+    fn pose(kinematics: &Kinematics) -> na::Isometry3<f32>;
+}
+
+impl Kinematics {
+    /// Compute the geometric Jacobian of end-effector `E` with respect to the joints of its chain.
+    ///
+    /// The first three rows map joint velocities to the linear velocity of the end-effector, the
+    /// last three rows to its angular velocity, both expressed in the robot frame.
+    /// The columns are ordered the same as [`EndEffector::joints`].
+    ///
+    /// ```ignore
+    /// let jacobian = kinematics.jacobian::<LeftSole, 6>();
+    /// ```
+    #[must_use]
+    pub fn jacobian<E, const N: usize>(&self) -> na::SMatrix<f32, 6, N>
+    where
+        E: EndEffector<N>,
+    {
+        let end_effector = E::pose(self).translation.vector;
+
+        let mut jacobian = na::SMatrix::<f32, 6, N>::zeros();
+        for (i, joint) in E::joints(self).into_iter().enumerate() {
+            let axis = joint.frame.rotation * joint.axis;
+            let lever = end_effector - joint.frame.translation.vector;
+
+            jacobian
+                .fixed_view_mut::<3, 1>(0, i)
+                .copy_from(&axis.cross(&lever));
+            jacobian.fixed_view_mut::<3, 1>(3, i).copy_from(&axis);
+        }
+
+        jacobian
+    }
+}
+
+macro_rules! joints {
+    ($kinematics:expr; $($space:ty => $axis:expr),* $(,)?) => {
+        [$(Joint {
+            frame: $kinematics.isometry::<$space, Robot>().inner,
+            axis: $axis,
+        }),*]
+    };
+}
+
+/// Joints of the left leg: hip yaw-pitch, hip roll, hip pitch, knee pitch, ankle pitch and ankle roll.
+fn left_leg(kinematics: &Kinematics) -> [Joint; 6] {
+    joints![kinematics;
+        LeftPelvis => na::Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        LeftHip => na::Vector3::x(),
+        LeftThigh => na::Vector3::y(),
+        LeftTibia => na::Vector3::y(),
+        LeftAnkle => na::Vector3::y(),
+        LeftFoot => na::Vector3::x(),
+    ]
+}
+
+/// Joints of the right leg: hip yaw-pitch, hip roll, hip pitch, knee pitch, ankle pitch and ankle roll.
+fn right_leg(kinematics: &Kinematics) -> [Joint; 6] {
+    joints![kinematics;
+        RightPelvis => na::Vector3::new(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        RightHip => na::Vector3::x(),
+        RightThigh => na::Vector3::y(),
+        RightTibia => na::Vector3::y(),
+        RightAnkle => na::Vector3::y(),
+        RightFoot => na::Vector3::x(),
+    ]
+}
+
+/// Joints of the left arm: shoulder pitch, shoulder roll, elbow yaw, elbow roll and wrist yaw.
+fn left_arm(kinematics: &Kinematics) -> [Joint; 5] {
+    joints![kinematics;
+        LeftShoulder => na::Vector3::y(),
+        LeftUpperArm => na::Vector3::z(),
+        LeftElbow => na::Vector3::x(),
+        LeftForearm => na::Vector3::z(),
+        LeftWrist => na::Vector3::x(),
+    ]
+}
+
+/// Joints of the right arm: shoulder pitch, shoulder roll, elbow yaw, elbow roll and wrist yaw.
+fn right_arm(kinematics: &Kinematics) -> [Joint; 5] {
+    joints![kinematics;
+        RightShoulder => na::Vector3::y(),
+        RightUpperArm => na::Vector3::z(),
+        RightElbow => na::Vector3::x(),
+        RightForearm => na::Vector3::z(),
+        RightWrist => na::Vector3::x(),
+    ]
+}
+
+macro_rules! impl_end_effector {
+    ($space:ty, $n:literal, $joints:ident) => {
+        impl EndEffector<$n> for $space {
+            fn joints(kinematics: &Kinematics) -> [Joint; $n] {
+                $joints(kinematics)
+            }
+
+            fn pose(kinematics: &Kinematics) -> na::Isometry3<f32> {
+                kinematics.isometry::<$space, Robot>().inner
+            }
+        }
+    };
+}
+
+impl_end_effector!(LeftFoot, 6, left_leg);
+impl_end_effector!(LeftSole, 6, left_leg);
+impl_end_effector!(RightFoot, 6, right_leg);
+impl_end_effector!(RightSole, 6, right_leg);
+impl_end_effector!(LeftWrist, 5, left_arm);
+impl_end_effector!(RightWrist, 5, right_arm);
+
+impl EndEffector<2> for Head {
+    /// Joints of the head: head yaw and head pitch.
+    fn joints(kinematics: &Kinematics) -> [Joint; 2] {
+        joints![kinematics;
+            Neck => na::Vector3::z(),
+            Head => na::Vector3::y(),
+        ]
+    }
+
+    fn pose(kinematics: &Kinematics) -> na::Isometry3<f32> {
+        kinematics.isometry::<Head, Robot>().inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nidhogg::types::JointArray;
+
+    use super::*;
+
+    const STEP: f32 = 1e-3;
+    const TOLERANCE: f32 = 1e-3;
+
+    fn joints() -> JointArray<f32> {
+        JointArray {
+            head_yaw: 0.3,
+            head_pitch: -0.2,
+            left_shoulder_pitch: 1.2,
+            left_shoulder_roll: 0.3,
+            left_elbow_yaw: -0.8,
+            left_elbow_roll: -0.6,
+            left_wrist_yaw: 0.1,
+            left_hip_yaw_pitch: -0.15,
+            left_hip_roll: 0.05,
+            left_hip_pitch: -0.4,
+            left_knee_pitch: 0.9,
+            left_ankle_pitch: -0.5,
+            left_ankle_roll: -0.05,
+            right_shoulder_pitch: 1.1,
+            right_shoulder_roll: -0.25,
+            right_elbow_yaw: 0.7,
+            right_elbow_roll: 0.5,
+            right_wrist_yaw: -0.2,
+            right_hip_roll: -0.08,
+            right_hip_pitch: -0.35,
+            right_knee_pitch: 0.8,
+            right_ankle_pitch: -0.45,
+            right_ankle_roll: 0.07,
+            ..Default::default()
+        }
+    }
+
+    /// Compares the Jacobian against central finite differences of the forward kinematics.
+    fn check_jacobian<E: EndEffector<N>, const N: usize>(
+        joint_refs: [fn(&mut JointArray<f32>) -> &mut f32; N],
+    ) {
+        let joints = joints();
+        let jacobian = Kinematics::from(&joints).jacobian::<E, N>();
+
+        for (i, joint_ref) in joint_refs.into_iter().enumerate() {
+            let mut plus = joints.clone();
+            *joint_ref(&mut plus) += STEP;
+            let mut minus = joints.clone();
+            *joint_ref(&mut minus) -= STEP;
+
+            let plus = E::pose(&Kinematics::from(&plus));
+            let minus = E::pose(&Kinematics::from(&minus));
+
+            let linear = (plus.translation.vector - minus.translation.vector) / (2.0 * STEP);
+            let angular = (plus.rotation * minus.rotation.inverse()).scaled_axis() / (2.0 * STEP);
+
+            let column = jacobian.column(i);
+
+            assert!(
+                (column.fixed_rows::<3>(0) - linear).norm() < TOLERANCE,
+                "linear velocity of joint {i} does not match: {column} vs {linear}"
+            );
+            assert!(
+                (column.fixed_rows::<3>(3) - angular).norm() < TOLERANCE,
+                "angular velocity of joint {i} does not match: {column} vs {angular}"
+            );
+        }
+    }
+
+    #[test]
+    fn left_leg_jacobian() {
+        check_jacobian::<LeftSole, 6>([
+            |j| &mut j.left_hip_yaw_pitch,
+            |j| &mut j.left_hip_roll,
+            |j| &mut j.left_hip_pitch,
+            |j| &mut j.left_knee_pitch,
+            |j| &mut j.left_ankle_pitch,
+            |j| &mut j.left_ankle_roll,
+        ]);
+    }
+
+    #[test]
+    fn right_leg_jacobian() {
+        // the right hip yaw-pitch joint is coupled to the left one
+        check_jacobian::<RightSole, 6>([
+            |j| &mut j.left_hip_yaw_pitch,
+            |j| &mut j.right_hip_roll,
+            |j| &mut j.right_hip_pitch,
+            |j| &mut j.right_knee_pitch,
+            |j| &mut j.right_ankle_pitch,
+            |j| &mut j.right_ankle_roll,
+        ]);
+    }
+
+    #[test]
+    fn arm_jacobian() {
+        check_jacobian::<LeftWrist, 5>([
+            |j| &mut j.left_shoulder_pitch,
+            |j| &mut j.left_shoulder_roll,
+            |j| &mut j.left_elbow_yaw,
+            |j| &mut j.left_elbow_roll,
+            |j| &mut j.left_wrist_yaw,
+        ]);
+        check_jacobian::<RightWrist, 5>([
+            |j| &mut j.right_shoulder_pitch,
+            |j| &mut j.right_shoulder_roll,
+            |j| &mut j.right_elbow_yaw,
+            |j| &mut j.right_elbow_roll,
+            |j| &mut j.right_wrist_yaw,
+        ]);
+    }
+
+    #[test]
+    fn head_jacobian() {
+        check_jacobian::<Head, 2>([|j| &mut j.head_yaw, |j| &mut j.head_pitch]);
+    }
+}
@@ -11,6 +11,7 @@ use self::visualization::KinematicsVisualizationPlugin;
 pub mod dimensions;
 pub mod forward;
 pub mod inverse;
+pub mod jacobian;
 pub mod spaces;
 pub mod visualization;
 
@@ -1,5 +1,5 @@
 use nalgebra as na;
-use spatial::{Space, SpaceOver};
+use spatial::{Space, impl_space_over};
 
 pub struct Left;
 pub struct Right;
@@ -8,20 +8,14 @@ macro_rules! impl_space {
     ($space:ident) => {
         pub struct $space;
         impl Space for $space {}
-        impl SpaceOver<na::Point3<f32>> for $space {}
-        impl SpaceOver<na::Vector3<f32>> for $space {}
-        impl SpaceOver<na::Isometry3<f32>> for $space {}
+        impl_space_over!($space: na::Point3<f32>, na::Vector3<f32>, na::Isometry3<f32>);
     };
     ($space:ident<T>) => {
         pub struct $space<T: ?Sized>(std::marker::PhantomData<T>);
         impl Space for $space<Left> {}
         impl Space for $space<Right> {}
-        impl SpaceOver<na::Point3<f32>> for $space<Left> {}
-        impl SpaceOver<na::Point3<f32>> for $space<Right> {}
-        impl SpaceOver<na::Vector3<f32>> for $space<Left> {}
-        impl SpaceOver<na::Vector3<f32>> for $space<Right> {}
-        impl SpaceOver<na::Isometry3<f32>> for $space<Left> {}
-        impl SpaceOver<na::Isometry3<f32>> for $space<Right> {}
+        impl_space_over!($space<Left>: na::Point3<f32>, na::Vector3<f32>, na::Isometry3<f32>);
+        impl_space_over!($space<Right>: na::Point3<f32>, na::Vector3<f32>, na::Isometry3<f32>);
     };
 }
 
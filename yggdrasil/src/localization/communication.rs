@@ -0,0 +1,95 @@
+//! Sharing the position of the robot with teammates, see [`TeammatePositions`].
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use nalgebra::Point2;
+
+use crate::{
+    communication::{TeamCommunication, TeamMessage},
+    core::config::showtime::PlayerConfig,
+    nao::Clock,
+};
+
+use super::RobotPose;
+
+/// Plugin that sends the position of the robot to teammates, and keeps track of the most recent
+/// position reported by every teammate.
+pub struct PoseCommunicationPlugin;
+
+impl Plugin for PoseCommunicationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TeammatePositions>().add_systems(
+            Update,
+            (
+                send_position.run_if(resource_exists::<TeamCommunication>),
+                receive_positions.run_if(resource_exists::<TeamCommunication>),
+            ),
+        );
+    }
+}
+
+/// The most recent positions reported by the teammates, in world coordinates.
+///
+/// The position broadcast by the robot itself is not included.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct TeammatePositions {
+    reports: HashMap<SocketAddr, (Point2<f32>, Instant)>,
+}
+
+impl TeammatePositions {
+    /// Stores a position reported by the teammate at `teammate` at `received_at`.
+    pub fn report(&mut self, teammate: SocketAddr, position: Point2<f32>, received_at: Instant) {
+        self.reports.insert(teammate, (position, received_at));
+    }
+
+    /// The reported positions of the teammates, if they were received within `max_age`.
+    pub fn recent<'a>(
+        &'a self,
+        clock: &'a Clock,
+        max_age: Duration,
+    ) -> impl Iterator<Item = (SocketAddr, Point2<f32>)> + 'a {
+        self.reports
+            .iter()
+            .filter(move |(_, (_, received_at))| clock.elapsed(*received_at) <= max_age)
+            .map(|(teammate, (position, _))| (*teammate, *position))
+    }
+}
+
+fn send_position(
+    mut tc: ResMut<TeamCommunication>,
+    pose: Res<RobotPose>,
+    player_config: Res<PlayerConfig>,
+) {
+    let position = pose.world_position();
+    tc.stage(TeamMessage::RobotPosition {
+        player_number: player_config.player_number,
+        position: [position.x, position.y],
+    });
+}
+
+fn receive_positions(
+    mut tc: ResMut<TeamCommunication>,
+    mut teammate_positions: ResMut<TeammatePositions>,
+    player_config: Res<PlayerConfig>,
+    clock: Res<Clock>,
+) {
+    while let Some((_, teammate, (player_number, position))) =
+        tc.inbound_mut().take_map(|_, _, msg| match &msg.message {
+            TeamMessage::RobotPosition {
+                player_number,
+                position: [x, y],
+            } => Some((*player_number, Point2::new(*x, *y))),
+            _ => None,
+        })
+    {
+        // the broadcast of the robot itself is received as well
+        if player_number != player_config.player_number {
+            teammate_positions.report(teammate, position, clock.now());
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use filter::CovarianceMatrix;
+
+/// The confidence in the current [`RobotPose`](super::RobotPose), based on the covariance of the
+/// best localization hypothesis.
+///
+/// Behavior can use this to avoid committing to actions that require an accurate pose, such as
+/// kicking towards the goal, while the robot is not well localized.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PoseConfidence {
+    covariance: CovarianceMatrix<3>,
+}
+
+impl PoseConfidence {
+    #[must_use]
+    pub fn new(covariance: CovarianceMatrix<3>) -> Self {
+        Self { covariance }
+    }
+
+    /// The covariance of the pose, in the order `x`, `y`, `rotation`.
+    #[must_use]
+    pub fn covariance(&self) -> CovarianceMatrix<3> {
+        self.covariance
+    }
+
+    /// The trace of the pose covariance, lower is more confident.
+    #[must_use]
+    pub fn trace(&self) -> f32 {
+        self.covariance.trace()
+    }
+
+    /// Whether the trace of the pose covariance does not exceed the given threshold.
+    #[must_use]
+    pub fn is_localized_well(&self, threshold: f32) -> bool {
+        self.trace() <= threshold
+    }
+}
+
+/// Run condition that checks whether the robot is localized well, see
+/// [`PoseConfidence::is_localized_well`].
+pub fn is_localized_well(threshold: f32) -> impl Fn(Option<Res<PoseConfidence>>) -> bool {
+    move |confidence| confidence.is_some_and(|confidence| confidence.is_localized_well(threshold))
+}
@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{LocalizationConfig, hypothesis::RobotPoseHypothesis};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadReckoningConfig {
+    /// Number of consecutive cycles without field line observations after which the robot starts
+    /// dead reckoning
+    pub timeout_cycles: usize,
+    /// Variance added to the pose covariance every cycle while dead reckoning, on top of the
+    /// odometry variance
+    pub inflation_variance: [f32; 3],
+    /// Variance up to which the pose covariance is inflated while dead reckoning
+    pub max_variance: [f32; 3],
+}
+
+/// Keeps track of whether the robot is dead reckoning, i.e. localizing on odometry alone because
+/// no field lines have been observed for a while.
+///
+/// While dead reckoning the hypotheses still follow the odometry, but their covariance is inflated
+/// every cycle to account for the drift of the odometry, see [`DeadReckoningConfig`]. As soon as
+/// field lines are observed again, the regular line updates take over and correct the pose.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct DeadReckoning {
+    cycles_without_observation: usize,
+    observed: bool,
+    active: bool,
+}
+
+impl DeadReckoning {
+    /// Whether the robot is currently dead reckoning.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The number of consecutive cycles in which no field lines have been observed.
+    #[must_use]
+    pub fn cycles_without_observation(&self) -> usize {
+        self.cycles_without_observation
+    }
+
+    /// Records that field lines have been observed in the current cycle.
+    pub fn observe(&mut self) {
+        self.observed = true;
+    }
+
+    /// Ends the current cycle, and returns whether the robot is dead reckoning.
+    fn step(&mut self, cfg: &DeadReckoningConfig) -> bool {
+        if std::mem::take(&mut self.observed) {
+            self.cycles_without_observation = 0;
+        } else {
+            self.cycles_without_observation += 1;
+        }
+
+        let active = self.cycles_without_observation >= cfg.timeout_cycles;
+        if active != self.active {
+            tracing::info!(
+                cycles_without_observation = self.cycles_without_observation,
+                "{} dead reckoning",
+                if active { "Started" } else { "Stopped" }
+            );
+        }
+        self.active = active;
+
+        active
+    }
+}
+
+/// Run condition that checks whether the robot is dead reckoning.
+#[must_use]
+pub fn is_dead_reckoning(dead_reckoning: Res<DeadReckoning>) -> bool {
+    dead_reckoning.is_active()
+}
+
+pub fn dead_reckoning_update(
+    cfg: Res<LocalizationConfig>,
+    mut dead_reckoning: ResMut<DeadReckoning>,
+    mut hypotheses: Query<&mut RobotPoseHypothesis>,
+) {
+    if !dead_reckoning.step(&cfg.dead_reckoning) {
+        return;
+    }
+
+    for mut hypothesis in &mut hypotheses {
+        hypothesis.filter.inflate_covariance(
+            cfg.dead_reckoning.inflation_variance,
+            cfg.dead_reckoning.max_variance,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use filter::CovarianceMatrix;
+    use nalgebra::{Isometry2, vector};
+
+    use super::*;
+    use crate::localization::{RobotPose, pose::PoseFilter};
+
+    fn config() -> DeadReckoningConfig {
+        DeadReckoningConfig {
+            timeout_cycles: 10,
+            inflation_variance: [0.001, 0.001, 0.0001],
+            max_variance: [0.5, 0.5, 0.25],
+        }
+    }
+
+    #[test]
+    fn odometry_only_grows_covariance_and_tracks_path() {
+        let cfg = config();
+        let mut dead_reckoning = DeadReckoning::default();
+        let mut filter = PoseFilter::new(
+            RobotPose::from_translation_and_rotation(vector![-1.0, 0.5], 0.0),
+            CovarianceMatrix::from_diagonal_element(0.001),
+        );
+        let odometry_variance = CovarianceMatrix::from_diagonal_element(0.00001);
+
+        // walking a curve, one centimeter and a bit of rotation every cycle
+        let offset = Isometry2::new(vector![0.01, 0.0], 0.005);
+        let mut path = filter.state().inner;
+        let mut trace = filter.covariance().trace();
+
+        for cycle in 1..=200 {
+            filter.predict(offset, odometry_variance).unwrap();
+            path *= offset;
+
+            if dead_reckoning.step(&cfg) {
+                filter.inflate_covariance(cfg.inflation_variance, cfg.max_variance);
+            }
+            assert_eq!(dead_reckoning.is_active(), cycle >= cfg.timeout_cycles);
+
+            let new_trace = filter.covariance().trace();
+            assert!(
+                new_trace > trace,
+                "covariance did not grow in cycle {cycle}"
+            );
+            trace = new_trace;
+
+            // the uncertain heading pulls the mean slightly towards the start of the path
+            let state = filter.state();
+            assert!((state.inner.translation.vector - path.translation.vector).norm() < 0.02);
+            assert!((state.inner.rotation.angle() - path.rotation.angle()).abs() < 1e-3);
+        }
+
+        // the inflation itself is bounded
+        let mut inflated = filter.clone();
+        inflated.inflate_covariance([10.0; 3], cfg.max_variance);
+        for (i, max_variance) in cfg.max_variance.into_iter().enumerate() {
+            let variance = inflated.covariance()[(i, i)];
+            assert!(variance <= max_variance.max(filter.covariance()[(i, i)]) + 1e-6);
+        }
+
+        // observing field lines again hands back to the line updates
+        dead_reckoning.observe();
+        assert!(!dead_reckoning.step(&cfg));
+        assert_eq!(dead_reckoning.cycles_without_observation(), 0);
+    }
+}
@@ -0,0 +1,166 @@
+use std::{collections::VecDeque, time::Instant};
+
+use bevy::prelude::*;
+use nalgebra::Isometry2;
+
+use super::RobotPose;
+use crate::nao::CycleTime;
+
+/// Maximum number of poses kept in the [`PoseHistory`], which is roughly half a second of cycles.
+const POSE_HISTORY_LENGTH: usize = 48;
+
+/// Ring buffer of the most recent [`RobotPose`]s and the time at which they were estimated.
+///
+/// Detections are made on images that were captured some time before the detection runs, so
+/// projecting them with the current pose smears them while the robot is moving or turning.
+/// [`PoseHistory::pose_at`] gives the pose at the time the image was captured instead.
+#[derive(Resource, Debug, Clone)]
+pub struct PoseHistory {
+    poses: VecDeque<(Instant, RobotPose)>,
+}
+
+impl PoseHistory {
+    /// Creates a history containing a single pose.
+    #[must_use]
+    pub fn new(timestamp: Instant, pose: RobotPose) -> Self {
+        let mut poses = VecDeque::with_capacity(POSE_HISTORY_LENGTH);
+        poses.push_back((timestamp, pose));
+
+        Self { poses }
+    }
+
+    /// Records the pose at the given time, dropping the oldest pose if the history is full.
+    ///
+    /// Poses that are older than the newest recorded pose are ignored.
+    pub fn push(&mut self, timestamp: Instant, pose: RobotPose) {
+        if self
+            .poses
+            .back()
+            .is_some_and(|(newest, _)| timestamp < *newest)
+        {
+            return;
+        }
+
+        if self.poses.len() == POSE_HISTORY_LENGTH {
+            self.poses.pop_front();
+        }
+
+        self.poses.push_back((timestamp, pose));
+    }
+
+    /// The most recently recorded pose.
+    #[must_use]
+    pub fn latest(&self) -> RobotPose {
+        self.poses
+            .back()
+            .map(|(_, pose)| *pose)
+            .expect("pose history is never empty")
+    }
+
+    /// The pose at the given time, interpolated between the two closest recorded poses.
+    ///
+    /// Timestamps before the oldest or after the newest recorded pose are clamped to that pose.
+    #[must_use]
+    pub fn pose_at(&self, timestamp: Instant) -> RobotPose {
+        let next = self.poses.partition_point(|(time, _)| *time <= timestamp);
+
+        if next == 0 {
+            return self.poses[0].1;
+        }
+
+        let (previous_time, previous) = self.poses[next - 1];
+        let Some(&(next_time, next)) = self.poses.get(next) else {
+            return previous;
+        };
+
+        let t = timestamp.duration_since(previous_time).as_secs_f32()
+            / next_time.duration_since(previous_time).as_secs_f32();
+
+        previous.interpolate(&next, t)
+    }
+
+    /// The motion of the robot since the given time, as the transformation from the robot frame
+    /// at that time to the robot frame of the latest pose.
+    ///
+    /// Applying it to a detection in robot coordinates, made on an image captured at `timestamp`,
+    /// gives the position of that detection relative to the robot now.
+    #[must_use]
+    pub fn motion_since(&self, timestamp: Instant) -> Isometry2<f32> {
+        self.latest().inner.inverse() * self.pose_at(timestamp).inner
+    }
+}
+
+pub(super) fn update_pose_history(
+    mut history: ResMut<PoseHistory>,
+    cycle_time: Res<CycleTime>,
+    pose: Res<RobotPose>,
+) {
+    history.push(cycle_time.cycle_start, *pose);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use nalgebra::vector;
+
+    use super::*;
+
+    fn pose(x: f32, angle: f32) -> RobotPose {
+        RobotPose::from_translation_and_rotation(vector![x, 0.0], angle)
+    }
+
+    #[test]
+    fn interpolates_between_poses() {
+        let start = Instant::now();
+        let mut history = PoseHistory::new(start, pose(0.0, 0.0));
+        history.push(start + Duration::from_millis(10), pose(1.0, 1.0));
+
+        let interpolated = history.pose_at(start + Duration::from_millis(5));
+        assert!((interpolated.world_position().x - 0.5).abs() < 1e-5);
+        assert!((interpolated.world_rotation() - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn clamps_outside_history() {
+        let start = Instant::now() + Duration::from_secs(1);
+        let mut history = PoseHistory::new(start, pose(0.0, 0.0));
+        history.push(start + Duration::from_millis(10), pose(1.0, 0.0));
+
+        let before = history.pose_at(start - Duration::from_millis(5));
+        let after = history.pose_at(start + Duration::from_millis(20));
+        assert!(before.world_position().x.abs() < 1e-5);
+        assert!((after.world_position().x - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn motion_since_moves_detections_with_robot() {
+        let start = Instant::now();
+        let mut history = PoseHistory::new(start, pose(0.0, 0.0));
+        history.push(
+            start + Duration::from_millis(10),
+            pose(1.0, std::f32::consts::FRAC_PI_2),
+        );
+
+        // a ball two meters in front of the robot when the image was captured, is one meter
+        // to its right after it walked one meter forward and turned left
+        let ball = history.motion_since(start) * nalgebra::point![2.0, 0.0];
+        assert!(ball.x.abs() < 1e-5);
+        assert!((ball.y + 1.0).abs() < 1e-5);
+
+        let latest = history.motion_since(start + Duration::from_millis(10));
+        assert!(latest.translation.vector.norm() < 1e-5);
+    }
+
+    #[test]
+    fn drops_oldest_pose() {
+        let start = Instant::now();
+        let mut history = PoseHistory::new(start, pose(0.0, 0.0));
+        for i in 1..=POSE_HISTORY_LENGTH {
+            history.push(start + Duration::from_millis(i as u64), pose(i as f32, 0.0));
+        }
+
+        assert_eq!(history.poses.len(), POSE_HISTORY_LENGTH);
+        assert!((history.pose_at(start).world_position().x - 1.0).abs() < 1e-5);
+    }
+}
@@ -1,8 +1,6 @@
 use bevy::prelude::*;
 use bifrost::communication::{GameControllerMessage, GamePhase, Penalty};
-use filter::{
-    CovarianceMatrix, StateMatrix, StateTransform, StateVector, UnscentedKalmanFilter, WeightVector,
-};
+use filter::{CovarianceMatrix, StateMatrix, StateTransform, StateVector, WeightVector};
 use nalgebra::{ComplexField, Point2, Rotation2, UnitComplex, point, vector};
 use num::Complex;
 use serde::{Deserialize, Serialize};
@@ -13,19 +11,21 @@ use crate::{
         showtime::PlayerConfig,
     },
     game_controller::penalty::PenaltyState,
-    vision::line_detection::DetectedLines,
+    vision::{camera::ImageTimestamp, line_detection::DetectedLines},
 };
 
 use super::{
     LocalizationConfig, RobotPose,
+    confidence::PoseConfidence,
     correction::fit_field_lines,
     correspondence::FieldLineCorrespondence,
+    dead_reckoning::DeadReckoning,
+    history::PoseHistory,
+    kidnapped::KidnappedDetector,
     odometry::Odometry,
-    pose::{penalized_pose, penalty_kick_pose},
+    pose::{PoseFilter, penalized_pose, penalty_kick_pose},
 };
 
-type RobotPoseUkf = UnscentedKalmanFilter<3, 7, RobotPose>;
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HypothesisConfig {
     /// Variance of the odometry
@@ -59,17 +59,11 @@ pub fn odometry_update(
         let _ = hypothesis
             .filter
             .predict(
-                |pose| RobotPose {
-                    inner: pose.inner * odometry.offset_to_last,
-                },
+                odometry.offset_to_last,
                 CovarianceMatrix::from_diagonal(&cfg.hypothesis.odometry_variance.into()),
             )
             .inspect_err(|_| tracing::warn!("Cholesky failed in odometry"));
 
-        // TODO: B-Human and HULKs both do this.
-        // Need to put some research into the problem and if we can find a more elegant solution.
-        hypothesis.fix_covariance();
-
         hypothesis.score *= cfg.hypothesis.score_decay;
     }
 }
@@ -77,32 +71,47 @@ pub fn odometry_update(
 pub fn line_update(
     cfg: Res<LocalizationConfig>,
     layout: Res<LayoutConfig>,
-    new_lines: Query<&DetectedLines, Added<DetectedLines>>,
+    new_lines: Query<(&ImageTimestamp, &DetectedLines), Added<DetectedLines>>,
     mut hypotheses: Query<&mut RobotPoseHypothesis>,
+    mut kidnapped_detector: ResMut<KidnappedDetector>,
+    mut dead_reckoning: ResMut<DeadReckoning>,
+    pose_history: Res<PoseHistory>,
 ) {
-    // get the measured lines in robot space
+    // get the measured lines in robot space, compensated for the motion of the robot since the
+    // image was captured
     let segments = new_lines
         .iter()
-        .flat_map(|lines| &lines.segments)
+        .flat_map(|(timestamp, lines)| {
+            let motion = pose_history.motion_since(**timestamp);
+            lines.segments.iter().map(move |segment| motion * *segment)
+        })
         .collect::<Vec<_>>();
 
     if segments.is_empty() {
         return;
     }
 
+    let mut best_likelihood: f32 = 0.0;
+
     for mut hypothesis in &mut hypotheses {
         let pose = hypothesis.filter.state();
 
         // get measured lines in field space
         let measured = segments
             .iter()
-            .map(|&&segment| pose.inner * segment)
+            .map(|&segment| pose.inner * segment)
             .collect::<Vec<_>>();
 
         let Some((correspondences, fit_error)) = fit_field_lines(&measured, &cfg, &layout) else {
             continue;
         };
 
+        // at least one hypothesis matched the measured lines to the field
+        dead_reckoning.observe();
+
+        let likelihood = KidnappedDetector::likelihood(fit_error, &cfg.kidnapped);
+        best_likelihood = best_likelihood.max(likelihood);
+
         let clamped_fit_error = fit_error.max(cfg.correspondence.min_fit_error);
         let num_measurements_weight = 1.0 / correspondences.len() as f32;
 
@@ -172,18 +181,28 @@ pub fn line_update(
 
         hypothesis.score += cfg.hypothesis.score_default_increase;
     }
+
+    // the likelihood stays zero if no hypothesis could match the measured lines to the field
+    kidnapped_detector.add_measurement(best_likelihood, &cfg.kidnapped);
 }
 
 pub fn filter_hypotheses(
     mut commands: Commands,
     mut pose: ResMut<RobotPose>,
+    mut confidence: ResMut<PoseConfidence>,
     cfg: Res<LocalizationConfig>,
     hypotheses: Query<(Entity, &RobotPoseHypothesis)>,
 ) {
-    let (new_pose, best_score) = hypotheses
+    let (new_pose, new_covariance, best_score) = hypotheses
         .iter()
         .max_by(|(_, a), (_, b)| a.score.total_cmp(&b.score))
-        .map(|(_, hypothesis)| (hypothesis.filter.state(), hypothesis.score))
+        .map(|(_, hypothesis)| {
+            (
+                hypothesis.filter.state(),
+                hypothesis.filter.covariance(),
+                hypothesis.score,
+            )
+        })
         .expect("Could not get best hypothesis");
 
     // remove all hypotheses that are not good enough
@@ -195,6 +214,7 @@ pub fn filter_hypotheses(
 
     // set the new best pose
     *pose = new_pose;
+    *confidence = PoseConfidence::new(new_covariance);
 }
 
 /// Checks if the penalty should be in place (and not be placed on the side of the field)
@@ -249,7 +269,7 @@ pub fn reset_hypotheses(
 
 #[derive(Clone, Component)]
 pub struct RobotPoseHypothesis {
-    pub filter: RobotPoseUkf,
+    pub filter: PoseFilter,
     pub score: f32,
 }
 
@@ -260,19 +280,13 @@ impl RobotPoseHypothesis {
         initial_covariance: CovarianceMatrix<3>,
         initial_score: f32,
     ) -> Self {
-        let filter = RobotPoseUkf::new(initial_pose, initial_covariance);
+        let filter = PoseFilter::new(initial_pose, initial_covariance);
 
         Self {
             filter,
             score: initial_score,
         }
     }
-
-    /// Mitigate numerical instability with covariance matrix by ensuring it is symmetric
-    pub fn fix_covariance(&mut self) {
-        let cov = &mut self.filter.covariance;
-        *cov = (*cov + cov.transpose()) * 0.5;
-    }
 }
 
 #[derive(Debug, Clone, Copy)]
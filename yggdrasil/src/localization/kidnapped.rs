@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use filter::CovarianceMatrix;
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::layout::LayoutConfig;
+
+use super::{LocalizationConfig, RobotPose, hypothesis::RobotPoseHypothesis};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KidnappedConfig {
+    /// Variance of the field line fit error, used to turn a fit error into a likelihood
+    pub fit_error_variance: f32,
+    /// Smoothing factor of the running average likelihood, between 0 and 1
+    pub smoothing: f32,
+    /// Running average likelihood below which the measurements are considered poor
+    pub likelihood_threshold: f32,
+    /// Number of consecutive poor measurement updates after which the robot is considered kidnapped
+    pub window: usize,
+}
+
+/// Event that is sent when the robot is considered kidnapped, right before the hypotheses are
+/// reinitialized at the legal positions on the field.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Kidnapped {
+    /// The running average likelihood at the moment the robot was considered kidnapped.
+    pub likelihood: f32,
+}
+
+/// Monitors the likelihood of the field mark measurements to detect when the robot has been
+/// displaced without the odometry noticing, for instance when it is picked up by a referee.
+///
+/// A single bad measurement update is not enough: the running average likelihood has to stay
+/// below [`KidnappedConfig::likelihood_threshold`] for [`KidnappedConfig::window`] consecutive
+/// updates.
+#[derive(Resource, Debug, Clone)]
+pub struct KidnappedDetector {
+    average_likelihood: f32,
+    poor_updates: usize,
+}
+
+impl Default for KidnappedDetector {
+    fn default() -> Self {
+        Self {
+            average_likelihood: 1.0,
+            poor_updates: 0,
+        }
+    }
+}
+
+impl KidnappedDetector {
+    /// The likelihood of a field line fit with the given fit error.
+    #[must_use]
+    pub fn likelihood(fit_error: f32, cfg: &KidnappedConfig) -> f32 {
+        (-0.5 * fit_error / cfg.fit_error_variance).exp()
+    }
+
+    /// The running average likelihood of the measurement updates.
+    #[must_use]
+    pub fn average_likelihood(&self) -> f32 {
+        self.average_likelihood
+    }
+
+    /// Adds the likelihood of a new measurement update to the running average.
+    pub fn add_measurement(&mut self, likelihood: f32, cfg: &KidnappedConfig) {
+        self.average_likelihood += cfg.smoothing * (likelihood - self.average_likelihood);
+
+        if self.average_likelihood < cfg.likelihood_threshold {
+            self.poor_updates += 1;
+        } else {
+            self.poor_updates = 0;
+        }
+    }
+
+    /// Whether the measurements have been poor for long enough to consider the robot kidnapped.
+    #[must_use]
+    pub fn is_kidnapped(&self, cfg: &KidnappedConfig) -> bool {
+        self.poor_updates >= cfg.window
+    }
+
+    /// Resets the detector, assuming the pose estimate is good again.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Reinitializes the hypotheses at all legal positions on the field once the robot is considered
+/// kidnapped.
+pub fn relocalize_when_kidnapped(
+    mut commands: Commands,
+    mut detector: ResMut<KidnappedDetector>,
+    mut kidnapped: EventWriter<Kidnapped>,
+    hypotheses: Query<Entity, With<RobotPoseHypothesis>>,
+    layout: Res<LayoutConfig>,
+    localization: Res<LocalizationConfig>,
+) {
+    if !detector.is_kidnapped(&localization.kidnapped) {
+        return;
+    }
+
+    tracing::warn!(
+        likelihood = detector.average_likelihood(),
+        "Robot is kidnapped, reinitializing hypotheses"
+    );
+    kidnapped.write(Kidnapped {
+        likelihood: detector.average_likelihood(),
+    });
+
+    for entity in &hypotheses {
+        commands.entity(entity).despawn();
+    }
+
+    let positions = layout
+        .initial_positions
+        .iter()
+        .chain(layout.set_positions.iter());
+
+    for position in positions {
+        commands.spawn(RobotPoseHypothesis::new(
+            RobotPose::from_isometry(position.isometry),
+            CovarianceMatrix::from_diagonal(&localization.hypothesis.variance_initial.into()),
+            localization.hypothesis.score_initial,
+        ));
+    }
+
+    detector.reset();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> KidnappedConfig {
+        KidnappedConfig {
+            fit_error_variance: 0.01,
+            smoothing: 0.2,
+            likelihood_threshold: 0.3,
+            window: 10,
+        }
+    }
+
+    #[test]
+    fn large_pose_error_trips_detector() {
+        let cfg = config();
+        let mut detector = KidnappedDetector::default();
+
+        // well localized, the measured lines are within a few centimeters of the field lines
+        for _ in 0..100 {
+            detector.add_measurement(KidnappedDetector::likelihood(0.0025, &cfg), &cfg);
+        }
+        assert!(!detector.is_kidnapped(&cfg));
+
+        // the robot is displaced by a meter, so the lines no longer fit
+        let mut updates = 0;
+        while !detector.is_kidnapped(&cfg) {
+            detector.add_measurement(KidnappedDetector::likelihood(1.0, &cfg), &cfg);
+            updates += 1;
+            assert!(updates < 100, "detector did not trip");
+        }
+
+        assert!(updates >= cfg.window);
+        assert!(detector.average_likelihood() < cfg.likelihood_threshold);
+
+        detector.reset();
+        assert!(!detector.is_kidnapped(&cfg));
+    }
+
+    #[test]
+    fn single_bad_update_does_not_trip_detector() {
+        let cfg = config();
+        let mut detector = KidnappedDetector::default();
+
+        for i in 0..100 {
+            let fit_error = if i % 10 == 0 { 1.0 } else { 0.0025 };
+            detector.add_measurement(KidnappedDetector::likelihood(fit_error, &cfg), &cfg);
+            assert!(!detector.is_kidnapped(&cfg));
+        }
+    }
+}
@@ -1,18 +1,29 @@
+pub mod communication;
+pub mod confidence;
 pub mod correction;
 pub mod correspondence;
+pub mod dead_reckoning;
+pub mod history;
 pub mod hypothesis;
+pub mod kidnapped;
 pub mod odometry;
 pub mod pose;
 
+use std::time::Instant;
+
 use bevy::prelude::*;
 
+use confidence::PoseConfidence;
 use correction::GradientDescentConfig;
 use correspondence::CorrespondenceConfig;
+use dead_reckoning::{DeadReckoning, DeadReckoningConfig, dead_reckoning_update};
 use filter::CovarianceMatrix;
+use history::{PoseHistory, update_pose_history};
 use hypothesis::{
     HypothesisConfig, RobotPoseHypothesis, filter_hypotheses, line_update, odometry_update,
     reset_hypotheses,
 };
+use kidnapped::{Kidnapped, KidnappedConfig, KidnappedDetector, relocalize_when_kidnapped};
 use odal::Config;
 use odometry::OdometryConfig;
 pub use pose::RobotPose;
@@ -40,19 +51,44 @@ pub struct LocalizationPlugin;
 impl Plugin for LocalizationPlugin {
     fn build(&self, app: &mut App) {
         app.init_config::<LocalizationConfig>()
-            .add_plugins(odometry::OdometryPlugin)
+            .init_resource::<KidnappedDetector>()
+            .init_resource::<DeadReckoning>()
+            .add_event::<Kidnapped>()
+            .add_plugins((
+                odometry::OdometryPlugin,
+                communication::PoseCommunicationPlugin,
+            ))
             .add_systems(PostStartup, (initialize_pose, setup_pose_visualization))
             .add_systems(
                 PreUpdate,
                 (
-                    (odometry_update, line_update.run_if(not(motion_is_unsafe)))
+                    (
+                        odometry_update,
+                        line_update.run_if(not(motion_is_unsafe)),
+                        dead_reckoning_update
+                            .after(odometry_update)
+                            .after(line_update),
+                    )
                         .run_if(not(is_penalized.or(in_pre_walking_state))),
                     filter_hypotheses,
+                    update_pose_history.after(filter_hypotheses),
                     reset_hypotheses,
+                    // the poses from the game controller take precedence over relocalizing, so
+                    // they replace the relocalized hypotheses when both happen in the same cycle
+                    relocalize_when_kidnapped
+                        .after(filter_hypotheses)
+                        .before(reset_hypotheses),
                 )
                     .after(odometry::update_odometry),
             )
-            .add_systems(PostUpdate, (visualize_pose, visualize_pose_hypotheses));
+            .add_systems(
+                PostUpdate,
+                (
+                    visualize_pose,
+                    visualize_pose_hypotheses,
+                    visualize_pose_confidence,
+                ),
+            );
     }
 }
 
@@ -62,6 +98,8 @@ pub struct LocalizationConfig {
     pub correspondence: CorrespondenceConfig,
     pub hypothesis: HypothesisConfig,
     pub gradient_descent: GradientDescentConfig,
+    pub kidnapped: KidnappedConfig,
+    pub dead_reckoning: DeadReckoningConfig,
 }
 
 impl Config for LocalizationConfig {
@@ -75,15 +113,16 @@ fn initialize_pose(
     localization: Res<LocalizationConfig>,
 ) {
     let pose = initial_pose(&layout, player.player_number);
+    let covariance =
+        CovarianceMatrix::from_diagonal(&localization.hypothesis.variance_initial.into());
 
-    let hypothesis = RobotPoseHypothesis::new(
-        pose,
-        CovarianceMatrix::from_diagonal(&localization.hypothesis.variance_initial.into()),
-        localization.hypothesis.score_initial,
-    );
+    let hypothesis =
+        RobotPoseHypothesis::new(pose, covariance, localization.hypothesis.score_initial);
 
     commands.spawn(hypothesis);
     commands.insert_resource(pose);
+    commands.insert_resource(PoseHistory::new(Instant::now(), pose));
+    commands.insert_resource(PoseConfidence::new(covariance));
 }
 
 #[must_use]
@@ -164,3 +203,15 @@ fn visualize_pose_hypotheses(
         .with_colors(hypotheses.iter().map(|_| (0, 255, 255))),
     );
 }
+
+fn visualize_pose_confidence(
+    dbg: DebugContext,
+    cycle: Res<Cycle>,
+    confidence: Res<PoseConfidence>,
+) {
+    dbg.log_with_cycle(
+        "localization/confidence/trace",
+        *cycle,
+        &rerun::Scalars::single(f64::from(confidence.trace())),
+    );
+}
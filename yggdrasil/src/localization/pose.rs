@@ -1,5 +1,7 @@
 use bevy::prelude::*;
-use filter::{StateMatrix, StateTransform, StateVector, WeightVector};
+use filter::{
+    CovarianceMatrix, StateMatrix, StateTransform, StateVector, UnscentedKalmanFilter, WeightVector,
+};
 use num::Complex;
 
 use crate::core::config::layout::LayoutConfig;
@@ -82,6 +84,15 @@ impl RobotPose {
         HeadJoints { yaw, pitch }
     }
 
+    /// Interpolates between this pose and another pose, where `t = 0` is this pose and `t = 1`
+    /// is the other pose.
+    ///
+    /// The translation is interpolated linearly and the rotation along the shortest arc.
+    #[must_use]
+    pub fn interpolate(&self, other: &RobotPose, t: f32) -> RobotPose {
+        RobotPose::from_isometry(self.inner.lerp_slerp(&other.inner, t))
+    }
+
     #[must_use]
     pub fn distance_to(&self, point: &Point2<f32>) -> f32 {
         (self.world_position() - point).norm()
@@ -132,6 +143,107 @@ impl StateTransform<3> for RobotPose {
     }
 }
 
+/// Filter that estimates the [`RobotPose`] from odometry and observed field marks.
+///
+/// # State
+///
+/// The state is `[x, y, θ]`: the position of the robot on the field in meters, followed by its
+/// heading in radians. The heading is a circular quantity in `(-π, π]`, so the mean and residual
+/// of the filter are computed on the unit circle, see the [`StateTransform`] implementation of
+/// [`RobotPose`]. This keeps estimates near `±π` from averaging out to a heading of zero.
+///
+/// # Noise
+///
+/// Both the process noise of [`PoseFilter::predict`] and the measurement noise of
+/// [`PoseFilter::update`] are covariance matrices in the units of their respective state, so
+/// meters squared for positions and radians squared for angles.
+#[derive(Debug, Clone)]
+pub struct PoseFilter {
+    ukf: UnscentedKalmanFilter<3, 7, RobotPose>,
+}
+
+impl PoseFilter {
+    /// Creates a filter with an initial pose estimate and its covariance.
+    #[must_use]
+    pub fn new(pose: RobotPose, covariance: CovarianceMatrix<3>) -> Self {
+        Self {
+            ukf: UnscentedKalmanFilter::new(pose, covariance),
+        }
+    }
+
+    /// The current estimate of the pose.
+    #[must_use]
+    pub fn state(&self) -> RobotPose {
+        self.ukf.state()
+    }
+
+    /// The covariance of the current estimate, with the layout of the state.
+    #[must_use]
+    pub fn covariance(&self) -> CovarianceMatrix<3> {
+        self.ukf.covariance()
+    }
+
+    /// Moves the pose by the `odometry` offset since the previous prediction, which is expressed
+    /// in the frame of the robot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the covariance is no longer positive-definite.
+    pub fn predict(
+        &mut self,
+        odometry: Isometry2<f32>,
+        process_noise: CovarianceMatrix<3>,
+    ) -> filter::Result<()> {
+        self.ukf.predict(
+            |pose| RobotPose::from_isometry(pose.inner * odometry),
+            process_noise,
+        )?;
+
+        // Mitigate numerical instability with the covariance matrix by ensuring it is symmetric.
+        // TODO: B-Human and HULKs both do this.
+        // Need to put some research into the problem and if we can find a more elegant solution.
+        let covariance = &mut self.ukf.covariance;
+        *covariance = (*covariance + covariance.transpose()) * 0.5;
+
+        Ok(())
+    }
+
+    /// Adds `variance` to the diagonal of the covariance without moving the pose, but only up to
+    /// `max_variance`, so the uncertainty grows at a bounded rate to a bounded value.
+    ///
+    /// Variances that are already above `max_variance` are left as they are.
+    pub fn inflate_covariance(&mut self, variance: [f32; 3], max_variance: [f32; 3]) {
+        let covariance = &mut self.ukf.covariance;
+
+        for (i, (variance, max_variance)) in variance.into_iter().zip(max_variance).enumerate() {
+            let room = (max_variance - covariance[(i, i)]).max(0.0);
+            covariance[(i, i)] += variance.min(room);
+        }
+    }
+
+    /// Corrects the pose with an observation of a field mark.
+    ///
+    /// The `measurement_function` computes the observation that would be expected from a pose, so
+    /// it can be compared to the actual `observation`. Observations containing angles should
+    /// implement [`StateTransform`] such that the residual wraps around, like [`RobotPose`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the covariance is no longer positive-definite.
+    pub fn update<const D: usize, M>(
+        &mut self,
+        measurement_function: impl Fn(RobotPose) -> M,
+        observation: M,
+        measurement_noise: CovarianceMatrix<D>,
+    ) -> filter::Result<()>
+    where
+        M: StateTransform<D>,
+    {
+        self.ukf
+            .update(measurement_function, observation, measurement_noise)
+    }
+}
+
 /// Returns the starting pose of the robot.
 #[must_use]
 pub fn initial_pose(layout: &LayoutConfig, player_num: u8) -> RobotPose {
@@ -181,3 +293,48 @@ pub fn penalty_kick_pose(layout: &LayoutConfig, is_kicking_team: bool) -> RobotP
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn heading_near_pi_is_filtered_across_the_wrap() {
+        let mut filter = PoseFilter::new(
+            RobotPose::from_translation_and_rotation(vector![1.0, 0.0], PI - 0.05),
+            CovarianceMatrix::from_diagonal_element(0.01),
+        );
+        let noise = CovarianceMatrix::from_diagonal_element(0.001);
+
+        // turning left past π wraps the heading around to -π
+        filter
+            .predict(Isometry2::new(vector![0.1, 0.0], 0.1), noise)
+            .unwrap();
+        let heading = filter.state().world_rotation();
+        assert!((heading - (-PI + 0.05)).abs() < 1e-3, "heading: {heading}");
+
+        // observing the heading on the other side of the wrap only moves it slightly
+        for _ in 0..10 {
+            filter
+                .update(
+                    |pose| pose,
+                    RobotPose::from_translation_and_rotation(vector![0.9, 0.0], PI - 0.05),
+                    noise,
+                )
+                .unwrap();
+        }
+
+        let pose = filter.state();
+        let error = UnitComplex::new(pose.world_rotation()) / UnitComplex::new(PI - 0.05);
+        assert!(
+            error.angle().abs() < 0.02,
+            "heading: {}",
+            pose.world_rotation()
+        );
+        assert!((pose.world_position().x - 0.9).abs() < 0.02);
+        assert!(filter.covariance().iter().all(|value| !value.is_nan()));
+        assert!(filter.covariance()[(2, 2)] < 0.01);
+    }
+}
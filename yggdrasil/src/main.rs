@@ -23,12 +23,12 @@ fn main() -> Result<()> {
     miette::set_panic_hook();
 
     App::new()
-        .add_plugins((MinimalPlugins, StatesPlugin))
+        // the task plugin creates the task pools, so it has to be added before `MinimalPlugins`
+        .add_plugins((tasks::TaskPlugin::default(), MinimalPlugins, StatesPlugin))
         .add_plugins((
             schedule::NaoSchedulePlugin,
             game_controller::GameControllerPlugin,
             nao::NaoPlugins,
-            tasks::TaskPlugin,
             ml::MlPlugin,
             core::CorePlugins,
             localization::LocalizationPlugin,
@@ -0,0 +1,322 @@
+//! Selection, execution and verification of the get-up motions.
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::{DurationMilliSeconds, serde_as};
+
+use crate::{
+    motion::keyframe::MotionType,
+    nao::Clock,
+    prelude::*,
+    sensor::falling::{FallDirection, FallState, LyingDirection},
+};
+
+/// Plugin that keeps track of the get-up sequence of the robot.
+///
+/// The sequence itself is driven by the [`Standup`](crate::behavior::behaviors::Standup)
+/// behavior, which executes the motions selected by [`GetUp`].
+///
+/// This module provides the following resources to the application:
+/// - [`GetUp`]
+pub(super) struct GetUpPlugin;
+
+impl Plugin for GetUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_config::<GetUpConfig>()
+            .init_resource::<GetUp>()
+            .add_systems(Update, track_fall);
+    }
+}
+
+/// Configuration for verifying the get-up motions.
+#[serde_as]
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GetUpConfig {
+    /// Maximum number of get-up motions executed for a single fall, before giving up.
+    pub max_attempts: u32,
+    /// Maximum roll and pitch of the robot in radians, for it to be considered upright.
+    pub max_upright_angle: f32,
+    /// The time to wait after giving up before starting a new get-up sequence, while the robot is
+    /// still lying down, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds")]
+    pub retry_backoff: Duration,
+}
+
+impl Config for GetUpConfig {
+    const PATH: &'static str = "get_up.toml";
+}
+
+/// State of the get-up sequence.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GetUpState {
+    /// The robot is not getting up.
+    #[default]
+    Idle,
+    /// The robot is executing a get-up motion.
+    Executing {
+        motion: MotionType,
+        /// The attempt that is being executed, starting at one.
+        attempt: u32,
+    },
+    /// The robot is upright and has ground contact after executing the get-up motions.
+    Succeeded { attempts: u32 },
+    /// The robot is still not upright after executing the maximum number of get-up motions.
+    Failed { attempts: u32 },
+}
+
+/// Controller of the get-up sequence, which selects the get-up motion and verifies whether it
+/// succeeded.
+///
+/// The motions are selected by the direction the robot is lying in. The robot usually lands on the
+/// side it falls towards, but the measured lying direction takes precedence, as the robot can roll
+/// over during the fall. When a motion does not get the robot upright, it is retried with the
+/// motion for the direction the robot is lying in after the failed attempt.
+///
+/// After giving up, a new sequence is started once the retry backoff of the [`GetUpConfig`] has
+/// passed, if the robot is still lying down.
+#[derive(Resource, Debug, Default)]
+pub struct GetUp {
+    state: GetUpState,
+    fall_direction: Option<FallDirection>,
+    failed_at: Option<Instant>,
+}
+
+impl GetUp {
+    /// The current state of the get-up sequence.
+    #[must_use]
+    pub fn state(&self) -> GetUpState {
+        self.state
+    }
+
+    /// Whether the robot is executing a get-up motion.
+    #[must_use]
+    pub fn is_executing(&self) -> bool {
+        matches!(self.state, GetUpState::Executing { .. })
+    }
+
+    /// Starts a new get-up sequence for a robot lying in the `lying` direction, and returns the
+    /// motion to execute.
+    pub fn start(&mut self, lying: &LyingDirection) -> MotionType {
+        let motion = lying_motion(lying);
+        let fall_motion = match self.fall_direction {
+            Some(FallDirection::Forwards) => Some(MotionType::StandupStomach),
+            Some(FallDirection::Backwards) => Some(MotionType::StandupBack),
+            Some(FallDirection::Left | FallDirection::Right) | None => None,
+        };
+
+        if fall_motion.is_some_and(|fall_motion| fall_motion != motion) {
+            tracing::info!(
+                fall_direction = ?self.fall_direction,
+                ?lying,
+                "rolled over during the fall"
+            );
+        }
+
+        self.state = GetUpState::Executing { motion, attempt: 1 };
+        motion
+    }
+
+    /// Verifies the motion that has just finished, and returns the motion to retry with if the
+    /// robot is not `upright` with `grounded` feet.
+    ///
+    /// The `lying` direction is the direction the robot is lying in after the motion, if any.
+    pub fn verify(
+        &mut self,
+        upright: bool,
+        grounded: bool,
+        lying: Option<&LyingDirection>,
+        config: &GetUpConfig,
+    ) -> Option<MotionType> {
+        let GetUpState::Executing { motion, attempt } = self.state else {
+            return None;
+        };
+
+        if upright && grounded {
+            self.state = GetUpState::Succeeded { attempts: attempt };
+            self.fall_direction = None;
+            return None;
+        }
+
+        if attempt >= config.max_attempts {
+            tracing::warn!(attempts = attempt, "failed to get up, giving up");
+            self.state = GetUpState::Failed { attempts: attempt };
+            self.fall_direction = None;
+            return None;
+        }
+
+        let motion = lying.map_or(motion, lying_motion);
+        tracing::info!(attempt, ?motion, "failed to get up, retrying");
+
+        self.state = GetUpState::Executing {
+            motion,
+            attempt: attempt + 1,
+        };
+        Some(motion)
+    }
+
+    /// Resets a failed get-up sequence once the `backoff` has passed since it failed at `now`, so
+    /// a robot that is still lying down tries to get up again.
+    ///
+    /// Returns whether the sequence was reset.
+    pub fn rearm_failed(&mut self, now: Instant, backoff: Duration) -> bool {
+        if !matches!(self.state, GetUpState::Failed { .. }) {
+            self.failed_at = None;
+            return false;
+        }
+
+        let failed_at = *self.failed_at.get_or_insert(now);
+        if now.saturating_duration_since(failed_at) < backoff {
+            return false;
+        }
+
+        tracing::info!("retrying to get up after giving up");
+        self.state = GetUpState::Idle;
+        self.fall_direction = None;
+        self.failed_at = None;
+        true
+    }
+}
+
+/// The get-up motion for a robot lying in the `lying` direction.
+fn lying_motion(lying: &LyingDirection) -> MotionType {
+    match lying {
+        LyingDirection::FacingDown => MotionType::StandupStomach,
+        LyingDirection::FacingUp => MotionType::StandupBack,
+    }
+}
+
+/// Keeps track of the direction of the last fall, and resets a finished get-up sequence once the
+/// robot stands or falls again, or after the retry backoff if it is still lying down.
+fn track_fall(
+    mut get_up: ResMut<GetUp>,
+    fall_state: Res<FallState>,
+    clock: Res<Clock>,
+    config: Res<GetUpConfig>,
+) {
+    let finished = matches!(
+        get_up.state,
+        GetUpState::Succeeded { .. } | GetUpState::Failed { .. }
+    );
+
+    match fall_state.as_ref() {
+        FallState::Falling(direction) => {
+            get_up.fall_direction = Some(direction.clone());
+            if finished {
+                get_up.state = GetUpState::Idle;
+            }
+        }
+        FallState::None if finished => {
+            get_up.state = GetUpState::Idle;
+            get_up.fall_direction = None;
+        }
+        FallState::Lying(_) => {
+            get_up.rearm_failed(clock.now(), config.retry_backoff);
+        }
+        FallState::None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_failed_get_up() {
+        let config = GetUpConfig {
+            max_attempts: 3,
+            max_upright_angle: 0.3,
+            retry_backoff: Duration::from_secs(3),
+        };
+        let mut get_up = GetUp {
+            fall_direction: Some(FallDirection::Forwards),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            get_up.start(&LyingDirection::FacingDown),
+            MotionType::StandupStomach
+        );
+
+        // the robot is still lying on its stomach, so the same motion is retried
+        let retry = get_up.verify(false, true, Some(&LyingDirection::FacingDown), &config);
+        assert_eq!(retry, Some(MotionType::StandupStomach));
+        assert_eq!(
+            get_up.state(),
+            GetUpState::Executing {
+                motion: MotionType::StandupStomach,
+                attempt: 2
+            }
+        );
+
+        // the robot rolled onto its back, so it switches to the other motion
+        let retry = get_up.verify(false, false, Some(&LyingDirection::FacingUp), &config);
+        assert_eq!(retry, Some(MotionType::StandupBack));
+
+        assert_eq!(get_up.verify(false, false, None, &config), None);
+        assert_eq!(get_up.state(), GetUpState::Failed { attempts: 3 });
+
+        assert!(get_up.fall_direction.is_none());
+
+        get_up.start(&LyingDirection::FacingUp);
+        assert_eq!(get_up.verify(true, true, None, &config), None);
+        assert_eq!(get_up.state(), GetUpState::Succeeded { attempts: 1 });
+    }
+
+    #[test]
+    fn lying_direction_takes_precedence_over_fall() {
+        let config = GetUpConfig {
+            max_attempts: 3,
+            max_upright_angle: 0.3,
+            retry_backoff: Duration::from_secs(3),
+        };
+        let mut get_up = GetUp {
+            fall_direction: Some(FallDirection::Forwards),
+            ..Default::default()
+        };
+
+        // the robot fell forwards, but rolled onto its back
+        assert_eq!(
+            get_up.start(&LyingDirection::FacingUp),
+            MotionType::StandupBack
+        );
+        assert_eq!(get_up.verify(true, true, None, &config), None);
+
+        // the fall is forgotten once the robot got up, so it does not affect the next sequence
+        assert!(get_up.fall_direction.is_none());
+        assert_eq!(
+            get_up.start(&LyingDirection::FacingDown),
+            MotionType::StandupStomach
+        );
+    }
+
+    #[test]
+    fn retries_after_backoff() {
+        let config = GetUpConfig {
+            max_attempts: 1,
+            max_upright_angle: 0.3,
+            retry_backoff: Duration::from_secs(3),
+        };
+        let mut get_up = GetUp::default();
+        let start = Instant::now();
+
+        get_up.start(&LyingDirection::FacingUp);
+        assert!(!get_up.rearm_failed(start, config.retry_backoff));
+        assert_eq!(get_up.verify(false, false, None, &config), None);
+        assert_eq!(get_up.state(), GetUpState::Failed { attempts: 1 });
+
+        // the robot keeps lying down, but only tries again once the backoff has passed
+        assert!(!get_up.rearm_failed(start, config.retry_backoff));
+        assert!(!get_up.rearm_failed(start + Duration::from_secs(2), config.retry_backoff));
+        assert!(get_up.rearm_failed(start + Duration::from_secs(3), config.retry_backoff));
+        assert_eq!(get_up.state(), GetUpState::Idle);
+
+        // the backoff starts over for the next failure
+        get_up.start(&LyingDirection::FacingUp);
+        get_up.verify(false, false, None, &config);
+        assert!(!get_up.rearm_failed(start + Duration::from_secs(4), config.retry_backoff));
+        assert!(get_up.rearm_failed(start + Duration::from_secs(7), config.retry_backoff));
+    }
+}
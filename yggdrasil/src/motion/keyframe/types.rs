@@ -259,7 +259,7 @@ impl Motion {
 }
 
 /// An enumeration of all possible motions.
-#[derive(PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[non_exhaustive]
 pub enum MotionType {
     Example,
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 
 // TODO(#639): Joint optimizer does not handle high cycle time
 // pub mod energy_optimizer;
+pub mod get_up;
 pub mod keyframe;
 pub mod path_finding;
 pub mod step_planner;
@@ -14,6 +15,7 @@ pub struct MotionPlugins;
 impl PluginGroup for MotionPlugins {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
+            .add(get_up::GetUpPlugin)
             .add(keyframe::KeyframePlugin)
             .add(step_planner::StepPlannerPlugin)
             .add(walking_engine::WalkingEnginePlugin)
@@ -122,20 +122,39 @@ impl StepPlanner {
     fn get_all_obstacles(&mut self, robot_pose: &RobotPose) -> Vec<Obstacle> {
         let all_dynamic_obstacles = self.collect_and_gc_dynamic_obstacles();
 
-        let abs_dynamic_obstacles: Vec<_> = all_dynamic_obstacles
+        let mut all_obstacles = self.static_obstacles.clone();
+        all_obstacles.extend(Self::to_absolute(&all_dynamic_obstacles, robot_pose));
+
+        all_obstacles
+    }
+
+    /// All obstacles that have not expired yet, in absolute coordinates.
+    #[must_use]
+    pub fn obstacles(&self, robot_pose: &RobotPose) -> Vec<Obstacle> {
+        let now = Instant::now();
+        let dynamic_obstacles: Vec<_> = self
+            .dynamic_obstacles
             .iter()
-            .map(|obs| {
-                let abs_pos = robot_pose.robot_to_world(&Point2::new(obs.x.0, obs.y.0));
-                Obstacle::new(abs_pos.x, abs_pos.y, obs.radius.0)
-            })
+            .filter(|obs| now < obs.ttl)
+            .map(|obs| obs.obs)
             .collect();
 
         let mut all_obstacles = self.static_obstacles.clone();
-        all_obstacles.extend_from_slice(&abs_dynamic_obstacles);
+        all_obstacles.extend(Self::to_absolute(&dynamic_obstacles, robot_pose));
 
         all_obstacles
     }
 
+    fn to_absolute<'a>(
+        obstacles: &'a [Obstacle],
+        robot_pose: &'a RobotPose,
+    ) -> impl Iterator<Item = Obstacle> + 'a {
+        obstacles.iter().map(|obs| {
+            let abs_pos = robot_pose.robot_to_world(&Point2::new(obs.x.0, obs.y.0));
+            Obstacle::new(abs_pos.x, abs_pos.y, obs.radius.0)
+        })
+    }
+
     fn calc_path(&mut self, robot_pose: &RobotPose) -> Option<(Vec<Point2<f32>>, f32)> {
         let target_position = self.target?.position;
         let all_obstacles = self.get_all_obstacles(robot_pose);
@@ -29,6 +29,22 @@ pub struct BalancingConfig {
     pub foot_leveling: FootLevelingConfig,
 }
 
+/// Configuration for kicks executed during a walking step.
+///
+/// The kick parameters are clamped to these values, to keep the robot stable.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct KickStepConfig {
+    /// The maximum direction of a kick in radians, in both directions.
+    pub max_direction: f32,
+
+    /// The distance the swing foot is extended in the direction of the kick at full strength,
+    /// in metres.
+    pub extension: f32,
+
+    /// The additional amount to lift the swing foot at full strength, in metres.
+    pub extra_foot_lift: f32,
+}
+
 /// Configuration for foot leveling behavior during locomotion.
 ///
 /// This struct contains parameters that control how the robot's feet are leveled
@@ -164,6 +180,9 @@ pub struct WalkingEngineConfig {
 
     /// Hip height parameters
     pub hip_height: HipHeightConfig,
+
+    /// Kick step parameters
+    pub kick_step: KickStepConfig,
 }
 
 impl Config for WalkingEngineConfig {
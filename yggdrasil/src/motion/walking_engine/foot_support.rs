@@ -15,7 +15,8 @@ pub(super) struct FootSupportPlugin;
 impl Plugin for FootSupportPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<FootSupportState>();
-        app.add_systems(PostStartup, init_foot_support);
+        app.add_systems(PostStartup, init_foot_support)
+            .on_config_changed::<WalkingEngineConfig, _>(init_foot_support);
         app.add_systems(
             Sensor,
             update_foot_support
@@ -6,7 +6,7 @@ use super::{
     FootSwitchedEvent, Gait, WalkingEngineSet, config::WalkingEngineConfig,
     foot_support::FootSupportState, smoothing::parabolic_step, step::PlannedStep,
 };
-use crate::prelude::*;
+use crate::{prelude::*, sensor::foot_contact::FootTouchdown};
 
 mod sit;
 mod stand;
@@ -25,6 +25,7 @@ impl Plugin for GaitPlugin {
             Sensor,
             update_support_foot
                 .after(crate::sensor::fsr::update_force_sensitive_resistor_sensor)
+                .after(crate::sensor::foot_contact::update_foot_contact)
                 .after(WalkingEngineSet::Prepare)
                 .run_if(in_state(Gait::Walking).or(in_state(Gait::Stopping))),
         );
@@ -81,11 +82,18 @@ fn update_support_foot(
     mut foot_support: ResMut<FootSupportState>,
     mut event: EventWriter<FootSwitchedEvent>,
     config: Res<WalkingEngineConfig>,
+    mut touchdown: EventReader<FootTouchdown>,
 ) {
     // only switch if we've completed the minimum ratio of the step
     let is_switch_allowed = state.linear() > config.minimum_step_duration_ratio;
 
-    let foot_switched = is_switch_allowed && foot_support.predicted_or_switched();
+    // the swing foot touching down ends the step, even if the pressure has not shifted yet
+    let swing_touchdown = touchdown
+        .read()
+        .any(|touchdown| touchdown.side == foot_support.swing_side());
+
+    let foot_switched =
+        is_switch_allowed && (foot_support.predicted_or_switched() || swing_touchdown);
 
     if foot_switched {
         state.phase = Duration::ZERO;
@@ -59,6 +59,7 @@ fn init_starting_step(
             target: FootPositions::default(),
             swing_foot_height: config.starting_foot_lift,
             duration: config.starting_step_duration,
+            kick: None,
             ..step_context.planned_step
         },
     });
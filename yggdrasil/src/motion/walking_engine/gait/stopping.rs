@@ -60,6 +60,7 @@ fn init_stopping_step(
             target: FootPositions::default(),
             swing_foot_height: config.stopping_foot_lift,
             duration: config.stopping_step_duration,
+            kick: None,
             ..step_context.planned_step
         },
     });
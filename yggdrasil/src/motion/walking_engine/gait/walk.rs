@@ -71,6 +71,7 @@ fn generate_walk_gait(
     cycle_time: Res<CycleTime>,
     step_context: Res<StepContext>,
     foot_support: Res<FootSupportState>,
+    config: Res<WalkingEngineConfig>,
 ) {
     state.phase += cycle_time.duration;
 
@@ -98,6 +99,14 @@ fn generate_walk_gait(
     left.translation.z = left_lift;
     right.translation.z = right_lift;
 
+    if let Some(kick) = planned.kick {
+        let offset = kick.swing_offset(linear, &config.kick_step);
+        match &foot_support.swing_side() {
+            Side::Left => left.translation.vector += offset,
+            Side::Right => right.translation.vector += offset,
+        }
+    }
+
     **target_positions = FootPositions {
         left: left.into(),
         right: right.into(),
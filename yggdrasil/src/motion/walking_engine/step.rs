@@ -3,11 +3,12 @@ use std::{
     time::Duration,
 };
 
+use nalgebra::{Vector3, vector};
 use serde::{Deserialize, Serialize};
 
 use crate::kinematics::Kinematics;
 
-use super::{Side, feet::FootPositions};
+use super::{Side, config::KickStepConfig, feet::FootPositions, smoothing::parabolic_return};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Step {
@@ -160,6 +161,49 @@ impl Div for Step {
     }
 }
 
+/// A kick that is executed by the swing foot during a walking step, so the robot does not
+/// have to stop walking in order to kick the ball.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KickStep {
+    /// Direction of the kick in radians, relative to the forward direction of the robot.
+    ///
+    /// Positive values will kick to the left, and negative values will kick to the right.
+    pub direction: f32,
+    /// Strength of the kick, between 0 and 1.
+    pub strength: f32,
+}
+
+impl KickStep {
+    /// Clamp the kick to the ranges that keep the robot stable.
+    #[must_use]
+    pub fn clamp(self, config: &KickStepConfig) -> Self {
+        Self {
+            direction: self
+                .direction
+                .clamp(-config.max_direction, config.max_direction),
+            strength: self.strength.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Offset of the swing foot w.r.t. its regular trajectory, at the provided linear phase
+    /// of the step.
+    ///
+    /// The swing foot is extended in the direction of the kick and lifted higher than usual.
+    /// The offset peaks halfway through the step, and returns to zero at the end of the step so
+    /// the foot still lands on the planned target.
+    #[must_use]
+    pub fn swing_offset(self, phase: f32, config: &KickStepConfig) -> Vector3<f32> {
+        let weight = parabolic_return(phase) * self.strength;
+        let extension = weight * config.extension;
+
+        vector![
+            extension * self.direction.cos(),
+            extension * self.direction.sin(),
+            weight * config.extra_foot_lift
+        ]
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct PlannedStep {
     pub step: Step,
@@ -168,6 +212,8 @@ pub struct PlannedStep {
     pub duration: Duration,
     pub swing_foot_height: f32,
     pub swing_side: Side,
+    /// The kick executed by the swing foot during this step, if any.
+    pub kick: Option<KickStep>,
 }
 
 impl Default for PlannedStep {
@@ -179,6 +225,7 @@ impl Default for PlannedStep {
             duration: Duration::from_millis(250),
             swing_foot_height: 0.,
             swing_side: Side::Left,
+            kick: None,
         }
     }
 }
@@ -194,3 +241,48 @@ impl PlannedStep {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> KickStepConfig {
+        KickStepConfig {
+            max_direction: 0.5,
+            extension: 0.04,
+            extra_foot_lift: 0.015,
+        }
+    }
+
+    #[test]
+    fn kick_step_swing_is_larger_but_bounded() {
+        let config = config();
+        let kick = KickStep {
+            direction: 2.0,
+            strength: 10.0,
+        }
+        .clamp(&config);
+
+        assert!((kick.direction - config.max_direction).abs() < f32::EPSILON);
+        assert!((kick.strength - 1.0).abs() < f32::EPSILON);
+
+        let offsets = (0..=100)
+            .map(|i| kick.swing_offset(i as f32 / 100.0, &config))
+            .collect::<Vec<_>>();
+
+        let max_extension = offsets.iter().map(|o| o.xy().norm()).fold(0.0, f32::max);
+        let max_lift = offsets.iter().map(|o| o.z).fold(0.0, f32::max);
+
+        // the swing foot travels further and higher than during a regular step
+        assert!(max_extension > 0.9 * config.extension);
+        assert!(max_lift > 0.9 * config.extra_foot_lift);
+
+        // but never beyond the configured bounds
+        assert!(max_extension <= config.extension + f32::EPSILON);
+        assert!(max_lift <= config.extra_foot_lift + f32::EPSILON);
+
+        // and the foot still lands on the planned target
+        assert!(offsets[0].norm() < 1e-6);
+        assert!(offsets[100].norm() < 1e-6);
+    }
+}
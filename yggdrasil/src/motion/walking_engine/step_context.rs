@@ -4,7 +4,7 @@ use crate::{
     behavior::{behaviors::Standup, engine::in_behavior},
     core::debug::{
         DebugContext,
-        debug_system::{DebugAppExt, SystemToggle},
+        debug_system::{DebugAppExt, DebugLevel, SystemToggle},
     },
     kinematics::Kinematics,
     motion::walking_engine::foot_support::FootSupportState,
@@ -17,7 +17,7 @@ use super::{
     feet::FootPositions,
     gait::StandingHeight,
     schedule::{Gait, WalkingEngineSet},
-    step::{PlannedStep, Step},
+    step::{KickStep, PlannedStep, Step},
 };
 use bevy::prelude::*;
 use nalgebra::Vector2;
@@ -50,6 +50,8 @@ impl Plugin for StepContextPlugin {
                 .run_if(on_event::<FootSwitchedEvent>)
                 .in_set(WalkingEngineSet::PlanStep),
             "Visualize planned step",
+            "motion",
+            DebugLevel::Info,
             SystemToggle::Enable,
         );
     }
@@ -59,6 +61,7 @@ impl Plugin for StepContextPlugin {
 pub struct StepContext {
     requested_gait: Gait,
     requested_step: Step,
+    requested_kick: Option<KickStep>,
     pub requested_standing_height: Option<StandingHeight>,
     stand_return_start: Option<Instant>,
     last_step: PlannedStep,
@@ -72,6 +75,7 @@ impl StepContext {
         Self {
             requested_gait: gait,
             requested_step: Step::default(),
+            requested_kick: None,
             requested_standing_height: None,
             stand_return_start: None,
             last_step,
@@ -156,6 +160,18 @@ impl StepContext {
         }
     }
 
+    /// Request a walk, where the swing foot of the next step executes the provided kick.
+    ///
+    /// The kick is only executed if the robot is already walking, and is clamped to the ranges
+    /// in the [`KickStepConfig`](super::config::KickStepConfig).
+    pub fn request_kick_step(&mut self, step: Step, kick: KickStep) {
+        self.request_walk(step);
+
+        if self.requested_gait == Gait::Walking {
+            self.requested_kick = Some(kick);
+        }
+    }
+
     pub fn finish_step(&mut self) {
         self.last_step = self.planned_step;
     }
@@ -201,6 +217,10 @@ impl StepContext {
             target,
             swing_foot_height: config.base_foot_lift + foot_lift_modifier,
             swing_side: next_swing_foot,
+            kick: self
+                .requested_kick
+                .take()
+                .map(|kick| kick.clamp(&config.kick_step)),
         };
     }
 }
@@ -13,8 +13,12 @@ use crate::{
     prelude::*,
 };
 use bevy::prelude::*;
+use nalgebra::{self as na, Point2, Vector3};
 pub use robot_masses::*;
-use spatial::types::Point3;
+use spatial::{Space, SpaceOver, Transform, types::Point3};
+
+/// Gravitational acceleration in m/s².
+const GRAVITY_CONSTANT: f32 = 9.81;
 
 /// Plugin which adds the `CoM` of the robot to the storage, and updates it each cycle.
 ///
@@ -46,34 +50,70 @@ pub struct CenterOfMass {
     pub position: Point3<Robot>,
 }
 
-pub(super) fn update_com(kinematics: Res<Kinematics>, mut com: ResMut<CenterOfMass>) {
-    let new_com = kinematics.transform(&TORSO.center) * TORSO.mass
-        + kinematics.transform(&NECK.center) * NECK.mass
-        + kinematics.transform(&HEAD.center) * HEAD.mass
-        + kinematics.transform(&LEFT_SHOULDER.center) * LEFT_SHOULDER.mass
-        + kinematics.transform(&LEFT_UPPER_ARM.center) * LEFT_UPPER_ARM.mass
-        + kinematics.transform(&LEFT_ELBOW.center) * LEFT_ELBOW.mass
-        + kinematics.transform(&LEFT_FOREARM.center) * LEFT_FOREARM.mass
-        + kinematics.transform(&LEFT_WRIST.center) * LEFT_WRIST.mass
-        + kinematics.transform(&RIGHT_SHOULDER.center) * RIGHT_SHOULDER.mass
-        + kinematics.transform(&RIGHT_UPPER_ARM.center) * RIGHT_UPPER_ARM.mass
-        + kinematics.transform(&RIGHT_ELBOW.center) * RIGHT_ELBOW.mass
-        + kinematics.transform(&RIGHT_FOREARM.center) * RIGHT_FOREARM.mass
-        + kinematics.transform(&RIGHT_WRIST.center) * RIGHT_WRIST.mass
-        + kinematics.transform(&LEFT_HIP.center) * LEFT_HIP.mass
-        + kinematics.transform(&LEFT_PELVIS.center) * LEFT_PELVIS.mass
-        + kinematics.transform(&LEFT_THIGH.center) * LEFT_THIGH.mass
-        + kinematics.transform(&LEFT_TIBIA.center) * LEFT_TIBIA.mass
-        + kinematics.transform(&LEFT_ANKLE.center) * LEFT_ANKLE.mass
-        + kinematics.transform(&LEFT_FOOT.center) * LEFT_FOOT.mass
-        + kinematics.transform(&RIGHT_HIP.center) * RIGHT_HIP.mass
-        + kinematics.transform(&RIGHT_PELVIS.center) * RIGHT_PELVIS.mass
-        + kinematics.transform(&RIGHT_THIGH.center) * RIGHT_THIGH.mass
-        + kinematics.transform(&RIGHT_TIBIA.center) * RIGHT_TIBIA.mass
-        + kinematics.transform(&RIGHT_ANKLE.center) * RIGHT_ANKLE.mass;
+impl CenterOfMass {
+    /// Estimates the zero moment point (ZMP) in *robot* frame, using the linear inverted pendulum
+    /// model with the acceleration measured by the IMU.
+    #[must_use]
+    pub fn zmp_estimate(&self, imu_accel: &Vector3<f32>) -> Point2<f32> {
+        let height = self.position.z / GRAVITY_CONSTANT;
+
+        Point2::new(
+            self.position.x - height * imu_accel.x,
+            self.position.y - height * imu_accel.y,
+        )
+    }
+}
+
+impl Kinematics {
+    /// Computes the center of mass of the robot in *robot* frame for the current joint positions.
+    ///
+    /// The center of mass of each link in [`robot_masses`] is transformed to the robot frame and
+    /// weighted by the mass of the link.
+    #[must_use]
+    pub fn center_of_mass(&self) -> Point3<Robot> {
+        let weighted_sum = self.weighted_center(&TORSO)
+            + self.weighted_center(&NECK)
+            + self.weighted_center(&HEAD)
+            + self.weighted_center(&LEFT_SHOULDER)
+            + self.weighted_center(&LEFT_UPPER_ARM)
+            + self.weighted_center(&LEFT_ELBOW)
+            + self.weighted_center(&LEFT_FOREARM)
+            + self.weighted_center(&LEFT_WRIST)
+            + self.weighted_center(&RIGHT_SHOULDER)
+            + self.weighted_center(&RIGHT_UPPER_ARM)
+            + self.weighted_center(&RIGHT_ELBOW)
+            + self.weighted_center(&RIGHT_FOREARM)
+            + self.weighted_center(&RIGHT_WRIST)
+            + self.weighted_center(&LEFT_PELVIS)
+            + self.weighted_center(&LEFT_HIP)
+            + self.weighted_center(&LEFT_THIGH)
+            + self.weighted_center(&LEFT_TIBIA)
+            + self.weighted_center(&LEFT_ANKLE)
+            + self.weighted_center(&LEFT_FOOT)
+            + self.weighted_center(&RIGHT_PELVIS)
+            + self.weighted_center(&RIGHT_HIP)
+            + self.weighted_center(&RIGHT_THIGH)
+            + self.weighted_center(&RIGHT_TIBIA)
+            + self.weighted_center(&RIGHT_ANKLE)
+            + self.weighted_center(&RIGHT_FOOT);
+
+        na::Point3::from(weighted_sum / TOTAL_MASS).into()
+    }
+
+    /// The center of mass of a link in *robot* frame, multiplied by the mass of the link.
+    fn weighted_center<S>(&self, link: &RobotMass<S>) -> na::Vector3<f32>
+    where
+        S: Space + SpaceOver<na::Point3<f32>>,
+        Self: Transform<na::Point3<f32>, na::Point3<f32>, S, Robot>,
+    {
+        let center: Point3<Robot> = self.transform(&link.center);
+        center.inner.coords * link.mass
+    }
+}
 
+pub(super) fn update_com(kinematics: Res<Kinematics>, mut com: ResMut<CenterOfMass>) {
     *com = CenterOfMass {
-        position: (new_com / TOTAL_MASS).map(From::from),
+        position: kinematics.center_of_mass(),
     };
 }
 
@@ -91,3 +131,45 @@ fn visualize_com(dbg: DebugContext, com: Res<CenterOfMass>) {
             .with_radii([0.05]),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use nidhogg::types::JointArray;
+
+    use super::*;
+    use crate::kinematics::spaces::LeftSole;
+
+    #[test]
+    fn center_of_mass_of_standing_robot() {
+        // slightly bent knees, with the arms hanging down alongside the body
+        let joints = JointArray {
+            left_shoulder_pitch: 1.57,
+            right_shoulder_pitch: 1.57,
+            left_hip_pitch: -0.4,
+            right_hip_pitch: -0.4,
+            left_knee_pitch: 0.8,
+            right_knee_pitch: 0.8,
+            left_ankle_pitch: -0.4,
+            right_ankle_pitch: -0.4,
+            ..Default::default()
+        };
+        let kinematics = Kinematics::from(&joints);
+
+        let com = kinematics.center_of_mass();
+        let sole: Point3<Robot> = kinematics.transform(&spatial::point3!(LeftSole));
+
+        // the robot is symmetric, and balanced above its feet
+        assert!(com.y.abs() < 0.001, "com is not centered: {:?}", com.inner);
+        assert!((com.x - sole.x).abs() < 0.02, "com is not above the feet");
+
+        let height = com.z - sole.z;
+        assert!(
+            (0.25..0.29).contains(&height),
+            "unexpected com height: {height}"
+        );
+
+        // without any acceleration, the zmp lies directly below the com
+        let zmp = CenterOfMass { position: com }.zmp_estimate(&Vector3::zeros());
+        assert_eq!(zmp, Point2::new(com.x, com.y));
+    }
+}
@@ -1,169 +1,169 @@
 //! Contains the masses of each link of the robot in kilograms, along with the center of mass (`CoM`) of each
-//! link relative to the origin of the robot frame.
+//! link relative to the origin of the link's own frame.
 //!
-//! The origin is the center of the robot's torso, the x-axis vectors forward, the y-axis vectors left,
-//! and the z-axis vectors up.
+//! For every frame, the x-axis vectors forward, the y-axis vectors left, and the z-axis vectors up
+//! when all joints are at zero.
 use crate::kinematics::prelude::*;
 use nalgebra as na;
-use spatial::{Space, SpaceOver, types::Vector3};
+use spatial::{Space, SpaceOver, types::Point3};
 
 /// The mass and center of mass of a link.
 #[derive(Debug, Clone)]
-pub struct RobotMass<S: Space + SpaceOver<na::Vector3<f32>>> {
+pub struct RobotMass<S: Space + SpaceOver<na::Point3<f32>>> {
     /// Mass of the link in kilograms.
     pub mass: f32,
-    /// Center of mass of the link relative to the origin of the robot frame.
-    pub center: Vector3<S>,
+    /// Center of mass of the link in the frame of the link.
+    pub center: Point3<S>,
 }
 
 /// Mass and `CoM` of the torso.
 pub const TORSO: RobotMass<Torso> = RobotMass {
     mass: 1.0496,
-    center: spatial::vector3!(0.0, 0.0, 0.0),
+    center: spatial::point3!(0.0, 0.0, 0.0),
 };
 
 /// Mass and `CoM` of the neck.
 pub const NECK: RobotMass<Neck> = RobotMass {
     mass: 0.07842,
-    center: spatial::vector3!(-0.00001, 0.0, -0.02742),
+    center: spatial::point3!(-0.00001, 0.0, -0.02742),
 };
 
 /// Mass and `CoM` of the head.
 pub const HEAD: RobotMass<Head> = RobotMass {
     mass: 0.65937,
-    center: spatial::vector3!(0.00109, 0.00146, 0.05719),
+    center: spatial::point3!(0.00109, 0.00146, 0.05719),
 };
 
 /// Mass and `CoM` of the left.
 pub const LEFT_SHOULDER: RobotMass<LeftShoulder> = RobotMass {
     mass: 0.09304,
-    center: spatial::vector3!(-0.00165, -0.02663, 0.00014),
+    center: spatial::point3!(-0.00165, -0.02663, 0.00014),
 };
 
 /// Mass and `CoM` of the left upper arm.
 pub const LEFT_UPPER_ARM: RobotMass<LeftUpperArm> = RobotMass {
     mass: 0.15777,
-    center: spatial::vector3!(0.02455, 0.00563, 0.0033),
+    center: spatial::point3!(0.02455, 0.00563, 0.0033),
 };
 
 /// Mass and `CoM` of the left elbow.
 pub const LEFT_ELBOW: RobotMass<LeftElbow> = RobotMass {
     mass: 0.06483,
-    center: spatial::vector3!(-0.02744, 0.0, -0.00014),
+    center: spatial::point3!(-0.02744, 0.0, -0.00014),
 };
 
 /// Mass and `CoM` of the left forearm.
 pub const LEFT_FOREARM: RobotMass<LeftForearm> = RobotMass {
     mass: 0.07761,
-    center: spatial::vector3!(0.02556, 0.00281, 0.00076),
+    center: spatial::point3!(0.02556, 0.00281, 0.00076),
 };
 
 /// Mass and `CoM` of the left wrist.
 pub const LEFT_WRIST: RobotMass<LeftWrist> = RobotMass {
     mass: 0.18533,
-    center: spatial::vector3!(0.03434, -0.00088, 0.00308),
+    center: spatial::point3!(0.03434, -0.00088, 0.00308),
 };
 
 /// Mass and `CoM` of the right shoulder.
 pub const RIGHT_SHOULDER: RobotMass<RightShoulder> = RobotMass {
     mass: 0.09304,
-    center: spatial::vector3!(-0.00165, 0.02663, 0.00014),
+    center: spatial::point3!(-0.00165, 0.02663, 0.00014),
 };
 
 /// Mass and `CoM` of the right upper arm.
 pub const RIGHT_UPPER_ARM: RobotMass<RightUpperArm> = RobotMass {
     mass: 0.15777,
-    center: spatial::vector3!(0.02455, -0.00563, 0.0033),
+    center: spatial::point3!(0.02455, -0.00563, 0.0033),
 };
 
 /// Mass and `CoM` of the right elbow.
 pub const RIGHT_ELBOW: RobotMass<RightElbow> = RobotMass {
     mass: 0.06483,
-    center: spatial::vector3!(-0.02744, 0.0, -0.00014),
+    center: spatial::point3!(-0.02744, 0.0, -0.00014),
 };
 
 /// Mass and `CoM` of the right forearm.
 pub const RIGHT_FOREARM: RobotMass<RightForearm> = RobotMass {
     mass: 0.07761,
-    center: spatial::vector3!(0.02556, -0.00281, 0.00076),
+    center: spatial::point3!(0.02556, -0.00281, 0.00076),
 };
 
 /// Mass and `CoM` of the right wrist.
 pub const RIGHT_WRIST: RobotMass<RightWrist> = RobotMass {
     mass: 0.18533,
-    center: spatial::vector3!(0.03434, 0.00088, 0.00308),
+    center: spatial::point3!(0.03434, 0.00088, 0.00308),
 };
 
 /// Mass and `CoM` of the left hip.
 pub const LEFT_PELVIS: RobotMass<LeftPelvis> = RobotMass {
     mass: 0.06981,
-    center: spatial::vector3!(-0.00781, -0.01114, 0.02661),
+    center: spatial::point3!(-0.00781, -0.01114, 0.02661),
 };
 
 /// Mass and `CoM` of the left thigh.
 pub const LEFT_HIP: RobotMass<LeftHip> = RobotMass {
     mass: 0.14053,
-    center: spatial::vector3!(-0.01549, 0.00029, -0.00515),
+    center: spatial::point3!(-0.01549, 0.00029, -0.00515),
 };
 
 /// Mass and `CoM` of the left thigh.
 pub const LEFT_THIGH: RobotMass<LeftThigh> = RobotMass {
     mass: 0.38968,
-    center: spatial::vector3!(0.00138, 0.00221, -0.05373),
+    center: spatial::point3!(0.00138, 0.00221, -0.05373),
 };
 
 /// Mass and `CoM` of the left tibia.
 pub const LEFT_TIBIA: RobotMass<LeftTibia> = RobotMass {
     mass: 0.30142,
-    center: spatial::vector3!(0.00453, 0.00225, -0.04936),
+    center: spatial::point3!(0.00453, 0.00225, -0.04936),
 };
 
 /// Mass and `CoM` of the left ankle.
 pub const LEFT_ANKLE: RobotMass<LeftAnkle> = RobotMass {
     mass: 0.13416,
-    center: spatial::vector3!(0.00045, 0.00029, 0.00685),
+    center: spatial::point3!(0.00045, 0.00029, 0.00685),
 };
 
 /// Mass and `CoM` of the left foot.
 pub const LEFT_FOOT: RobotMass<LeftFoot> = RobotMass {
     mass: 0.17184,
-    center: spatial::vector3!(0.02542, 0.0033, -0.03239),
+    center: spatial::point3!(0.02542, 0.0033, -0.03239),
 };
 
 /// Mass and `CoM` of the right pelvis.
 pub const RIGHT_PELVIS: RobotMass<RightPelvis> = RobotMass {
     mass: 0.06981,
-    center: spatial::vector3!(-0.00781, 0.01114, 0.02661),
+    center: spatial::point3!(-0.00781, 0.01114, 0.02661),
 };
 
 /// Mass and `CoM` of the right thigh.
 pub const RIGHT_HIP: RobotMass<RightHip> = RobotMass {
     mass: 0.14053,
-    center: spatial::vector3!(-0.01549, -0.00029, -0.00515),
+    center: spatial::point3!(-0.01549, -0.00029, -0.00515),
 };
 
 /// Mass and `CoM` of the right thigh.
 pub const RIGHT_THIGH: RobotMass<RightThigh> = RobotMass {
     mass: 0.38968,
-    center: spatial::vector3!(0.00138, -0.00221, -0.05373),
+    center: spatial::point3!(0.00138, -0.00221, -0.05373),
 };
 
 /// Mass and `CoM` of the right tibia.
 pub const RIGHT_TIBIA: RobotMass<RightTibia> = RobotMass {
     mass: 0.30142,
-    center: spatial::vector3!(0.00453, -0.00225, -0.04936),
+    center: spatial::point3!(0.00453, -0.00225, -0.04936),
 };
 
 /// Mass and `CoM` of the right ankle.
 pub const RIGHT_ANKLE: RobotMass<RightAnkle> = RobotMass {
     mass: 0.13416,
-    center: spatial::vector3!(0.00045, -0.00029, 0.00685),
+    center: spatial::point3!(0.00045, -0.00029, 0.00685),
 };
 
 /// Mass and `CoM` of the right foot.
 pub const RIGHT_FOOT: RobotMass<RightFoot> = RobotMass {
     mass: 0.17184,
-    center: spatial::vector3!(0.02542, -0.0033, -0.03239),
+    center: spatial::point3!(0.02542, -0.0033, -0.03239),
 };
 
 /// Total mass of the robot.
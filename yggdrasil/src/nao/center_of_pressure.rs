@@ -33,8 +33,6 @@ const RIGHT_FOOT_SENSOR_OFFSETS: SensorOffsets = SensorOffsets {
     rear_right: Vector2::new(-0.03025, 0.0299),
 };
 
-const GRAVITY_CONSTANT: f32 = 9.81;
-
 /// Plugin that adds systems and resources for calculating the center of pressure.
 pub(super) struct CenterOfPressurePlugin;
 
@@ -89,15 +87,5 @@ fn update_zmp(
     imu: Res<IMUValues>,
     center_of_mass: Res<center_of_mass::CenterOfMass>,
 ) {
-    let accel_x = imu.accelerometer.x;
-    let accel_y = imu.accelerometer.y;
-
-    let x_com = center_of_mass.position.x;
-    let y_com = center_of_mass.position.y;
-    let z_com = center_of_mass.position.z;
-
-    zero_moment_point.point = Point2::new(
-        x_com - (z_com / GRAVITY_CONSTANT) * accel_x,
-        y_com - (z_com / GRAVITY_CONSTANT) * accel_y,
-    );
+    zero_moment_point.point = center_of_mass.zmp_estimate(&imu.accelerometer);
 }
@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// Plugin that adds the [`Clock`] resource, and advances it every cycle when it is simulated.
+pub(super) struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Clock>()
+            .add_systems(PostWrite, advance_simulated_clock);
+    }
+}
+
+/// The source of the current time, which should be used instead of [`Instant::now`].
+///
+/// On the robot this is the [`Clock::Real`] system clock. A simulation can insert a
+/// [`Clock::Simulated`] before adding the [`NaoPlugins`](super::NaoPlugins), whose time only
+/// advances by a fixed step at the end of every cycle. This makes time-dependent logic, such as
+/// timeouts and filter predictions, reproducible regardless of how fast the simulation runs.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use yggdrasil::nao::Clock;
+///
+/// let mut clock = Clock::simulated(Duration::from_millis(12));
+/// let start = clock.now();
+///
+/// clock.step();
+/// assert_eq!(clock.elapsed(start), Duration::from_millis(12));
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub enum Clock {
+    /// The time of the system clock.
+    #[default]
+    Real,
+    /// Time that is advanced by `step` at the end of every cycle.
+    Simulated { now: Instant, step: Duration },
+}
+
+impl Clock {
+    /// Creates a simulated clock, starting at the current time of the system clock.
+    #[must_use]
+    pub fn simulated(step: Duration) -> Self {
+        Self::Simulated {
+            now: Instant::now(),
+            step,
+        }
+    }
+
+    /// The current time.
+    #[must_use]
+    pub fn now(&self) -> Instant {
+        match self {
+            Clock::Real => Instant::now(),
+            Clock::Simulated { now, .. } => *now,
+        }
+    }
+
+    /// The time that has passed since `instant`, or zero if `instant` lies in the future.
+    #[must_use]
+    pub fn elapsed(&self, instant: Instant) -> Duration {
+        self.now().saturating_duration_since(instant)
+    }
+
+    /// Advances a simulated clock by its step, this does nothing for the real clock.
+    pub fn step(&mut self) {
+        if let Clock::Simulated { now, step } = self {
+            *now += *step;
+        }
+    }
+}
+
+fn advance_simulated_clock(mut clock: ResMut<Clock>) {
+    clock.step();
+}
@@ -2,14 +2,24 @@ use std::time::{Duration, Instant};
 
 use crate::prelude::*;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::{DurationMicroSeconds, DurationMilliSeconds, serde_as};
 
 /// Plugin that adds resources and systems for tracking the cycle time of yggdrasil.
 pub(super) struct CycleTimePlugin;
 
 impl Plugin for CycleTimePlugin {
     fn build(&self, app: &mut App) {
+        app.add_event::<CycleOverrun>();
         app.add_systems(PostStartup, initialize_cycle_counter);
-        app.add_systems(PostWrite, update_cycle_stats);
+        app.add_systems(
+            PostWrite,
+            (
+                update_cycle_stats,
+                check_cycle_budget.run_if(resource_exists::<CycleBudgetConfig>),
+            )
+                .chain(),
+        );
     }
 }
 
@@ -26,6 +36,33 @@ pub struct CycleTime {
     pub duration: Duration,
 }
 
+/// Configuration of the real-time budget of a cycle.
+#[serde_as]
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CycleBudgetConfig {
+    /// The maximum duration of a cycle, in microseconds.
+    #[serde_as(as = "DurationMicroSeconds<u64>")]
+    pub budget: Duration,
+    /// The minimum time between two overrun warnings, in milliseconds.
+    ///
+    /// Overruns within this interval still send a [`CycleOverrun`] event, but are only counted
+    /// in the next warning.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub warning_interval: Duration,
+}
+
+/// Event that is sent when a cycle took longer than [`CycleBudgetConfig::budget`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CycleOverrun {
+    /// The cycle that ran long.
+    pub cycle: Cycle,
+    /// How long the cycle took.
+    pub duration: Duration,
+    /// The budget that was exceeded.
+    pub budget: Duration,
+}
+
 pub(crate) fn initialize_cycle_counter(mut commands: Commands) {
     commands.insert_resource(Cycle::default());
     commands.insert_resource(CycleTime {
@@ -39,3 +76,100 @@ fn update_cycle_stats(mut cycle: ResMut<Cycle>, mut cycle_time: ResMut<CycleTime
     cycle_time.duration = Instant::now().duration_since(cycle_time.cycle_start);
     cycle_time.cycle_start = Instant::now();
 }
+
+/// Limits how often overrun warnings are logged, counting the overruns in between.
+#[derive(Debug, Default)]
+struct OverrunWarnings {
+    last_warning: Option<Instant>,
+    suppressed: usize,
+}
+
+impl OverrunWarnings {
+    /// Returns the number of overruns since the last warning if a warning should be logged now.
+    fn should_warn(&mut self, now: Instant, interval: Duration) -> Option<usize> {
+        if self
+            .last_warning
+            .is_some_and(|last_warning| now.duration_since(last_warning) < interval)
+        {
+            self.suppressed += 1;
+            return None;
+        }
+
+        self.last_warning = Some(now);
+        Some(std::mem::take(&mut self.suppressed) + 1)
+    }
+}
+
+fn check_cycle_budget(
+    cycle: Res<Cycle>,
+    cycle_time: Res<CycleTime>,
+    config: Res<CycleBudgetConfig>,
+    mut warnings: Local<OverrunWarnings>,
+    mut overruns: EventWriter<CycleOverrun>,
+) {
+    if cycle_time.duration <= config.budget {
+        return;
+    }
+
+    overruns.write(CycleOverrun {
+        cycle: *cycle,
+        duration: cycle_time.duration,
+        budget: config.budget,
+    });
+
+    if let Some(count) = warnings.should_warn(Instant::now(), config.warning_interval) {
+        tracing::warn!(
+            cycle = cycle.0,
+            "Cycle took {:?}, exceeding the budget of {:?} ({count} overruns since last warning)",
+            cycle_time.duration,
+            config.budget,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_cycle_sends_overrun() {
+        let mut app = App::new();
+        app.add_event::<CycleOverrun>()
+            .insert_resource(Cycle(42))
+            .insert_resource(CycleTime {
+                cycle_start: Instant::now(),
+                duration: Duration::from_millis(11),
+            })
+            .insert_resource(CycleBudgetConfig {
+                budget: Duration::from_millis(12),
+                warning_interval: Duration::from_secs(1),
+            })
+            .add_systems(Update, check_cycle_budget);
+
+        app.update();
+        assert!(app.world().resource::<Events<CycleOverrun>>().is_empty());
+
+        app.world_mut().resource_mut::<CycleTime>().duration = Duration::from_millis(30);
+        app.update();
+
+        let events = app.world().resource::<Events<CycleOverrun>>();
+        let overrun = events
+            .iter_current_update_events()
+            .next()
+            .expect("long cycle should send an overrun");
+        assert_eq!(overrun.cycle, Cycle(42));
+        assert_eq!(overrun.duration, Duration::from_millis(30));
+    }
+
+    #[test]
+    fn overrun_warnings_are_rate_limited() {
+        let mut warnings = OverrunWarnings::default();
+        let interval = Duration::from_secs(1);
+        let start = Instant::now();
+
+        assert_eq!(warnings.should_warn(start, interval), Some(1));
+        assert_eq!(warnings.should_warn(start + interval / 4, interval), None);
+        assert_eq!(warnings.should_warn(start + interval / 2, interval), None);
+        assert_eq!(warnings.should_warn(start + interval, interval), Some(3));
+    }
+}
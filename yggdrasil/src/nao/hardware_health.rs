@@ -0,0 +1,265 @@
+use std::time::{Duration, Instant};
+
+use crate::prelude::*;
+use bevy::prelude::*;
+use nidhogg::{NaoState, types::JointArray};
+use serde::{Deserialize, Serialize};
+use serde_with::{DurationMilliSeconds, serde_as};
+
+/// Names of the joints, in the order of a [`JointArray`].
+const JOINT_NAMES: [&str; 25] = [
+    "head_yaw",
+    "head_pitch",
+    "left_shoulder_pitch",
+    "left_shoulder_roll",
+    "left_elbow_yaw",
+    "left_elbow_roll",
+    "left_wrist_yaw",
+    "left_hip_yaw_pitch",
+    "left_hip_roll",
+    "left_hip_pitch",
+    "left_knee_pitch",
+    "left_ankle_pitch",
+    "left_ankle_roll",
+    "right_shoulder_pitch",
+    "right_shoulder_roll",
+    "right_elbow_yaw",
+    "right_elbow_roll",
+    "right_wrist_yaw",
+    "right_hip_roll",
+    "right_hip_pitch",
+    "right_knee_pitch",
+    "right_ankle_pitch",
+    "right_ankle_roll",
+    "left_hand",
+    "right_hand",
+];
+
+/// Plugin that monitors the temperatures and currents of the joints, see [`HardwareHealth`].
+pub(super) struct HardwareHealthPlugin;
+
+impl Plugin for HardwareHealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<JointFault>()
+            .init_resource::<HardwareHealth>()
+            .add_systems(
+                Sensor,
+                check_joint_health.run_if(
+                    resource_exists::<HardwareHealthConfig>.and(resource_exists::<NaoState>),
+                ),
+            );
+    }
+}
+
+/// Configuration of the limits of the joints.
+#[serde_as]
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HardwareHealthConfig {
+    /// The temperature above which a joint is overheated, in degrees Celsius.
+    pub max_temperature: f32,
+    /// The current above which a joint is drawing too much current, e.g. because it is stuck,
+    /// in amperes.
+    pub max_current: f32,
+    /// The minimum time between two warnings about faulty joints, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub warning_interval: Duration,
+}
+
+/// The kind of fault of a joint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointFaultKind {
+    /// The joint is hotter than [`HardwareHealthConfig::max_temperature`].
+    Overheated { temperature: f32 },
+    /// The joint draws more than [`HardwareHealthConfig::max_current`].
+    Overcurrent { current: f32 },
+}
+
+/// Event that is sent when a joint becomes faulty.
+///
+/// The event is only sent once when the fault starts, [`HardwareHealth`] keeps track of the
+/// faults that are still present.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct JointFault {
+    /// Index of the joint in a [`JointArray`].
+    pub joint: usize,
+    pub kind: JointFaultKind,
+}
+
+impl JointFault {
+    /// The name of the faulty joint, e.g. `left_knee_pitch`.
+    #[must_use]
+    pub fn joint_name(&self) -> &'static str {
+        JOINT_NAMES[self.joint]
+    }
+}
+
+/// The health of the joints, based on the most recent [`NaoState`].
+#[derive(Resource, Debug, Default, Clone)]
+pub struct HardwareHealth {
+    faults: JointArray<Option<JointFaultKind>>,
+    total_faults: usize,
+}
+
+impl HardwareHealth {
+    /// Whether none of the joints are faulty.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.faults
+            .as_array_ref()
+            .iter()
+            .all(|fault| fault.is_none())
+    }
+
+    /// The current fault of every faulty joint.
+    pub fn faults(&self) -> impl Iterator<Item = JointFault> + '_ {
+        self.faults
+            .as_array_ref()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(joint, kind)| kind.map(|kind| JointFault { joint, kind }))
+    }
+
+    /// Whether the joint is overheated, e.g. to reduce its stiffness.
+    #[must_use]
+    pub fn is_overheated(&self, joint: usize) -> bool {
+        matches!(
+            self.faults.get(joint),
+            Some(Some(JointFaultKind::Overheated { .. }))
+        )
+    }
+
+    /// The number of faults that have started since yggdrasil has been running.
+    #[must_use]
+    pub fn total_faults(&self) -> usize {
+        self.total_faults
+    }
+}
+
+/// Returns the fault of a joint with the given readings, an overheated joint takes precedence.
+fn joint_fault(
+    temperature: f32,
+    current: f32,
+    config: &HardwareHealthConfig,
+) -> Option<JointFaultKind> {
+    if temperature > config.max_temperature {
+        Some(JointFaultKind::Overheated { temperature })
+    } else if current > config.max_current {
+        Some(JointFaultKind::Overcurrent { current })
+    } else {
+        None
+    }
+}
+
+fn check_joint_health(
+    state: Res<NaoState>,
+    config: Res<HardwareHealthConfig>,
+    mut health: ResMut<HardwareHealth>,
+    mut faults: EventWriter<JointFault>,
+    mut last_warning: Local<Option<Instant>>,
+) {
+    let readings = state.temperature.clone().zip(state.current.clone());
+    let mut new_faults = Vec::new();
+
+    for (joint, ((temperature, current), previous)) in readings
+        .into_iter()
+        .zip(health.faults.as_array_mut())
+        .enumerate()
+    {
+        let fault = joint_fault(temperature, current, &config);
+
+        // only the start of a fault is reported, or a change in its kind
+        if let Some(kind) = fault.filter(|kind| {
+            previous.is_none_or(|previous| {
+                std::mem::discriminant(&previous) != std::mem::discriminant(kind)
+            })
+        }) {
+            new_faults.push(JointFault { joint, kind });
+        }
+
+        *previous = fault;
+    }
+
+    if new_faults.is_empty() {
+        return;
+    }
+
+    health.total_faults += new_faults.len();
+    faults.write_batch(new_faults.iter().copied());
+
+    let now = Instant::now();
+    if last_warning
+        .is_some_and(|last_warning| now.duration_since(last_warning) < config.warning_interval)
+    {
+        return;
+    }
+    *last_warning = Some(now);
+
+    for fault in new_faults {
+        match fault.kind {
+            JointFaultKind::Overheated { temperature } => tracing::warn!(
+                "Joint `{}` is overheated: {temperature:.1}°C, the limit is {:.1}°C",
+                fault.joint_name(),
+                config.max_temperature,
+            ),
+            JointFaultKind::Overcurrent { current } => tracing::warn!(
+                "Joint `{}` draws too much current: {current:.2}A, the limit is {:.2}A",
+                fault.joint_name(),
+                config.max_current,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nidhogg::types::FillExt;
+
+    use super::*;
+
+    #[test]
+    fn overheated_joint_sends_fault() {
+        let mut app = App::new();
+        app.add_event::<JointFault>()
+            .init_resource::<HardwareHealth>()
+            .insert_resource(HardwareHealthConfig {
+                max_temperature: 75.0,
+                max_current: 2.0,
+                warning_interval: Duration::from_secs(1),
+            })
+            .insert_resource(NaoState {
+                temperature: JointArray::fill(40.0),
+                current: JointArray::fill(0.5),
+                ..Default::default()
+            })
+            .add_systems(Update, check_joint_health);
+
+        app.update();
+        assert!(app.world().resource::<Events<JointFault>>().is_empty());
+        assert!(app.world().resource::<HardwareHealth>().is_healthy());
+
+        app.world_mut()
+            .resource_mut::<NaoState>()
+            .temperature
+            .left_knee_pitch = 80.0;
+        app.update();
+
+        let events = app.world().resource::<Events<JointFault>>();
+        let fault = events
+            .iter_current_update_events()
+            .next()
+            .expect("overheated joint should send a fault");
+        assert_eq!(fault.joint_name(), "left_knee_pitch");
+        assert_eq!(fault.kind, JointFaultKind::Overheated { temperature: 80.0 });
+
+        let health = app.world().resource::<HardwareHealth>();
+        assert!(health.is_overheated(fault.joint));
+        assert_eq!(health.total_faults(), 1);
+
+        // a fault that persists is not reported again
+        app.update();
+        let events = app.world().resource::<Events<JointFault>>();
+        assert_eq!(events.iter_current_update_events().count(), 0);
+        assert!(!app.world().resource::<HardwareHealth>().is_healthy());
+    }
+}
@@ -0,0 +1,326 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bifrost::communication::{GameControllerMessage, TeamColor};
+use nidhogg::{
+    NaoState,
+    types::{FillExt, LeftEar, LeftEye, RgbF32, RightEar, RightEye, color},
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{DurationMilliSeconds, serde_as};
+
+use super::manager::{NaoManager, Priority};
+use crate::{
+    behavior::primary_state::{PrimaryState, PrimaryStateConfig, update_primary_state},
+    core::config::showtime::PlayerConfig,
+    game_controller::GameControllerConnection,
+};
+
+/// Priority of the manual overrides, which take precedence over every other LED request.
+const OVERRIDE_PRIORITY: Priority = Priority::Custom(100);
+/// Priority of the chest, which has to show the game state as required by the rules.
+const CHEST_PRIORITY: Priority = Priority::Critical;
+/// Priority of the low battery warning on the eyes, which takes precedence over the ball and
+/// role indicators that are requested with the default priority.
+const EYES_PRIORITY: Priority = Priority::Medium;
+/// Priority of the game controller connection on the ears, which only shows when no whistle is
+/// detected.
+const EARS_PRIORITY: Priority = Priority::Low;
+/// Priority of the team color on the feet.
+const FEET_PRIORITY: Priority = Priority::Low;
+
+/// Plugin that shows the state of the robot on its LEDs.
+///
+/// The chest shows the [`PrimaryState`], the feet show the team color, the ears show whether the
+/// robot is connected to the game controller, and the eyes blink red when the battery is low.
+///
+/// This module provides the following resources to the application:
+/// - [`Leds`]
+pub(super) struct LedPlugin;
+
+impl Plugin for LedPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Leds>().add_systems(
+            Update,
+            (update_leds, apply_leds)
+                .chain()
+                .after(update_primary_state),
+        );
+    }
+}
+
+/// Configuration for the LEDs that show the state of the robot.
+#[serde_as]
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LedConfig {
+    /// Battery charge below which the eyes blink red, between 0 and 1.
+    pub low_battery_charge: f32,
+    /// Time between two blinks of the low battery and lost connection warnings, in milliseconds.
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub warning_blink_interval: Duration,
+}
+
+/// Pattern shown by a group of LEDs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LedPattern {
+    /// The LEDs show the color.
+    Solid(RgbF32),
+    /// The LEDs turn the color on and off, switching every `interval`.
+    Blinking { color: RgbF32, interval: Duration },
+}
+
+impl LedPattern {
+    /// The LEDs are turned off.
+    pub const OFF: Self = Self::Solid(color::f32::EMPTY);
+
+    /// The color shown by the pattern, `elapsed` time after the robot started.
+    #[must_use]
+    pub fn color(&self, elapsed: Duration) -> RgbF32 {
+        match *self {
+            Self::Solid(color) => color,
+            Self::Blinking { color, interval } => {
+                let on = interval.is_zero()
+                    || (elapsed.as_millis() / interval.as_millis()).is_multiple_of(2);
+
+                if on { color } else { color::f32::EMPTY }
+            }
+        }
+    }
+}
+
+/// The patterns of the named LED groups of the robot.
+///
+/// A group without a pattern is left to the other systems that request LEDs through the
+/// [`NaoManager`]. The chest is requested with a critical priority as the rules require it to
+/// show the game state, and the low battery warning on the eyes takes precedence over the ball
+/// and role indicators. The ears and feet are requested with a low priority, so they only show
+/// when no other system requests them, such as the whistle detection.
+///
+/// The ears only have blue LEDs, so only the blue channel of their color is used.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LedGroups {
+    pub chest: Option<LedPattern>,
+    pub eyes: Option<LedPattern>,
+    pub ears: Option<LedPattern>,
+    pub feet: Option<LedPattern>,
+}
+
+/// The LED patterns that show the state of the robot, see [`LedPlugin`].
+#[derive(Resource, Debug, Default, Clone)]
+pub struct Leds {
+    /// Patterns derived from the state of the robot, updated every cycle.
+    pub state: LedGroups,
+    /// Manual overrides for debugging, which take precedence over the state and any other LED
+    /// request.
+    pub overrides: LedGroups,
+}
+
+impl Leds {
+    /// The pattern shown by a group and the priority it is requested with.
+    ///
+    /// An override is requested with the highest priority, the state pattern with `priority`.
+    fn pattern(
+        &self,
+        group: impl Fn(&LedGroups) -> Option<LedPattern>,
+        priority: Priority,
+    ) -> Option<(LedPattern, Priority)> {
+        group(&self.overrides)
+            .map(|pattern| (pattern, OVERRIDE_PRIORITY))
+            .or_else(|| group(&self.state).map(|pattern| (pattern, priority)))
+    }
+}
+
+/// The state of the robot that is shown on the LEDs.
+#[derive(Debug, Clone, Copy)]
+pub struct LedState {
+    pub primary_state: PrimaryState,
+    pub team_color: Option<TeamColor>,
+    pub game_controller_connected: bool,
+    pub battery_charge: f32,
+}
+
+impl LedGroups {
+    /// Maps the state of the robot to the patterns of the LED groups.
+    #[must_use]
+    pub fn from_state(
+        state: LedState,
+        primary_state_config: &PrimaryStateConfig,
+        config: &LedConfig,
+    ) -> Self {
+        let warning = |color| LedPattern::Blinking {
+            color,
+            interval: config.warning_blink_interval,
+        };
+
+        Self {
+            chest: Some(chest_pattern(state.primary_state, primary_state_config)),
+            eyes: (state.battery_charge < config.low_battery_charge)
+                .then(|| warning(color::f32::RED)),
+            ears: Some(if state.game_controller_connected {
+                LedPattern::Solid(color::f32::BLUE)
+            } else {
+                warning(color::f32::BLUE)
+            }),
+            feet: Some(state.team_color.map_or(LedPattern::OFF, |team_color| {
+                LedPattern::Solid(team_color_rgb(team_color))
+            })),
+        }
+    }
+}
+
+/// The chest pattern for a [`PrimaryState`], as required by the rules.
+#[must_use]
+pub fn chest_pattern(primary_state: PrimaryState, config: &PrimaryStateConfig) -> LedPattern {
+    use PrimaryState as PS;
+
+    match primary_state {
+        PS::Sitting => LedPattern::Blinking {
+            color: color::f32::BLUE,
+            interval: config.chest_blink_interval,
+        },
+        PS::Standby => LedPattern::Solid(color::f32::CYAN),
+        PS::Initial | PS::Finished => LedPattern::Solid(color::f32::GRAY),
+        PS::Ready { .. } => LedPattern::Solid(color::f32::BLUE),
+        PS::Set => LedPattern::Solid(color::f32::YELLOW),
+        PS::Playing { .. } => LedPattern::Solid(color::f32::GREEN),
+        PS::Penalized => LedPattern::Solid(color::f32::RED),
+        PS::Calibration => LedPattern::Solid(color::f32::PURPLE),
+    }
+}
+
+/// The color closest to the jersey color that the LEDs can show.
+fn team_color_rgb(team_color: TeamColor) -> RgbF32 {
+    match team_color {
+        TeamColor::Blue => color::f32::BLUE,
+        TeamColor::Red => color::f32::RED,
+        TeamColor::Yellow => color::f32::YELLOW,
+        // the LEDs cannot show black, so they are turned off
+        TeamColor::Black => color::f32::EMPTY,
+        TeamColor::White => color::f32::WHITE,
+        TeamColor::Green => color::f32::LIME,
+        TeamColor::Orange => color::f32::ORANGE,
+        TeamColor::Purple => color::f32::PURPLE,
+        TeamColor::Brown => color::f32::MAROON,
+        TeamColor::Gray => color::f32::GRAY,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_leds(
+    mut leds: ResMut<Leds>,
+    primary_state: Res<PrimaryState>,
+    game_controller_message: Option<Res<GameControllerMessage>>,
+    connection: Option<Res<GameControllerConnection>>,
+    player_config: Res<PlayerConfig>,
+    nao_state: Res<NaoState>,
+    primary_state_config: Res<PrimaryStateConfig>,
+    config: Res<LedConfig>,
+) {
+    let team_color = game_controller_message
+        .as_ref()
+        .and_then(|message| message.team(player_config.team_number))
+        .map(|team| {
+            if team.goalkeeper == player_config.player_number {
+                team.goalkeeper_colour
+            } else {
+                team.field_player_colour
+            }
+        });
+
+    let state = LedState {
+        primary_state: *primary_state,
+        team_color,
+        game_controller_connected: connection.is_some(),
+        battery_charge: nao_state.battery.charge,
+    };
+
+    leds.state = LedGroups::from_state(state, &primary_state_config, &config);
+}
+
+fn apply_leds(leds: Res<Leds>, time: Res<Time>, mut manager: ResMut<NaoManager>) {
+    let elapsed = time.elapsed();
+
+    if let Some((pattern, priority)) = leds.pattern(|groups| groups.chest, CHEST_PRIORITY) {
+        manager.set_chest_led(pattern.color(elapsed), priority);
+    }
+
+    if let Some((pattern, priority)) = leds.pattern(|groups| groups.eyes, EYES_PRIORITY) {
+        let color = pattern.color(elapsed);
+        manager
+            .set_left_eye_led(LeftEye::fill(color), priority)
+            .set_right_eye_led(RightEye::fill(color), priority);
+    }
+
+    if let Some((pattern, priority)) = leds.pattern(|groups| groups.ears, EARS_PRIORITY) {
+        let intensity = pattern.color(elapsed).blue;
+        manager
+            .set_left_ear_led(LeftEar::fill(intensity), priority)
+            .set_right_ear_led(RightEar::fill(intensity), priority);
+    }
+
+    if let Some((pattern, priority)) = leds.pattern(|groups| groups.feet, FEET_PRIORITY) {
+        let color = pattern.color(elapsed);
+        manager
+            .set_left_foot_led(color, priority)
+            .set_right_foot_led(color, priority);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primary_state_sets_chest_color() {
+        let primary_state_config = PrimaryStateConfig {
+            chest_blink_interval: Duration::from_millis(500),
+        };
+        let config = LedConfig {
+            low_battery_charge: 0.1,
+            warning_blink_interval: Duration::from_millis(250),
+        };
+        let state = LedState {
+            primary_state: PrimaryState::Penalized,
+            team_color: Some(TeamColor::Blue),
+            game_controller_connected: true,
+            battery_charge: 0.8,
+        };
+
+        let groups = LedGroups::from_state(state, &primary_state_config, &config);
+        assert_eq!(groups.chest, Some(LedPattern::Solid(color::f32::RED)));
+        assert_eq!(groups.feet, Some(LedPattern::Solid(color::f32::BLUE)));
+        assert_eq!(groups.eyes, None);
+
+        let playing = LedState {
+            primary_state: PrimaryState::Playing {
+                whistle_in_set: false,
+            },
+            ..state
+        };
+        let groups = LedGroups::from_state(playing, &primary_state_config, &config);
+        assert_eq!(groups.chest, Some(LedPattern::Solid(color::f32::GREEN)));
+
+        // the chest blinks blue while sitting
+        let sitting = LedState {
+            primary_state: PrimaryState::Sitting,
+            ..state
+        };
+        let chest = LedGroups::from_state(sitting, &primary_state_config, &config)
+            .chest
+            .unwrap();
+        assert_eq!(chest.color(Duration::from_millis(200)), color::f32::BLUE);
+        assert_eq!(chest.color(Duration::from_millis(700)), color::f32::EMPTY);
+
+        // an override takes precedence over the state
+        let mut leds = Leds {
+            state: groups,
+            ..Default::default()
+        };
+        leds.overrides.chest = Some(LedPattern::Solid(color::f32::PURPLE));
+        assert_eq!(
+            leds.pattern(|groups| groups.chest, CHEST_PRIORITY),
+            Some((LedPattern::Solid(color::f32::PURPLE), OVERRIDE_PRIORITY))
+        );
+    }
+}
@@ -4,6 +4,7 @@ use bevy::{ecs::system::RunSystemOnce, prelude::*};
 use nidhogg::{NaoBackend, NaoControlMessage, NaoState, backend::LolaBackend};
 
 use crate::core::debug::SerializeComponentBatch;
+use crate::core::shutdown::ShutdownAppExt;
 use crate::{core::debug, prelude::*};
 use crate::{core::debug::DebugContext, nao::RobotInfo};
 
@@ -29,6 +30,12 @@ impl Plugin for LolaPlugin {
             .run_system_once(initialize_nao)
             .expect("failed to initialize nao resources!");
 
+        // closes the connection to the LoLA socket
+        app.add_shutdown_hook("LoLA connection", |world| {
+            world.remove_resource::<Lola>();
+            Ok(())
+        });
+
         app.add_systems(
             Write,
             (
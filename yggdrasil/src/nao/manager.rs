@@ -15,7 +15,7 @@ use nidhogg::{
     },
 };
 /// The stiffness constant for the "unstiff"/"floppy" state for robot joints.
-const STIFFNESS_UNSTIFF: f32 = -1.0;
+pub(super) const STIFFNESS_UNSTIFF: f32 = -1.0;
 /// Stiffness for the hip joints during sitting mode to prevent robot falling over backwards.
 const HIP_LOCK_STIFFNESS: f32 = 0.1;
 /// The set hip position in sitting mode, where the robot sits and starts.
@@ -548,6 +548,39 @@ impl NaoManager {
         )
     }
 
+    /// Overrides the stiffness of single joints, keeping their positions.
+    ///
+    /// A joint is only overridden if the priority of its override is higher than the priority of
+    /// the request that set the legs, arms or head it belongs to. This way overrides cannot e.g.
+    /// stiffen the legs that are made unstiff while sitting.
+    pub fn override_stiffness(
+        &mut self,
+        overrides: &JointArray<Option<(JointValue, Priority)>>,
+    ) -> &mut Self {
+        let priorities = JointArray::builder()
+            .leg_joints(LegJoints::fill(self.leg_settings.priority))
+            .arm_joints(ArmJoints::fill(self.arm_settings.priority))
+            .head_joints(HeadJoints::fill(self.head_settings.priority))
+            .build();
+
+        let stiffness = self
+            .make_joint_stiffnesses()
+            .zip(priorities)
+            .zip(overrides.clone())
+            .map(|((stiffness, current), requested)| match requested {
+                Some((requested, priority)) if current.is_none_or(|current| current < priority) => {
+                    requested
+                }
+                _ => stiffness,
+            });
+
+        self.leg_settings.joints_stiffness = stiffness.leg_joints();
+        self.arm_settings.joints_stiffness = stiffness.arm_joints();
+        self.head_settings.joints_stiffness = stiffness.head_joints();
+
+        self
+    }
+
     pub fn set_left_ear_led(&mut self, left_ear: LeftEar, priority: Priority) -> &mut Self {
         Self::set_led_settings(&mut self.led_left_ear, left_ear, priority);
 
@@ -644,7 +677,7 @@ impl NaoManager {
             .build()
     }
 
-    fn make_joint_stiffnesses(&self) -> JointArray<JointValue> {
+    pub(super) fn make_joint_stiffnesses(&self) -> JointArray<JointValue> {
         JointArray::builder()
             .leg_joints(self.leg_settings.joints_stiffness.clone())
             .arm_joints(self.arm_settings.joints_stiffness.clone())
@@ -3,18 +3,26 @@ use bevy::app::{PluginGroup, PluginGroupBuilder};
 mod battery_led;
 mod center_of_mass;
 mod center_of_pressure;
+mod clock;
 mod cycle;
+mod hardware_health;
 mod head_motion_manager;
+mod leds;
 mod lola;
 mod manager;
 mod robot_info;
+mod stiffness;
 
 pub use center_of_mass::*;
 pub use center_of_pressure::*;
+pub use clock::*;
 pub use cycle::*;
+pub use hardware_health::*;
 pub(crate) use head_motion_manager::*;
+pub use leds::*;
 pub use manager::*;
 pub use robot_info::*;
+pub use stiffness::*;
 
 /// Plugin group which contains convenience plugins for the robot.
 pub struct NaoPlugins;
@@ -23,10 +31,14 @@ impl PluginGroup for NaoPlugins {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
             .add(lola::LolaPlugin)
+            .add(clock::ClockPlugin)
             .add(cycle::CycleTimePlugin)
+            .add(hardware_health::HardwareHealthPlugin)
             .add(battery_led::BatteryLedPlugin)
+            .add(leds::LedPlugin)
             .add(head_motion_manager::HeadMotionManagerPlugin)
             .add(manager::NaoManagerPlugin)
+            .add(stiffness::StiffnessPlugin)
             .add(center_of_mass::CenterOfMassPlugin)
             .add(center_of_pressure::CenterOfPressurePlugin)
     }
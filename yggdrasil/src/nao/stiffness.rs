@@ -0,0 +1,228 @@
+use bevy::prelude::*;
+use miette::{Result, ensure};
+use nidhogg::types::{FillExt, JointArray};
+
+use crate::prelude::*;
+
+use super::{NaoManager, Priority, finalize, manager::STIFFNESS_UNSTIFF};
+
+/// Plugin providing the [`StiffnessRequest`].
+pub(super) struct StiffnessPlugin;
+
+impl Plugin for StiffnessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StiffnessRequest>()
+            .add_systems(PreWrite, apply_stiffness_request.before(finalize));
+    }
+}
+
+/// A group of joints of the robot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limb {
+    Head,
+    LeftArm,
+    RightArm,
+    LeftLeg,
+    RightLeg,
+}
+
+impl Limb {
+    /// Indices of the joints of the limb in a [`JointArray`].
+    fn joints(self) -> &'static [usize] {
+        match self {
+            Limb::Head => &[0, 1],
+            Limb::LeftArm => &[2, 3, 4, 5, 6, 23],
+            Limb::LeftLeg => &[7, 8, 9, 10, 11, 12],
+            Limb::RightArm => &[13, 14, 15, 16, 17, 24],
+            Limb::RightLeg => &[18, 19, 20, 21, 22],
+        }
+    }
+}
+
+/// Overrides of the stiffness of individual joints or limbs, on top of the stiffness set through
+/// the [`NaoManager`].
+///
+/// The stiffness requested by the motion through the [`NaoManager`] is the default, the joints
+/// that are requested here get the requested stiffness instead. This allows e.g. compliant arms
+/// while walking, without every motion having to know about it.
+///
+/// Like the requests to the [`NaoManager`], every override has a priority. A joint is only
+/// overridden if the override has a higher priority than the motion that set the joint, see
+/// [`NaoManager::override_stiffness`].
+///
+/// Requests are only valid for a single cycle, so they have to be made every cycle before
+/// [`finalize`]. When a joint is requested multiple times, the last request wins.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct StiffnessRequest {
+    overrides: JointArray<Option<(f32, Priority)>>,
+}
+
+impl StiffnessRequest {
+    /// Requests the stiffness of the joint at `joint` in a [`JointArray`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stiffness is not in `[0, 1]`, the request is ignored.
+    pub fn set_joint(
+        &mut self,
+        joint: usize,
+        stiffness: f32,
+        priority: Priority,
+    ) -> Result<&mut Self> {
+        validate(stiffness)?;
+
+        Ok(self.override_joint(joint, stiffness, priority))
+    }
+
+    /// Requests the stiffness of all joints of a limb.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stiffness is not in `[0, 1]`, the request is ignored.
+    pub fn set_limb(
+        &mut self,
+        limb: Limb,
+        stiffness: f32,
+        priority: Priority,
+    ) -> Result<&mut Self> {
+        validate(stiffness)?;
+
+        Ok(self.override_limb(limb, stiffness, priority))
+    }
+
+    /// Requests the stiffness of all joints.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stiffness is not in `[0, 1]`, the request is ignored.
+    pub fn set_all(&mut self, stiffness: f32, priority: Priority) -> Result<&mut Self> {
+        validate(stiffness)?;
+
+        self.overrides = JointArray::fill(Some((stiffness, priority)));
+
+        Ok(self)
+    }
+
+    /// Requests the joint at `joint` in a [`JointArray`] to be unstiff, which disables its motor.
+    pub fn unstiff_joint(&mut self, joint: usize, priority: Priority) -> &mut Self {
+        self.override_joint(joint, STIFFNESS_UNSTIFF, priority)
+    }
+
+    /// Requests all joints of a limb to be unstiff, which disables their motors.
+    pub fn unstiff_limb(&mut self, limb: Limb, priority: Priority) -> &mut Self {
+        self.override_limb(limb, STIFFNESS_UNSTIFF, priority)
+    }
+
+    fn override_joint(&mut self, joint: usize, stiffness: f32, priority: Priority) -> &mut Self {
+        if let Some(value) = self.overrides.get_mut(joint) {
+            *value = Some((stiffness, priority));
+        }
+
+        self
+    }
+
+    fn override_limb(&mut self, limb: Limb, stiffness: f32, priority: Priority) -> &mut Self {
+        for &joint in limb.joints() {
+            *self.overrides.as_array_mut()[joint] = Some((stiffness, priority));
+        }
+
+        self
+    }
+
+    fn clear(&mut self) {
+        self.overrides = JointArray::default();
+    }
+}
+
+fn validate(stiffness: f32) -> Result<()> {
+    ensure!(
+        (0.0..=1.0).contains(&stiffness),
+        "stiffness {stiffness} is not in [0, 1]"
+    );
+
+    Ok(())
+}
+
+fn apply_stiffness_request(mut manager: ResMut<NaoManager>, mut request: ResMut<StiffnessRequest>) {
+    manager.override_stiffness(&request.overrides);
+    request.clear();
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use nidhogg::types::{ArmJoints, HeadJoints, LegJoints};
+
+    use super::*;
+
+    #[test]
+    fn limb_override_keeps_other_defaults() {
+        let mut manager = NaoManager::default();
+        manager.set_all(
+            JointArray::default(),
+            HeadJoints::fill(0.8),
+            ArmJoints::fill(0.8),
+            LegJoints::fill(0.8),
+            Priority::Medium,
+        );
+
+        let mut request = StiffnessRequest::default();
+        request
+            .set_limb(Limb::LeftArm, 0.2, Priority::High)
+            .unwrap()
+            .set_joint(0, 0.5, Priority::High)
+            .unwrap();
+
+        assert!(
+            request
+                .set_limb(Limb::RightLeg, 1.5, Priority::High)
+                .is_err()
+        );
+        assert!(request.set_joint(3, -1.0, Priority::High).is_err());
+
+        // invalid requests are ignored
+        manager.override_stiffness(&request.overrides);
+
+        let expected = JointArray {
+            head_yaw: 0.5,
+            left_shoulder_pitch: 0.2,
+            left_shoulder_roll: 0.2,
+            left_elbow_yaw: 0.2,
+            left_elbow_roll: 0.2,
+            left_wrist_yaw: 0.2,
+            left_hand: 0.2,
+            ..JointArray::fill(0.8)
+        };
+        assert_eq!(manager.make_joint_stiffnesses(), expected);
+    }
+
+    #[test]
+    fn override_respects_priorities() {
+        let mut manager = NaoManager::default();
+        manager.unstiff_sit(Priority::High).set_arms(
+            ArmJoints::default(),
+            ArmJoints::fill(0.8),
+            Priority::Medium,
+        );
+
+        let mut request = StiffnessRequest::default();
+        request
+            .set_all(0.5, Priority::Medium)
+            .unwrap()
+            .unstiff_limb(Limb::RightArm, Priority::High);
+        manager.override_stiffness(&request.overrides);
+
+        let stiffness = manager.make_joint_stiffnesses();
+        // the unstiff legs of the sitting robot are not stiffened again
+        assert_eq!(
+            stiffness.leg_joints(),
+            manager.current_legs().joints_stiffness
+        );
+        assert_eq!(stiffness.left_knee_pitch, STIFFNESS_UNSTIFF);
+        // the arms have been set with the same priority, so only the right arm is overridden
+        assert_eq!(stiffness.left_shoulder_pitch, 0.8);
+        assert_eq!(stiffness.right_shoulder_pitch, STIFFNESS_UNSTIFF);
+        // the head has not been set, so it is overridden
+        assert_eq!(stiffness.head_yaw, 0.5);
+    }
+}
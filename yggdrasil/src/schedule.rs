@@ -1,5 +1,54 @@
+//! The robot specific schedules, and the order in which they run.
+//!
+//! Every cycle, the [`Main`] schedule runs the following schedules in order:
+//!
+//! | Schedule          | Purpose                                                             |
+//! |-------------------|---------------------------------------------------------------------|
+//! | [`First`]         | Bevy internals, such as updating [`Time`] and events                |
+//! | [`Sensor`]        | Reading the sensor data received from `LoLA` and derived resources  |
+//! | [`FixedCycle`]    | Zero or more times, at a fixed rate, see [`FixedCycleTimestep`]     |
+//! | [`PreUpdate`]     | Bevy internals                                                      |
+//! | [`Update`]        | Vision, localization, behavior and motion                           |
+//! | [`PostUpdate`]    | Bevy internals, and debug visualization                             |
+//! | [`PreWrite`]      | Finalizing the actuator requests, such as the `NaoManager`          |
+//! | [`Write`]         | Writing the control message to `LoLA`                               |
+//! | [`PostWrite`]     | Systems that need the data that was just written                    |
+//! | [`Last`]          | Bevy internals                                                      |
+//!
+//! These schedules are part of the public API of the framework, so modules and tools outside of
+//! the framework can add their own systems to them, without depending on the systems of other
+//! modules:
+//!
+//! ```
+//! use bevy::prelude::*;
+//! use yggdrasil::{prelude::*, sensor::imu::IMUValues};
+//!
+//! struct ImuLoggingPlugin;
+//!
+//! impl Plugin for ImuLoggingPlugin {
+//!     fn build(&self, app: &mut App) {
+//!         // runs after the sensor data for this cycle has been read
+//!         app.add_systems(Sensor, log_imu.run_if(resource_exists::<IMUValues>));
+//!     }
+//! }
+//!
+//! fn log_imu(imu: Res<IMUValues>) {
+//!     tracing::debug!(gyroscope = ?imu.gyroscope, "imu");
+//! }
+//! ```
+//!
+//! The order of the schedules is configured by [`NaoSchedulePlugin`], but it does not matter
+//! whether a plugin adds its systems before or after that plugin is added: systems in a schedule
+//! always run in the position of that schedule in the table above. Within a schedule, systems are
+//! only ordered with respect to each other through explicit ordering constraints, such as
+//! [`IntoScheduleConfigs::after`].
+
+use std::time::Duration;
+
 use bevy::{app::MainScheduleOrder, ecs::schedule::ScheduleLabel, prelude::*};
 
+use crate::nao::CycleTime;
+
 /// The schedule that contains logic that updates resources using sensor data.
 ///
 /// This schedule runs directly after the [`First`] schedule, and is used to update resources
@@ -25,7 +74,119 @@ pub struct Write;
 #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PostWrite;
 
+/// The schedule that runs at a fixed rate, independent of the rate at which cycles complete.
+///
+/// The schedule is run zero or more times per cycle, directly after [`Sensor`], based on the time
+/// accumulated in [`FixedCycleTimestep`]. If a cycle stalled, it is run multiple times to catch up,
+/// similar to a fixed physics update. This is useful for systems such as filter predict steps,
+/// that should run at a stable rate.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FixedCycle;
+
+/// The schedule that runs [`FixedCycle`] as many times as needed to keep up with [`CycleTime`].
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct RunFixedCycle;
+
+/// Resource that keeps track of the time step of the [`FixedCycle`] schedule.
+#[derive(Resource, Debug, Clone)]
+pub struct FixedCycleTimestep {
+    step: Duration,
+    accumulator: Duration,
+    max_steps: u32,
+}
+
+impl Default for FixedCycleTimestep {
+    fn default() -> Self {
+        // the hardware runs at around 83Hz
+        Self::new(Duration::from_millis(12))
+    }
+}
+
+impl FixedCycleTimestep {
+    /// The default maximum number of steps per cycle, see [`Self::with_max_steps`].
+    pub const DEFAULT_MAX_STEPS: u32 = 8;
+
+    /// Create a new timestep that runs the [`FixedCycle`] schedule once per `step`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    #[must_use]
+    pub fn new(step: Duration) -> Self {
+        assert!(!step.is_zero(), "fixed timestep must be non-zero");
+
+        Self {
+            step,
+            accumulator: Duration::ZERO,
+            max_steps: Self::DEFAULT_MAX_STEPS,
+        }
+    }
+
+    /// Limit the number of catch-up steps in a single cycle.
+    ///
+    /// Time that would require more steps is dropped, to prevent a stall from snowballing into
+    /// ever longer cycles.
+    #[must_use]
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// The duration of a single step.
+    #[must_use]
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// Time that has accumulated but is not yet consumed by a step.
+    #[must_use]
+    pub fn overstep(&self) -> Duration {
+        self.accumulator
+    }
+
+    /// Accumulate `elapsed` time and return the number of steps that should be run.
+    pub fn accumulate(&mut self, elapsed: Duration) -> u32 {
+        self.accumulator += elapsed;
+
+        let mut steps = 0;
+        while self.accumulator >= self.step {
+            if steps == self.max_steps {
+                tracing::warn!(
+                    dropped = ?self.accumulator,
+                    "fixed cycle is falling behind, dropping accumulated time"
+                );
+                self.accumulator = Duration::ZERO;
+                break;
+            }
+
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        steps
+    }
+}
+
+fn run_fixed_cycle(world: &mut World) {
+    let Some(elapsed) = world
+        .get_resource::<CycleTime>()
+        .map(|cycle_time| cycle_time.duration)
+    else {
+        return;
+    };
+
+    let steps = world
+        .resource_mut::<FixedCycleTimestep>()
+        .accumulate(elapsed);
+
+    for _ in 0..steps {
+        world.run_schedule(FixedCycle);
+    }
+}
+
 /// Plugin configures the robot specific schedules in the [`MainScheduleOrder`].
+///
+/// See the [module documentation](self) for the order in which the schedules run.
 pub struct NaoSchedulePlugin;
 
 impl Plugin for NaoSchedulePlugin {
@@ -34,9 +195,77 @@ impl Plugin for NaoSchedulePlugin {
         app.world_mut()
             .resource_scope(|_, mut schedule: Mut<MainScheduleOrder>| {
                 schedule.insert_after(First, Sensor);
+                schedule.insert_after(Sensor, RunFixedCycle);
                 schedule.insert_after(PostUpdate, PreWrite);
                 schedule.insert_after(PreWrite, Write);
                 schedule.insert_after(Write, PostWrite);
             });
+
+        app.init_schedule(FixedCycle)
+            .init_resource::<FixedCycleTimestep>()
+            .add_systems(RunFixedCycle, run_fixed_cycle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Steps(u32);
+
+    fn world(step: Duration) -> World {
+        let mut world = World::new();
+        world.insert_resource(FixedCycleTimestep::new(step));
+        world.insert_resource(CycleTime {
+            cycle_start: Instant::now(),
+            duration: Duration::ZERO,
+        });
+        world.init_resource::<Steps>();
+
+        let mut schedule = Schedule::new(FixedCycle);
+        schedule.add_systems(|mut steps: ResMut<Steps>| steps.0 += 1);
+        world.add_schedule(schedule);
+
+        world
+    }
+
+    fn run_cycle(world: &mut World, duration: Duration) -> u32 {
+        world.resource_mut::<CycleTime>().duration = duration;
+        world.resource_mut::<Steps>().0 = 0;
+        run_fixed_cycle(world);
+        world.resource::<Steps>().0
+    }
+
+    #[test]
+    fn fixed_cycle_catches_up_after_stall() {
+        let mut world = world(Duration::from_millis(10));
+
+        assert_eq!(run_cycle(&mut world, Duration::from_millis(6)), 0);
+        assert_eq!(run_cycle(&mut world, Duration::from_millis(6)), 1);
+
+        // the main loop stalled, so multiple steps are needed to catch up
+        assert_eq!(run_cycle(&mut world, Duration::from_millis(43)), 4);
+        assert_eq!(
+            world.resource::<FixedCycleTimestep>().overstep(),
+            Duration::from_millis(5)
+        );
+
+        assert_eq!(run_cycle(&mut world, Duration::from_millis(5)), 1);
+        assert_eq!(
+            world.resource::<FixedCycleTimestep>().overstep(),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn fixed_cycle_limits_catch_up_steps() {
+        let mut timestep = FixedCycleTimestep::new(Duration::from_millis(10)).with_max_steps(3);
+
+        assert_eq!(timestep.accumulate(Duration::from_millis(100)), 3);
+        assert_eq!(timestep.overstep(), Duration::ZERO);
+        assert_eq!(timestep.accumulate(Duration::from_millis(10)), 1);
     }
 }
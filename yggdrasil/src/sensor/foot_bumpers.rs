@@ -213,10 +213,8 @@ fn set_foot_leds(manager: &mut NaoManager, current_state: ObstacleStatus) {
             manager.set_left_foot_led(color::f32::BLUE, Priority::Critical);
             manager.set_right_foot_led(color::f32::BLUE, Priority::Critical);
         }
-        ObstacleStatus::NotDetected => {
-            manager.set_left_foot_led(color::f32::EMPTY, Priority::Critical);
-            manager.set_right_foot_led(color::f32::EMPTY, Priority::Critical);
-        }
+        // leave the feet to the team color, see `Leds`
+        ObstacleStatus::NotDetected => {}
     }
 }
 
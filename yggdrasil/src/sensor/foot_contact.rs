@@ -0,0 +1,209 @@
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::{DurationMilliSeconds, serde_as};
+
+use super::{
+    SensorConfig,
+    fsr::{Contacts, update_contacts},
+};
+use crate::{motion::walking_engine::Side, nao::Clock, prelude::*};
+
+/// Plugin that detects touchdown of the feet using the ground contact of each foot.
+///
+/// A [`FootTouchdown`] event is emitted whenever a foot makes ground contact. Contact changes are
+/// debounced, so short spikes in the FSR readings during double support do not result in spurious
+/// events.
+///
+/// The walking engine ends a step once the swing foot touches down, the support foot itself is
+/// tracked by the
+/// [`FootSupportState`](crate::motion::walking_engine::foot_support::FootSupportState).
+pub struct FootContactPlugin;
+
+impl Plugin for FootContactPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FootContactTracker>()
+            .add_event::<FootTouchdown>()
+            .add_systems(Sensor, update_foot_contact.after(update_contacts));
+    }
+}
+
+/// Configuration for the foot contact tracking.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FootContactConfig {
+    /// The time a change in ground contact of a foot has to persist before it is accepted, in
+    /// milliseconds.
+    #[serde_as(as = "DurationMilliSeconds")]
+    pub debounce: Duration,
+}
+
+/// Event that is sent when a foot makes ground contact.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FootTouchdown {
+    pub side: Side,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContactChange {
+    Touchdown(Side),
+    Liftoff(Side),
+}
+
+/// Ground contact of a single foot, which only changes once the raw contact has been stable for
+/// the debounce duration.
+#[derive(Debug, Clone, Copy)]
+struct DebouncedContact {
+    contact: bool,
+    pending_since: Option<Instant>,
+}
+
+impl DebouncedContact {
+    /// Update the contact with the raw reading, returning whether the contact changed.
+    fn update(&mut self, raw: bool, now: Instant, debounce: Duration) -> bool {
+        if raw == self.contact {
+            self.pending_since = None;
+            return false;
+        }
+
+        let pending_since = *self.pending_since.get_or_insert(now);
+        if now.duration_since(pending_since) < debounce {
+            return false;
+        }
+
+        self.contact = raw;
+        self.pending_since = None;
+        true
+    }
+}
+
+/// The debounced ground contact of both feet.
+#[derive(Resource, Debug, Clone, Copy)]
+pub(crate) struct FootContactTracker {
+    left: DebouncedContact,
+    right: DebouncedContact,
+}
+
+impl Default for FootContactTracker {
+    fn default() -> Self {
+        let contact = DebouncedContact {
+            contact: true,
+            pending_since: None,
+        };
+
+        Self {
+            left: contact,
+            right: contact,
+        }
+    }
+}
+
+impl FootContactTracker {
+    /// Update the contacts with the raw readings of both feet, returning the accepted changes.
+    fn update(
+        &mut self,
+        left: bool,
+        right: bool,
+        now: Instant,
+        debounce: Duration,
+    ) -> [Option<ContactChange>; 2] {
+        let change = |side: Side, contact: &mut DebouncedContact, raw: bool| {
+            let changed = contact.update(raw, now, debounce);
+            let change = if contact.contact {
+                ContactChange::Touchdown(side)
+            } else {
+                ContactChange::Liftoff(side)
+            };
+
+            changed.then_some(change)
+        };
+
+        [
+            change(Side::Left, &mut self.left, left),
+            change(Side::Right, &mut self.right, right),
+        ]
+    }
+}
+
+pub(crate) fn update_foot_contact(
+    config: Res<SensorConfig>,
+    contacts: Res<Contacts>,
+    clock: Res<Clock>,
+    mut tracker: ResMut<FootContactTracker>,
+    mut touchdown: EventWriter<FootTouchdown>,
+) {
+    let changes = tracker.update(
+        contacts.left_foot,
+        contacts.right_foot,
+        clock.now(),
+        config.foot_contact.debounce,
+    );
+
+    for change in changes.into_iter().flatten() {
+        if let ContactChange::Touchdown(side) = change {
+            touchdown.write(FootTouchdown { side });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CYCLE: Duration = Duration::from_millis(12);
+    const DEBOUNCE: Duration = Duration::from_millis(20);
+
+    #[test]
+    fn tracks_gait_cycle() {
+        let start = Instant::now();
+        let mut tracker = FootContactTracker::default();
+
+        // (left, right) contact for each cycle
+        let pattern = [
+            // double support, with the right foot lifting off
+            (true, true),
+            (true, false),
+            (true, false),
+            (true, false),
+            // spurious contact of the swing foot
+            (true, true),
+            (true, false),
+            (true, false),
+            // right foot touches down, left foot lifts off
+            (true, true),
+            (true, true),
+            (true, true),
+            (false, true),
+            (false, true),
+            (false, true),
+            // left foot touches down
+            (true, true),
+            (true, true),
+            (true, true),
+        ];
+
+        let mut changes = Vec::new();
+
+        for (cycle, (left, right)) in pattern.into_iter().enumerate() {
+            let now = start + CYCLE * cycle as u32;
+            changes.extend(
+                tracker
+                    .update(left, right, now, DEBOUNCE)
+                    .into_iter()
+                    .flatten(),
+            );
+        }
+
+        assert_eq!(
+            changes,
+            [
+                ContactChange::Liftoff(Side::Right),
+                ContactChange::Touchdown(Side::Right),
+                ContactChange::Liftoff(Side::Left),
+                ContactChange::Touchdown(Side::Left),
+            ]
+        );
+    }
+}
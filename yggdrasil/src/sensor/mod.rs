@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 pub mod button;
 pub mod falling;
 pub mod foot_bumpers;
+pub mod foot_contact;
 pub mod fsr;
 pub mod imu;
 pub mod low_pass_filter;
@@ -19,6 +20,7 @@ impl PluginGroup for SensorPlugins {
             .add(button::ButtonPlugin)
             .add(foot_bumpers::FootBumperPlugin)
             .add(fsr::FSRSensorPlugin)
+            .add(foot_contact::FootContactPlugin)
             .add(imu::IMUSensorPlugin)
             .add(sonar::SonarSensorPlugin)
             .add(orientation::OrientationFilterPlugin)
@@ -33,9 +35,15 @@ pub struct SensorConfig {
     /// Configuration for the FSR sensor.
     pub fsr: fsr::FsrConfig,
 
+    /// Configuration for the foot contact tracking.
+    pub foot_contact: foot_contact::FootContactConfig,
+
     /// Configuration for the button sensitivities.
     pub button: button::ButtonConfig,
 
     /// Configuration for the foot bumpers.
     pub foot_bumpers: foot_bumpers::FootBumperConfig,
+
+    /// Configuration for the sonar obstacle detection.
+    pub sonar: sonar::SonarConfig,
 }
@@ -5,7 +5,7 @@ use nalgebra::{Quaternion, UnitQuaternion, Vector3};
 use serde::{Deserialize, Serialize};
 use serde_with::{DurationMilliSeconds, DurationSeconds, serde_as};
 use std::time::Duration;
-use vqf::Vqf;
+use vqf::{Vqf, VqfParameters};
 
 /// The NAO's IMU update rate.
 ///
@@ -48,6 +48,18 @@ pub struct RobotOrientation {
 }
 
 impl RobotOrientation {
+    /// Creates an orientation filter for the IMU rate of the NAO, which is initialized once it
+    /// receives IMU samples.
+    #[must_use]
+    pub fn new(parameters: VqfParameters) -> Self {
+        let imu_sample_period = Duration::from_secs_f32(1.0 / IMU_RATE);
+
+        Self {
+            vqf: Vqf::new(imu_sample_period, imu_sample_period, parameters),
+            yaw_offset: None,
+        }
+    }
+
     /// Returns whether the orientation filter is initialized.
     #[must_use]
     pub fn is_initialized(&self) -> bool {
@@ -104,13 +116,7 @@ impl RobotOrientation {
 }
 
 fn init_vqf(mut commands: Commands, config: Res<OrientationFilterConfig>) {
-    let imu_sample_period = Duration::from_secs_f32(1.0 / IMU_RATE);
-
-    let vqf = Vqf::new(imu_sample_period, imu_sample_period, config.as_ref().into());
-    commands.insert_resource(RobotOrientation {
-        vqf,
-        yaw_offset: None,
-    });
+    commands.insert_resource(RobotOrientation::new(config.as_ref().into()));
 }
 
 /// System that resets the orientation each cycle, iff we're in a state that doesn't need orientation data.
@@ -235,7 +241,7 @@ pub struct OrientationFilterConfig {
     pub rest_threshold_accel: f32,
 }
 
-impl From<&OrientationFilterConfig> for vqf::VqfParameters {
+impl From<&OrientationFilterConfig> for VqfParameters {
     fn from(config: &OrientationFilterConfig) -> Self {
         Self {
             tau_accelerometer: config.tau_accelerometer,
@@ -1,6 +1,13 @@
+use std::collections::VecDeque;
+
+use super::SensorConfig;
 use crate::prelude::*;
 use bevy::prelude::*;
-use nidhogg::{NaoState, types::SonarValues};
+use nidhogg::{
+    NaoControlMessage, NaoState,
+    types::{SonarEnabled, SonarValues},
+};
+use serde::{Deserialize, Serialize};
 
 /// Plugin that offers a structured wrappers for sonar,
 /// derived from the raw [`NaoState`].
@@ -8,12 +15,126 @@ pub struct SonarSensorPlugin;
 
 impl Plugin for SonarSensorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Sensor, sonar_sensor)
-            .init_resource::<SonarValues>();
+        app.add_systems(Sensor, (sonar_sensor, detect_sonar_obstacles).chain())
+            .add_systems(PreWrite, enable_sonar)
+            .init_resource::<SonarValues>()
+            .init_resource::<SonarObstacles>()
+            .init_resource::<SonarFilters>();
+    }
+}
+
+/// Configuration for the sonar obstacle detection.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SonarConfig {
+    /// Number of consecutive readings the median is taken over.
+    pub window_size: usize,
+    /// Readings at or above this distance mean that there is no echo, in meters.
+    pub max_range: f32,
+}
+
+/// Distance to the closest obstacle in front of the left and right sonar, in meters.
+///
+/// A side is `None` if there is no obstacle within the range of its sonar.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct SonarObstacles {
+    pub left: Option<f32>,
+    pub right: Option<f32>,
+}
+
+/// Median filter over the most recent readings of a sonar, which rejects single spikes.
+#[derive(Debug, Default, Clone)]
+pub struct SonarFilter {
+    window: VecDeque<f32>,
+}
+
+impl SonarFilter {
+    /// Adds a raw sonar reading, and returns the filtered distance to the obstacle.
+    ///
+    /// Readings of zero are errors, and readings at or above the maximum range mean that there is
+    /// no echo. Both count as no obstacle, so `None` is returned if that is the median.
+    pub fn update(&mut self, reading: f32, config: &SonarConfig) -> Option<f32> {
+        let distance = if reading <= 0.0 || reading >= config.max_range {
+            f32::INFINITY
+        } else {
+            reading
+        };
+
+        self.window.push_back(distance);
+        while self.window.len() > config.window_size.max(1) {
+            self.window.pop_front();
+        }
+
+        let mut sorted: Vec<_> = self.window.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let median = sorted[sorted.len() / 2];
+
+        median.is_finite().then_some(median)
     }
 }
 
+#[derive(Resource, Debug, Default, Clone)]
+struct SonarFilters {
+    left: SonarFilter,
+    right: SonarFilter,
+}
+
 fn sonar_sensor(nao_state: Res<NaoState>, mut sonar: ResMut<SonarValues>) {
     sonar.left = nao_state.sonar.left;
     sonar.right = nao_state.sonar.right;
 }
+
+fn detect_sonar_obstacles(
+    config: Res<SensorConfig>,
+    sonar: Res<SonarValues>,
+    mut filters: ResMut<SonarFilters>,
+    mut obstacles: ResMut<SonarObstacles>,
+) {
+    let config = &config.sonar;
+
+    obstacles.set_if_neq(SonarObstacles {
+        left: filters.left.update(sonar.left, config),
+        right: filters.right.update(sonar.right, config),
+    });
+}
+
+fn enable_sonar(mut control_message: ResMut<NaoControlMessage>) {
+    control_message.sonar = SonarEnabled {
+        left: true,
+        right: true,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_rejects_outlier_spike() {
+        let config = SonarConfig {
+            window_size: 5,
+            max_range: 5.0,
+        };
+        let mut filter = SonarFilter::default();
+
+        let readings = [0.80, 0.78, 0.81, 0.05, 0.79, 0.77, 5.0, 0.76, 0.75];
+        let filtered: Vec<_> = readings
+            .into_iter()
+            .map(|reading| filter.update(reading, &config))
+            .collect();
+
+        // neither the close spike nor the missing echo get through
+        for distance in &filtered {
+            let distance = distance.expect("obstacle should stay detected");
+            assert!((0.74..=0.82).contains(&distance), "distance: {distance}");
+        }
+        assert_eq!(filtered.last(), Some(&Some(0.77)));
+
+        // once the obstacle is gone, the sonar reports no echo
+        let filtered: Vec<_> = [5.0, 5.0, 0.0, 5.0]
+            .into_iter()
+            .map(|reading| filter.update(reading, &config))
+            .collect();
+        assert_eq!(filtered, [Some(0.77), None, None, None]);
+    }
+}
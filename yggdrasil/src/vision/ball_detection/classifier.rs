@@ -6,13 +6,14 @@ use bevy::prelude::*;
 use heimdall::{Bottom, CameraLocation, CameraMatrix, Top};
 use itertools::Itertools;
 use ml::prelude::ModelExecutor;
-use nalgebra::Point2;
+use nalgebra::{Matrix2, Matrix3, Point2};
 
 use serde::{Deserialize, Serialize};
 use serde_with::{DurationMicroSeconds, serde_as};
 
 use crate::core::debug::DebugContext;
 
+use crate::localization::history::PoseHistory;
 use crate::nao::Cycle;
 use crate::vision::referee::detect::VisualRefereeDetectionStatus;
 use ml::prelude::*;
@@ -31,6 +32,12 @@ pub struct BallClassifierConfig {
     /// The amount of time in microseconds we allow the classifier to run, proposals that take longer are discarded.
     #[serde_as(as = "DurationMicroSeconds<u64>")]
     pub time_budget: Duration,
+
+    /// Standard deviation of the ball center in the image, in pixels
+    pub pixel_noise: f32,
+
+    /// Standard deviation of the orientation of the camera relative to the ground, in radians
+    pub orientation_noise: f32,
 }
 
 /// Plugin for classifying ball proposals produced by [`super::proposal::BallProposalPlugin`].
@@ -48,7 +55,8 @@ impl Plugin for BallClassifierPlugin {
                     .run_if(resource_exists_and_changed::<BallProposals<Bottom>>),
             )
                 .chain()
-                .run_if(in_state(VisualRefereeDetectionStatus::Inactive)),
+                .run_if(in_state(VisualRefereeDetectionStatus::Inactive))
+                .run_if(ml::model_loaded::<BallClassifierModel>),
         );
     }
 }
@@ -66,11 +74,13 @@ impl MlModel for BallClassifierModel {
 pub struct BallPerception {
     /// Ball position relative to the robot
     pub position: Point2<f32>,
+    /// Covariance of the ball position, propagated from the uncertainty of the projection
+    pub covariance: Matrix2<f32>,
     pub cycle: Cycle,
 }
 
 #[allow(clippy::too_many_arguments)]
-fn classify_balls<T: CameraLocation>(
+pub(super) fn classify_balls<T: CameraLocation>(
     ctx: DebugContext,
     cycle: Res<Cycle>,
     mut commands: Commands,
@@ -78,6 +88,7 @@ fn classify_balls<T: CameraLocation>(
     mut model: ResMut<ModelExecutor<BallClassifierModel>>,
     camera_matrix: Res<CameraMatrix<T>>,
     config: Res<BallDetectionConfig>,
+    pose_history: Res<PoseHistory>,
 ) {
     let classifier = &config.classifier;
     let start = Instant::now();
@@ -119,15 +130,29 @@ fn classify_balls<T: CameraLocation>(
             continue;
         }
 
-        let Ok(robot_to_ball) = camera_matrix.pixel_to_ground(proposal.position.cast(), 0.0) else {
+        let pixel_cov = Matrix2::identity() * classifier.pixel_noise.powi(2);
+        let rotation_cov = Matrix3::identity() * classifier.orientation_noise.powi(2);
+        let Ok((robot_to_ball, covariance)) = camera_matrix.pixel_to_ground_with_cov(
+            proposal.position.cast(),
+            0.0,
+            pixel_cov,
+            Some(rotation_cov),
+        ) else {
             tracing::warn!(?proposal.position, "failed to project ball position to ground");
             continue;
         };
 
-        let position = robot_to_ball.xy();
+        // the robot moved since the image was captured, so project the ball relative to the
+        // robot now
+        let motion = pose_history.motion_since(proposals.image.timestamp());
+        let position = motion * robot_to_ball.xy();
+        let rotation = motion.rotation.to_rotation_matrix();
+        let covariance =
+            rotation.matrix() * covariance.fixed_view::<2, 2>(0, 0) * rotation.matrix().transpose();
 
         commands.spawn(BallPerception {
             position,
+            covariance,
             cycle: *cycle,
         });
 
@@ -0,0 +1,81 @@
+//! Sharing the position of the ball with teammates, see [`TeammateBall`].
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use nalgebra::Point2;
+
+use crate::{
+    communication::{TeamCommunication, TeamMessage},
+    localization::RobotPose,
+    nao::{Clock, Cycle},
+};
+
+use super::hypothesis::Ball;
+
+/// Plugin that sends the position of a freshly detected ball to teammates, and keeps track of
+/// the most recent ball reported by a teammate.
+pub struct BallCommunicationPlugin;
+
+impl Plugin for BallCommunicationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TeammateBall>().add_systems(
+            Update,
+            (
+                send_ball.run_if(resource_exists::<TeamCommunication>),
+                receive_ball.run_if(resource_exists::<TeamCommunication>),
+            ),
+        );
+    }
+}
+
+/// The most recent position of the ball as reported by a teammate, in world coordinates.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct TeammateBall {
+    report: Option<(Point2<f32>, Instant)>,
+}
+
+impl TeammateBall {
+    /// Stores a ball reported by a teammate at `received_at`.
+    pub fn report(&mut self, position: Point2<f32>, received_at: Instant) {
+        self.report = Some((position, received_at));
+    }
+
+    /// The reported position of the ball, if it was received within `max_age`.
+    #[must_use]
+    pub fn recent(&self, clock: &Clock, max_age: Duration) -> Option<Point2<f32>> {
+        self.report
+            .filter(|(_, received_at)| clock.elapsed(*received_at) <= max_age)
+            .map(|(position, _)| position)
+    }
+}
+
+fn send_ball(
+    mut tc: ResMut<TeamCommunication>,
+    ball: Res<Ball>,
+    pose: Res<RobotPose>,
+    cycle: Res<Cycle>,
+) {
+    // only share balls that have been seen this cycle, to not spend the budget on stale ones
+    let Some(ball) = ball.as_option().filter(|ball| ball.last_cycle == *cycle) else {
+        return;
+    };
+
+    let position = pose.robot_to_world(&ball.position);
+    tc.stage(TeamMessage::DetectedBall([position.x, position.y]));
+}
+
+fn receive_ball(
+    mut tc: ResMut<TeamCommunication>,
+    mut teammate_ball: ResMut<TeammateBall>,
+    clock: Res<Clock>,
+) {
+    let incoming_msg = tc.inbound_mut().take_map(|_, _, msg| match &msg.message {
+        TeamMessage::DetectedBall([x, y]) => Some(Point2::new(*x, *y)),
+        _ => None,
+    });
+
+    if let Some((_, _, position)) = incoming_msg {
+        teammate_ball.report(position, clock.now());
+    }
+}
@@ -0,0 +1,245 @@
+//! See [`BallConfirmationPlugin`].
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use heimdall::{Bottom, Top};
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+
+use crate::localization::odometry::Odometry;
+
+use super::{classifier, hypothesis, proposal::BallProposals};
+
+#[derive(Debug, Clone, Default, Resource, Serialize, Deserialize)]
+pub struct BallConfirmationConfig {
+    /// Number of most recent frames that are considered when confirming a ball (N)
+    pub window_size: usize,
+
+    /// Minimum number of frames within the window that need a consistent detection, before a
+    /// ball is confirmed (K). The ball is lost again once this many frames lack a detection.
+    pub min_detections: usize,
+
+    /// Maximum distance in meters between detections in different frames to be consistent
+    pub max_detection_distance: f32,
+}
+
+impl BallConfirmationConfig {
+    /// Checks that a ball can be confirmed at all within the window.
+    pub fn validate(&self) -> Result<(), odal::ValidationError> {
+        if self.min_detections > self.window_size {
+            return Err(odal::ValidationError::new(format!(
+                "`confirmation.min_detections` ({}) must be at most `confirmation.window_size` ({})",
+                self.min_detections, self.window_size
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Plugin that filters single-frame false positives from the ball detections.
+///
+/// A [`BallPerception`](super::classifier::BallPerception) is only passed on to the ball
+/// hypotheses once the ball has been detected at a consistent position in enough recent frames,
+/// see [`BallConfirmation`].
+pub struct BallConfirmationPlugin;
+
+impl Plugin for BallConfirmationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BallConfirmation>().add_systems(
+            Update,
+            confirm_ball_perceptions
+                .after(classifier::classify_balls::<Top>)
+                .after(classifier::classify_balls::<Bottom>)
+                .before(hypothesis::measurement_update)
+                .run_if(
+                    resource_exists_and_changed::<BallProposals<Top>>
+                        .or(resource_exists_and_changed::<BallProposals<Bottom>>),
+                ),
+        );
+    }
+}
+
+/// Detections of the ball in the most recent frames.
+///
+/// Detections are stored in the odometry frame, so detections from earlier frames can be
+/// compared to new ones while the robot is moving.
+///
+/// A ball is confirmed once [`BallConfirmationConfig::min_detections`] frames in the window
+/// contain a detection close to the newest one. After that, detections close to the confirmed
+/// ball are accepted without further support, and the confirmed position follows the ball as it
+/// moves. The confirmation is lost once as many frames in the window have no detection at all.
+/// Losing the confirmation only gates new measurements, the ball hypotheses are kept until they
+/// become too uncertain, so a ball that is out of view is not forgotten.
+#[derive(Resource, Debug, Default)]
+pub struct BallConfirmation {
+    frames: VecDeque<Vec<Point2<f32>>>,
+    /// Position of the confirmed ball in the odometry frame, if any.
+    confirmed: Option<Point2<f32>>,
+}
+
+impl BallConfirmation {
+    /// Adds the detections of a new frame, and updates whether the ball is confirmed.
+    pub fn push_frame(&mut self, detections: Vec<Point2<f32>>, config: &BallConfirmationConfig) {
+        self.frames.push_back(detections);
+        while self.frames.len() > config.window_size {
+            self.frames.pop_front();
+        }
+
+        let newest = self.frames.back().map(Vec::as_slice).unwrap_or_default();
+        self.confirmed = match self.confirmed {
+            Some(confirmed) => {
+                let misses = self.frames.iter().filter(|frame| frame.is_empty()).count();
+                (misses < config.min_detections).then(|| {
+                    // follow the ball to the closest consistent detection in the newest frame
+                    newest
+                        .iter()
+                        .copied()
+                        .filter(|detection| {
+                            nalgebra::distance(&confirmed, detection)
+                                <= config.max_detection_distance
+                        })
+                        .min_by(|a, b| {
+                            nalgebra::distance(&confirmed, a)
+                                .total_cmp(&nalgebra::distance(&confirmed, b))
+                        })
+                        .unwrap_or(confirmed)
+                })
+            }
+            None => newest
+                .iter()
+                .copied()
+                .find(|detection| self.support(*detection, config) >= config.min_detections),
+        };
+    }
+
+    /// Whether the ball is currently confirmed.
+    #[must_use]
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed.is_some()
+    }
+
+    /// Whether a detection from the newest frame should be used as a measurement.
+    ///
+    /// Detections are accepted if they are close to the confirmed ball, or if they have enough
+    /// support in the window themselves. So a single-frame false positive elsewhere on the field is
+    /// rejected, even while a ball is confirmed.
+    #[must_use]
+    pub fn accepts(&self, detection: Point2<f32>, config: &BallConfirmationConfig) -> bool {
+        self.confirmed.is_some_and(|confirmed| {
+            nalgebra::distance(&confirmed, &detection) <= config.max_detection_distance
+        }) || self.support(detection, config) >= config.min_detections
+    }
+
+    /// Number of frames in the window with a detection close to `detection`.
+    fn support(&self, detection: Point2<f32>, config: &BallConfirmationConfig) -> usize {
+        self.frames
+            .iter()
+            .filter(|frame| {
+                frame.iter().any(|other| {
+                    nalgebra::distance(&detection, other) <= config.max_detection_distance
+                })
+            })
+            .count()
+    }
+}
+
+fn confirm_ball_perceptions(
+    mut commands: Commands,
+    mut confirmation: ResMut<BallConfirmation>,
+    perceptions: Query<(Entity, &classifier::BallPerception), Added<classifier::BallPerception>>,
+    odometry: Res<Odometry>,
+    config: Res<BallConfirmationConfig>,
+) {
+    let detections = perceptions
+        .iter()
+        .map(|(entity, perception)| (entity, odometry.accumulated * perception.position))
+        .collect::<Vec<_>>();
+
+    confirmation.push_frame(
+        detections.iter().map(|(_, position)| *position).collect(),
+        &config,
+    );
+
+    for (entity, position) in detections {
+        if !confirmation.accepts(position, &config) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use super::*;
+
+    #[test]
+    fn intermittent_detection_is_confirmed_after_k_of_n_frames() {
+        let config = BallConfirmationConfig {
+            window_size: 5,
+            min_detections: 3,
+            max_detection_distance: 0.2,
+        };
+        let ball = Point2::new(2.0, 0.5);
+        let mut confirmation = BallConfirmation::default();
+
+        let mut accepted = Vec::new();
+        for detected in [true, false, true, false, true] {
+            let detections = if detected { vec![ball] } else { vec![] };
+            confirmation.push_frame(detections, &config);
+            accepted.push(detected && confirmation.accepts(ball, &config));
+        }
+
+        assert_eq!(accepted, [false, false, false, false, true]);
+
+        // a detection far away from the ball does not count towards its support
+        let mut other = BallConfirmation::default();
+        for detection in [ball, Point2::new(4.0, -1.0), ball] {
+            other.push_frame(vec![detection], &config);
+        }
+        assert!(!other.accepts(ball, &config));
+
+        // once confirmed, the ball is followed while it rolls away, even though the detections
+        // move further than the maximum distance from the earlier ones
+        let mut rolling = ball;
+        for _ in 0..10 {
+            rolling += Vector2::new(0.15, 0.0);
+            confirmation.push_frame(vec![rolling], &config);
+            assert!(confirmation.accepts(rolling, &config));
+        }
+
+        // a single-frame phantom elsewhere on the field is still rejected
+        let phantom = Point2::new(-1.0, 2.0);
+        confirmation.push_frame(vec![rolling, phantom], &config);
+        assert!(confirmation.accepts(rolling, &config));
+        assert!(!confirmation.accepts(phantom, &config));
+
+        // the confirmation is kept until the ball is missed in K of the last N frames
+        for _ in 0..2 {
+            confirmation.push_frame(vec![], &config);
+            assert!(confirmation.is_confirmed());
+            assert!(confirmation.accepts(rolling, &config));
+        }
+        confirmation.push_frame(vec![], &config);
+        assert!(!confirmation.is_confirmed());
+        assert!(!confirmation.accepts(rolling, &config));
+    }
+
+    #[test]
+    fn rejects_unreachable_min_detections() {
+        let mut config = BallConfirmationConfig {
+            window_size: 5,
+            min_detections: 5,
+            max_detection_distance: 0.2,
+        };
+        assert!(config.validate().is_ok());
+
+        config.min_detections = 6;
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "`confirmation.min_detections` (6) must be at most `confirmation.window_size` (5)"
+        );
+    }
+}
@@ -5,7 +5,10 @@ use rerun::{AsComponents, components::PoseRotationAxisAngle};
 
 use bevy::prelude::*;
 
-use filter::{CovarianceMatrix, KalmanFilter, mahalanobis_distance};
+use filter::{
+    CovarianceMatrix, KalmanFilter, mahalanobis_distance,
+    motion_models::constant_velocity_transition,
+};
 use nalgebra::{
     Matrix2, Matrix2x4, Matrix4, Point2, Rotation3, UnitVector3, Vector2, Vector3, Vector4, matrix,
     vector,
@@ -15,7 +18,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     core::debug::{DebugContext, SerializeComponentBatch},
     localization::{RobotPose, odometry::Odometry},
-    nao::Cycle,
+    nao::{Clock, Cycle},
     vision::ball_detection::classifier::BallPerception,
 };
 
@@ -54,7 +57,7 @@ pub struct BallHypothesisConfig {
     /// Process noise for moving ball model
     pub moving_process_noise: [f32; 4],
 
-    /// Ball position measurement noise
+    /// Minimum ball position measurement noise, added to the projected covariance of a measurement
     pub measurement_noise: [f32; 2],
 
     /// Initial covariance for new ball hypotheses
@@ -125,12 +128,12 @@ impl MovingBallKf {
         process_noise: CovarianceMatrix<4>,
     ) {
         let dt = dt.as_secs_f32();
-        let constant_velocity_prediction = matrix![
-            1.0, 0.0, dt, 0.0;
-            0.0, 1.0, 0.0, dt;
-            0.0, 0.0, 1.0 - exponential_velocity_decay * dt, 0.0;
-            0.0, 0.0, 0.0, 1.0 - exponential_velocity_decay * dt;
-        ];
+        let mut constant_velocity_prediction = constant_velocity_transition::<4>(dt);
+
+        // apply exponential velocity decay
+        constant_velocity_prediction
+            .fixed_view_mut::<2, 2>(2, 2)
+            .fill_diagonal(1.0 - exponential_velocity_decay * dt);
 
         let inverse_odometry = odometry.offset_to_last.inverse();
 
@@ -196,7 +199,13 @@ impl BallHypothesis {
         self.filter.state().position
     }
 
-    pub fn predict(&mut self, odometry: &Odometry, dt: Duration, config: &BallHypothesisConfig) {
+    pub fn predict(
+        &mut self,
+        odometry: &Odometry,
+        dt: Duration,
+        config: &BallHypothesisConfig,
+        clock: &Clock,
+    ) {
         let moving_process_noise =
             Matrix4::from_diagonal(&Vector4::from(config.moving_process_noise));
 
@@ -212,7 +221,7 @@ impl BallHypothesis {
         let is_reliable = self.num_observations >= config.min_demotion_observations;
 
         // demote to stationary
-        if (!is_reliable && self.last_update.elapsed() > UNRELIABLE_DEMOTION_DURATION)
+        if (!is_reliable && clock.elapsed(self.last_update) > UNRELIABLE_DEMOTION_DURATION)
             || (is_reliable && self.filter.state().velocity.norm() < config.min_moving_speed)
         {
             let pos = self.position();
@@ -249,21 +258,25 @@ impl BallHypothesis {
 fn predict(
     mut hypotheses: Query<&mut BallHypothesis>,
     odometry: Res<Odometry>,
-    time: Res<Time>,
+    clock: Res<Clock>,
+    mut last_prediction: Local<Option<Instant>>,
     config: Res<BallHypothesisConfig>,
 ) {
-    for mut hypothesis in &mut hypotheses {
-        let dt = time.delta();
+    let now = clock.now();
+    let dt = last_prediction.map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+    *last_prediction = Some(now);
 
-        hypothesis.predict(&odometry, dt, &config);
+    for mut hypothesis in &mut hypotheses {
+        hypothesis.predict(&odometry, dt, &config, &clock);
     }
 }
 
-fn measurement_update(
+pub(super) fn measurement_update(
     mut commands: Commands,
     mut hypotheses: Query<&mut BallHypothesis>,
     measurements: Query<(Entity, &BallPerception), Added<BallPerception>>,
     config: Res<BallHypothesisConfig>,
+    clock: Res<Clock>,
 ) {
     for (entity, measurement) in &measurements {
         let amount_of_hypotheses = hypotheses.iter().count();
@@ -288,14 +301,13 @@ fn measurement_update(
             .map(|mut hypothesis| {
                 hypothesis.num_observations += 1;
                 hypothesis.last_cycle = measurement.cycle;
-                hypothesis.last_update = Instant::now();
+                hypothesis.last_update = clock.now();
 
-                // use measured ball distance to robot in noise calculation
-                // (further away ball projections will be more noisy)
-                let measurement_noise = Matrix2::from_diagonal(
-                    &(Vector2::from(config.measurement_noise)
-                        * noise_scale(measurement.position.coords.norm())),
-                );
+                // the projected covariance grows with the distance to the robot, as further away
+                // ball projections will be more noisy
+                let measurement_noise =
+                    Matrix2::from_diagonal(&Vector2::from(config.measurement_noise))
+                        + measurement.covariance;
 
                 // update nll
                 let position = hypothesis.position();
@@ -340,8 +352,8 @@ fn measurement_update(
                 nll_of_measurements: INITIAL_NLL_OF_MEASUREMENTS,
                 nll_weight: INITIAL_NLL_WEIGHT,
                 num_observations: 1,
-                spawned_at: Instant::now(),
-                last_update: Instant::now(),
+                spawned_at: clock.now(),
+                last_update: clock.now(),
                 last_cycle: measurement.cycle,
                 is_best: false,
             });
@@ -406,6 +418,20 @@ pub struct BallState {
     pub velocity: Option<Vector2<f32>>,
 }
 
+#[cfg(test)]
+impl BallState {
+    /// A ball that lies still at `position` relative to the robot, and was just seen.
+    pub(crate) fn at(position: Point2<f32>) -> Self {
+        Self {
+            last_cycle: Cycle::default(),
+            last_update: Instant::now(),
+            covariance: Matrix4::identity() * 0.01,
+            position,
+            velocity: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Resource)]
 pub enum Ball {
     Some(BallState),
@@ -423,6 +449,19 @@ impl Ball {
     }
 }
 
+impl BallState {
+    /// Standard deviation of the estimated ball position along its most uncertain axis, in
+    /// meters.
+    #[must_use]
+    pub fn position_uncertainty(&self) -> f32 {
+        self.covariance
+            .fixed_view::<2, 2>(0, 0)
+            .diagonal()
+            .max()
+            .sqrt()
+    }
+}
+
 fn update_best_ball(mut hypotheses: Query<&mut BallHypothesis>, mut ball: ResMut<Ball>) {
     let Some(mut best_ball) = hypotheses
         .iter_mut()
@@ -451,9 +490,10 @@ fn clean_old_hypotheses(
     mut commands: Commands,
     hypotheses: Query<(Entity, &BallHypothesis)>,
     config: Res<BallHypothesisConfig>,
+    clock: Res<Clock>,
 ) {
     for (entity, hypothesis) in &hypotheses {
-        if hypothesis.spawned_at.elapsed() < MIN_BALL_LIFETIME {
+        if clock.elapsed(hypothesis.spawned_at) < MIN_BALL_LIFETIME {
             continue;
         }
 
@@ -675,13 +715,6 @@ fn nll_of_mean(cov: Matrix2<f32>) -> f32 {
     0.5 * cov.determinant().max(0.0).ln()
 }
 
-/// Scales measurement noise based on distance to robot
-///
-/// See: <https://www.desmos.com/calculator/ijpgmecivz>
-fn noise_scale(distance: f32) -> f32 {
-    0.5 * distance.powi(2).min(1.0)
-}
-
 impl From<Vector4<f32>> for MovingBall {
     fn from(v: Vector4<f32>) -> Self {
         Self {
@@ -1,6 +1,8 @@
 //! Module for detecting balls in the top and bottom images.
 
 pub mod classifier;
+pub mod communication;
+pub mod confirmation;
 pub mod hypothesis;
 pub mod proposal;
 
@@ -15,12 +17,12 @@ use serde::{Deserialize, Serialize};
 use serde_with::{DurationMilliSeconds, serde_as};
 
 use crate::{
-    nao::{NaoManager, Priority},
+    nao::{Clock, NaoManager, Priority},
     prelude::*,
     vision::ball_detection::hypothesis::{Ball, BallHypothesisConfig},
 };
 
-use self::classifier::BallClassifierConfig;
+use self::{classifier::BallClassifierConfig, confirmation::BallConfirmationConfig};
 
 /// Plugin for detecting balls in the top and bottom images.
 pub struct BallDetectionPlugin;
@@ -32,9 +34,12 @@ impl Plugin for BallDetectionPlugin {
             proposal::BallProposalPlugin::<Top>::default(),
             proposal::BallProposalPlugin::<Bottom>::default(),
             classifier::BallClassifierPlugin,
+            communication::BallCommunicationPlugin,
+            confirmation::BallConfirmationPlugin,
             hypothesis::BallHypothesisPlugin,
         ))
         .add_systems(PostStartup, (init_subconfigs,))
+        .on_config_changed::<BallDetectionConfig, _>(init_subconfigs)
         .add_systems(Update, detected_ball_eye_color);
     }
 }
@@ -46,17 +51,23 @@ pub struct BallDetectionConfig {
     pub max_classification_age_eye_color: Duration,
     pub proposal: BallProposalConfigs,
     pub classifier: BallClassifierConfig,
+    pub confirmation: BallConfirmationConfig,
     pub hypothesis: BallHypothesisConfig,
 }
 
 impl Config for BallDetectionConfig {
     const PATH: &'static str = "ball_detection.toml";
+
+    fn validate(&self) -> std::result::Result<(), odal::ValidationError> {
+        self.confirmation.validate()
+    }
 }
 
 // TODO: find a better way to do this (reflection :sob:)
 fn init_subconfigs(mut commands: Commands, config: Res<BallDetectionConfig>) {
     commands.insert_resource(config.proposal.clone());
     commands.insert_resource(config.classifier.clone());
+    commands.insert_resource(config.confirmation.clone());
     commands.insert_resource(config.hypothesis.clone());
 }
 
@@ -64,13 +75,14 @@ fn detected_ball_eye_color(
     mut nao: ResMut<NaoManager>,
     ball: Res<Ball>,
     config: Res<BallDetectionConfig>,
+    clock: Res<Clock>,
 ) {
     let Ball::Some(ref ball) = *ball else {
         nao.set_left_eye_led(LeftEye::fill(color::f32::EMPTY), Priority::default());
         return;
     };
 
-    if ball.last_update.elapsed() >= config.max_classification_age_eye_color {
+    if clock.elapsed(ball.last_update) >= config.max_classification_age_eye_color {
         nao.set_left_eye_led(
             LeftEye::fill(color::Rgb::new(1.0, 1.0, 0.0)),
             Priority::default(),
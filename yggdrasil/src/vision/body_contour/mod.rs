@@ -1,4 +1,4 @@
-use crate::core::debug::debug_system::{DebugAppExt, SystemToggle};
+use crate::core::debug::debug_system::{DebugAppExt, DebugLevel, SystemToggle};
 use bevy::prelude::*;
 use heimdall::{Bottom, CameraLocation, CameraMatrix};
 use nalgebra::{Isometry3, Point2, Translation3, Vector3};
@@ -48,6 +48,8 @@ impl Plugin for BodyContourPlugin {
                 PostUpdate,
                 visualize_body_contour.run_if(resource_changed::<BodyContour>),
                 "Visualize body contour",
+                "vision",
+                DebugLevel::Debug,
                 SystemToggle::Disable,
             );
     }
@@ -72,6 +74,7 @@ pub fn update_body_contours(
     body_contour.update_shoulders(&orientation, &bottom_camera_matrix);
     body_contour.update_thighs(&orientation, &kinematics, &bottom_camera_matrix);
     body_contour.update_tibias(&orientation, &kinematics, &bottom_camera_matrix);
+    body_contour.update_top();
 }
 
 fn visualize_body_contour(
@@ -128,11 +131,27 @@ pub struct BodyContour {
 
     left_tibia_point: Option<Point2<f32>>,
     right_tibia_point: Option<Point2<f32>>,
+
+    /// Image row above which no part of the body can be, see [`BodyContour::is_above_body`].
+    top: f32,
 }
 
 impl BodyContour {
     #[must_use]
     pub fn is_part_of_body(&self, image_coordinate: Point2<f32>) -> bool {
+        !self.is_above_body(image_coordinate) && self.is_part_of_any_body_part(image_coordinate)
+    }
+
+    /// Whether the image coordinate is above the highest point of the body contour.
+    ///
+    /// This is a cheap check that avoids testing every body part for points that can never be
+    /// occluded by the body.
+    #[must_use]
+    pub fn is_above_body(&self, image_coordinate: Point2<f32>) -> bool {
+        image_coordinate.y < self.top
+    }
+
+    fn is_part_of_any_body_part(&self, image_coordinate: Point2<f32>) -> bool {
         self.is_part_of_left_shoulder(image_coordinate)
             || self.is_part_of_right_shoulder(image_coordinate)
             || Self::is_part_of_chest(&self.chest_points, image_coordinate)
@@ -206,6 +225,39 @@ impl BodyContour {
             && tibia_point.y + 80.0 > image_coordinate.y
     }
 
+    /// Computes the highest image row that is covered by any of the body part tests below.
+    fn update_top(&mut self) {
+        // a shoulder without a top point extends to the top of the image
+        let shoulder_top = |points: &ShoulderPoints| {
+            points
+                .front
+                .map(|_| points.top.map_or(f32::NEG_INFINITY, |top| top.y))
+        };
+
+        // the chest is interpolated between its points, so it is never above the highest one
+        let chest_top = (self.chest_points.len() > 1)
+            .then(|| {
+                self.chest_points
+                    .iter()
+                    .map(|point| point.y)
+                    .reduce(f32::min)
+            })
+            .flatten();
+
+        self.top = [
+            shoulder_top(&self.left_shoulder_cap_points),
+            shoulder_top(&self.right_shoulder_cap_points),
+            chest_top,
+            self.left_thigh_point.map(|point| point.y - 100.0),
+            self.right_thigh_point.map(|point| point.y - 100.0),
+            self.left_tibia_point.map(|point| point.y),
+            self.right_tibia_point.map(|point| point.y),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(f32::INFINITY, f32::min);
+    }
+
     fn update_chest(
         &mut self,
         orientation: &RobotOrientation,
@@ -408,3 +460,71 @@ fn robot_to_tibias(
         adjust_for_imu(orientation, robot_to_right_tibia),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::point;
+
+    use super::*;
+
+    fn assert_same_as_full_check(body_contour: &BodyContour) {
+        for x in (0..640).step_by(4) {
+            for y in (0..480).step_by(4) {
+                let point = point![x as f32, y as f32];
+                assert_eq!(
+                    body_contour.is_part_of_body(point),
+                    body_contour.is_part_of_any_body_part(point),
+                    "short-circuit changed the result at {point}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn top_check_does_not_change_result() {
+        let mut body_contour = BodyContour {
+            left_shoulder_cap_points: ShoulderPoints {
+                front: Some(point![120.0, 380.0]),
+                back: None,
+                top: Some(point![60.0, 350.0]),
+            },
+            right_shoulder_cap_points: ShoulderPoints {
+                front: Some(point![520.0, 380.0]),
+                back: None,
+                top: Some(point![580.0, 350.0]),
+            },
+            chest_points: vec![
+                point![150.0, 470.0],
+                point![250.0, 420.0],
+                point![320.0, 400.0],
+                point![390.0, 420.0],
+                point![490.0, 470.0],
+            ],
+            left_thigh_point: Some(point![200.0, 520.0]),
+            right_thigh_point: None,
+            left_tibia_point: None,
+            right_tibia_point: Some(point![440.0, 460.0]),
+            top: 0.0,
+        };
+        body_contour.update_top();
+
+        assert!(body_contour.top > 300.0);
+        assert_same_as_full_check(&body_contour);
+
+        // a shoulder without a top point covers the full height of the image
+        body_contour.left_shoulder_cap_points.top = None;
+        body_contour.update_top();
+
+        assert!(body_contour.top.is_infinite() && body_contour.top < 0.0);
+        assert_same_as_full_check(&body_contour);
+    }
+
+    #[test]
+    fn nothing_is_body_without_contour() {
+        let mut body_contour = BodyContour::default();
+        body_contour.update_top();
+
+        assert!(body_contour.is_above_body(point![320.0, 479.0]));
+        assert_same_as_full_check(&body_contour);
+    }
+}
@@ -0,0 +1,128 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use heimdall::{AutoExposureConvergence, CameraLocation, CameraPosition, ExposureReading};
+
+use crate::{core::debug::DebugContext, nao::Cycle};
+
+use super::{Camera, Image, fetch_latest_frame};
+
+/// Number of consecutive frames with a stable exposure, before the auto exposure is converged.
+const CONVERGENCE_WINDOW: usize = 10;
+
+/// Maximum relative difference between the exposure and gain of the frames in the window.
+const CONVERGENCE_TOLERANCE: f32 = 0.05;
+
+/// Plugin that reads back the exposure and gain of a camera for every new image, see
+/// [`CameraExposure`].
+pub(super) struct CameraExposurePlugin<T: CameraLocation>(PhantomData<T>);
+
+impl<T: CameraLocation> Default for CameraExposurePlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: CameraLocation> Plugin for CameraExposurePlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraExposure<T>>().add_systems(
+            Update,
+            read_exposure::<T>
+                .after(fetch_latest_frame::<T>)
+                .run_if(resource_exists_and_changed::<Image<T>>.and(resource_exists::<Camera<T>>)),
+        );
+    }
+}
+
+/// The exposure and gain of the camera, and whether its auto exposure has converged.
+///
+/// Flows that capture images for later use, such as calibration, should wait until the auto
+/// exposure has converged, see [`auto_exposure_converged`].
+#[derive(Resource)]
+pub struct CameraExposure<T: CameraLocation> {
+    convergence: AutoExposureConvergence,
+    supported: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T: CameraLocation> Default for CameraExposure<T> {
+    fn default() -> Self {
+        Self {
+            convergence: AutoExposureConvergence::new(CONVERGENCE_WINDOW, CONVERGENCE_TOLERANCE),
+            supported: true,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: CameraLocation> CameraExposure<T> {
+    /// The most recent exposure and gain of the camera, if they could be read.
+    #[must_use]
+    pub fn latest(&self) -> Option<ExposureReading> {
+        self.convergence.latest()
+    }
+
+    /// Whether the auto exposure of the camera has converged.
+    ///
+    /// This is always true for cameras that do not support reading back the exposure, as there
+    /// is no way to tell.
+    #[must_use]
+    pub fn is_converged(&self) -> bool {
+        !self.supported || self.convergence.auto_exposure_converged()
+    }
+}
+
+/// Run condition that is true once the auto exposure of the camera has converged.
+#[must_use]
+pub fn auto_exposure_converged<T: CameraLocation>(exposure: Res<CameraExposure<T>>) -> bool {
+    exposure.is_converged()
+}
+
+fn read_exposure<T: CameraLocation>(
+    dbg: DebugContext,
+    cycle: Res<Cycle>,
+    camera: Res<Camera<T>>,
+    mut exposure: ResMut<CameraExposure<T>>,
+) {
+    if !exposure.supported {
+        return;
+    }
+
+    let reading = match camera.exposure_reading() {
+        Some(Ok(reading)) => reading,
+        Some(Err(heimdall::Error::Unsupported { property })) => {
+            tracing::info!(
+                "Camera `{}` does not support reading `{property}`, assuming a converged exposure",
+                camera.settings.path
+            );
+            exposure.supported = false;
+            return;
+        }
+        Some(Err(error)) => {
+            tracing::warn!(
+                "Failed to read exposure of `{}`: {error}",
+                camera.settings.path
+            );
+            return;
+        }
+        // the camera is busy or disconnected
+        None => return,
+    };
+
+    exposure.convergence.push(reading);
+
+    let entity_path = match T::POSITION {
+        CameraPosition::Top => "camera/top",
+        CameraPosition::Bottom => "camera/bottom",
+    };
+    dbg.log_with_cycle(
+        format!("{entity_path}/exposure"),
+        *cycle,
+        &rerun::Scalars::single(f64::from(reading.exposure)),
+    );
+    dbg.log_with_cycle(
+        format!("{entity_path}/gain"),
+        *cycle,
+        &rerun::Scalars::single(f64::from(reading.gain)),
+    );
+}
@@ -4,6 +4,13 @@ use std::{marker::PhantomData, sync::Arc, time::Instant};
 
 use crate::nao::Cycle;
 
+/// The instant at which the image a detection was made on was captured.
+///
+/// Added to the entities of detections, so they can be projected with the pose of the robot at
+/// the time of capture, see [`PoseHistory`](crate::localization::history::PoseHistory).
+#[derive(Component, Debug, Clone, Copy, Deref)]
+pub struct ImageTimestamp(pub Instant);
+
 #[derive(Resource, Deref)]
 pub struct Image<T: CameraLocation> {
     #[deref]
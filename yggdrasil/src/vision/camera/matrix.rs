@@ -1,9 +1,12 @@
 use std::marker::PhantomData;
 
 use bevy::prelude::*;
-use heimdall::{CameraLocation, CameraMatrix, CameraPosition};
+use heimdall::{CameraLocation, CameraMatrix, CameraPosition, DistortionCoefficients};
 use nalgebra::{Isometry3, Point2, UnitQuaternion, Vector2, Vector3, vector};
-use rerun::external::glam::{Quat, Vec3};
+use rerun::{
+    LineStrip3D,
+    external::glam::{Quat, Vec3},
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -28,6 +31,8 @@ pub struct CalibrationConfig {
     pub extrinsic_rotation: Vector3<f32>,
     focal_lengths: Vector2<f32>,
     cc_optical_center: Point2<f32>,
+    #[serde(default)]
+    distortion: DistortionCoefficients,
 }
 
 #[derive(Default)]
@@ -40,14 +45,34 @@ impl<T: CameraLocation> Plugin for CameraMatrixPlugin<T> {
             .add_systems(
                 Update,
                 (
-                    update_camera_matrix::<T>.before(super::fetch_latest_frame::<T>),
-                    visualize_camera_matrix::<T>,
+                    update_camera_matrix::<T>
+                        .before(super::fetch_latest_frame::<T>)
+                        .run_if(camera_matrix_inputs_changed),
+                    visualize_camera_matrix::<T>.run_if(resource_changed::<CameraMatrix<T>>),
                 )
                     .chain(),
             );
     }
 }
 
+/// Whether any of the inputs of the camera matrix changed since it was last computed.
+///
+/// Bevy's change detection marks a resource as changed whenever it is mutably dereferenced, and a
+/// change is visible to a system until that system has run once after it. So the camera matrix is
+/// only recomputed in cycles in which one of its inputs was written, e.g. not while the robot is
+/// not receiving new sensor data.
+fn camera_matrix_inputs_changed(
+    foot_support: Res<FootSupportState>,
+    orientation: Res<RobotOrientation>,
+    kinematics: Res<Kinematics>,
+    config: Res<CameraConfig>,
+) -> bool {
+    foot_support.is_changed()
+        || orientation.is_changed()
+        || kinematics.is_changed()
+        || config.is_changed()
+}
+
 fn update_camera_matrix<T: CameraLocation>(
     foot_support: Res<FootSupportState>,
     orientation: Res<RobotOrientation>,
@@ -69,7 +94,8 @@ fn update_camera_matrix<T: CameraLocation>(
         camera_to_head,
         kinematics.isometry::<Head, Robot>().inner,
         robot_to_ground(foot_support.support_side(), &orientation, &kinematics),
-    );
+    )
+    .with_distortion(config.calibration.distortion);
 }
 
 fn robot_to_ground(
@@ -155,4 +181,69 @@ fn visualize_camera_matrix<T: CameraLocation>(
             .with_translation(Into::<Vec3>::into(camera_pos.translation))
             .with_quaternion(Into::<Quat>::into(camera_pos.rotation)),
     );
+
+    // the area of the field that is visible in the image, as a closed polygon
+    let polygon = matrix.ground_fov_polygon();
+    dbg.log_with_cycle(
+        format!("field/{}", T::make_entity_path("fov")),
+        *cycle,
+        &rerun::LineStrips3D::new([LineStrip3D::from_iter(
+            polygon.iter().chain(polygon.first()).map(|vertex| {
+                let vertex = pose.robot_to_world(vertex);
+                (vertex.x, vertex.y, 0.01)
+            }),
+        )]),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use vqf::VqfParameters;
+
+    use super::*;
+    use crate::vision::camera::CameraSettings;
+
+    fn camera_settings() -> CameraSettings {
+        CameraSettings {
+            path: String::new(),
+            width: 640,
+            height: 480,
+            num_buffers: 1,
+            flip_horizontally: false,
+            flip_vertically: false,
+            calibration: CalibrationConfig::default(),
+            focus_auto: false,
+            exposure_auto: false,
+            white_balance_temperature: 0,
+            white_balance_temperature_auto: false,
+        }
+    }
+
+    #[test]
+    fn inputs_changed_only_after_write() {
+        let mut world = World::new();
+        world.init_resource::<FootSupportState>();
+        world.insert_resource(RobotOrientation::new(VqfParameters::default()));
+        world.init_resource::<Kinematics>();
+        world.insert_resource(CameraConfig {
+            top: camera_settings(),
+            bottom: camera_settings(),
+        });
+        let condition = world.register_system(camera_matrix_inputs_changed);
+
+        // the inputs have just been added
+        assert!(world.run_system(condition).unwrap());
+        assert!(!world.run_system(condition).unwrap());
+
+        world.resource_mut::<Kinematics>().set_changed();
+        assert!(world.run_system(condition).unwrap());
+        assert!(!world.run_system(condition).unwrap());
+
+        // a mutable borrow that is never written to is not a change
+        let _ = world.resource_mut::<RobotOrientation>();
+        assert!(!world.run_system(condition).unwrap());
+
+        world.resource_mut::<CameraConfig>().top.width = 320;
+        assert!(world.run_system(condition).unwrap());
+    }
 }
@@ -1,3 +1,4 @@
+pub mod exposure;
 #[cfg(not(feature = "local"))]
 pub mod exposure_weights;
 
@@ -5,7 +6,10 @@ pub mod image;
 pub mod matrix;
 
 use crate::{
-    core::debug::{self, DebugContext},
+    core::{
+        debug::{self, CycleTimeline, DebugContext},
+        shutdown::ShutdownAppExt,
+    },
     nao::Cycle,
     prelude::Result,
 };
@@ -15,16 +19,18 @@ use miette::IntoDiagnostic;
 use rerun::external::re_log::ResultExt;
 use serde::{Deserialize, Serialize};
 use std::{
+    io,
     marker::PhantomData,
     ops::Deref,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
 };
 use tasks::conditions::task_finished;
 
 use heimdall::{
-    Camera as HardwareCamera, CameraDevice, CameraLocation, CameraPosition, YuvPlanarImage,
+    Camera as HardwareCamera, CameraDevice, CameraLocation, CameraPosition, ExposureReading,
 };
-pub use image::Image;
+pub use image::{Image, ImageTimestamp};
 use matrix::CalibrationConfig;
 
 const JPEG_QUALITY: i32 = 30;
@@ -79,7 +85,15 @@ impl<T: CameraLocation> Plugin for CameraPlugin<T> {
             ),
         );
 
-        app.add_plugins(matrix::CameraMatrixPlugin::<T>::default());
+        app.add_plugins(matrix::CameraMatrixPlugin::<T>::default())
+            .add_plugins(exposure::CameraExposurePlugin::<T>::default())
+            .add_shutdown_hook("camera", |world| {
+                if let Some(camera) = world.get_resource::<Camera<T>>() {
+                    camera.stop();
+                }
+
+                Ok(())
+            });
     }
 }
 
@@ -109,9 +123,77 @@ fn setup_camera_device(settings: &CameraSettings) -> Result<CameraDevice> {
     Ok(camera_device)
 }
 
+/// Number of times to try re-opening a camera device after it failed, before giving up.
+const RECONNECT_ATTEMPTS: usize = 3;
+
+/// Minimum time between reconnecting a camera that could not be reconnected before.
+///
+/// This prevents a camera that is permanently gone from stalling every cycle.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The hardware camera, which is [`None`] while the device is disconnected.
+struct CameraState {
+    camera: Option<HardwareCamera>,
+    last_failed_reconnect: Option<Instant>,
+}
+
+impl CameraState {
+    /// Re-opens the camera device using the stored settings.
+    ///
+    /// The old device is closed first, as the device cannot be streamed from twice.
+    fn reconnect(&mut self, settings: &CameraSettings) -> Result<&mut HardwareCamera> {
+        self.camera = None;
+
+        let mut last_error = None;
+        for attempt in 1..=RECONNECT_ATTEMPTS {
+            tracing::warn!(
+                "Reconnecting camera `{}` (attempt {attempt}/{RECONNECT_ATTEMPTS})",
+                settings.path
+            );
+
+            let camera = setup_camera_device(settings).and_then(|camera_device| {
+                HardwareCamera::new(
+                    camera_device,
+                    settings.width,
+                    settings.height,
+                    settings.num_buffers,
+                )
+                .into_diagnostic()
+            });
+
+            match camera {
+                Ok(camera) => {
+                    tracing::info!("Reconnected camera `{}`", settings.path);
+                    self.last_failed_reconnect = None;
+                    return Ok(self.camera.insert(camera));
+                }
+                Err(error) => {
+                    tracing::warn!("Failed to reconnect camera `{}`: {error}", settings.path);
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        self.last_failed_reconnect = Some(Instant::now());
+
+        let error = last_error.expect("at least one reconnect attempt is made");
+        Err(error.wrap_err(format!(
+            "Failed to reconnect camera `{}` after {RECONNECT_ATTEMPTS} attempts",
+            settings.path
+        )))
+    }
+
+    /// Whether enough time has passed since the last failed reconnect to try again.
+    fn can_reconnect(&self) -> bool {
+        self.last_failed_reconnect
+            .is_none_or(|instant| instant.elapsed() >= RECONNECT_INTERVAL)
+    }
+}
+
 #[derive(Resource)]
 pub struct Camera<T: CameraLocation> {
-    inner: Arc<Mutex<HardwareCamera>>,
+    inner: Arc<Mutex<CameraState>>,
+    settings: Arc<CameraSettings>,
     _marker: PhantomData<T>,
 }
 
@@ -121,34 +203,93 @@ impl<T: CameraLocation> Clone for Camera<T> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            settings: self.settings.clone(),
             _marker: PhantomData,
         }
     }
 }
 
+/// Whether the error is caused by the camera not having a new frame available yet,
+/// as opposed to the device failing.
+fn is_would_block(error: &heimdall::Error) -> bool {
+    matches!(error, heimdall::Error::IO(error) if error.kind() == io::ErrorKind::WouldBlock)
+}
+
 impl<T: CameraLocation + Send + Sync> Camera<T> {
-    fn new(camera: HardwareCamera) -> Self {
+    fn new(camera: HardwareCamera, settings: CameraSettings) -> Self {
         Self {
-            inner: Arc::new(Mutex::new(camera)),
+            inner: Arc::new(Mutex::new(CameraState {
+                camera: Some(camera),
+                last_failed_reconnect: None,
+            })),
+            settings: Arc::new(settings),
             _marker: PhantomData,
         }
     }
 
     fn try_fetch_image(&mut self, cycle: Cycle) -> Option<Image<T>> {
-        let Ok(mut camera) = self.inner.try_lock() else {
+        let Ok(mut state) = self.inner.try_lock() else {
             return None;
         };
 
-        camera
-            .try_get_yuyv_image()
-            .ok()
-            .map(|img| Image::new(img, cycle))
+        if let Some(camera) = state.camera.as_mut() {
+            match camera.try_get_yuyv_image() {
+                Ok(image) => return Some(Image::new(image, cycle)),
+                Err(error) if is_would_block(&error) => return None,
+                Err(error) => {
+                    tracing::warn!(
+                        "Failed to fetch image from `{}`: {error}",
+                        self.settings.path
+                    );
+                }
+            }
+        } else if !state.can_reconnect() {
+            return None;
+        }
+
+        // The new camera only has a frame available in the next cycle
+        if let Err(error) = state.reconnect(&self.settings) {
+            tracing::error!("{error:?}");
+        }
+
+        None
+    }
+
+    /// Reads the exposure and gain the camera currently uses.
+    ///
+    /// Returns [`None`] if the camera is in use or disconnected.
+    fn exposure_reading(&self) -> Option<heimdall::Result<ExposureReading>> {
+        let state = self.inner.try_lock().ok()?;
+
+        state
+            .camera
+            .as_ref()
+            .map(|camera| camera.camera_device().exposure_reading())
+    }
+
+    /// Stops streaming and closes the camera device.
+    fn stop(&self) {
+        let mut state = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        state.camera = None;
     }
 
     fn loop_fetch_image(&self) -> Result<Image<T>> {
-        let mut camera = self.inner.lock().unwrap();
+        let mut state = self.inner.lock().unwrap();
+
+        if let Some(camera) = state.camera.as_mut() {
+            match camera.loop_try_get_yuyv_image() {
+                Ok(image) => return Ok(Image::new(image, Cycle::default())),
+                Err(error) => {
+                    tracing::warn!(
+                        "Failed to fetch image from `{}`: {error}",
+                        self.settings.path
+                    );
+                }
+            }
+        }
 
-        camera
+        state
+            .reconnect(&self.settings)?
             .loop_try_get_yuyv_image()
             .into_diagnostic()
             .map(|img| Image::new(img, Cycle::default()))
@@ -187,7 +328,7 @@ pub fn init_camera<T: CameraLocation>(
         settings.num_buffers,
     )?;
 
-    let camera = Camera::<T>::new(hardware_camera);
+    let camera = Camera::<T>::new(hardware_camera, settings.clone());
 
     let image = camera.loop_fetch_image()?;
 
@@ -203,14 +344,22 @@ fn log_image_jpeg<T: CameraLocation>(dbg: DebugContext, image: Res<Image<T>>) {
             let image = image.clone();
             let dbg = dbg.clone();
             async move {
-                let yuv_planar_image = YuvPlanarImage::from_yuyv(image.yuyv_image());
-                let Some(jpeg) = yuv_planar_image.to_jpeg(JPEG_QUALITY).ok_or_log_error() else {
+                let Some(jpeg) = image
+                    .yuyv_image()
+                    .encode_jpeg(JPEG_QUALITY)
+                    .ok_or_log_error()
+                else {
                     return;
                 };
                 let encoded_image =
-                    rerun::EncodedImage::new(jpeg.as_ref()).with_media_type(rerun::MediaType::JPEG);
+                    rerun::EncodedImage::new(jpeg).with_media_type(rerun::MediaType::JPEG);
 
-                dbg.log_with_cycle(T::make_entity_image_path(""), image.cycle(), &encoded_image);
+                dbg.log_with_time(
+                    T::make_entity_image_path(""),
+                    image.timestamp(),
+                    CycleTimeline::At(image.cycle()),
+                    &encoded_image,
+                );
             }
         })
         .detach();
@@ -227,7 +376,12 @@ fn log_image_yuyv<T: CameraLocation>(dbg: DebugContext, image: Res<Image<T>>) {
                     rerun::PixelFormat::YUY2,
                     (*image).deref().as_ref(),
                 );
-                dbg.log_with_cycle(T::make_entity_image_path(""), image.cycle(), &rerun_image);
+                dbg.log_with_time(
+                    T::make_entity_image_path(""),
+                    image.timestamp(),
+                    CycleTimeline::At(image.cycle()),
+                    &rerun_image,
+                );
             }
         })
         .detach();
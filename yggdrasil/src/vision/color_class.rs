@@ -0,0 +1,132 @@
+//! Classification of pixels into the colors of the objects on the field, see [`ColorConfig`].
+
+use bevy::prelude::*;
+use odal::Config;
+use serde::{Deserialize, Serialize};
+
+/// A range of colors in the YHS color space, as returned by [`YuvPixel::to_yhs2`].
+///
+/// Every channel is an inclusive `[min, max]` range, an upper bound of `inf` leaves the channel
+/// unbounded.
+///
+/// [`YuvPixel::to_yhs2`]: heimdall::YuvPixel::to_yhs2
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, Reflect)]
+#[serde(deny_unknown_fields)]
+pub struct YhsRange {
+    pub luminance: [f32; 2],
+    pub hue: [f32; 2],
+    pub saturation: [f32; 2],
+}
+
+impl YhsRange {
+    /// Whether the `(y, h, s)` color lies within the range.
+    #[must_use]
+    pub fn contains(&self, (y, h, s): (f32, f32, f32)) -> bool {
+        let within = |[min, max]: [f32; 2], value: f32| (min..=max).contains(&value);
+
+        within(self.luminance, y) && within(self.hue, h) && within(self.saturation, s)
+    }
+}
+
+/// The color classes of the objects on the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorClass {
+    WhiteLine,
+    Field,
+    Ball,
+}
+
+/// Calibration of the color classes, so the colors can be tuned per venue without recompiling.
+#[derive(Resource, Debug, Clone, Deserialize, Serialize, Reflect)]
+#[serde(deny_unknown_fields)]
+pub struct ColorConfig {
+    /// Color of the white field lines
+    pub white_line: YhsRange,
+    /// Color of the green field
+    pub field: YhsRange,
+    /// Color of the dark patches of the ball
+    pub ball: YhsRange,
+}
+
+impl Config for ColorConfig {
+    const PATH: &'static str = "color.toml";
+}
+
+impl ColorConfig {
+    /// The range of colors of a color class.
+    #[must_use]
+    pub fn range(&self, class: ColorClass) -> &YhsRange {
+        match class {
+            ColorClass::WhiteLine => &self.white_line,
+            ColorClass::Field => &self.field,
+            ColorClass::Ball => &self.ball,
+        }
+    }
+
+    /// Whether the `(y, h, s)` color belongs to the color class.
+    #[must_use]
+    pub fn is(&self, class: ColorClass, yhs: (f32, f32, f32)) -> bool {
+        self.range(class).contains(yhs)
+    }
+
+    /// Classifies a `(y, h, s)` color, or returns `None` if it does not belong to any class.
+    ///
+    /// The ranges may overlap, in which case the white lines take precedence over the ball, which
+    /// takes precedence over the field.
+    #[must_use]
+    pub fn classify(&self, yhs: (f32, f32, f32)) -> Option<ColorClass> {
+        [ColorClass::WhiteLine, ColorClass::Ball, ColorClass::Field]
+            .into_iter()
+            .find(|&class| self.is(class, yhs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ColorConfig {
+        ColorConfig {
+            white_line: YhsRange {
+                luminance: [90.0, 255.0],
+                hue: [0.0, 255.0],
+                saturation: [0.0, 100.0],
+            },
+            field: YhsRange {
+                luminance: [0.0, 200.0],
+                hue: [0.0, 80.0],
+                saturation: [45.0, f32::INFINITY],
+            },
+            ball: YhsRange {
+                luminance: [0.0, 60.0],
+                hue: [0.0, 255.0],
+                saturation: [0.0, 160.0],
+            },
+        }
+    }
+
+    #[test]
+    fn classification_respects_configured_ranges() {
+        let mut config = config();
+
+        assert_eq!(
+            config.classify((200.0, 120.0, 20.0)),
+            Some(ColorClass::WhiteLine)
+        );
+        assert_eq!(
+            config.classify((120.0, 40.0, 300.0)),
+            Some(ColorClass::Field)
+        );
+        assert_eq!(config.classify((30.0, 200.0, 50.0)), Some(ColorClass::Ball));
+        assert_eq!(config.classify((120.0, 200.0, 300.0)), None);
+
+        // a dim venue, where the lines are darker
+        assert!(!config.is(ColorClass::WhiteLine, (80.0, 120.0, 20.0)));
+        config.white_line.luminance[0] = 70.0;
+        assert!(config.is(ColorClass::WhiteLine, (80.0, 120.0, 20.0)));
+
+        // the bounds are inclusive
+        assert!(config.is(ColorClass::Field, (200.0, 80.0, 45.0)));
+        assert!(!config.is(ColorClass::Field, (200.1, 80.0, 45.0)));
+    }
+}
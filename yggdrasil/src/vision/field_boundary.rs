@@ -32,7 +32,8 @@ impl Plugin for FieldBoundaryPlugin {
                 detect_field_boundary
                     .run_if(resource_exists_and_changed::<Image<Top>>)
                     .run_if(task_finished::<FieldBoundary>)
-                    .run_if(in_state(VisualRefereeDetectionStatus::Inactive)),
+                    .run_if(in_state(VisualRefereeDetectionStatus::Inactive))
+                    .run_if(ml::model_loaded::<FieldBoundaryModel>),
             )
             .add_systems(
                 PostUpdate,
@@ -18,9 +18,15 @@ use ransac::{Ransac, line::LineDetector};
 use serde::{Deserialize, Serialize};
 
 use super::body_contour::{BodyContour, update_body_contours};
-use super::{camera::Image, scan_lines::ScanLines};
-use crate::core::debug::debug_system::{DebugAppExt, SystemToggle};
-use crate::{core::debug::DebugContext, localization::RobotPose, nao::Cycle, prelude::ConfigExt};
+use super::{
+    camera::{Image, ImageTimestamp},
+    color_class::{ColorClass, ColorConfig},
+    scan_lines::ScanLines,
+};
+use crate::core::debug::debug_system::{DebugAppExt, DebugLevel, SystemToggle};
+use crate::{
+    core::debug::DebugContext, localization::history::PoseHistory, nao::Cycle, prelude::ConfigExt,
+};
 
 /// The amount of cycles to wait for new lines before clearing the lines.
 const LINE_DEBUG_CLEAR_CYCLES: usize = 5;
@@ -92,6 +98,7 @@ pub struct LineDetectionPlugin<T: CameraLocation>(PhantomData<T>);
 impl<T: CameraLocation> Plugin for LineDetectionPlugin<T> {
     fn build(&self, app: &mut App) {
         app.init_config::<LineDetectionConfigs>()
+            .init_config::<ColorConfig>()
             .add_systems(PostStartup, setup_debug::<T>)
             .add_systems(
                 Update,
@@ -114,12 +121,16 @@ impl<T: CameraLocation> Plugin for LineDetectionPlugin<T> {
                 Update,
                 debug_rejected_lines::<T>,
                 "Visualize rejected lines",
+                "vision",
+                DebugLevel::Trace,
                 SystemToggle::Disable,
             )
             .add_named_debug_systems(
                 Update,
                 debug_lines_inliers::<T>,
                 "Visualize line inliers",
+                "vision",
+                DebugLevel::Debug,
                 SystemToggle::Disable,
             );
     }
@@ -190,6 +201,7 @@ fn detect_lines_system<T: CameraLocation>(
     scan_lines: Res<ScanLines<T>>,
     camera_matrix: Res<CameraMatrix<T>>,
     cfg: Res<LineDetectionConfigs>,
+    colors: Res<ColorConfig>,
     body_contour: Res<BodyContour>,
 ) {
     // TODO: Current tasks API is not flexible enough for this :)
@@ -200,15 +212,17 @@ fn detect_lines_system<T: CameraLocation>(
     };
 
     let cycle = scan_lines.image().cycle();
-    let entity = commands.spawn(cycle).id();
+    let timestamp = ImageTimestamp(scan_lines.image().timestamp());
+    let entity = commands.spawn((cycle, timestamp)).id();
     let pool = AsyncComputeTaskPool::get();
     let body_contour = body_contour.clone();
+    let colors = colors.clone();
 
     let handle = pool.spawn({
         let scan_lines = scan_lines.clone();
         let camera_matrix = camera_matrix.clone();
 
-        async move { detect_lines(scan_lines, camera_matrix, cfg, body_contour) }
+        async move { detect_lines(scan_lines, camera_matrix, cfg, colors, body_contour) }
     });
 
     commands
@@ -265,6 +279,7 @@ fn detect_lines<T: CameraLocation>(
     scan_lines: ScanLines<T>,
     camera_matrix: CameraMatrix<T>,
     cfg: LineDetectionConfig,
+    colors: ColorConfig,
     body_contour: BodyContour,
 ) -> (Vec<LineCandidate>, Vec<Option<Rejection>>) {
     let spots = scan_lines.line_spots().filter(|point| {
@@ -315,7 +330,7 @@ fn detect_lines<T: CameraLocation>(
     }
 
     // try to merge the candidates
-    merge_candidates(&mut candidates, &scan_lines, &camera_matrix, &cfg);
+    merge_candidates(&mut candidates, &scan_lines, &camera_matrix, &cfg, &colors);
 
     // sort candidates by distance (closest first)
     candidates.sort_unstable_by(|a, b| {
@@ -331,7 +346,8 @@ fn detect_lines<T: CameraLocation>(
             let is_too_short = c.segment.length() < cfg.line_segment_min_length;
             let is_too_long = c.segment.length() > cfg.line_segment_max_length;
 
-            let passes_white_test = passes_white_test(c, &scan_lines, &camera_matrix, &cfg);
+            let passes_white_test =
+                passes_white_test(c, &scan_lines, &camera_matrix, &cfg, &colors);
 
             if not_enough_spots {
                 Some(Rejection::NotEnoughSpots)
@@ -356,6 +372,7 @@ fn passes_white_test<T: CameraLocation>(
     scan_lines: &ScanLines<T>,
     camera_matrix: &CameraMatrix<T>,
     cfg: &LineDetectionConfig,
+    colors: &ColorConfig,
 ) -> bool {
     // do a white test
     let mut tests = vec![];
@@ -379,11 +396,11 @@ fn passes_white_test<T: CameraLocation>(
 
         let test_1 = camera_matrix
             .ground_to_pixel(point![offset_1.x, offset_1.y, 0.0])
-            .is_ok_and(|p| is_less_bright_and_more_saturated(p, sample_pixel, image));
+            .is_ok_and(|p| is_less_bright_and_more_saturated(p, sample_pixel, image, colors));
 
         let test_2 = camera_matrix
             .ground_to_pixel(point![offset_2.x, offset_2.y, 0.0])
-            .is_ok_and(|p| is_less_bright_and_more_saturated(p, sample_pixel, image));
+            .is_ok_and(|p| is_less_bright_and_more_saturated(p, sample_pixel, image, colors));
 
         tests.extend([test_1, test_2]);
     }
@@ -399,6 +416,7 @@ fn merge_candidates<T: CameraLocation>(
     scan_lines: &ScanLines<T>,
     camera_matrix: &CameraMatrix<T>,
     cfg: &LineDetectionConfig,
+    colors: &ColorConfig,
 ) {
     // check if we can merge two line candidates
     for i in (0..candidates.len()).rev() {
@@ -447,11 +465,15 @@ fn merge_candidates<T: CameraLocation>(
 
                 let test_1 = camera_matrix
                     .ground_to_pixel(point![offset_1.x, offset_1.y, 0.0])
-                    .is_ok_and(|p| is_less_bright_and_more_saturated(p, sample_pixel, image));
+                    .is_ok_and(|p| {
+                        is_less_bright_and_more_saturated(p, sample_pixel, image, colors)
+                    });
 
                 let test_2 = camera_matrix
                     .ground_to_pixel(point![offset_2.x, offset_2.y, 0.0])
-                    .is_ok_and(|p| is_less_bright_and_more_saturated(p, sample_pixel, image));
+                    .is_ok_and(|p| {
+                        is_less_bright_and_more_saturated(p, sample_pixel, image, colors)
+                    });
 
                 tests.extend([test_1, test_2]);
             }
@@ -468,10 +490,13 @@ fn merge_candidates<T: CameraLocation>(
     }
 }
 
+/// Whether the pixel at `p1` is less bright and more saturated than the pixel at `p2`, which has
+/// the color of a white line according to the [`ColorConfig`].
 fn is_less_bright_and_more_saturated<T: CameraLocation>(
     p1: Point2<f32>,
     p2: Point2<f32>,
     image: &Image<T>,
+    colors: &ColorConfig,
 ) -> bool {
     #[inline]
     fn yhs_triple(p: Point2<f32>, image: &YuyvImage) -> Option<(f32, f32, f32)> {
@@ -480,18 +505,32 @@ fn is_less_bright_and_more_saturated<T: CameraLocation>(
         Some(pixel.to_yhs2())
     }
 
-    let (Some((y1, _h1, s1)), Some((y2, _h2, s2))) = (yhs_triple(p1, image), yhs_triple(p2, image))
-    else {
+    let (Some(yhs1), Some(yhs2)) = (yhs_triple(p1, image), yhs_triple(p2, image)) else {
         return false;
     };
 
-    y1 < y2 && s1 > s2
+    is_line_edge(yhs1, yhs2, colors)
+}
+
+/// Whether the `(y, h, s)` colors of a pixel next to a line and a pixel on the line form the edge
+/// of a white line.
+pub(super) fn is_line_edge(
+    (y1, _h1, s1): (f32, f32, f32),
+    line: (f32, f32, f32),
+    colors: &ColorConfig,
+) -> bool {
+    let (y2, _h2, s2) = line;
+
+    colors.is(ColorClass::WhiteLine, line) && y1 < y2 && s1 > s2
 }
 
 fn setup_debug<T: CameraLocation>(dbg: DebugContext) {
+    let camera = dbg.scoped::<T>();
+    let image = dbg.scoped::<T>().image();
+
     // lines
-    dbg.log_static(
-        T::make_entity_image_path("lines/detected"),
+    image.log_static(
+        "lines/detected",
         &rerun::LineStrips2D::update_fields().with_colors([(255, 100, 0)]),
     );
 
@@ -502,22 +541,19 @@ fn setup_debug<T: CameraLocation>(dbg: DebugContext) {
         (155, 60, 0)
     };
 
-    dbg.log_static(
-        T::make_entity_path("lines/detected"),
+    camera.log_static(
+        "lines/detected",
         &rerun::LineStrips3D::update_fields().with_colors([color]),
     );
 
     // inliers
-    dbg.log_static(
-        T::make_entity_image_path("lines/inliers"),
+    image.log_static(
+        "lines/inliers",
         &rerun::Points2D::update_fields().with_radii([2.0]),
     );
 
     // rejected lines
-    dbg.log_static(
-        T::make_entity_image_path("lines/rejected"),
-        &rerun::LineStrips3D::update_fields(),
-    );
+    image.log_static("lines/rejected", &rerun::LineStrips3D::update_fields());
 }
 
 fn debug_lines<T: CameraLocation>(
@@ -532,30 +568,28 @@ fn debug_lines<T: CameraLocation>(
             last_logged_cycle.0 + LINE_DEBUG_CLEAR_CYCLES < cycle.0
         })
     {
-        dbg.log_with_cycle(
-            T::make_entity_image_path("lines/detected"),
+        dbg.scoped::<T>().image().log_with_cycle(
+            "lines/detected",
             *cycle,
             &rerun::LineStrips2D::update_fields().with_strips(std::iter::empty::<&[(f32, f32)]>()),
         );
     }
 
     for (cycle, lines) in accepted.iter() {
-        dbg.log_with_cycle(
-            T::make_entity_image_path("lines/detected"),
+        dbg.scoped::<T>().image().log_with_cycle(
+            "lines/detected",
             *cycle,
             &rerun::LineStrips2D::update_fields().with_strips(
                 lines
                     .segments
                     .iter()
                     .filter_map(|s| {
-                        let (Ok(start), Ok(end)) = (
-                            camera_matrix.ground_to_pixel(point![s.start.x, s.start.y, 0.0]),
-                            camera_matrix.ground_to_pixel(point![s.end.x, s.end.y, 0.0]),
-                        ) else {
-                            return None;
-                        };
-                        Some(LineSegment2::new(start, end))
+                        camera_matrix.project_segment(
+                            point![s.start.x, s.start.y, 0.0],
+                            point![s.end.x, s.end.y, 0.0],
+                        )
                     })
+                    .map(|(start, end)| LineSegment2::new(start, end))
                     .map(<[(f32, f32); 2]>::from),
             ),
         );
@@ -566,8 +600,8 @@ fn debug_lines<T: CameraLocation>(
 
 fn debug_lines_projected<T: CameraLocation>(
     dbg: DebugContext,
-    pose: Res<RobotPose>,
-    accepted: Query<(&Cycle, &DetectedLines), (With<T>, Added<DetectedLines>)>,
+    pose_history: Res<PoseHistory>,
+    accepted: Query<(&Cycle, &ImageTimestamp, &DetectedLines), (With<T>, Added<DetectedLines>)>,
     cycle: Res<Cycle>,
     mut last_logged: Local<Option<Cycle>>,
 ) {
@@ -576,17 +610,20 @@ fn debug_lines_projected<T: CameraLocation>(
             last_logged_cycle.0 + LINE_DEBUG_CLEAR_CYCLES < cycle.0
         })
     {
-        dbg.log_with_cycle(
-            T::make_entity_path("lines/detected"),
+        dbg.scoped::<T>().log_with_cycle(
+            "lines/detected",
             *cycle,
             &rerun::LineStrips3D::update_fields()
                 .with_strips(std::iter::empty::<&[(f32, f32, f32)]>()),
         );
     }
 
-    for (cycle, lines) in accepted.iter() {
-        dbg.log_with_cycle(
-            T::make_entity_path("lines/detected"),
+    for (cycle, timestamp, lines) in accepted.iter() {
+        // project the lines using the pose at the time the image was captured
+        let pose = pose_history.pose_at(**timestamp);
+
+        dbg.scoped::<T>().log_with_cycle(
+            "lines/detected",
             *cycle,
             &rerun::LineStrips3D::update_fields().with_strips(lines.segments.iter().map(|s| {
                 let point = pose.inner * *s;
@@ -633,8 +670,8 @@ fn debug_lines_inliers<T: CameraLocation>(
             points.extend(p);
         });
 
-        dbg.log_with_cycle(
-            T::make_entity_image_path("lines/inliers"),
+        dbg.scoped::<T>().image().log_with_cycle(
+            "lines/inliers",
             *cycle,
             &rerun::Points2D::new(points).with_colors(colors),
         );
@@ -653,30 +690,28 @@ fn debug_rejected_lines<T: CameraLocation>(
             last_logged_cycle.0 + LINE_DEBUG_CLEAR_CYCLES < cycle.0
         })
     {
-        dbg.log_with_cycle(
-            T::make_entity_image_path("lines/rejected"),
+        dbg.scoped::<T>().image().log_with_cycle(
+            "lines/rejected",
             *cycle,
             &rerun::LineStrips2D::update_fields().with_strips(std::iter::empty::<&[(f32, f32)]>()),
         );
     }
 
     for (cycle, lines) in rejected.iter() {
-        dbg.log_with_cycle(
-            T::make_entity_image_path("lines/rejected"),
+        dbg.scoped::<T>().image().log_with_cycle(
+            "lines/rejected",
             *cycle,
             &rerun::LineStrips2D::new(
                 lines
                     .segments
                     .iter()
                     .filter_map(|s| {
-                        let (Ok(start), Ok(end)) = (
-                            camera_matrix.ground_to_pixel(point![s.start.x, s.start.y, 0.0]),
-                            camera_matrix.ground_to_pixel(point![s.end.x, s.end.y, 0.0]),
-                        ) else {
-                            return None;
-                        };
-                        Some(LineSegment2::new(start, end))
+                        camera_matrix.project_segment(
+                            point![s.start.x, s.start.y, 0.0],
+                            point![s.end.x, s.end.y, 0.0],
+                        )
                     })
+                    .map(|(start, end)| LineSegment2::new(start, end))
                     .map(<[(f32, f32); 2]>::from),
             )
             .with_colors(
@@ -696,3 +731,43 @@ fn debug_rejected_lines<T: CameraLocation>(
         *last_logged = Some(*cycle);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::color_class::YhsRange;
+
+    fn colors() -> ColorConfig {
+        let any = YhsRange {
+            luminance: [0.0, 255.0],
+            hue: [0.0, 255.0],
+            saturation: [0.0, f32::INFINITY],
+        };
+
+        ColorConfig {
+            white_line: YhsRange {
+                luminance: [90.0, 255.0],
+                saturation: [0.0, 100.0],
+                ..any
+            },
+            field: any,
+            ball: any,
+        }
+    }
+
+    #[test]
+    fn line_edge_respects_configured_white() {
+        let mut colors = colors();
+        let field = (60.0, 40.0, 150.0);
+
+        assert!(is_line_edge(field, (180.0, 120.0, 30.0), &colors));
+        // the line has to be brighter and less saturated than its surroundings
+        assert!(!is_line_edge((180.0, 120.0, 30.0), field, &colors));
+
+        // a gray line is not white enough, unless the venue is calibrated for it
+        let gray = (80.0, 120.0, 30.0);
+        assert!(!is_line_edge(field, gray, &colors));
+        colors.white_line.luminance[0] = 70.0;
+        assert!(is_line_edge(field, gray, &colors));
+    }
+}
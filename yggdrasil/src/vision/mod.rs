@@ -5,8 +5,10 @@ pub mod ball_detection;
 pub mod body_contour;
 pub mod camera;
 pub mod color;
+pub mod color_class;
 pub mod field_boundary;
 pub mod line_detection;
+pub mod penalty_mark_detection;
 pub mod referee;
 pub mod robot_detection;
 pub mod scan_grid;
@@ -26,6 +28,8 @@ impl PluginGroup for VisionPlugins {
             .add(scan_lines::ScanLinesPlugin)
             .add(line_detection::LineDetectionPlugin::<Top>::default())
             .add(line_detection::LineDetectionPlugin::<Bottom>::default())
+            .add(penalty_mark_detection::PenaltyMarkDetectionPlugin::<Top>::default())
+            .add(penalty_mark_detection::PenaltyMarkDetectionPlugin::<Bottom>::default())
             .add(field_boundary::FieldBoundaryPlugin)
             .add(ball_detection::BallDetectionPlugin)
             // .add(robot_detection::RobotDetectionPlugin)
@@ -0,0 +1,441 @@
+//! Detection of the penalty marks, see [`DetectedPenaltyMark`].
+
+use std::{f32::consts::TAU, marker::PhantomData};
+
+use bevy::prelude::*;
+use heimdall::{CameraLocation, CameraMatrix, CameraPosition};
+use itertools::Itertools;
+use nalgebra::{Point2, Vector2, point};
+use odal::Config;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    body_contour::{BodyContour, update_body_contours},
+    camera::ImageTimestamp,
+    color_class::{ColorClass, ColorConfig},
+    line_detection::is_line_edge,
+    scan_lines::{RegionColor, ScanLines},
+};
+use crate::{
+    core::{
+        config::layout::{FieldConfig, LayoutConfig},
+        debug::DebugContext,
+    },
+    localization::{RobotPose, history::PoseHistory},
+    nao::Cycle,
+    prelude::ConfigExt,
+};
+
+#[derive(Resource, Debug, Clone, Deserialize, Serialize, Reflect)]
+#[serde(deny_unknown_fields)]
+pub struct PenaltyMarkDetectionConfig {
+    /// maximum distance of a penalty mark from the robot in meters
+    pub max_distance: f32,
+
+    /// maximum relative difference between the size of a white region on the ground and the
+    /// size of the penalty mark
+    pub size_tolerance: f32,
+
+    /// number of samples around a penalty mark for the white test
+    pub white_test_samples: usize,
+
+    /// distance of the white test samples from the center of a penalty mark in meters
+    pub white_test_sample_distance: f32,
+
+    /// ratio of white tests that need to pass for a penalty mark to be accepted
+    pub white_test_ratio: f32,
+
+    /// maximum distance of a penalty mark from its position on the field in meters
+    pub max_field_distance: f32,
+}
+
+impl Config for PenaltyMarkDetectionConfig {
+    const PATH: &'static str = "penalty_mark_detection.toml";
+}
+
+/// Plugin that adds systems to detect the penalty marks from scan-lines.
+#[derive(Default)]
+pub struct PenaltyMarkDetectionPlugin<T: CameraLocation>(PhantomData<T>);
+
+impl<T: CameraLocation> Plugin for PenaltyMarkDetectionPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_config::<PenaltyMarkDetectionConfig>()
+            .init_config::<ColorConfig>()
+            .add_systems(PostStartup, setup_debug::<T>)
+            .add_systems(
+                Update,
+                (detect_penalty_marks_system::<T>, debug_penalty_marks::<T>)
+                    .chain()
+                    .run_if(resource_exists_and_changed::<ScanLines<T>>)
+                    .after(update_body_contours),
+            );
+    }
+}
+
+/// A detected penalty mark.
+///
+/// Only white spots close to one of the penalty marks of the field are detected, so the center
+/// mark is never mistaken for a penalty mark.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct DetectedPenaltyMark {
+    /// Position of the penalty mark in the field frame.
+    pub position: Point2<f32>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn detect_penalty_marks_system<T: CameraLocation>(
+    mut commands: Commands,
+    scan_lines: Res<ScanLines<T>>,
+    camera_matrix: Res<CameraMatrix<T>>,
+    pose_history: Res<PoseHistory>,
+    layout: Res<LayoutConfig>,
+    config: Res<PenaltyMarkDetectionConfig>,
+    colors: Res<ColorConfig>,
+    body_contour: Res<BodyContour>,
+    previous: Query<Entity, (With<T>, With<DetectedPenaltyMark>)>,
+) {
+    // remove the old penalty marks
+    for entity in &previous {
+        commands.entity(entity).despawn();
+    }
+
+    let image = scan_lines.image();
+    let pose = pose_history.pose_at(image.timestamp());
+
+    let segments = scan_lines
+        .vertical()
+        .regions()
+        .filter(|region| *region.color() == RegionColor::WhiteOrBlack)
+        .filter(|region| {
+            T::POSITION == CameraPosition::Top
+                || !body_contour.is_part_of_body(region.region().line_spot())
+        })
+        .map(|region| {
+            let region = region.region();
+            let x = region.fixed_point() as f32;
+
+            (
+                point![x, region.start_point() as f32],
+                point![x, region.end_point() as f32],
+            )
+        });
+
+    let yhs = |pixel: Point2<f32>| {
+        if pixel.x < 0.0 || pixel.y < 0.0 {
+            return None;
+        }
+
+        image
+            .pixel(pixel.x as usize, pixel.y as usize)
+            .map(|pixel| pixel.to_yhs2())
+    };
+
+    let marks = detect_penalty_marks(
+        segments,
+        yhs,
+        &camera_matrix,
+        &pose,
+        &layout.field,
+        &config,
+        &colors,
+    );
+
+    for mark in marks {
+        commands.spawn((
+            mark,
+            image.cycle(),
+            ImageTimestamp(image.timestamp()),
+            T::default(),
+        ));
+    }
+}
+
+/// Detects the penalty marks from the white `segments` of vertical scan-lines, given as the start
+/// and end pixel of each segment.
+///
+/// Segments that are about as long as the penalty mark on the ground are grouped into candidates,
+/// which have to be white and surrounded by field in the image, see [`passes_white_test`]. The
+/// candidates are then projected onto the field using the `pose` of the robot, and have to lie
+/// close to one of the penalty marks.
+fn detect_penalty_marks<T: CameraLocation>(
+    segments: impl IntoIterator<Item = (Point2<f32>, Point2<f32>)>,
+    yhs: impl Fn(Point2<f32>) -> Option<(f32, f32, f32)>,
+    camera_matrix: &CameraMatrix<T>,
+    pose: &RobotPose,
+    field: &FieldConfig,
+    config: &PenaltyMarkDetectionConfig,
+    colors: &ColorConfig,
+) -> Vec<DetectedPenaltyMark> {
+    let min_size = field.line_width * (1.0 - config.size_tolerance);
+    let max_size = field.penalty_mark_size * (1.0 + config.size_tolerance);
+
+    // the segments of a mark, projected to the ground in robot frame
+    let spots = segments.into_iter().filter_map(|(start, end)| {
+        let start = camera_matrix.pixel_to_ground(start, 0.0).ok()?.xy();
+        let end = camera_matrix.pixel_to_ground(end, 0.0).ok()?.xy();
+
+        let size = (end - start).norm();
+        let center = start + (end - start) / 2.0;
+
+        ((min_size..=max_size).contains(&size) && center.coords.norm() < config.max_distance)
+            .then_some(center)
+    });
+
+    // group the segments that cross the same mark
+    let mut clusters: Vec<(Vector2<f32>, usize)> = Vec::new();
+    for spot in spots {
+        let cluster = clusters.iter_mut().find(|(sum, count)| {
+            (*sum / *count as f32 - spot.coords).norm() < field.penalty_mark_size
+        });
+
+        match cluster {
+            Some((sum, count)) => {
+                *sum += spot.coords;
+                *count += 1;
+            }
+            None => clusters.push((spot.coords, 1)),
+        }
+    }
+
+    let [own_mark, opponent_mark] = field.penalty_marks();
+
+    clusters
+        .into_iter()
+        .map(|(sum, count)| Point2::from(sum / count as f32))
+        .filter(|&center| passes_white_test(center, &yhs, camera_matrix, config, colors))
+        .map(|center| pose.robot_to_world(&center))
+        .filter(|&position| {
+            let distance = |mark: Point2<f32>| (position - mark).norm();
+            let mark_distance = distance(own_mark).min(distance(opponent_mark));
+
+            // the center mark looks the same, so the mark has to be closest to a penalty mark
+            mark_distance < config.max_field_distance && mark_distance < distance(Point2::origin())
+        })
+        .map(|position| DetectedPenaltyMark { position })
+        .collect()
+}
+
+/// Whether the candidate at `center` on the ground is white, and isolated within the field.
+///
+/// The mark is sampled on a circle around the center, where every sample has to be field colored
+/// and form an edge with the white center, see [`is_line_edge`]. This rejects white spots on lines
+/// and robots.
+fn passes_white_test<T: CameraLocation>(
+    center: Point2<f32>,
+    yhs: impl Fn(Point2<f32>) -> Option<(f32, f32, f32)>,
+    camera_matrix: &CameraMatrix<T>,
+    config: &PenaltyMarkDetectionConfig,
+    colors: &ColorConfig,
+) -> bool {
+    let Some(center_yhs) = camera_matrix
+        .ground_to_pixel(point![center.x, center.y, 0.0])
+        .ok()
+        .and_then(&yhs)
+    else {
+        return false;
+    };
+
+    let samples = config.white_test_samples.max(1);
+    let passed = (0..samples)
+        .filter(|&i| {
+            let angle = TAU * i as f32 / samples as f32;
+            let sample =
+                center + Vector2::new(angle.cos(), angle.sin()) * config.white_test_sample_distance;
+
+            camera_matrix
+                .ground_to_pixel(point![sample.x, sample.y, 0.0])
+                .ok()
+                .and_then(&yhs)
+                .is_some_and(|sample| {
+                    colors.is(ColorClass::Field, sample) && is_line_edge(sample, center_yhs, colors)
+                })
+        })
+        .count();
+
+    passed as f32 / samples as f32 >= config.white_test_ratio
+}
+
+fn setup_debug<T: CameraLocation>(dbg: DebugContext) {
+    dbg.scoped::<T>().image().log_static(
+        "penalty_marks/detected",
+        &rerun::Points2D::update_fields()
+            .with_colors([(255, 0, 255)])
+            .with_radii([4.0]),
+    );
+
+    dbg.scoped::<T>().log_static(
+        "penalty_marks/detected",
+        &rerun::Points3D::update_fields()
+            .with_colors([(255, 0, 255)])
+            .with_radii([0.05]),
+    );
+}
+
+fn debug_penalty_marks<T: CameraLocation>(
+    dbg: DebugContext,
+    scan_lines: Res<ScanLines<T>>,
+    camera_matrix: Res<CameraMatrix<T>>,
+    pose_history: Res<PoseHistory>,
+    marks: Query<&DetectedPenaltyMark, With<T>>,
+) {
+    let cycle = scan_lines.image().cycle();
+    let pose = pose_history.pose_at(scan_lines.image().timestamp());
+
+    let pixels = marks
+        .iter()
+        .filter_map(|mark| {
+            let relative = pose.world_to_robot(&mark.position);
+            camera_matrix
+                .ground_to_pixel(point![relative.x, relative.y, 0.0])
+                .ok()
+        })
+        .map(|pixel| (pixel.x, pixel.y))
+        .collect_vec();
+
+    dbg.scoped::<T>().image().log_with_cycle(
+        "penalty_marks/detected",
+        cycle,
+        &rerun::Points2D::new(pixels),
+    );
+
+    dbg.scoped::<T>().log_with_cycle(
+        "penalty_marks/detected",
+        cycle,
+        &rerun::Points3D::new(
+            marks
+                .iter()
+                .map(|mark| (mark.position.x, mark.position.y, 0.0)),
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use heimdall::Top;
+    use nalgebra::{Isometry3, vector};
+
+    use super::*;
+    use crate::vision::color_class::YhsRange;
+
+    const WHITE: (f32, f32, f32) = (200.0, 120.0, 20.0);
+    const GREEN: (f32, f32, f32) = (100.0, 40.0, 150.0);
+
+    fn field() -> FieldConfig {
+        FieldConfig {
+            length: 9.0,
+            width: 6.0,
+            line_width: 0.05,
+            penalty_mark_size: 0.1,
+            goal_area_length: 0.6,
+            goal_area_width: 2.2,
+            penalty_area_length: 1.65,
+            penalty_area_width: 4.0,
+            penalty_mark_distance: 1.3,
+            centre_circle_diameter: 1.5,
+            border_strip_width: 0.7,
+        }
+    }
+
+    fn config() -> PenaltyMarkDetectionConfig {
+        PenaltyMarkDetectionConfig {
+            max_distance: 3.0,
+            size_tolerance: 0.5,
+            white_test_samples: 8,
+            white_test_sample_distance: 0.15,
+            white_test_ratio: 0.8,
+            max_field_distance: 0.5,
+        }
+    }
+
+    fn colors() -> ColorConfig {
+        let any = YhsRange {
+            luminance: [0.0, 255.0],
+            hue: [0.0, 255.0],
+            saturation: [0.0, f32::INFINITY],
+        };
+
+        ColorConfig {
+            white_line: YhsRange {
+                luminance: [150.0, 255.0],
+                saturation: [0.0, 60.0],
+                ..any
+            },
+            field: YhsRange {
+                saturation: [100.0, f32::INFINITY],
+                ..any
+            },
+            ball: any,
+        }
+    }
+
+    /// A camera half a meter above the ground, pitched down by 45 degrees.
+    fn camera_matrix() -> CameraMatrix<Top> {
+        CameraMatrix::new(
+            vector![500.0, 500.0],
+            point![320.0, 240.0],
+            vector![640.0, 480.0],
+            Isometry3::rotation(vector![0.0, std::f32::consts::FRAC_PI_4, 0.0]),
+            Isometry3::identity(),
+            Isometry3::translation(0.0, 0.0, 0.5),
+        )
+    }
+
+    /// Renders a square white spot of `size` at `center` on the ground in robot frame, and
+    /// detects the penalty marks in it using vertical scan-lines.
+    fn detect(center: Point2<f32>, size: f32, pose: &RobotPose) -> Vec<DetectedPenaltyMark> {
+        let camera_matrix = camera_matrix();
+        let yhs = |pixel: Point2<f32>| {
+            let ground = camera_matrix.pixel_to_ground(pixel, 0.0).ok()?.xy();
+            let offset = ground - center;
+
+            Some(if offset.abs().max() <= size / 2.0 {
+                WHITE
+            } else {
+                GREEN
+            })
+        };
+
+        let mut segments = Vec::new();
+        for x in (0..640).step_by(8).map(|x| x as f32) {
+            let white = (0..480)
+                .map(|y| y as f32)
+                .filter(|&y| yhs(point![x, y]) == Some(WHITE))
+                .collect_vec();
+
+            if let (Some(&start), Some(&end)) = (white.first(), white.last()) {
+                segments.push((point![x, start], point![x, end + 1.0]));
+            }
+        }
+
+        detect_penalty_marks(
+            segments,
+            yhs,
+            &camera_matrix,
+            pose,
+            &field(),
+            &config(),
+            &colors(),
+        )
+    }
+
+    #[test]
+    fn detects_spot_of_penalty_mark_size() {
+        // half a meter in front of our own penalty mark
+        let pose = RobotPose::from_translation_and_rotation(vector![-3.7, 0.0], 0.0);
+
+        let marks = detect(point![0.5, 0.0], 0.1, &pose);
+        assert_eq!(marks.len(), 1);
+        assert!(
+            (marks[0].position - point![-3.2, 0.0]).norm() < 0.02,
+            "position: {}",
+            marks[0].position
+        );
+
+        // a spot that is too large is not a penalty mark
+        assert!(detect(point![0.5, 0.0], 0.3, &pose).is_empty());
+
+        // the center mark looks the same, but is at the wrong position on the field
+        let pose = RobotPose::from_translation_and_rotation(vector![-0.5, 0.0], 0.0);
+        assert!(detect(point![0.5, 0.0], 0.1, &pose).is_empty());
+    }
+}
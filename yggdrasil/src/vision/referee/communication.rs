@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bifrost::broadcast::Deadline;
+use bifrost::broadcast::{Deadline, Stamped};
 
 use crate::communication::{TeamCommunication, TeamMessage};
 
@@ -21,7 +21,7 @@ fn send_message(
         if pose_event.pose == RefereePose::Ready {
             tc.outbound_mut()
                 .update_or_push_by(
-                    TeamMessage::RecognizedRefereePose(pose_event.pose),
+                    Stamped::now(TeamMessage::RecognizedRefereePose(pose_event.pose)),
                     Deadline::ASAP,
                 )
                 .expect("unable to encode recognized referee pose");
@@ -33,7 +33,7 @@ fn receive_message(
     mut tc: ResMut<TeamCommunication>,
     mut writer: EventWriter<ReceivedRefereePose>,
 ) {
-    let incoming_msg = tc.inbound_mut().take_map(|_, _, msg| match msg {
+    let incoming_msg = tc.inbound_mut().take_map(|_, _, msg| match &msg.message {
         TeamMessage::RecognizedRefereePose(pose) => Some(*pose),
         _ => None,
     });
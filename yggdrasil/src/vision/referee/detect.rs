@@ -35,7 +35,8 @@ impl Plugin for RefereePoseDetectionPlugin {
                 (
                     detect_referee_pose
                         .after(request_recognition)
-                        .after(recognizing_pose),
+                        .after(recognizing_pose)
+                        .run_if(ml::model_loaded::<RefereePoseDetectionModel>),
                     send_referee_pose_output,
                     log_estimated_pose,
                 )
@@ -1,6 +1,5 @@
-use itertools::{Itertools, repeat_n};
-use miette::{Context, IntoDiagnostic, Result};
-use ndarray::{Array, Array1, Array2, ArrayD, ArrayView, Axis, IxDyn, Order, concatenate, stack};
+use itertools::repeat_n;
+use ndarray::{Array, Array1, Array2, ArrayView, Axis, Order, concatenate, stack};
 
 #[derive(Debug, Clone)]
 pub struct DefaultBoxGenerator {
@@ -116,11 +115,14 @@ impl DefaultBoxGenerator {
         let all_shifts_x = (Array::range(0.0, x_fk as f32, 1.0) + 0.5) / x_fk as f32;
         let all_shifts_y = (Array::range(0.0, y_fk as f32, 1.0) + 0.5) / y_fk as f32;
 
-        let grids = meshgrid(&[all_shifts_y, all_shifts_x]).unwrap();
-
-        // flatten into shape (total_features,)
-        let shift_x = grids[1].to_shape(total_features).unwrap();
-        let shift_y = grids[0].to_shape(total_features).unwrap();
+        // the boxes are ordered row by row, so the x shifts vary fastest
+        let (shift_x, shift_y) = meshgrid(
+            all_shifts_x.as_slice().unwrap(),
+            all_shifts_y.as_slice().unwrap(),
+            Indexing::Xy,
+        );
+        let shift_x = Array1::from(shift_x);
+        let shift_y = Array1::from(shift_y);
 
         let num_pairs = self.wh_pairs[0].dim().0;
 
@@ -135,7 +137,7 @@ impl DefaultBoxGenerator {
 
         // clip the default boxes, while they're encoded in cxcywh format
         let wh_pair = self.wh_pairs[0].map(|x| x.clamp(0.0, 1.0));
-        let wh_pairs = repeat_n(wh_pair, y_fk * x_fk)
+        let wh_pairs = repeat_n(wh_pair, total_features)
             .reduce(|acc, x| concatenate!(Axis(0), acc, x))
             .unwrap();
 
@@ -143,37 +145,51 @@ impl DefaultBoxGenerator {
     }
 }
 
-/// Generate a meshgrid from a list of arrays.
-///
-/// This is like numpy's meshgrid function, but for ndarray and using ij-indexing.
-fn meshgrid<T>(xi: &[Array1<T>]) -> Result<Vec<ArrayD<T>>>
-where
-    T: Copy,
-{
-    let ndim = xi.len();
-    let product = xi.iter().map(|x| x.iter()).multi_cartesian_product();
-
-    let mut grids: Vec<ArrayD<T>> = Vec::with_capacity(ndim);
-
-    for (dim_index, _) in xi.iter().enumerate() {
-        // Generate a flat vector with the correct repeated pattern
-        let values: Vec<T> = product.clone().map(|p| *p[dim_index]).collect();
-
-        let mut grid_shape: Vec<usize> = vec![1; ndim];
-        grid_shape[dim_index] = xi[dim_index].len();
-
-        // Determine the correct repetition for each dimension
-        for (j, len) in xi.iter().map(ndarray::ArrayBase::len).enumerate() {
-            if j != dim_index {
-                grid_shape[j] = len;
-            }
-        }
+/// The indexing convention of a [`meshgrid`], matching numpy's `indexing` argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indexing {
+    /// Cartesian indexing, the grids have shape `(ys.len(), xs.len())`, so `x` varies fastest.
+    ///
+    /// This is what the [`DefaultBoxGenerator`] expects: the detector outputs its boxes row by
+    /// row, from left to right.
+    Xy,
+    /// Matrix indexing, the grids have shape `(xs.len(), ys.len())`, so `y` varies fastest.
+    Ij,
+}
 
-        let grid = Array::from_shape_vec(IxDyn(&grid_shape), values)
-            .into_diagnostic()
-            .wrap_err("Failed to create array from shape vec")?;
-        grids.push(grid);
+/// Generate coordinate grids from the coordinate vectors `xs` and `ys`, like numpy's meshgrid.
+///
+/// The grids are returned flattened in row-major order, as `(grid_x, grid_y)`.
+pub fn meshgrid(xs: &[f32], ys: &[f32], indexing: Indexing) -> (Vec<f32>, Vec<f32>) {
+    match indexing {
+        Indexing::Xy => ys
+            .iter()
+            .flat_map(|&y| xs.iter().map(move |&x| (x, y)))
+            .unzip(),
+        Indexing::Ij => xs
+            .iter()
+            .flat_map(|&x| ys.iter().map(move |&y| (x, y)))
+            .unzip(),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meshgrid_indexing() {
+        let xs = [1.0, 2.0, 3.0];
+        let ys = [10.0, 20.0];
 
-    Ok(grids)
+        // numpy.meshgrid([1, 2, 3], [10, 20], indexing="xy")
+        let (grid_x, grid_y) = meshgrid(&xs, &ys, Indexing::Xy);
+        assert_eq!(grid_x, [1.0, 2.0, 3.0, 1.0, 2.0, 3.0]);
+        assert_eq!(grid_y, [10.0, 10.0, 10.0, 20.0, 20.0, 20.0]);
+
+        // numpy.meshgrid([1, 2, 3], [10, 20], indexing="ij")
+        let (grid_x, grid_y) = meshgrid(&xs, &ys, Indexing::Ij);
+        assert_eq!(grid_x, [1.0, 1.0, 2.0, 2.0, 3.0, 3.0]);
+        assert_eq!(grid_y, [10.0, 20.0, 10.0, 20.0, 10.0, 20.0]);
+    }
 }
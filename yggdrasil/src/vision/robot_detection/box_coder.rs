@@ -1,6 +1,9 @@
 use ndarray::{Array2, Axis, s, stack};
 
-/// Utility that decodes bounding boxes from the regression format output by the model.
+use crate::vision::util::bbox::{Bbox, ConvertBbox, Cxcywh, Xyxy};
+
+/// Utility that encodes and decodes bounding boxes to and from the regression format output by
+/// the model.
 ///
 /// Based on the implementation in [torchvision].
 ///
@@ -73,4 +76,24 @@ impl BoxCoder {
 
         stack![Axis(1), pred_boxes1, pred_boxes2, pred_boxes3, pred_boxes4]
     }
+
+    /// Encode a reference box relative to an anchor box, into the regression format output by
+    /// the model.
+    ///
+    /// This is the inverse of [`BoxCoder::decode_single`], and returns the weighted offset of the
+    /// center and the weighted log-ratio of the width and height, as `(dx, dy, dw, dh)`.
+    pub fn encode(&self, reference: Bbox<Xyxy>, anchor: Bbox<Xyxy>) -> (f32, f32, f32, f32) {
+        let (wx, wy, ww, wh) = self.weights;
+        let (reference_x, reference_y, reference_w, reference_h) =
+            ConvertBbox::<Cxcywh>::convert(&reference).inner;
+        let (anchor_x, anchor_y, anchor_w, anchor_h) =
+            ConvertBbox::<Cxcywh>::convert(&anchor).inner;
+
+        (
+            wx * (reference_x - anchor_x) / anchor_w,
+            wy * (reference_y - anchor_y) / anchor_h,
+            ww * (reference_w / anchor_w).ln(),
+            wh * (reference_h / anchor_h).ln(),
+        )
+    }
 }
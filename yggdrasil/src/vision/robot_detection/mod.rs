@@ -19,19 +19,26 @@ use serde_with::{DurationMilliSeconds, serde_as};
 
 mod anchor_generator;
 mod box_coder;
+pub mod target_assignment;
+pub mod tracker;
 
 use anchor_generator::DefaultBoxGenerator;
 use serde::{Deserialize, Serialize};
 use tasks::conditions::task_finished;
+use tracker::{RobotTracker, RobotTrackerConfig};
 
 use super::referee::detect::VisualRefereeDetectionStatus;
 use super::util::bbox::{ConvertBbox, Cxcywh};
 
+/// The weights of the x, y, width and height of the box regression the model is trained with.
+const BOX_CODER_WEIGHTS: (f32, f32, f32, f32) = (10.0, 10.0, 5.0, 5.0);
+
 #[serde_as]
 #[derive(Resource, Debug, Clone, Deserialize, Serialize, Reflect)]
 #[serde(deny_unknown_fields)]
 pub struct RobotDetectionConfig {
     confidence_threshold: f32,
+    score_temperature: f32,
     nms_threshold: f32,
     top_k_detections: usize,
     #[serde_as(as = "DurationMilliSeconds<u64>")]
@@ -40,6 +47,7 @@ pub struct RobotDetectionConfig {
     input_height: u32,
     num_anchor_boxes: usize,
     feature_map_shape: (usize, usize, usize),
+    tracker: RobotTrackerConfig,
 }
 
 impl Config for RobotDetectionConfig {
@@ -64,12 +72,18 @@ impl Plugin for RobotDetectionPlugin {
     fn build(&self, app: &mut App) {
         app.init_config::<RobotDetectionConfig>()
             .init_ml_model::<RobotDetectionModel>()
+            .init_resource::<RobotTracker>()
             .add_systems(PostStartup, setup_robot_detection)
             .add_systems(
                 Update,
                 detect_robots
                     .run_if(task_finished::<Image<Top>>.and(task_finished::<RobotDetectionData>))
-                    .run_if(in_state(VisualRefereeDetectionStatus::Inactive)),
+                    .run_if(in_state(VisualRefereeDetectionStatus::Inactive))
+                    .run_if(ml::model_loaded::<RobotDetectionModel>),
+            )
+            .add_systems(
+                Update,
+                tracker::track_robots.run_if(resource_exists_and_changed::<RobotDetectionData>),
             )
             .add_systems(
                 PostUpdate,
@@ -170,7 +184,7 @@ fn postprocess_detections(
     k: usize,
 ) -> Vec<DetectedRobot> {
     let anchor_generator = DefaultBoxGenerator::new(vec![vec![0.4, 0.5], vec![0.85]], 0.15, 0.9);
-    let box_coder = BoxCoder::new((10.0, 10.0, 5.0, 5.0));
+    let box_coder = BoxCoder::new(BOX_CODER_WEIGHTS);
 
     let decoded_boxes = box_coder.decode_single(
         box_regression,
@@ -191,11 +205,12 @@ fn postprocess_detections(
         .filter(|r| r.timestamp.elapsed() < config.detection_lifetime)
         .cloned();
 
-    let filtered_boxes = scores
+    let candidates = scores
         .axis_iter(Axis(0))
         .enumerate()
         .filter_map(|(i, s)| {
-            let scores = ml::util::softmax(&[s[0], s[1]]);
+            let logits = ml::util::calibrate_scores(&[s[0], s[1]], config.score_temperature);
+            let scores = ml::util::softmax(&logits);
             if scores[1] < threshold {
                 return None;
             }
@@ -217,8 +232,12 @@ fn postprocess_detections(
             timestamp: Instant::now(),
         })
         .chain(persisted)
-        .sorted_by(|a, b| b.confidence.total_cmp(&a.confidence))
-        .take(k)
+        .collect::<Vec<_>>();
+
+    let confidences = candidates.iter().map(|r| r.confidence).collect_vec();
+    let filtered_boxes = ml::util::top_k(&confidences, k)
+        .into_iter()
+        .map(|i| candidates[i].clone())
         .collect::<Vec<_>>();
 
     non_max_suppression(
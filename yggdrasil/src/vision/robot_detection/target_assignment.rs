@@ -0,0 +1,121 @@
+//! Assignment of anchor boxes to ground truth boxes, to export training targets for the robot
+//! detection model from labeled frames.
+
+use crate::vision::util::bbox::{Bbox, Xyxy};
+
+use super::{BOX_CODER_WEIGHTS, box_coder::BoxCoder};
+
+/// The training target of a single anchor box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Assignment {
+    /// The anchor matches a ground truth box, and should regress towards it.
+    Positive {
+        /// Index of the matched ground truth box.
+        gt_index: usize,
+        /// The matched ground truth box encoded relative to the anchor, as `(dx, dy, dw, dh)`.
+        delta: (f32, f32, f32, f32),
+    },
+    /// The anchor does not overlap any ground truth box enough, and should be classified as
+    /// background.
+    Negative,
+    /// The anchor overlaps a ground truth box too much to be background, but too little to be a
+    /// match, and is left out of the loss.
+    Ignore,
+}
+
+/// Assigns every anchor box to the ground truth box it overlaps the most.
+///
+/// An anchor is [`Assignment::Positive`] if its best intersection over union (`IoU`) with a ground
+/// truth box is at least `pos_iou`, [`Assignment::Negative`] if it is below `neg_iou`, and
+/// [`Assignment::Ignore`] otherwise. Every ground truth box is additionally force-matched to the
+/// anchor it overlaps the most, so that boxes with an unusual size or aspect ratio still have a
+/// positive anchor.
+///
+/// The returned assignments are in the same order as the `anchors`.
+#[must_use]
+pub fn assign_targets(
+    anchors: &[Bbox<Xyxy>],
+    gts: &[Bbox<Xyxy>],
+    pos_iou: f32,
+    neg_iou: f32,
+) -> Vec<Assignment> {
+    let box_coder = BoxCoder::new(BOX_CODER_WEIGHTS);
+    let positive = |anchor: usize, gt_index: usize| Assignment::Positive {
+        gt_index,
+        delta: box_coder.encode(gts[gt_index], anchors[anchor]),
+    };
+
+    // the iou between every anchor (rows) and ground truth box (columns)
+    let ious: Vec<Vec<f32>> = anchors
+        .iter()
+        .map(|anchor| gts.iter().map(|gt| anchor.iou(gt)).collect())
+        .collect();
+
+    let mut assignments: Vec<_> = ious
+        .iter()
+        .enumerate()
+        .map(|(anchor, ious)| match best_match(ious.iter().copied()) {
+            Some((gt_index, iou)) if iou >= pos_iou => positive(anchor, gt_index),
+            Some((_, iou)) if iou >= neg_iou => Assignment::Ignore,
+            _ => Assignment::Negative,
+        })
+        .collect();
+
+    for gt_index in 0..gts.len() {
+        if let Some((anchor, _)) = best_match(ious.iter().map(|ious| ious[gt_index])) {
+            assignments[anchor] = positive(anchor, gt_index);
+        }
+    }
+
+    assignments
+}
+
+/// The index and value of the highest overlapping iou, ignoring boxes that do not overlap.
+fn best_match(ious: impl Iterator<Item = f32>) -> Option<(usize, f32)> {
+    ious.enumerate()
+        .filter(|(_, iou)| *iou > 0.0)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assigns_anchors_by_iou() {
+        let anchors = [
+            // matches the first robot
+            Bbox::xyxy(0.0, 0.0, 10.0, 20.0),
+            // overlaps the first robot, but not enough to be a match
+            Bbox::xyxy(6.0, 0.0, 16.0, 20.0),
+            // background
+            Bbox::xyxy(50.0, 50.0, 60.0, 70.0),
+            // a wide anchor that is the best, but a poor, match of the lying robot
+            Bbox::xyxy(30.0, 0.0, 50.0, 10.0),
+        ];
+        let gts = [
+            Bbox::xyxy(1.0, 0.0, 11.0, 20.0),
+            Bbox::xyxy(28.0, 4.0, 58.0, 12.0),
+        ];
+
+        let assignments = assign_targets(&anchors, &gts, 0.5, 0.3);
+
+        let Assignment::Positive { gt_index, delta } = assignments[0] else {
+            panic!("expected a positive assignment, got {:?}", assignments[0]);
+        };
+        assert_eq!(gt_index, 0);
+        // the ground truth is shifted by a tenth of the anchor width, with the same size
+        assert!((delta.0 - 1.0).abs() < 1e-5);
+        assert!(delta.1.abs() < 1e-5 && delta.2.abs() < 1e-5 && delta.3.abs() < 1e-5);
+
+        assert_eq!(assignments[1], Assignment::Ignore);
+        assert_eq!(assignments[2], Assignment::Negative);
+
+        // the lying robot is force-matched to its best anchor, despite the low iou
+        assert!(anchors[3].iou(&gts[1]) < 0.5);
+        assert!(matches!(
+            assignments[3],
+            Assignment::Positive { gt_index: 1, .. }
+        ));
+    }
+}
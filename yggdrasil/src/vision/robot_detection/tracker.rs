@@ -0,0 +1,174 @@
+//! Tracking of detected robots over multiple frames, see [`RobotTracker`].
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{DetectedRobot, RobotDetectionConfig, RobotDetectionData};
+use crate::vision::util::bbox::{Bbox, Xyxy};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Reflect)]
+#[serde(deny_unknown_fields)]
+pub struct RobotTrackerConfig {
+    /// Minimum `IoU` between a track and a detection for the detection to be associated with it.
+    pub min_iou: f32,
+    /// Number of frames a track is kept without being matched to a detection.
+    pub max_missed_frames: u32,
+    /// Minimum confidence of an unmatched detection to start a new track.
+    pub min_spawn_confidence: f32,
+}
+
+/// A robot that has been detected in one or more consecutive frames.
+#[derive(Debug, Clone, Reflect)]
+pub struct Track {
+    /// Identifier of the track, which stays the same for as long as the robot is tracked.
+    pub id: u64,
+    /// The bounding box of the most recent detection of the robot, in image coordinates.
+    pub bbox: Bbox<Xyxy>,
+    /// Number of frames since the track was started.
+    pub age: u32,
+    /// Number of consecutive frames in which the track was not matched to a detection.
+    pub missed_frames: u32,
+}
+
+/// Track-by-detection of robots, based on the overlap of their bounding boxes.
+///
+/// Every frame the detections are associated with the existing tracks by greedily matching the
+/// pairs with the highest `IoU`. Tracks that are not matched for more than
+/// [`RobotTrackerConfig::max_missed_frames`] frames are dropped, which allows a robot to keep its
+/// id while it is briefly occluded.
+#[derive(Resource, Debug, Default)]
+pub struct RobotTracker {
+    tracks: Vec<Track>,
+    next_id: u64,
+}
+
+impl RobotTracker {
+    /// The currently tracked robots.
+    #[must_use]
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    /// Updates the tracks with the detections of a new frame.
+    pub fn update(&mut self, detections: &[DetectedRobot], config: &RobotTrackerConfig) {
+        let mut pairs = Vec::new();
+        for (track_index, track) in self.tracks.iter().enumerate() {
+            for (detection_index, detection) in detections.iter().enumerate() {
+                let iou = track.bbox.iou(&detection.bbox);
+                if iou >= config.min_iou {
+                    pairs.push((iou, track_index, detection_index));
+                }
+            }
+        }
+        pairs.sort_by(|(a, ..), (b, ..)| b.total_cmp(a));
+
+        let mut track_matched = vec![false; self.tracks.len()];
+        let mut detection_matched = vec![false; detections.len()];
+        for (_, track_index, detection_index) in pairs {
+            if track_matched[track_index] || detection_matched[detection_index] {
+                continue;
+            }
+
+            track_matched[track_index] = true;
+            detection_matched[detection_index] = true;
+
+            let track = &mut self.tracks[track_index];
+            track.bbox = detections[detection_index].bbox;
+            track.missed_frames = 0;
+        }
+
+        for (track, matched) in self.tracks.iter_mut().zip(track_matched) {
+            track.age += 1;
+            if !matched {
+                track.missed_frames += 1;
+            }
+        }
+        self.tracks
+            .retain(|track| track.missed_frames <= config.max_missed_frames);
+
+        for (detection, matched) in detections.iter().zip(detection_matched) {
+            if matched || detection.confidence < config.min_spawn_confidence {
+                continue;
+            }
+
+            self.tracks.push(Track {
+                id: self.next_id,
+                bbox: detection.bbox,
+                age: 0,
+                missed_frames: 0,
+            });
+            self.next_id += 1;
+        }
+    }
+}
+
+pub(super) fn track_robots(
+    mut tracker: ResMut<RobotTracker>,
+    robot_data: Res<RobotDetectionData>,
+    config: Res<RobotDetectionConfig>,
+) {
+    tracker.update(&robot_data.detected, &config.tracker);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    fn robot(x: f32, confidence: f32) -> DetectedRobot {
+        DetectedRobot {
+            bbox: Bbox::xyxy(x, 100.0, x + 40.0, 200.0),
+            confidence,
+            timestamp: Instant::now(),
+        }
+    }
+
+    fn ids(tracker: &RobotTracker) -> Vec<u64> {
+        tracker.tracks().iter().map(|track| track.id).collect()
+    }
+
+    #[test]
+    fn ids_are_stable_through_occlusion() {
+        let config = RobotTrackerConfig {
+            min_iou: 0.3,
+            max_missed_frames: 2,
+            min_spawn_confidence: 0.5,
+        };
+        let mut tracker = RobotTracker::default();
+
+        // two robots walking to the right, the second is occluded in the third and fourth frame
+        let frames = [
+            vec![robot(0.0, 0.9), robot(200.0, 0.8)],
+            vec![robot(4.0, 0.9), robot(204.0, 0.8)],
+            vec![robot(8.0, 0.9)],
+            vec![robot(12.0, 0.9)],
+            vec![robot(16.0, 0.9), robot(212.0, 0.8)],
+        ];
+        for frame in &frames {
+            tracker.update(frame, &config);
+        }
+        assert_eq!(ids(&tracker), [0, 1]);
+        assert_eq!(tracker.tracks()[1].age, 4);
+
+        // a new robot is only tracked when its detection is confident enough
+        tracker.update(
+            &[robot(20.0, 0.9), robot(216.0, 0.8), robot(400.0, 0.2)],
+            &config,
+        );
+        assert_eq!(ids(&tracker), [0, 1]);
+        tracker.update(
+            &[robot(24.0, 0.9), robot(220.0, 0.8), robot(400.0, 0.7)],
+            &config,
+        );
+        assert_eq!(ids(&tracker), [0, 1, 2]);
+
+        // the second robot disappears for longer than the track is kept
+        for _ in 0..3 {
+            tracker.update(&[robot(24.0, 0.9), robot(400.0, 0.7)], &config);
+        }
+        assert_eq!(ids(&tracker), [0, 2]);
+        tracker.update(&[robot(24.0, 0.9), robot(220.0, 0.8)], &config);
+        assert_eq!(ids(&tracker), [0, 2, 3]);
+    }
+}
@@ -3,8 +3,9 @@ use std::{ops::Deref, sync::Arc};
 use crate::{
     core::debug::{
         DebugContext,
-        debug_system::{DebugAppExt, SystemToggle},
+        debug_system::{DebugAppExt, DebugLevel, SystemToggle},
     },
+    debug_log,
     nao::Cycle,
     prelude::*,
 };
@@ -117,6 +118,8 @@ impl Plugin for ScanLinesPlugin {
                         .run_if(resource_exists_and_changed::<ScanLines<Bottom>>),
                 ),
                 "Visualize scan lines",
+                "vision",
+                DebugLevel::Trace,
                 SystemToggle::Disable,
             );
     }
@@ -847,16 +850,18 @@ fn visualize_scan_line_spots<T: CameraLocation>(
         Direction::Vertical => "vertical",
     };
 
-    let line_spots = scan_line
-        .line_spots()
-        .map(|s| (s.x, s.y))
-        .collect::<Vec<_>>();
-
-    let colors = vec![color; line_spots.len()];
-
-    dbg.log_with_cycle(
+    debug_log!(
+        dbg,
         T::make_entity_image_path(format!("scan_lines/spots/{direction_str}")),
-        cycle,
-        &rerun::Points2D::new(line_spots).with_colors(colors),
+        cycle = cycle,
+        {
+            let line_spots = scan_line
+                .line_spots()
+                .map(|s| (s.x, s.y))
+                .collect::<Vec<_>>();
+            let colors = vec![color; line_spots.len()];
+
+            rerun::Points2D::new(line_spots).with_colors(colors)
+        }
     );
 }
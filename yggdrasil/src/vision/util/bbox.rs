@@ -1,4 +1,5 @@
 use bevy::reflect::Reflect;
+use nalgebra::{Point2, Rotation2, point, vector};
 
 /// A type-safe bounding box.
 ///
@@ -240,6 +241,141 @@ impl ConvertBbox<Xywh> for Bbox<Cxcywh> {
     }
 }
 
+/// A bounding box that is rotated around its center.
+///
+/// Unlike [`Bbox`], the sides of a rotated bounding box do not have to be aligned with the image
+/// axes, which better fits objects that are seen at an angle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Reflect)]
+pub struct RotatedBbox {
+    /// The x coordinate of the center.
+    pub cx: f32,
+    /// The y coordinate of the center.
+    pub cy: f32,
+    /// The width of the box, before rotating.
+    pub w: f32,
+    /// The height of the box, before rotating.
+    pub h: f32,
+    /// The rotation of the box around its center, in radians.
+    pub angle: f32,
+}
+
+impl RotatedBbox {
+    /// Create a rotated bounding box from its center, size and rotation in radians.
+    #[must_use]
+    pub fn new(cx: f32, cy: f32, w: f32, h: f32, angle: f32) -> Self {
+        Self {
+            cx,
+            cy,
+            w,
+            h,
+            angle,
+        }
+    }
+
+    /// Compute the area of the bounding box.
+    #[must_use]
+    pub fn area(&self) -> f32 {
+        self.w * self.h
+    }
+
+    /// The corners of the bounding box, in counter-clockwise order.
+    #[must_use]
+    pub fn corners(&self) -> [Point2<f32>; 4] {
+        let rotation = Rotation2::new(self.angle);
+        let center = point![self.cx, self.cy];
+        let (w, h) = (self.w / 2.0, self.h / 2.0);
+
+        [
+            center + rotation * vector![-w, -h],
+            center + rotation * vector![w, -h],
+            center + rotation * vector![w, h],
+            center + rotation * vector![-w, h],
+        ]
+    }
+
+    /// Compute the intersection area between two rotated bounding boxes.
+    ///
+    /// The overlapping polygon is found using Sutherland-Hodgman clipping.
+    /// If the bounding boxes do not overlap, the intersection area is `0.0`.
+    #[must_use]
+    pub fn intersection(&self, other: &RotatedBbox) -> f32 {
+        let clip = other.corners();
+        let mut polygon = self.corners().to_vec();
+
+        for (&a, &b) in clip.iter().zip(clip.iter().cycle().skip(1)) {
+            if polygon.is_empty() {
+                break;
+            }
+
+            polygon = clip_polygon(&polygon, a, b);
+        }
+
+        polygon_area(&polygon)
+    }
+
+    /// Compute the union area between two rotated bounding boxes.
+    #[must_use]
+    pub fn union(&self, other: &RotatedBbox) -> f32 {
+        self.area() + other.area() - self.intersection(other)
+    }
+
+    /// Compute the intersection over union (`IoU`) between two rotated bounding boxes.
+    ///
+    /// Returns `0.0` if both bounding boxes are empty.
+    #[must_use]
+    pub fn iou(&self, other: &RotatedBbox) -> f32 {
+        let intersect = self.intersection(other);
+        let union = self.area() + other.area() - intersect;
+
+        if union <= 0.0 {
+            return 0.0;
+        }
+
+        intersect / union
+    }
+}
+
+impl From<Bbox<Cxcywh>> for RotatedBbox {
+    fn from(bbox: Bbox<Cxcywh>) -> Self {
+        let (cx, cy, w, h) = bbox.inner;
+        Self::new(cx, cy, w, h, 0.0)
+    }
+}
+
+/// Clip a polygon to the half-plane left of the directed edge from `a` to `b`.
+fn clip_polygon(polygon: &[Point2<f32>], a: Point2<f32>, b: Point2<f32>) -> Vec<Point2<f32>> {
+    let edge = b - a;
+    let side = |p: Point2<f32>| edge.perp(&(p - a));
+
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (&current, &next) in polygon.iter().zip(polygon.iter().cycle().skip(1)) {
+        let (current_side, next_side) = (side(current), side(next));
+
+        if current_side >= 0.0 {
+            clipped.push(current);
+        }
+
+        // the edge from `current` to `next` crosses the clipping line
+        if (current_side >= 0.0) != (next_side >= 0.0) {
+            let t = current_side / (current_side - next_side);
+            clipped.push(current + (next - current) * t);
+        }
+    }
+
+    clipped
+}
+
+/// Compute the area of a simple polygon using the shoelace formula.
+fn polygon_area(polygon: &[Point2<f32>]) -> f32 {
+    let twice_area: f32 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(p, q)| p.coords.perp(&q.coords))
+        .sum();
+
+    twice_area.abs() / 2.0
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
@@ -274,4 +410,47 @@ mod tests {
         assert_eq!(bbox1.union(&bbox2), 175.0);
         assert_eq!(bbox1.iou(&bbox2), 25.0 / 175.0);
     }
+
+    #[test]
+    fn rotated_iou_axis_aligned() {
+        let bbox1 = RotatedBbox::new(5.0, 5.0, 10.0, 10.0, 0.0);
+        let bbox2 = RotatedBbox::new(10.0, 10.0, 10.0, 10.0, 0.0);
+
+        assert!((bbox1.intersection(&bbox2) - 25.0).abs() < 1e-4);
+        assert!((bbox1.iou(&bbox2) - 25.0 / 175.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rotated_iou_identical() {
+        let bbox = RotatedBbox::new(3.0, -2.0, 4.0, 2.0, 0.7);
+
+        assert!((bbox.iou(&bbox) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rotated_iou_no_overlap() {
+        let bbox1 = RotatedBbox::new(0.0, 0.0, 2.0, 2.0, std::f32::consts::FRAC_PI_4);
+        let bbox2 = RotatedBbox::new(5.0, 0.0, 2.0, 2.0, 0.3);
+
+        assert_eq!(bbox1.intersection(&bbox2), 0.0);
+        assert_eq!(bbox1.iou(&bbox2), 0.0);
+    }
+
+    #[test]
+    fn rotated_iou_rotated_square() {
+        // a square and the same square rotated by 45 degrees overlap in a regular octagon
+        let bbox1 = RotatedBbox::new(0.0, 0.0, 2.0, 2.0, 0.0);
+        let bbox2 = RotatedBbox::new(0.0, 0.0, 2.0, 2.0, std::f32::consts::FRAC_PI_4);
+
+        let octagon = 8.0 * (std::f32::consts::SQRT_2 - 1.0);
+        assert!((bbox1.intersection(&bbox2) - octagon).abs() < 1e-4);
+        assert!((bbox1.iou(&bbox2) - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn rotated_iou_empty() {
+        let bbox = RotatedBbox::new(0.0, 0.0, 0.0, 0.0, 0.0);
+
+        assert_eq!(bbox.iou(&bbox), 0.0);
+    }
 }
@@ -0,0 +1,74 @@
+use miette::{Result, ensure};
+
+/// Compute the intersection over union (`IoU`) between two boolean masks of `width` by `height`
+/// pixels, stored in row-major order.
+///
+/// This is the segmentation counterpart of [`Bbox::iou`](super::bbox::Bbox::iou), and walks
+/// over both masks once without allocating.
+///
+/// Returns `0.0` if both masks are empty.
+///
+/// # Errors
+///
+/// Returns an error if the length of either mask is not `width * height`.
+pub fn mask_iou(a: &[bool], b: &[bool], width: usize, height: usize) -> Result<f32> {
+    let len = width * height;
+    ensure!(
+        a.len() == len && b.len() == len,
+        "masks of {} and {} pixels do not match the dimensions {width}x{height}",
+        a.len(),
+        b.len(),
+    );
+
+    let (intersection, union) =
+        a.iter()
+            .zip(b)
+            .fold((0_u32, 0_u32), |(intersection, union), (&a, &b)| {
+                (intersection + u32::from(a && b), union + u32::from(a || b))
+            });
+
+    if union == 0 {
+        return Ok(0.0);
+    }
+
+    Ok(intersection as f32 / union as f32)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_iou_identical() {
+        let mask = [true, true, false, false, true, false];
+
+        assert_eq!(mask_iou(&mask, &mask, 3, 2).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn mask_iou_partial_overlap() {
+        let a = [true, true, false, false];
+        let b = [false, true, true, false];
+
+        assert_eq!(mask_iou(&a, &b, 2, 2).unwrap(), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn mask_iou_disjoint() {
+        let a = [true, true, false, false];
+        let b = [false, false, true, true];
+
+        assert_eq!(mask_iou(&a, &b, 2, 2).unwrap(), 0.0);
+        assert_eq!(mask_iou(&[false; 4], &[false; 4], 2, 2).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn mask_iou_mismatched_dimensions() {
+        let a = [true; 6];
+        let b = [true; 4];
+
+        assert!(mask_iou(&a, &b, 3, 2).is_err());
+        assert!(mask_iou(&b, &b, 3, 2).is_err());
+    }
+}
@@ -1,8 +1,9 @@
 pub mod bbox;
+pub mod mask;
 
 use miette::{IntoDiagnostic, Result};
 
-use bbox::{ConvertBbox, Xyxy};
+use bbox::{ConvertBbox, RotatedBbox, Xyxy};
 use fast_image_resize::{self as fir, ResizeOptions};
 use itertools::Itertools;
 
@@ -41,6 +42,29 @@ where
     final_indices
 }
 
+/// Applies Non-Maximum Suppression (NMS) to the given rotated bounding boxes and scores.
+///
+/// See [`non_max_suppression`], but using the [`RotatedBbox::iou`] between boxes.
+#[must_use]
+pub fn rotated_non_max_suppression(
+    detections: &[(RotatedBbox, f32)],
+    threshold: f32,
+) -> Vec<usize> {
+    let mut final_indices = Vec::new();
+
+    for (i, (box_i, score_i)) in detections.iter().enumerate() {
+        let discard = detections.iter().enumerate().any(|(j, (box_j, score_j))| {
+            i != j && box_i.iou(box_j) >= threshold && score_j >= score_i
+        });
+
+        if !discard {
+            final_indices.push(i);
+        }
+    }
+
+    final_indices
+}
+
 /// Resizes a raw buffer of yuyv data.
 pub fn resize_image(
     image: Vec<u8>,
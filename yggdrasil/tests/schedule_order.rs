@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use yggdrasil::{
+    nao::CycleTime,
+    prelude::*,
+    schedule::{FixedCycle, NaoSchedulePlugin},
+};
+
+/// The schedules in which the systems of [`ExternalPlugin`] ran, in order.
+#[derive(Resource, Default)]
+struct Order(Vec<&'static str>);
+
+fn record(stage: &'static str) -> impl FnMut(ResMut<Order>) {
+    move |mut order| order.0.push(stage)
+}
+
+/// A plugin that only uses the public schedule labels, like a tool outside of the framework.
+struct ExternalPlugin;
+
+impl Plugin for ExternalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Order>()
+            .add_systems(PostWrite, record("post_write"))
+            .add_systems(Write, record("write"))
+            .add_systems(PreWrite, record("pre_write"))
+            .add_systems(Update, record("update"))
+            .add_systems(FixedCycle, record("fixed_cycle"))
+            .add_systems(Sensor, record("sensor"));
+    }
+}
+
+fn app(plugins_before: bool) -> App {
+    let mut app = App::new();
+    app.insert_resource(CycleTime {
+        cycle_start: Instant::now(),
+        duration: Duration::from_millis(12),
+    });
+
+    if plugins_before {
+        app.add_plugins((ExternalPlugin, NaoSchedulePlugin));
+    } else {
+        app.add_plugins((NaoSchedulePlugin, ExternalPlugin));
+    }
+
+    app
+}
+
+#[test]
+fn external_systems_run_in_stage_order() {
+    for plugins_before in [true, false] {
+        let mut app = app(plugins_before);
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<Order>().0,
+            [
+                "sensor",
+                "fixed_cycle",
+                "update",
+                "pre_write",
+                "write",
+                "post_write"
+            ],
+            "wrong order when the external plugin is added {}",
+            if plugins_before { "first" } else { "last" }
+        );
+    }
+}